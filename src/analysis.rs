@@ -1,8 +1,13 @@
 //! Analysis tools for simulation results.
 
+use crate::core::Holdings;
 use crate::events::{Event, EventType, ResourceType, TradeSide};
+use crate::metrics::{LastPrices, MetricsCalculator};
+use crate::scenario::{Scenario, SimulationParameters, VillageConfig};
+use crate::types::ResourceTypeExt;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::ToPrimitive;
+use rust_decimal_macros::dec;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
@@ -14,6 +19,8 @@ pub struct SimulationAnalysis {
     pub total_days: usize,
     pub villages: Vec<VillageAnalysis>,
     pub market: MarketAnalysis,
+    pub fairness: FairnessReport,
+    pub arbitrage: ArbitrageReport,
     pub insights: Vec<String>,
 }
 
@@ -30,6 +37,22 @@ pub struct VillageAnalysis {
     pub trading_summary: TradingSummary,
     pub worker_deaths: HashMap<String, usize>, // cause -> count
     pub strategy_effectiveness: f64,
+    pub population_trend: Option<PopulationTrend>,
+    /// Money, food and wood at the last snapshot, valued at the last
+    /// clearing prices
+    pub final_wealth: Decimal,
+    /// Estimated sustainable population, known only when the scenario is
+    /// supplied through `set_carrying_capacities`
+    pub carrying_capacity: Option<usize>,
+}
+
+/// Linear trend of a village's population over the last third of the run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PopulationTrend {
+    /// Least-squares slope in workers per tick
+    pub slope: f64,
+    /// Ticks until the population reaches zero at the current slope, if declining
+    pub ticks_to_extinction: Option<f64>,
 }
 
 #[derive(Debug, Default)]
@@ -69,6 +92,69 @@ pub struct PriceHistory {
     pub food_volatility: f64,
 }
 
+/// Per-village order fill rates and whether any village is systematically disadvantaged.
+#[derive(Debug, Default)]
+pub struct FairnessReport {
+    /// Sorted by village id
+    pub villages: Vec<VillageFillRate>,
+    /// Ids of villages whose fill rate differs significantly from everyone else's
+    pub flagged: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VillageFillRate {
+    pub village_id: String,
+    pub ordered: Decimal,
+    pub filled: Decimal,
+    pub fill_rate: f64,
+    /// Two-proportion z-score of this village's fill rate against all other villages
+    pub z_score: f64,
+}
+
+/// |z| above this (two-sided 99%) counts as a significant fill-rate disparity.
+const FAIRNESS_Z_THRESHOLD: f64 = 2.576;
+
+/// Villages that both buy and sell a resource, and which of them look like
+/// arbitrageurs buying cheap and reselling dear.
+#[derive(Debug, Default)]
+pub struct ArbitrageReport {
+    /// Sorted by village id, then resource
+    pub spreads: Vec<TradeSpread>,
+    /// Spreads wide enough and traded heavily enough to count as arbitrage
+    pub flagged: Vec<TradeSpread>,
+}
+
+/// A village's average buy and sell prices for one resource it traded both ways.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeSpread {
+    pub village_id: String,
+    pub resource: ResourceType,
+    pub avg_buy_price: Decimal,
+    pub avg_sell_price: Decimal,
+    /// Units both bought and sold, i.e. the smaller of the two volumes
+    pub round_trip_volume: Decimal,
+    /// Share of the resource's total traded volume this village round-tripped
+    pub volume_share: f64,
+}
+
+impl TradeSpread {
+    /// Sell price over buy price, minus one.
+    pub fn relative_spread(&self) -> f64 {
+        if self.avg_buy_price > Decimal::ZERO {
+            (self.avg_sell_price / self.avg_buy_price - Decimal::ONE)
+                .to_f64()
+                .unwrap_or(0.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Selling this much above the average buy price counts as a large spread.
+const ARBITRAGE_MIN_SPREAD: f64 = 0.25;
+/// Round-tripping at least this share of a resource's volume counts as high volume.
+const ARBITRAGE_MIN_VOLUME_SHARE: f64 = 0.1;
+
 /// Load and analyze simulation events from a file.
 pub fn analyze_simulation(path: &Path) -> Result<SimulationAnalysis, String> {
     // Load events
@@ -85,10 +171,12 @@ pub fn analyze_events(events: &[Event]) -> Result<SimulationAnalysis, String> {
     let mut villages: HashMap<String, VillageData> = HashMap::new();
     let mut market_data = MarketData::default();
     let mut max_tick = 0;
+    let mut prices = LastPrices::default();
 
     // Process each event
     for event in events {
         max_tick = max_tick.max(event.tick);
+        prices.observe(event);
 
         match &event.event_type {
             EventType::WorkerAllocation {
@@ -110,6 +198,7 @@ pub fn analyze_events(events: &[Event]) -> Result<SimulationAnalysis, String> {
                 match resource {
                     ResourceType::Food => village.total_production.food += amount,
                     ResourceType::Wood => village.total_production.wood += amount,
+                    ResourceType::Labor => {}
                 }
             }
 
@@ -120,11 +209,23 @@ pub fn analyze_events(events: &[Event]) -> Result<SimulationAnalysis, String> {
                 match resource {
                     ResourceType::Food => village.total_consumption.food += amount,
                     ResourceType::Wood => village.total_consumption.wood += amount,
+                    ResourceType::Labor => {}
                 }
             }
 
-            EventType::VillageStateSnapshot { population, .. } => {
+            EventType::VillageStateSnapshot {
+                population,
+                food,
+                wood,
+                money,
+                ..
+            } => {
                 let village = villages.entry(event.village_id.clone()).or_default();
+                village.final_holdings = Holdings {
+                    money: *money,
+                    food: *food,
+                    wood: *wood,
+                };
                 village.population_history.push((event.tick, *population));
                 if village.initial_population == 0 {
                     village.initial_population = *population;
@@ -174,6 +275,7 @@ pub fn analyze_events(events: &[Event]) -> Result<SimulationAnalysis, String> {
                 match resource {
                     ResourceType::Wood => market_data.wood_prices.push((event.tick, *price)),
                     ResourceType::Food => market_data.food_prices.push((event.tick, *price)),
+                    ResourceType::Labor => {}
                 }
 
                 *market_data
@@ -182,7 +284,11 @@ pub fn analyze_events(events: &[Event]) -> Result<SimulationAnalysis, String> {
                     .or_insert(Decimal::ZERO) += *quantity;
             }
 
-            EventType::AuctionCleared { wood_price, food_price, .. } => {
+            EventType::AuctionCleared {
+                wood_price,
+                food_price,
+                ..
+            } => {
                 if let Some(price) = wood_price {
                     market_data.wood_prices.push((event.tick, *price));
                 }
@@ -214,6 +320,7 @@ pub fn analyze_events(events: &[Event]) -> Result<SimulationAnalysis, String> {
         let net_profit = data.trading.total_earned - data.trading.total_spent;
 
         let effectiveness = calculate_effectiveness(&data);
+        let population_trend = population_trend(&data.population_history);
 
         village_analyses.push(VillageAnalysis {
             id: id.clone(),
@@ -230,6 +337,9 @@ pub fn analyze_events(events: &[Event]) -> Result<SimulationAnalysis, String> {
             },
             worker_deaths: data.deaths,
             strategy_effectiveness: effectiveness,
+            population_trend,
+            final_wealth: prices.wealth(&data.final_holdings),
+            carrying_capacity: None,
         });
     }
 
@@ -242,7 +352,27 @@ pub fn analyze_events(events: &[Event]) -> Result<SimulationAnalysis, String> {
     };
 
     // Generate insights
-    let insights = generate_insights(&village_analyses, &price_history, max_tick);
+    let fairness = audit_auction_fairness(events);
+    let arbitrage = detect_arbitrage(events);
+    let mut insights = generate_insights(&village_analyses, &price_history, max_tick);
+    if !fairness.flagged.is_empty() {
+        insights.push(format!(
+            "Fill rates differ significantly for {} - check auction tie-breaking",
+            fairness.flagged.join(", ")
+        ));
+    }
+    if !arbitrage.flagged.is_empty() {
+        let mut arbitrageurs: Vec<&str> = arbitrage
+            .flagged
+            .iter()
+            .map(|s| s.village_id.as_str())
+            .collect();
+        arbitrageurs.dedup();
+        insights.push(format!(
+            "{} profit by buying cheap and reselling dear - check for exploitative strategies",
+            arbitrageurs.join(", ")
+        ));
+    }
 
     Ok(SimulationAnalysis {
         total_events: events.len(),
@@ -255,10 +385,228 @@ pub fn analyze_events(events: &[Event]) -> Result<SimulationAnalysis, String> {
             price_history,
             volume_by_resource: market_data.volume_by_resource,
         },
+        fairness,
+        arbitrage,
         insights,
     })
 }
 
+/// Gini coefficients of money and of wealth across villages at the end of
+/// every tick with a `VillageStateSnapshot`, as `(tick, gini_money, gini_wealth)`.
+///
+/// Each village counts at its latest snapshot until it goes bankrupt or its
+/// last worker dies. Wealth is money plus food and wood valued at their last
+/// clearing prices; a resource that has never cleared adds nothing.
+pub fn inequality_timeseries(events: &[Event]) -> Vec<(usize, f64, f64)> {
+    let mut holdings: HashMap<&str, Holdings> = HashMap::new();
+    let mut prices = LastPrices::default();
+    let mut series = Vec::new();
+    let mut pending_tick = None;
+
+    let row = |tick: usize, holdings: &HashMap<&str, Holdings>, prices: &LastPrices| {
+        let money: Vec<f64> = holdings
+            .values()
+            .map(|h| h.money.to_f64().unwrap_or(0.0))
+            .collect();
+        let wealth: Vec<f64> = holdings
+            .values()
+            .map(|h| prices.wealth(h).to_f64().unwrap_or(0.0))
+            .collect();
+        (
+            tick,
+            MetricsCalculator::calculate_gini_coefficient(&money),
+            MetricsCalculator::calculate_gini_coefficient(&wealth),
+        )
+    };
+
+    for event in events {
+        if let Some(tick) = pending_tick
+            && event.tick != tick
+        {
+            series.push(row(tick, &holdings, &prices));
+            pending_tick = None;
+        }
+        prices.observe(event);
+        match &event.event_type {
+            // An empty village keeps logging snapshots but no longer takes part
+            EventType::VillageStateSnapshot { population: 0, .. }
+            | EventType::VillageBankrupt { .. }
+            | EventType::WorkerDied {
+                total_population: 0,
+                ..
+            } => {
+                holdings.remove(event.village_id.as_str());
+                pending_tick = Some(event.tick);
+            }
+            EventType::VillageStateSnapshot {
+                food, wood, money, ..
+            } => {
+                holdings.insert(
+                    &event.village_id,
+                    Holdings {
+                        money: *money,
+                        food: *food,
+                        wood: *wood,
+                    },
+                );
+                pending_tick = Some(event.tick);
+            }
+            _ => {}
+        }
+    }
+    if let Some(tick) = pending_tick {
+        series.push(row(tick, &holdings, &prices));
+    }
+    series
+}
+
+/// Formats an inequality time series as CSV.
+pub fn format_inequality_csv(series: &[(usize, f64, f64)]) -> String {
+    let mut csv = String::from("tick,gini_money,gini_wealth\n");
+    for (tick, gini_money, gini_wealth) in series {
+        csv.push_str(&format!("{},{:.4},{:.4}\n", tick, gini_money, gini_wealth));
+    }
+    csv
+}
+
+/// Compare each village's average buy and sell prices per resource.
+///
+/// A village that resells a resource well above what it paid, on a sizeable
+/// share of that resource's volume, is profiting from other villages rather
+/// than from production and is flagged as an arbitrageur.
+pub fn detect_arbitrage(events: &[Event]) -> ArbitrageReport {
+    // (bought, spent, sold, earned) per village and resource
+    let mut totals: HashMap<(&str, ResourceType), (Decimal, Decimal, Decimal, Decimal)> =
+        HashMap::new();
+    let mut market_volume: HashMap<ResourceType, Decimal> = HashMap::new();
+    for event in events {
+        if let EventType::TradeExecuted {
+            resource,
+            quantity,
+            price,
+            side,
+            ..
+        } = &event.event_type
+        {
+            let entry = totals.entry((&event.village_id, *resource)).or_default();
+            match side {
+                TradeSide::Buy => {
+                    entry.0 += quantity;
+                    entry.1 += quantity * price;
+                    *market_volume.entry(*resource).or_default() += quantity;
+                }
+                TradeSide::Sell => {
+                    entry.2 += quantity;
+                    entry.3 += quantity * price;
+                }
+            }
+        }
+    }
+
+    let mut report = ArbitrageReport::default();
+    for ((village_id, resource), (bought, spent, sold, earned)) in totals {
+        if bought <= Decimal::ZERO || sold <= Decimal::ZERO {
+            continue;
+        }
+        let round_trip_volume = bought.min(sold);
+        let volume = market_volume.get(&resource).copied().unwrap_or_default();
+        let volume_share = if volume > Decimal::ZERO {
+            (round_trip_volume / volume).to_f64().unwrap_or(0.0)
+        } else {
+            0.0
+        };
+        report.spreads.push(TradeSpread {
+            village_id: village_id.to_string(),
+            resource,
+            avg_buy_price: spent / bought,
+            avg_sell_price: earned / sold,
+            round_trip_volume,
+            volume_share,
+        });
+    }
+
+    report.spreads.sort_by(|a, b| {
+        (&a.village_id, a.resource.as_str()).cmp(&(&b.village_id, b.resource.as_str()))
+    });
+    report.flagged = report
+        .spreads
+        .iter()
+        .filter(|s| {
+            s.relative_spread() >= ARBITRAGE_MIN_SPREAD
+                && s.volume_share >= ARBITRAGE_MIN_VOLUME_SHARE
+        })
+        .cloned()
+        .collect();
+    report
+}
+
+/// Compare each village's fill rate (filled / ordered quantity) against the others.
+///
+/// Each ordered unit is treated as a trial that either fills or doesn't, and a
+/// two-proportion z-test compares a village with the pooled rest of the market.
+/// Villages beyond the 99% threshold are flagged, which catches systematic biases
+/// such as insertion-order tie-breaking always favouring the same village.
+pub fn audit_auction_fairness(events: &[Event]) -> FairnessReport {
+    let mut totals: HashMap<&str, (Decimal, Decimal)> = HashMap::new(); // (ordered, filled)
+    for event in events {
+        match &event.event_type {
+            EventType::OrderPlaced { quantity, .. } => {
+                totals.entry(&event.village_id).or_default().0 += quantity;
+            }
+            EventType::TradeExecuted { quantity, .. } => {
+                totals.entry(&event.village_id).or_default().1 += quantity;
+            }
+            _ => {}
+        }
+    }
+    totals.retain(|_, (ordered, _)| *ordered > Decimal::ZERO);
+
+    let all_ordered: f64 = totals
+        .values()
+        .map(|(o, _)| o.to_f64().unwrap_or(0.0))
+        .sum();
+    let all_filled: f64 = totals
+        .values()
+        .map(|(_, f)| f.to_f64().unwrap_or(0.0))
+        .sum();
+
+    let mut report = FairnessReport::default();
+    for (village_id, (ordered, filled)) in totals {
+        let n = ordered.to_f64().unwrap_or(0.0);
+        let x = filled.to_f64().unwrap_or(0.0);
+        let (rest_n, rest_x) = (all_ordered - n, all_filled - x);
+
+        let z_score = if rest_n > 0.0 {
+            let pooled = all_filled / all_ordered;
+            let std_err = (pooled * (1.0 - pooled) * (1.0 / n + 1.0 / rest_n)).sqrt();
+            if std_err > 0.0 {
+                (x / n - rest_x / rest_n) / std_err
+            } else {
+                0.0
+            }
+        } else {
+            0.0
+        };
+
+        if z_score.abs() > FAIRNESS_Z_THRESHOLD {
+            report.flagged.push(village_id.to_string());
+        }
+        report.villages.push(VillageFillRate {
+            village_id: village_id.to_string(),
+            ordered,
+            filled,
+            fill_rate: x / n,
+            z_score,
+        });
+    }
+
+    report
+        .villages
+        .sort_by(|a, b| a.village_id.cmp(&b.village_id));
+    report.flagged.sort();
+    report
+}
+
 /// Compare multiple simulation results.
 pub fn compare_simulations(analyses: &[SimulationAnalysis]) -> ComparisonReport {
     let mut report = ComparisonReport::default();
@@ -311,6 +659,47 @@ pub fn compare_simulations(analyses: &[SimulationAnalysis]) -> ComparisonReport
     report
 }
 
+/// Estimates the largest population a village can sustain on its own land.
+///
+/// Food is the binding need: with every food slot worked, the village feeds
+/// `max food output / 1 food per worker-day` workers. That is capped by
+/// housing, taking as many houses as the wood slots can keep in upkeep
+/// (0.1 wood per house per day), each sheltering 5 units of
+/// `shelter_per_worker`. Trade, upgrades and global modifiers are ignored.
+pub fn estimate_carrying_capacity(config: &VillageConfig, params: &SimulationParameters) -> usize {
+    if config.is_market_agent {
+        return 0;
+    }
+    let food = params.food_production_curve.max_output(
+        (config.food_slots.0 as u32, config.food_slots.1 as u32),
+        dec!(2.0),
+    ) * config.food_quality;
+    let wood = params.wood_production_curve.max_output(
+        (config.wood_slots.0 as u32, config.wood_slots.1 as u32),
+        dec!(0.1),
+    ) * config.wood_quality;
+
+    let houses = (wood / dec!(0.1)).floor();
+    let housed = if params.shelter_per_worker > Decimal::ZERO {
+        (houses * dec!(5) / params.shelter_per_worker).floor()
+    } else {
+        food
+    };
+    food.floor().min(housed).to_usize().unwrap_or(0)
+}
+
+/// Fills in each analysed village's carrying capacity from its configuration
+/// in `scenario`.
+pub fn set_carrying_capacities(analysis: &mut SimulationAnalysis, scenario: &Scenario) {
+    for village in &mut analysis.villages {
+        village.carrying_capacity = scenario
+            .villages
+            .iter()
+            .find(|config| config.id == village.id)
+            .map(|config| estimate_carrying_capacity(config, &scenario.parameters));
+    }
+}
+
 /// Generate a narrative explanation of simulation events.
 pub fn explain_simulation(analysis: &SimulationAnalysis) -> String {
     let mut explanation = String::new();
@@ -381,6 +770,41 @@ pub fn explain_simulation(analysis: &SimulationAnalysis) -> String {
             "{} {} over the simulation period, going from {} to {} workers.\n",
             village.id, fate, village.initial_population, village.final_population
         ));
+        match village.carrying_capacity {
+            Some(capacity) if village.initial_population > capacity => {
+                explanation.push_str(&format!(
+                    "{}'s carrying capacity is ~{} but it starts with {} workers.\n",
+                    village.id, capacity, village.initial_population
+                ));
+            }
+            Some(capacity) => explanation.push_str(&format!(
+                "{}'s carrying capacity is ~{} workers.\n",
+                village.id, capacity
+            )),
+            None => {}
+        }
+
+        // Recent trend
+        if let Some(trend) = village.population_trend
+            && village.final_population > 0
+        {
+            if trend.slope <= -TREND_EPSILON {
+                explanation.push_str(&format!(
+                    "{} is declining at ~{:.1} workers/tick",
+                    village.id, -trend.slope
+                ));
+                match trend.ticks_to_extinction {
+                    Some(ticks) => explanation
+                        .push_str(&format!(" and will reach zero in ~{:.0} ticks.\n", ticks)),
+                    None => explanation.push_str(".\n"),
+                }
+            } else if trend.slope >= TREND_EPSILON {
+                explanation.push_str(&format!(
+                    "{} is growing at ~{:.1} workers/tick.\n",
+                    village.id, trend.slope
+                ));
+            }
+        }
 
         // Deaths
         if !village.worker_deaths.is_empty() {
@@ -398,6 +822,23 @@ pub fn explain_simulation(analysis: &SimulationAnalysis) -> String {
             ));
         }
 
+        for spread in analysis
+            .arbitrage
+            .flagged
+            .iter()
+            .filter(|s| s.village_id == village.id)
+        {
+            explanation.push_str(&format!(
+                "It bought {} at {:.2} on average and resold it at {:.2}, \
+                 round-tripping {:.0} units ({:.0}% of the market).\n",
+                spread.resource.as_str(),
+                spread.avg_buy_price,
+                spread.avg_sell_price,
+                spread.round_trip_volume,
+                spread.volume_share * 100.0
+            ));
+        }
+
         // Trading
         if village.trading_summary.total_trades > 0 {
             match village.trading_summary.net_profit.cmp(&Decimal::ZERO) {
@@ -443,6 +884,7 @@ struct VillageData {
     trading: TradingSummary,
     deaths: HashMap<String, usize>,
     allocations: Vec<(u32, u32)>, // (food_workers, wood_workers)
+    final_holdings: Holdings,
 }
 
 #[derive(Default)]
@@ -533,6 +975,44 @@ fn calculate_price_statistics(market_data: &MarketData) -> PriceHistory {
     history
 }
 
+/// Slopes smaller than this (in workers/tick) are treated as a flat population.
+const TREND_EPSILON: f64 = 0.05;
+
+/// Fit a least-squares line to the last third of a population series.
+///
+/// Returns `None` when fewer than two snapshots fall in that window.
+fn population_trend(history: &[(usize, usize)]) -> Option<PopulationTrend> {
+    let window = &history[history.len() - history.len() / 3..];
+    let window = if window.len() < 2 { history } else { window };
+    if window.len() < 2 {
+        return None;
+    }
+
+    let n = window.len() as f64;
+    let mean_x = window.iter().map(|(t, _)| *t as f64).sum::<f64>() / n;
+    let mean_y = window.iter().map(|(_, p)| *p as f64).sum::<f64>() / n;
+    let covariance: f64 = window
+        .iter()
+        .map(|(t, p)| (*t as f64 - mean_x) * (*p as f64 - mean_y))
+        .sum();
+    let variance: f64 = window
+        .iter()
+        .map(|(t, _)| (*t as f64 - mean_x).powi(2))
+        .sum();
+    if variance == 0.0 {
+        return None;
+    }
+
+    let slope = covariance / variance;
+    let current = window[window.len() - 1].1 as f64;
+    let ticks_to_extinction = (slope < 0.0 && current > 0.0).then(|| current / -slope);
+
+    Some(PopulationTrend {
+        slope,
+        ticks_to_extinction,
+    })
+}
+
 fn calculate_volatility(prices: &[f64]) -> f64 {
     if prices.len() < 2 {
         return 0.0;
@@ -606,3 +1086,306 @@ fn extract_strategy_name(village_id: &str) -> Option<String> {
     // For now, just return the village ID
     Some(village_id.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{DeathCause, EventLogger};
+
+    #[test]
+    fn test_declining_population_trend_and_extinction_estimate() {
+        let mut logger = EventLogger::new();
+        for tick in 0..=60 {
+            // Steady at 30 for the first two thirds, then loses one worker every 2 ticks
+            let population = if tick <= 40 { 30 } else { 30 - (tick - 40) / 2 };
+            logger.log(
+                tick,
+                "village_0".to_string(),
+                EventType::VillageStateSnapshot {
+                    population,
+                    houses: 6,
+                    food: Decimal::ZERO,
+                    wood: Decimal::ZERO,
+                    money: Decimal::ZERO,
+                },
+            );
+        }
+
+        let analysis = analyze_events(logger.get_events()).unwrap();
+        let trend = analysis.villages[0].population_trend.unwrap();
+
+        assert!(trend.slope < -0.4 && trend.slope > -0.6);
+        let ticks = trend.ticks_to_extinction.unwrap();
+        assert!(ticks > 0.0 && ticks < 60.0);
+
+        let explanation = explain_simulation(&analysis);
+        assert!(explanation.contains("village_0 is declining at ~0.5 workers/tick"));
+        assert!(explanation.contains("will reach zero in ~"));
+    }
+
+    #[test]
+    fn test_inequality_timeseries_tracks_a_widening_gap() {
+        let mut logger = EventLogger::new();
+        for tick in 0..5 {
+            // village_a keeps getting richer while village_b stands still
+            for (village, money) in [("village_a", 100 + 50 * tick), ("village_b", 100)] {
+                logger.log(
+                    tick,
+                    village.to_string(),
+                    EventType::VillageStateSnapshot {
+                        population: 5,
+                        houses: 1,
+                        food: dec!(10),
+                        wood: dec!(10),
+                        money: Decimal::from(money),
+                    },
+                );
+            }
+            logger.log(
+                tick,
+                "market".to_string(),
+                EventType::AuctionCleared {
+                    wood_price: Some(dec!(2)),
+                    food_price: (tick == 0).then_some(dec!(1)),
+                    wood_volume: 1,
+                    food_volume: 0,
+                    total_participants: 2,
+                },
+            );
+        }
+
+        let series = inequality_timeseries(logger.get_events());
+        assert_eq!(series.len(), 5);
+        assert_eq!(series[0], (0, 0.0, 0.0));
+        assert!(series.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(series.windows(2).all(|w| w[0].1 < w[1].1));
+        assert!(series.windows(2).all(|w| w[0].2 < w[1].2));
+        // Equal goods dilute the money gap
+        assert!(series[4].2 < series[4].1);
+
+        let csv = format_inequality_csv(&series);
+        assert!(csv.starts_with("tick,gini_money,gini_wealth\n0,0.0000,0.0000\n"));
+    }
+
+    #[test]
+    fn test_inequality_timeseries_drops_bankrupt_and_dead_villages() {
+        let snapshot = |population: usize, money: Decimal| EventType::VillageStateSnapshot {
+            population,
+            houses: 1,
+            food: Decimal::ZERO,
+            wood: Decimal::ZERO,
+            money,
+        };
+        let mut logger = EventLogger::new();
+        for (village, money) in [
+            ("village_a", dec!(100)),
+            ("village_b", dec!(0)),
+            ("village_c", dec!(0)),
+        ] {
+            logger.log(0, village.to_string(), snapshot(1, money));
+        }
+        // village_b goes bankrupt; village_c's last worker dies but it keeps
+        // logging snapshots
+        logger.log(
+            1,
+            "village_b".to_string(),
+            EventType::VillageBankrupt {
+                heir: "market".to_string(),
+                food: Decimal::ZERO,
+                wood: Decimal::ZERO,
+                money: Decimal::ZERO,
+                workers: 1,
+            },
+        );
+        logger.log(
+            1,
+            "village_c".to_string(),
+            EventType::WorkerDied {
+                worker_id: 0,
+                cause: DeathCause::Starvation,
+                total_population: 0,
+            },
+        );
+        logger.log(1, "village_a".to_string(), snapshot(1, dec!(100)));
+        logger.log(1, "village_c".to_string(), snapshot(0, dec!(0)));
+
+        let series = inequality_timeseries(logger.get_events());
+        assert_eq!(series.len(), 2);
+        assert!(series[0].1 > 0.0);
+        // Only village_a is left, so there is no gap
+        assert_eq!(series[1], (1, 0.0, 0.0));
+    }
+
+    fn village_config(food_slots: (usize, usize), wood_slots: (usize, usize)) -> VillageConfig {
+        let mut config = crate::scenario::create_standard_scenarios()["basic"].villages[0].clone();
+        config.food_slots = food_slots;
+        config.wood_slots = wood_slots;
+        config
+    }
+
+    #[test]
+    fn test_carrying_capacity_from_slots_and_quality() {
+        let params = SimulationParameters::default();
+
+        // 4 full + 2 half food slots feed (4 + 1) * 2 = 10; 20 houses of wood upkeep
+        let mut config = village_config((4, 2), (20, 0));
+        assert_eq!(estimate_carrying_capacity(&config, &params), 10);
+
+        config.food_quality = dec!(1.5);
+        assert_eq!(estimate_carrying_capacity(&config, &params), 15);
+
+        // Wood for 2 houses shelters 10 workers, however much food there is
+        config.wood_slots = (2, 0);
+        assert_eq!(estimate_carrying_capacity(&config, &params), 10);
+        config.wood_quality = dec!(0.5);
+        assert_eq!(estimate_carrying_capacity(&config, &params), 5);
+
+        config.is_market_agent = true;
+        assert_eq!(estimate_carrying_capacity(&config, &params), 0);
+    }
+
+    #[test]
+    fn test_explanation_flags_villages_above_carrying_capacity() {
+        let mut logger = EventLogger::new();
+        for tick in 0..3 {
+            logger.log(
+                tick,
+                "village_0".to_string(),
+                EventType::VillageStateSnapshot {
+                    population: 15,
+                    houses: 3,
+                    food: Decimal::ZERO,
+                    wood: Decimal::ZERO,
+                    money: Decimal::ZERO,
+                },
+            );
+        }
+        let mut analysis = analyze_events(logger.get_events()).unwrap();
+        let mut scenario = crate::scenario::create_standard_scenarios()["basic"].clone();
+        scenario.villages = vec![village_config((3, 2), (20, 0))];
+        scenario.villages[0].id = "village_0".to_string();
+
+        set_carrying_capacities(&mut analysis, &scenario);
+        let explanation = explain_simulation(&analysis);
+        assert!(
+            explanation
+                .contains("village_0's carrying capacity is ~8 but it starts with 15 workers")
+        );
+    }
+
+    #[test]
+    fn test_fairness_audit_flags_one_sided_fills() {
+        let mut logger = EventLogger::new();
+        for tick in 0..20 {
+            for village in ["village_0", "village_1"] {
+                logger.log(
+                    tick,
+                    village.to_string(),
+                    EventType::OrderPlaced {
+                        resource: ResourceType::Wood,
+                        quantity: Decimal::from(5),
+                        price: Decimal::from(5),
+                        side: TradeSide::Buy,
+                        order_id: format!("{}_{}", village, tick),
+                    },
+                );
+            }
+            // Only village_0 ever gets filled
+            logger.log(
+                tick,
+                "village_0".to_string(),
+                EventType::TradeExecuted {
+                    resource: ResourceType::Wood,
+                    quantity: Decimal::from(5),
+                    price: Decimal::from(5),
+                    counterparty: "market".to_string(),
+                    side: TradeSide::Buy,
+                },
+            );
+        }
+
+        let report = audit_auction_fairness(logger.get_events());
+
+        assert_eq!(report.villages.len(), 2);
+        assert_eq!(report.villages[0].fill_rate, 1.0);
+        assert_eq!(report.villages[1].fill_rate, 0.0);
+        assert_eq!(report.flagged, vec!["village_0", "village_1"]);
+    }
+
+    #[test]
+    fn test_arbitrageur_buying_low_and_selling_high_is_flagged() {
+        let mut logger = EventLogger::new();
+        let mut trade = |tick, village: &str, price, side| {
+            logger.log(
+                tick,
+                village.to_string(),
+                EventType::TradeExecuted {
+                    resource: ResourceType::Wood,
+                    quantity: Decimal::from(5),
+                    price: Decimal::from(price),
+                    counterparty: "market".to_string(),
+                    side,
+                },
+            );
+        };
+        for tick in 0..20 {
+            if tick % 2 == 0 {
+                trade(tick, "trader", 5, TradeSide::Buy);
+                trade(tick, "producer", 5, TradeSide::Sell);
+            } else {
+                trade(tick, "trader", 10, TradeSide::Sell);
+                trade(tick, "consumer", 10, TradeSide::Buy);
+            }
+        }
+
+        let analysis = analyze_events(logger.get_events()).unwrap();
+        let flagged = &analysis.arbitrage.flagged;
+
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].village_id, "trader");
+        assert_eq!(flagged[0].avg_buy_price, Decimal::from(5));
+        assert_eq!(flagged[0].avg_sell_price, Decimal::from(10));
+        assert_eq!(flagged[0].round_trip_volume, Decimal::from(50));
+        assert!(
+            explain_simulation(&analysis)
+                .contains("It bought wood at 5.00 on average and resold it at 10.00")
+        );
+    }
+
+    #[test]
+    fn test_fairness_audit_accepts_equal_fills() {
+        let mut logger = EventLogger::new();
+        for tick in 0..20 {
+            for village in ["village_0", "village_1"] {
+                logger.log(
+                    tick,
+                    village.to_string(),
+                    EventType::OrderPlaced {
+                        resource: ResourceType::Food,
+                        quantity: Decimal::from(4),
+                        price: Decimal::ONE,
+                        side: TradeSide::Sell,
+                        order_id: format!("{}_{}", village, tick),
+                    },
+                );
+                logger.log(
+                    tick,
+                    village.to_string(),
+                    EventType::TradeExecuted {
+                        resource: ResourceType::Food,
+                        quantity: Decimal::from(2),
+                        price: Decimal::ONE,
+                        counterparty: "market".to_string(),
+                        side: TradeSide::Sell,
+                    },
+                );
+            }
+        }
+
+        assert!(
+            audit_auction_fairness(logger.get_events())
+                .flagged
+                .is_empty()
+        );
+    }
+}
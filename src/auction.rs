@@ -13,6 +13,14 @@
 //! - **Multi-resource clearing**: Simultaneously clears multiple resource markets (e.g., wood and food)
 //! - **Budget enforcement**: Prevents participants from overspending through iterative order pruning
 //! - **Price discovery**: Finds equilibrium prices that maximize trading volume
+//! - **All-or-nothing orders**: Orders flagged `all_or_nothing` fill completely or not at all
+//! - **Fill-or-kill orders**: Orders with `TimeInForce::FillOrKill` fill completely or are
+//!   cancelled, reported in `AuctionSuccess::killed_orders`, and never rest
+//! - **Iceberg orders**: Orders with a `visible_quantity` show only that much to each clearing
+//! - **Price controls**: Optional per-resource ceilings and floors; a binding control clears at
+//!   the bound and rations the long side pro rata
+//! - **Tracing**: With `AuctionConfig::trace`, each clearing and pruning step is recorded
+//!   in `AuctionSuccess::trace`
 //! - **Decimal precision**: Uses rust_decimal for exact financial calculations without floating-point errors
 //!
 //! ## Algorithm
@@ -28,9 +36,12 @@
 //! pruning algorithm ensures feasibility while maintaining fairness - orders are reduced proportionally
 //! based on how much a participant is over-budget, preserving their relative preferences.
 
+use crate::core::derive_seed;
+pub use crate::events::PriceControlKind;
+use crate::types::TimeInForce;
 use rust_decimal::prelude::*; // Includes Decimal, Zero, One, FromPrimitive, ToPrimitive
 use rust_decimal_macros::dec; // For the dec! macro
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fmt;
 
@@ -63,6 +74,27 @@ pub struct Order {
     pub effective_quantity: u64, // Quantity used in matching, potentially reduced by pruning
     pub limit_price: Decimal,    // <-- Use Decimal for price
     pub timestamp: u64,
+    pub all_or_nothing: bool, // Fill the full original quantity or nothing
+    pub time_in_force: TimeInForce,
+    /// Iceberg orders show only this much of their quantity to each clearing;
+    /// the hidden rest is released by `replenish_icebergs` once it fills
+    pub visible_quantity: Option<u64>,
+}
+
+impl Order {
+    /// Quantity this order offers to matching: its visible portion for an
+    /// iceberg order, otherwise its whole effective quantity
+    pub fn matchable_quantity(&self) -> u64 {
+        self.visible_quantity
+            .map_or(self.effective_quantity, |visible| {
+                visible.min(self.effective_quantity)
+            })
+    }
+
+    /// Whether the order must fill its whole original quantity or not at all
+    fn fills_whole_or_nothing(&self) -> bool {
+        self.all_or_nothing || self.time_in_force == TimeInForce::FillOrKill
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -84,6 +116,100 @@ pub struct ResourceClearing {
     pub clearing_price: Decimal, // <-- Use Decimal
     pub matched_volume: u64,
     pub tentative_fills: Vec<TentativeFill>,
+    pub excess_demand: u64, // Bid quantity at or above the clearing price left unfilled
+    pub excess_supply: u64, // Ask quantity at or below the clearing price left unsold
+    pub price_control: Option<PriceControlBinding>, // Set when a price control held the price
+    pub choice: PriceChoice, // Why this price won
+    pub levels: Vec<PriceLevel>, // Candidate prices considered, only filled in when tracing
+}
+
+/// Why a resource cleared at the price it did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceChoice {
+    /// The only candidate price with the most volume
+    MaxVolume,
+    /// Several prices tied on volume; the one closest to the last price won
+    ClosestToLastPrice,
+    /// Several prices tied on volume and there was no last price; the highest won
+    HighestPrice,
+    /// Reached by tâtonnement rather than by comparing candidate prices
+    Tatonnement,
+    /// A one-sided market filled against `AuctionConfig::oracle_prices`
+    Oracle,
+}
+
+/// Demand and supply at one candidate clearing price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceLevel {
+    pub price: Decimal,
+    pub demand: u64,
+    pub supply: u64,
+}
+
+/// A budget-pruned order's matchable quantity before and after pruning.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderReduction {
+    pub order_id: OrderId,
+    pub from: u64,
+    pub to: u64,
+}
+
+/// One step of an auction, recorded when `AuctionConfig::trace` is set.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuctionTraceStep {
+    /// A resource cleared in an iteration
+    Cleared {
+        iteration: u32,
+        resource: ResourceId,
+        price: Decimal,
+        volume: u64,
+        choice: PriceChoice,
+        /// Every candidate price, highest first
+        levels: Vec<PriceLevel>,
+    },
+    /// No price let anything trade for a resource in an iteration
+    NoClearing {
+        iteration: u32,
+        resource: ResourceId,
+    },
+    /// A participant over budget had its buy orders cut
+    Pruned {
+        iteration: u32,
+        participant: ParticipantId,
+        shortfall: Decimal,
+        reductions: Vec<OrderReduction>,
+    },
+}
+
+/// A price control that moved a resource's clearing price to its bound.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceControlBinding {
+    pub kind: PriceControlKind,
+    /// Price the resource would have cleared at without the control
+    pub unconstrained_price: Decimal,
+}
+
+/// A bid whose limit was clamped by `AuctionConfig::max_price_move`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceLimitHit {
+    pub order_id: OrderId,
+    pub participant_id: ParticipantId,
+    pub resource_id: ResourceId,
+    /// Limit price the bid was submitted with
+    pub limit_price: Decimal,
+    /// Limit price the bid was matched with
+    pub clamped_price: Decimal,
+}
+
+/// One currency unit moved onto a fill's settlement to carry the remainder
+/// left by `AuctionConfig::settlement_rounding`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundingAdjustment {
+    pub order_id: OrderId,
+    pub participant_id: ParticipantId,
+    pub resource_id: ResourceId,
+    /// Added to the fill's settled value; negative when taken off
+    pub amount: Decimal,
 }
 
 // Result of net outflow calculations
@@ -103,6 +229,8 @@ pub struct FinalFill {
     pub order_type: OrderType,
     pub filled_quantity: u64,
     pub price: Decimal, // <-- Use Decimal
+    /// Money exchanged for this fill: quantity × price, after any settlement rounding
+    pub value: Decimal,
 }
 
 #[derive(Debug, Clone)]
@@ -116,18 +244,160 @@ pub struct AuctionSuccess {
     pub final_fills: Vec<FinalFill>,
     pub final_balances: Vec<FinalBalance>,
     pub clearing_prices: HashMap<ResourceId, Decimal>, // <-- Use Decimal
+    pub excess_demand: HashMap<ResourceId, u64>,
+    pub excess_supply: HashMap<ResourceId, u64>,
+    /// Resources whose clearing price was held at a price control
+    pub price_controls: HashMap<ResourceId, PriceControlBinding>,
+    /// Clearing and pruning steps, empty unless `AuctionConfig::trace` is set
+    pub trace: Vec<AuctionTraceStep>,
+    /// Bids clamped by `AuctionConfig::max_price_move`
+    pub price_limits: Vec<PriceLimitHit>,
+    /// Rounding remainders carried by individual fills, empty unless
+    /// `AuctionConfig::settlement_rounding` is set
+    pub rounding_adjustments: Vec<RoundingAdjustment>,
+    /// Listing fees charged to each participant, already deducted from `final_balances`
+    pub listing_fees: HashMap<ParticipantId, Decimal>,
+    /// Gains from trade the fills realized, valued at each order's limit price
+    pub realized_surplus: Decimal,
+    /// Largest gains from trade the submitted orders allowed, ignoring budgets
+    pub max_surplus: Decimal,
+    /// Fill-or-kill orders cancelled because they couldn't fill completely
+    pub killed_orders: Vec<OrderId>,
+    /// Resources that cleared against `AuctionConfig::oracle_prices`. The
+    /// oracle is the counterparty to every one of their fills, so the money
+    /// and goods they move enter or leave the market.
+    pub oracle_resources: HashSet<ResourceId>,
+}
+
+/// Namespace for deriving per-order tie-break keys from `AuctionConfig::tie_break_seed`.
+const TIE_BREAK_NAMESPACE: &str = "auction_tie_break";
+
+/// How the clearing price for a single resource is found.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MatchingMode {
+    /// Test every unique limit price and pick the one that maximizes volume
+    #[default]
+    DiscretePrice,
+    /// Walrasian tâtonnement: start from the last price (or the bid/ask midpoint)
+    /// and move it by `step` toward whichever side is in excess, for at most
+    /// `iterations` adjustments or until demand equals supply
+    Tatonnement { step: Decimal, iterations: u32 },
+}
+
+/// How matched volume is shared among orders at the same limit price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AllocationRule {
+    /// Earlier orders at a price fill completely before later ones get anything
+    #[default]
+    PriceTime,
+    /// Better prices, and then higher participant priorities, still fill
+    /// first, but the volume left at the marginal price and priority is split
+    /// in proportion to order size
+    ProRata,
+}
+
+/// Tunable auction behaviour. The default reproduces `run_auction`.
+#[derive(Debug, Clone, Default)]
+pub struct AuctionConfig {
+    pub matching_mode: MatchingMode,
+    pub allocation_rule: AllocationRule,
+    /// Seed for ordering orders with equal price and timestamp during fill
+    /// allocation. `None` leaves their relative order unspecified.
+    pub tie_break_seed: Option<u64>,
+    /// Decimal places each fill's settled value is rounded to, modelling a
+    /// currency denomination. `None` settles at full precision.
+    pub settlement_rounding: Option<u32>,
+    /// Highest price each resource may clear at
+    pub price_ceilings: HashMap<ResourceId, Decimal>,
+    /// Lowest price each resource may clear at
+    pub price_floors: HashMap<ResourceId, Decimal>,
+    /// Most units of each resource that may change hands per auction, across
+    /// all participants. A capped volume is allocated by price-time priority.
+    pub max_market_volume: HashMap<ResourceId, u64>,
+    /// Highest multiple of a resource's last clearing price a bid may be
+    /// placed at; higher bids are clamped to it. Unlimited if `None`.
+    pub max_price_move: Option<Decimal>,
+    /// Exogenous reference prices. A resource with only bids or only asks
+    /// clears against its oracle price, held within any price controls:
+    /// asks at or below it and bids at or above it fill at that price, up to
+    /// the resource's `max_market_volume`.
+    pub oracle_prices: HashMap<ResourceId, Decimal>,
+    /// How far a participant's net outflow may exceed its currency and still
+    /// count as within budget. Zero prunes until every budget holds exactly;
+    /// a small tolerance skips pruning whole units over tiny overages, at the
+    /// cost of balances ending up to this much below zero.
+    pub budget_tolerance: Decimal,
+    /// Zero out bids from participants with no currency before the first
+    /// iteration instead of pruning them down one round at a time. Bids a
+    /// participant's own asks pay for (a barter) are kept.
+    pub skip_insolvent_bids: bool,
+    /// Flat fee per submitted order, filled or not. It comes out of the
+    /// participant's currency before any budget check, but never takes more
+    /// than the participant holds.
+    pub listing_fee: Decimal,
+    /// Instead of failing with `MaxIterationsReached`, keep clearing past the
+    /// iteration budget until every budget holds. A participant still over
+    /// budget has its bids scaled down to what its currency covers once, and
+    /// if that doesn't fit either, all its remaining bids are dropped.
+    pub degrade_on_max_iterations: bool,
+    /// Reputation of each participant. Among orders at the same limit price,
+    /// higher priority fills first; participants missing from the map count
+    /// as 0, and equal priorities fall back to timestamp order.
+    pub participant_priority: HashMap<ParticipantId, i64>,
+    /// Record each clearing and pruning step in `AuctionSuccess::trace`
+    pub trace: bool,
+}
+
+/// The price range a resource may clear within under `AuctionConfig`'s controls.
+#[derive(Debug, Clone, Copy, Default)]
+struct PriceBounds {
+    floor: Option<Decimal>,
+    ceiling: Option<Decimal>,
+}
+
+impl PriceBounds {
+    fn for_resource(config: &AuctionConfig, resource_id: &ResourceId) -> Self {
+        Self {
+            floor: config.price_floors.get(resource_id).copied(),
+            ceiling: config.price_ceilings.get(resource_id).copied(),
+        }
+    }
+
+    fn is_unbounded(&self) -> bool {
+        self.floor.is_none() && self.ceiling.is_none()
+    }
+
+    fn clamp(&self, price: Decimal) -> Decimal {
+        let price = self.ceiling.map_or(price, |ceiling| price.min(ceiling));
+        self.floor.map_or(price, |floor| price.max(floor))
+    }
+
+    /// The control an unconstrained clearing price would violate, if any
+    fn violated_by(&self, price: Decimal) -> Option<PriceControlKind> {
+        if self.ceiling.is_some_and(|ceiling| price > ceiling) {
+            Some(PriceControlKind::Ceiling)
+        } else if self.floor.is_some_and(|floor| price < floor) {
+            Some(PriceControlKind::Floor)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum AuctionError {
-    MaxIterationsReached,
+    /// Budgets still didn't hold after the last iteration. `trace` holds the
+    /// steps taken so far when `AuctionConfig::trace` is set
+    MaxIterationsReached {
+        trace: Vec<AuctionTraceStep>,
+    },
     InternalError(String),
 }
 
 impl fmt::Display for AuctionError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            AuctionError::MaxIterationsReached => write!(f, "Maximum iterations reached"),
+            AuctionError::MaxIterationsReached { .. } => write!(f, "Maximum iterations reached"),
             AuctionError::InternalError(msg) => write!(f, "Internal error: {}", msg),
         }
     }
@@ -272,18 +542,14 @@ fn apply_budget_pruning(
         let reduction_percentage = (*shortfall / total_cost).min(Decimal::ONE);
         let reduction_factor = Decimal::ONE - reduction_percentage; // Factor to multiply quantities by
 
-        // println!( // Debugging
-        //     "  Pruning Participant {:?}: Shortfall={}, Cost={}, Reduction%={:.2}",
-        //     participant_id, shortfall, total_cost, reduction_percentage * dec!(100.0)
-        // );
-
         // Use the collected buy fill info
         if let Some(buy_fills) = tentative_buy_fills_info.get(participant_id) {
             for (order_id, _filled_qty, _price) in buy_fills {
                 // Find the mutable order in current_orders vec AND the map
                 if let Some(order_to_prune) = current_orders.iter_mut().find(|o| o.id == *order_id)
                 {
-                    let original_effective = order_to_prune.effective_quantity;
+                    // Only an iceberg's visible portion is matched, so that is what gets pruned
+                    let original_effective = order_to_prune.matchable_quantity();
                     if original_effective == 0 {
                         continue;
                     } // Already fully pruned
@@ -307,17 +573,26 @@ fn apply_budget_pruning(
                             ))
                         })?;
 
-                    // Apply the prune
-                    order_to_prune.effective_quantity = new_effective_qty_u64;
-
-                    // println!( // Debugging
-                    //          "    Pruning Order {:?}: Original Effective={}, New Effective={}",
-                    //          order_to_prune.id, original_effective, order_to_prune.effective_quantity);
+                    // Apply the prune. An all-or-nothing or fill-or-kill order can't
+                    // be partially kept, so any reduction withdraws it entirely. An
+                    // iceberg keeps its hidden reserve and shrinks its visible portion.
+                    if order_to_prune.fills_whole_or_nothing()
+                        && new_effective_qty_u64 < order_to_prune.original_quantity
+                    {
+                        order_to_prune.effective_quantity = 0;
+                    } else if order_to_prune.visible_quantity.is_some() {
+                        order_to_prune.effective_quantity -=
+                            original_effective - new_effective_qty_u64;
+                        order_to_prune.visible_quantity = Some(new_effective_qty_u64);
+                    } else {
+                        order_to_prune.effective_quantity = new_effective_qty_u64;
+                    }
 
                     // Also update the central map for consistency in the next loop
                     // This ensures find_clearing_for_resource sees the pruned quantity
                     if let Some(map_order) = order_map.get_mut(&order_to_prune.id) {
                         map_order.effective_quantity = order_to_prune.effective_quantity;
+                        map_order.visible_quantity = order_to_prune.visible_quantity;
                     } else {
                         // Should not happen if current_orders and order_map are in sync
                         return Err(AuctionError::InternalError(format!(
@@ -340,15 +615,48 @@ fn create_final_results(
     net_outflows: HashMap<ParticipantId, Decimal>,
     current_participants: &mut HashMap<ParticipantId, Participant>,
     order_map: &HashMap<OrderId, Order>,
+    settlement_rounding: Option<u32>,
+    budget_tolerance: Decimal,
 ) -> Result<AuctionSuccess, AuctionError> {
     let mut final_fills = Vec::new();
+    let mut rounding_adjustments = Vec::new();
+    // What each participant may still spend, so rounding remainders stay in budget
+    let mut headroom: HashMap<ParticipantId, Decimal> = current_participants
+        .iter()
+        .map(|(id, p)| {
+            let outflow = net_outflows.get(id).copied().unwrap_or_default();
+            (id.clone(), p.currency + budget_tolerance - outflow)
+        })
+        .collect();
     let final_clearing_prices = iteration_clearings
         .iter()
         .map(|(rid, rc)| (rid.clone(), rc.clearing_price))
         .collect::<HashMap<_, _>>();
-
-    for (resource_id, clearing) in iteration_clearings {
+    let excess_demand = iteration_clearings
+        .iter()
+        .map(|(rid, rc)| (rid.clone(), rc.excess_demand))
+        .collect::<HashMap<_, _>>();
+    let excess_supply = iteration_clearings
+        .iter()
+        .map(|(rid, rc)| (rid.clone(), rc.excess_supply))
+        .collect::<HashMap<_, _>>();
+    let price_controls = iteration_clearings
+        .iter()
+        .filter_map(|(rid, rc)| rc.price_control.map(|binding| (rid.clone(), binding)))
+        .collect::<HashMap<_, _>>();
+    let oracle_resources = iteration_clearings
+        .iter()
+        .filter(|(_, rc)| rc.choice == PriceChoice::Oracle)
+        .map(|(rid, _)| rid.clone())
+        .collect::<HashSet<_>>();
+
+    // Walk resources in name order so fills don't follow HashMap order
+    let mut clearings: Vec<_> = iteration_clearings.into_iter().collect();
+    clearings.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+    for (resource_id, clearing) in clearings {
         let price = clearing.clearing_price;
+        let against_oracle = clearing.choice == PriceChoice::Oracle;
+        let first_fill = final_fills.len();
         for fill in clearing.tentative_fills {
             // Reuse order lookup logic
             let order = match order_map.get(&fill.order_id) {
@@ -367,15 +675,74 @@ fn create_final_results(
                 order_type: order.order_type,
                 filled_quantity: fill.filled_quantity,
                 price, // Already a Decimal
+                value: Decimal::from(fill.filled_quantity) * price,
             });
         }
+        if let Some(dp) = settlement_rounding {
+            let resource_fills = &mut final_fills[first_fill..];
+            let side_total = |fills: &[FinalFill], side| {
+                fills
+                    .iter()
+                    .filter(|f| f.order_type == side)
+                    .map(|f| f.value)
+                    .sum::<Decimal>()
+                    .round_dp(dp)
+            };
+            // Both sides settle the matched value, rounded once; against the
+            // oracle there is only one side, which settles its own total
+            let total = side_total(resource_fills, OrderType::Bid);
+            let settled = round_settlements(
+                resource_fills,
+                OrderType::Bid,
+                dp,
+                total,
+                &mut headroom,
+                &mut rounding_adjustments,
+            );
+            let ask_total = if against_oracle {
+                side_total(resource_fills, OrderType::Ask)
+            } else {
+                settled
+            };
+            round_settlements(
+                resource_fills,
+                OrderType::Ask,
+                dp,
+                ask_total,
+                &mut headroom,
+                &mut rounding_adjustments,
+            );
+        }
     }
 
+    // Rounded values no longer match the outflows computed at full precision
+    let net_outflows = match settlement_rounding {
+        None => net_outflows,
+        Some(_) => {
+            let mut rounded: HashMap<ParticipantId, Decimal> = HashMap::new();
+            for fill in &final_fills {
+                let outflow = rounded.entry(fill.participant_id.clone()).or_default();
+                match fill.order_type {
+                    OrderType::Bid => *outflow += fill.value,
+                    OrderType::Ask => *outflow -= fill.value,
+                }
+            }
+            rounded
+        }
+    };
+    // Rounding can push a buyer at the edge of its budget up by one unit,
+    // on top of whatever overage the pruning loop already accepted
+    let tolerance = budget_tolerance
+        + match settlement_rounding {
+            Some(dp) => Decimal::new(1, dp),
+            None => dec!(1e-9),
+        };
+
     // Update balances (using final net_outflows calculated previously)
     for (p_id, outflow) in net_outflows {
         if let Some(p) = current_participants.get_mut(&p_id) {
             // Check sufficient funds before final debit (should be guaranteed by loop logic, but belt-and-suspenders)
-            if outflow > Decimal::ZERO && outflow > p.currency + dec!(1e-9) {
+            if outflow > Decimal::ZERO && outflow > p.currency + tolerance {
                 // Allow tiny tolerance just in case
                 return Err(AuctionError::InternalError(format!(
                     "Participant {:?} unexpectedly short ({}) on final settlement (needs {})",
@@ -406,24 +773,161 @@ fn create_final_results(
         final_fills,
         final_balances,
         clearing_prices: final_clearing_prices,
+        excess_demand,
+        excess_supply,
+        price_controls,
+        trace: Vec::new(),
+        price_limits: Vec::new(),
+        rounding_adjustments,
+        listing_fees: HashMap::new(),
+        realized_surplus: Decimal::ZERO,
+        max_surplus: Decimal::ZERO,
+        killed_orders: Vec::new(),
+        oracle_resources,
     })
 }
 
+/// Clamps bids above `max_move` times their resource's last clearing price.
+///
+/// Resources that have not cleared before are left unlimited.
+fn clamp_bids(
+    orders: &mut [Order],
+    last_clearing_prices: &HashMap<ResourceId, Decimal>,
+    max_move: Decimal,
+) -> Vec<PriceLimitHit> {
+    let mut hits = Vec::new();
+    for order in orders.iter_mut() {
+        if order.order_type != OrderType::Bid {
+            continue;
+        }
+        let Some(last_price) = last_clearing_prices.get(&order.resource_id) else {
+            continue;
+        };
+        let limit = last_price * max_move;
+        if order.limit_price > limit {
+            hits.push(PriceLimitHit {
+                order_id: order.id,
+                participant_id: order.participant_id.clone(),
+                resource_id: order.resource_id.clone(),
+                limit_price: order.limit_price,
+                clamped_price: limit,
+            });
+            order.limit_price = limit;
+        }
+    }
+    hits
+}
+
+/// Deducts `fee` per order from each participant's currency, capped at what
+/// it holds, and returns the amount charged to each.
+fn charge_listing_fees(
+    orders: &[Order],
+    participants: &mut HashMap<ParticipantId, Participant>,
+    fee: Decimal,
+) -> HashMap<ParticipantId, Decimal> {
+    let mut charged = HashMap::new();
+    if fee <= Decimal::ZERO {
+        return charged;
+    }
+    for order in orders {
+        let Some(participant) = participants.get_mut(&order.participant_id) else {
+            continue;
+        };
+        let paid = fee.min(participant.currency.max(Decimal::ZERO));
+        participant.currency -= paid;
+        *charged
+            .entry(order.participant_id.clone())
+            .or_insert(Decimal::ZERO) += paid;
+    }
+    charged
+}
+
+/// Zeroes bids from participants with no currency, keeping as many of each
+/// one's bids, in timestamp order, as the value of its asks at their limit
+/// prices covers.
+fn drop_insolvent_bids(orders: &mut [Order], participants: &HashMap<ParticipantId, Participant>) {
+    let mut barter_budget: HashMap<ParticipantId, Decimal> = HashMap::new();
+    for order in orders.iter() {
+        if order.order_type == OrderType::Ask {
+            *barter_budget
+                .entry(order.participant_id.clone())
+                .or_default() += order.limit_price * Decimal::from(order.effective_quantity);
+        }
+    }
+
+    let mut bids: Vec<&mut Order> = orders
+        .iter_mut()
+        .filter(|order| order.order_type == OrderType::Bid)
+        .filter(|order| {
+            participants
+                .get(&order.participant_id)
+                .is_some_and(|p| p.currency <= Decimal::ZERO)
+        })
+        .collect();
+    bids.sort_by_key(|order| order.timestamp);
+    for bid in bids {
+        let budget = barter_budget.entry(bid.participant_id.clone()).or_default();
+        let cost = bid.limit_price * Decimal::from(bid.effective_quantity);
+        if cost <= *budget {
+            *budget -= cost;
+        } else {
+            bid.effective_quantity = 0;
+        }
+    }
+}
+
 pub fn run_auction(
     orders: Vec<Order>,
     participants: HashMap<ParticipantId, Participant>,
     max_iterations: u32,
     last_clearing_prices: HashMap<ResourceId, Decimal>, // <-- Use Decimal
+) -> Result<AuctionSuccess, AuctionError> {
+    run_auction_with_config(
+        orders,
+        participants,
+        max_iterations,
+        last_clearing_prices,
+        &AuctionConfig::default(),
+    )
+}
+
+/// Same as [`run_auction`], with behaviour controlled by `config`.
+pub fn run_auction_with_config(
+    orders: Vec<Order>,
+    participants: HashMap<ParticipantId, Participant>,
+    max_iterations: u32,
+    last_clearing_prices: HashMap<ResourceId, Decimal>,
+    config: &AuctionConfig,
 ) -> Result<AuctionSuccess, AuctionError> {
     let mut current_orders = orders.clone(); // Orders whose effective_quantity might be pruned
+    let price_limits = match config.max_price_move {
+        Some(max_move) => clamp_bids(&mut current_orders, &last_clearing_prices, max_move),
+        None => Vec::new(),
+    };
     let mut current_participants = participants.clone();
+    let listing_fees = charge_listing_fees(
+        &current_orders,
+        &mut current_participants,
+        config.listing_fee,
+    );
+    if config.skip_insolvent_bids {
+        drop_insolvent_bids(&mut current_orders, &current_participants);
+    }
     // Build order_map once for efficient lookup
     let mut order_map: HashMap<OrderId, Order> =
         current_orders.iter().cloned().map(|o| (o.id, o)).collect();
 
-    for _iteration in 0..max_iterations {
-        // println!("--- Iteration {} ---", iteration + 1); // Keep for debugging if needed
+    let mut trace = Vec::new();
+    // Participants whose bids degrade mode has already scaled down
+    let mut degraded: HashSet<ParticipantId> = HashSet::new();
 
+    let mut iteration = 0;
+    loop {
+        // Past the iteration budget, degrade mode keeps going by shrinking bids
+        let degrading = iteration >= max_iterations;
+        if degrading && !config.degrade_on_max_iterations {
+            return Err(AuctionError::MaxIterationsReached { trace });
+        }
         let mut iteration_clearings: HashMap<ResourceId, ResourceClearing> = HashMap::new();
 
         // 1. Group orders by resource (using current effective quantities)
@@ -437,19 +941,28 @@ pub fn run_auction(
                 &orders_for_resource,
                 last_clearing_prices.get(&resource_id).copied(),
                 &order_map,
+                config,
             ) {
                 Ok(Some(clearing)) => {
-                    // println!( // Keep for debugging if needed
-                    //     "  Resource {:?}: Price={}, Volume={}",
-                    //     resource_id, clearing.clearing_price, clearing.matched_volume
-                    // );
-                    // for fill in &clearing.tentative_fills {
-                    //     println!("    Fill: Order {:?}, Qty {}", fill.order_id, fill.filled_quantity);
-                    // }
+                    if config.trace {
+                        trace.push(AuctionTraceStep::Cleared {
+                            iteration,
+                            resource: resource_id.clone(),
+                            price: clearing.clearing_price,
+                            volume: clearing.matched_volume,
+                            choice: clearing.choice,
+                            levels: clearing.levels.clone(),
+                        });
+                    }
                     iteration_clearings.insert(resource_id.clone(), clearing);
                 }
                 Ok(None) => {
-                    // println!("  Resource {:?}: No clearing possible", resource_id); // Debugging
+                    if config.trace {
+                        trace.push(AuctionTraceStep::NoClearing {
+                            iteration,
+                            resource: resource_id,
+                        });
+                    }
                 }
                 Err(e) => return Err(AuctionError::InternalError(e)),
             }
@@ -473,109 +986,409 @@ pub fn run_auction(
                 .get(&participant_id)
                 .copied()
                 .unwrap_or(Decimal::ZERO);
-            // println!("  Participant {:?}: Outflow={}, Currency={}", participant_id, outflow, participant.currency); // Debugging
-            if outflow > participant.currency {
+            // Only spending can overrun a budget; a participant already below
+            // zero that buys nothing is left alone
+            if outflow > Decimal::ZERO && outflow > participant.currency + config.budget_tolerance {
                 let shortfall = outflow - participant.currency;
-                // println!("    SHORT! Shortfall={}", shortfall); // Debugging
                 short_participants_info.push((participant_id.clone(), shortfall));
             }
         }
 
         if short_participants_info.is_empty() {
-            // println!("--- Convergence Reached ---"); // Debugging
             // Converged! Prepare Success result
             return create_final_results(
                 iteration_clearings,
                 net_outflows,
                 &mut current_participants,
                 &order_map,
-            );
+                config.settlement_rounding,
+                config.budget_tolerance,
+            )
+            .map(|success| AuctionSuccess {
+                trace,
+                price_limits,
+                listing_fees,
+                realized_surplus: realized_surplus(&orders, &success.final_fills),
+                max_surplus: max_surplus(&orders),
+                killed_orders: killed_orders(&orders, &success.final_fills),
+                ..success
+            });
         }
 
+        let quantities_before: HashMap<OrderId, u64> = if config.trace {
+            current_orders
+                .iter()
+                .map(|o| (o.id, o.matchable_quantity()))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
         // --- Pruning Logic ---
         // This is the key innovation: proportionally reduce buy orders to fit budget
         // Reduction percentage = shortfall / total_cost_of_buys
         // This maintains relative preferences while ensuring feasibility
-        apply_budget_pruning(
-            &short_participants_info,
-            &costs,
-            &tentative_buy_fills_info,
-            &mut current_orders,
-            &mut order_map,
-        )?;
+        if !degrading {
+            apply_budget_pruning(
+                &short_participants_info,
+                &costs,
+                &tentative_buy_fills_info,
+                &mut current_orders,
+                &mut order_map,
+            )?;
+        } else {
+            // Scale each participant's bids once, and drop them if still short
+            let (to_drop, to_scale): (Vec<_>, Vec<_>) = short_participants_info
+                .iter()
+                .cloned()
+                .partition(|(id, _)| degraded.contains(id));
+            let dropped = drop_bids(&to_drop, &mut current_orders, &mut order_map);
+            if to_scale.is_empty() && !dropped {
+                // Nothing left to drop, so the budgets can never hold
+                return Err(AuctionError::MaxIterationsReached { trace });
+            }
+            degraded.extend(to_scale.iter().map(|(id, _)| id.clone()));
+            apply_budget_pruning(
+                &to_scale,
+                &costs,
+                &tentative_buy_fills_info,
+                &mut current_orders,
+                &mut order_map,
+            )?;
+        }
+
+        if config.trace {
+            for (participant_id, shortfall) in short_participants_info {
+                let reductions = current_orders
+                    .iter()
+                    .filter(|o| o.participant_id == participant_id)
+                    .filter_map(|o| {
+                        let from = quantities_before[&o.id];
+                        let to = o.matchable_quantity();
+                        (to != from).then_some(OrderReduction {
+                            order_id: o.id,
+                            from,
+                            to,
+                        })
+                    })
+                    .collect();
+                trace.push(AuctionTraceStep::Pruned {
+                    iteration,
+                    participant: participant_id,
+                    shortfall,
+                    reductions,
+                });
+            }
+        }
+        iteration += 1;
     } // End of iteration loop
+}
+
+/// Zeroes every remaining bid of the short participants, returning whether
+/// any bid changed. Degrade mode's last resort once scaling has not fit a
+/// participant's budget.
+fn drop_bids(
+    short_participants: &[(ParticipantId, Decimal)],
+    orders: &mut [Order],
+    order_map: &mut HashMap<OrderId, Order>,
+) -> bool {
+    let mut dropped = false;
+    for order in orders.iter_mut().filter(|o| {
+        o.order_type == OrderType::Bid
+            && o.effective_quantity > 0
+            && short_participants
+                .iter()
+                .any(|(id, _)| *id == o.participant_id)
+    }) {
+        order.effective_quantity = 0;
+        if let Some(mapped) = order_map.get_mut(&order.id) {
+            mapped.effective_quantity = 0;
+        }
+        dropped = true;
+    }
+    dropped
+}
+
+/// Carries iceberg orders over to the next auction.
+///
+/// Each iceberg in `orders` (as submitted, before pruning) comes back with its
+/// filled quantity removed and its visible portion topped up from the hidden
+/// reserve. Fully filled icebergs, fill-or-kill orders, which never rest, and
+/// ordinary orders are dropped.
+pub fn replenish_icebergs(orders: &[Order], fills: &[FinalFill]) -> Vec<Order> {
+    orders
+        .iter()
+        .filter(|order| {
+            order.visible_quantity.is_some() && order.time_in_force != TimeInForce::FillOrKill
+        })
+        .filter_map(|order| {
+            let filled: u64 = fills
+                .iter()
+                .filter(|fill| fill.order_id == order.id)
+                .map(|fill| fill.filled_quantity)
+                .sum();
+            let remaining = order.original_quantity.saturating_sub(filled);
+            (remaining > 0).then(|| Order {
+                original_quantity: remaining,
+                effective_quantity: remaining,
+                ..order.clone()
+            })
+        })
+        .collect()
+}
+
+/// Fill-or-kill orders among `orders` that got no fill. Clearing drops any
+/// that would fill only partly, so these are the cancelled ones.
+fn killed_orders(orders: &[Order], fills: &[FinalFill]) -> Vec<OrderId> {
+    orders
+        .iter()
+        .filter(|order| order.time_in_force == TimeInForce::FillOrKill)
+        .filter(|order| fills.iter().all(|fill| fill.order_id != order.id))
+        .map(|order| order.id)
+        .collect()
+}
+
+/// Buyer plus seller surplus of `fills`: what each bid was willing to pay
+/// above the price and each ask received above its limit.
+///
+/// Limits are taken from `orders` as submitted, so a bid clamped by
+/// `max_price_move` still counts its full willingness to pay.
+fn realized_surplus(orders: &[Order], fills: &[FinalFill]) -> Decimal {
+    let limits: HashMap<OrderId, Decimal> = orders.iter().map(|o| (o.id, o.limit_price)).collect();
+    fills
+        .iter()
+        .filter_map(|fill| {
+            let limit = limits.get(&fill.order_id)?;
+            let quantity = Decimal::from(fill.filled_quantity);
+            Some(match fill.order_type {
+                OrderType::Bid => (limit - fill.price) * quantity,
+                OrderType::Ask => (fill.price - limit) * quantity,
+            })
+        })
+        .sum()
+}
 
-    // println!("--- Max Iterations Reached ---"); // Debugging
-    // If loop finishes, max iterations were reached before convergence
-    Err(AuctionError::MaxIterationsReached)
-} // Result used here
+/// Surplus of the efficient allocation: for each resource, the highest bids
+/// matched against the lowest asks for as long as they cross, with no
+/// budgets in the way.
+fn max_surplus(orders: &[Order]) -> Decimal {
+    let mut total = Decimal::ZERO;
+    for resource_orders in group_orders_by_resource(orders).values() {
+        let mut bids: Vec<(Decimal, u64)> = resource_orders
+            .iter()
+            .filter(|o| o.order_type == OrderType::Bid)
+            .map(|o| (o.limit_price, o.original_quantity))
+            .collect();
+        let mut asks: Vec<(Decimal, u64)> = resource_orders
+            .iter()
+            .filter(|o| o.order_type == OrderType::Ask)
+            .map(|o| (o.limit_price, o.original_quantity))
+            .collect();
+        bids.sort_by_key(|b| std::cmp::Reverse(b.0));
+        asks.sort_by_key(|a| a.0);
+
+        let (mut bid_idx, mut ask_idx) = (0, 0);
+        while bid_idx < bids.len() && ask_idx < asks.len() && bids[bid_idx].0 >= asks[ask_idx].0 {
+            let quantity = bids[bid_idx].1.min(asks[ask_idx].1);
+            total += (bids[bid_idx].0 - asks[ask_idx].0) * Decimal::from(quantity);
+            bids[bid_idx].1 -= quantity;
+            asks[ask_idx].1 -= quantity;
+            if bids[bid_idx].1 == 0 {
+                bid_idx += 1;
+            }
+            if asks[ask_idx].1 == 0 {
+                ask_idx += 1;
+            }
+        }
+    }
+    total
+}
+
+/// Rounds one side's fills to `dp` places so that together they settle `total`.
+///
+/// Each fill is rounded on its own first. The remainder that leaves against
+/// `total` is then spread one currency unit at a time, first to the fills
+/// rounding moved furthest the other way (lowest order ID on ties), and never
+/// so that a participant spends more than its `headroom`. Returns the total
+/// actually settled, short of `total` only when budgets leave no room.
+fn round_settlements(
+    fills: &mut [FinalFill],
+    side: OrderType,
+    dp: u32,
+    total: Decimal,
+    headroom: &mut HashMap<ParticipantId, Decimal>,
+    adjustments: &mut Vec<RoundingAdjustment>,
+) -> Decimal {
+    let mut side_fills: Vec<&mut FinalFill> =
+        fills.iter_mut().filter(|f| f.order_type == side).collect();
+    // How much more a participant spends when one of its fills' value changes
+    let spending = |change: Decimal| match side {
+        OrderType::Bid => change,
+        OrderType::Ask => -change,
+    };
+
+    let exact: Vec<Decimal> = side_fills.iter().map(|f| f.value).collect();
+    for fill in side_fills.iter_mut() {
+        let settled = fill.value.round_dp(dp);
+        *headroom.entry(fill.participant_id.clone()).or_default() -= spending(settled - fill.value);
+        if settled != fill.value {
+            log::debug!(
+                "Settlement of order {:?} rounded from {} to {}",
+                fill.order_id,
+                fill.value,
+                settled
+            );
+        }
+        fill.value = settled;
+    }
+
+    let mut remainder = total - side_fills.iter().map(|f| f.value).sum::<Decimal>();
+    let unit = Decimal::new(1, dp);
+    let step = if remainder > Decimal::ZERO {
+        unit
+    } else {
+        -unit
+    };
+    let mut order: Vec<usize> = (0..side_fills.len()).collect();
+    order.sort_by(|&a, &b| {
+        let pull = |i: usize| (exact[i] - side_fills[i].value) * step;
+        pull(b)
+            .cmp(&pull(a))
+            .then_with(|| side_fills[a].order_id.0.cmp(&side_fills[b].order_id.0))
+    });
+    while !remainder.is_zero() {
+        let mut placed = false;
+        for &i in &order {
+            if remainder.is_zero() {
+                break;
+            }
+            let fill = &mut side_fills[i];
+            let room = headroom.entry(fill.participant_id.clone()).or_default();
+            if *room < spending(step) {
+                continue;
+            }
+            *room -= spending(step);
+            fill.value += step;
+            remainder -= step;
+            placed = true;
+            adjustments.push(RoundingAdjustment {
+                order_id: fill.order_id,
+                participant_id: fill.participant_id.clone(),
+                resource_id: fill.resource_id.clone(),
+                amount: step,
+            });
+        }
+        if !placed {
+            log::warn!(
+                "No budget left to settle a rounding remainder of {} on {:?} fills",
+                remainder,
+                side
+            );
+            break;
+        }
+    }
+    total - remainder
+}
 
-/// Collects eligible orders and sorts them by price and timestamp priority.
+/// Collects eligible orders and sorts them by price, participant priority and
+/// timestamp. With a `tie_break_seed`, remaining ties are broken by a seeded hash of the
+/// order ID, so no participant is systematically favoured by submission order.
 /// Returns (sorted_bids, sorted_asks)
-fn collect_eligible_orders<'a>(orders: &[&'a Order]) -> (Vec<&'a Order>, Vec<&'a Order>) {
+fn collect_eligible_orders<'a>(
+    orders: &[&'a Order],
+    config: &AuctionConfig,
+) -> (Vec<&'a Order>, Vec<&'a Order>) {
     // Filter and collect bids/asks
     let bids: Vec<&Order> = orders
         .iter()
-        .filter(|o| o.order_type == OrderType::Bid && o.effective_quantity > 0)
+        .filter(|o| o.order_type == OrderType::Bid && o.matchable_quantity() > 0)
         .cloned()
         .collect();
     let mut asks: Vec<&Order> = orders
         .iter()
-        .filter(|o| o.order_type == OrderType::Ask && o.effective_quantity > 0)
+        .filter(|o| o.order_type == OrderType::Ask && o.matchable_quantity() > 0)
         .cloned()
         .collect();
 
-    // Sort bids: Descending price, Ascending timestamp
+    let priority = |order: &Order| participant_priority(config, order);
+    let tie_break = |order: &Order| {
+        config
+            .tie_break_seed
+            .map(|seed| derive_seed(seed, TIE_BREAK_NAMESPACE, order.id.0 as u64))
+    };
+
+    // Sort bids: Descending price, Descending priority, Ascending timestamp, then seeded tie-break
     let mut sorted_bids = bids;
     sorted_bids.sort_unstable_by(|a, b| {
         b.limit_price
             .cmp(&a.limit_price)
+            .then_with(|| priority(b).cmp(&priority(a)))
             .then_with(|| a.timestamp.cmp(&b.timestamp))
+            .then_with(|| tie_break(a).cmp(&tie_break(b)))
     });
 
-    // Sort asks: Ascending price, Ascending timestamp
+    // Sort asks: Ascending price, Descending priority, Ascending timestamp, then seeded tie-break
     asks.sort_unstable_by(|a, b| {
         a.limit_price
             .cmp(&b.limit_price)
+            .then_with(|| priority(b).cmp(&priority(a)))
             .then_with(|| a.timestamp.cmp(&b.timestamp))
+            .then_with(|| tie_break(a).cmp(&tie_break(b)))
     });
 
     (sorted_bids, asks)
 }
 
-/// Finds the best clearing price that maximizes trading volume.
-/// Returns Some((price, volume)) or None if no trades are possible.
-fn find_best_clearing(
-    sorted_bids: &[&Order],
-    asks: &[&Order],
-    last_price: Option<Decimal>,
-) -> Result<Option<(Decimal, u64)>, String> {
-    // We test every unique limit price from all orders as a potential clearing price
-    // This guarantees we find the optimal price (no need for binary search)
+/// Demand and supply at every candidate clearing price, highest price first.
+///
+/// We test every unique limit price from all orders as a potential clearing price.
+/// This guarantees we find the optimal price (no need for binary search).
+/// Prices outside the bounds are replaced by the bound they cross.
+fn price_levels(sorted_bids: &[&Order], asks: &[&Order], bounds: PriceBounds) -> Vec<PriceLevel> {
     let mut potential_prices: Vec<Decimal> = sorted_bids
         .iter()
         .map(|o| o.limit_price)
         .chain(asks.iter().map(|o| o.limit_price))
+        .map(|price| bounds.clamp(price))
         .collect();
     potential_prices.sort_unstable();
     potential_prices.dedup();
 
+    potential_prices
+        .into_iter()
+        .rev()
+        .map(|price| PriceLevel {
+            price,
+            demand: sorted_bids
+                .iter()
+                .filter(|o| o.limit_price >= price)
+                .map(|o| o.matchable_quantity())
+                .sum(),
+            supply: asks
+                .iter()
+                .filter(|o| o.limit_price <= price)
+                .map(|o| o.matchable_quantity())
+                .sum(),
+        })
+        .collect()
+}
+
+/// Finds the best clearing price within `bounds` that maximizes trading volume.
+/// Returns Some((price, volume, choice)) or None if no trades are possible.
+fn find_best_clearing(
+    sorted_bids: &[&Order],
+    asks: &[&Order],
+    last_price: Option<Decimal>,
+    bounds: PriceBounds,
+) -> Result<Option<(Decimal, u64, PriceChoice)>, String> {
+    let levels = price_levels(sorted_bids, asks, bounds);
+
     let mut max_volume = 0u64;
     let mut candidates = Vec::new(); // Store (price: Decimal, volume: u64) candidates
 
-    for current_price in potential_prices.iter().rev() {
-        // Calculate demand and supply at current_price
-        let demand = sorted_bids
-            .iter()
-            .filter(|o| o.limit_price >= *current_price)
-            .map(|o| o.effective_quantity)
-            .sum::<u64>();
-        let supply = asks
-            .iter()
-            .filter(|o| o.limit_price <= *current_price)
-            .map(|o| o.effective_quantity)
-            .sum::<u64>();
-        let volume = demand.min(supply);
+    for level in &levels {
+        let current_price = &level.price;
+        let volume = level.demand.min(level.supply);
 
         if volume > 0 {
             match volume.cmp(&max_volume) {
@@ -599,8 +1412,8 @@ fn find_best_clearing(
     // Tie Breaking
     // When multiple prices yield same max volume, we need consistent tie-breaking
     // Using last price improves stability; without it, we favor sellers (highest price)
-    let best_price = if candidates.len() == 1 {
-        *candidates[0].0
+    let (best_price, choice) = if candidates.len() == 1 {
+        (*candidates[0].0, PriceChoice::MaxVolume)
     } else if let Some(last_p) = last_price {
         // Sort by distance to last_p, then by price descending
         candidates.sort_unstable_by(|(p1, _), (p2, _)| {
@@ -609,23 +1422,133 @@ fn find_best_clearing(
                 .cmp(&(**p2 - last_p).abs())
                 .then_with(|| p2.cmp(p1)) // Secondary: highest price
         });
-        *candidates[0].0
+        (*candidates[0].0, PriceChoice::ClosestToLastPrice)
     } else {
         // No last price, choose highest price among max volume candidates
         candidates.sort_unstable_by(|(p1, _), (p2, _)| p2.cmp(p1));
-        *candidates[0].0
+        (*candidates[0].0, PriceChoice::HighestPrice)
+    };
+
+    Ok(Some((best_price, max_volume, choice)))
+}
+
+/// Approximates the equilibrium price by Walrasian tâtonnement.
+///
+/// Starting from `last_price` (or the midpoint of the best bid and best ask), the
+/// price rises by `step` while demand exceeds supply and falls by `step` while
+/// supply exceeds demand. A fixed step can oscillate around the equilibrium, so
+/// the result is the price after at most `iterations` adjustments. The price
+/// never leaves `bounds`.
+/// Returns Some((price, volume)) or None if nothing trades at that price.
+fn find_tatonnement_clearing(
+    sorted_bids: &[&Order],
+    asks: &[&Order],
+    last_price: Option<Decimal>,
+    step: Decimal,
+    iterations: u32,
+    bounds: PriceBounds,
+) -> Option<(Decimal, u64)> {
+    let best_bid = sorted_bids.first()?.limit_price;
+    let best_ask = asks.first()?.limit_price;
+    let demand_at = |price: Decimal| -> u64 {
+        sorted_bids
+            .iter()
+            .filter(|o| o.limit_price >= price)
+            .map(|o| o.matchable_quantity())
+            .sum()
+    };
+    let supply_at = |price: Decimal| -> u64 {
+        asks.iter()
+            .filter(|o| o.limit_price <= price)
+            .map(|o| o.matchable_quantity())
+            .sum()
     };
 
-    Ok(Some((best_price, max_volume)))
+    let mut price = bounds.clamp(last_price.unwrap_or((best_bid + best_ask) / dec!(2)));
+    for _ in 0..iterations {
+        let (demand, supply) = (demand_at(price), supply_at(price));
+        match demand.cmp(&supply) {
+            std::cmp::Ordering::Greater => price += step,
+            std::cmp::Ordering::Less => price = (price - step).max(step),
+            std::cmp::Ordering::Equal => break,
+        }
+        price = bounds.clamp(price);
+    }
+
+    let volume = demand_at(price).min(supply_at(price));
+    (volume > 0).then_some((price, volume))
 }
 
-/// Creates tentative fills for orders based on price-time priority.
+/// Splits `volume` across `orders` in proportion to their quantities. Units
+/// lost to rounding down go one each to orders in priority order.
+fn prorated_quantities(orders: &[&Order], volume: u64) -> Vec<u64> {
+    let total: u64 = orders.iter().map(|o| o.matchable_quantity()).sum();
+    if total <= volume {
+        return orders.iter().map(|o| o.matchable_quantity()).collect();
+    }
+    let mut shares: Vec<u64> = orders
+        .iter()
+        .map(|o| (o.matchable_quantity() as u128 * volume as u128 / total as u128) as u64)
+        .collect();
+    let mut remainder = volume - shares.iter().sum::<u64>();
+    for (share, order) in shares.iter_mut().zip(orders) {
+        if remainder == 0 {
+            break;
+        }
+        if *share < order.matchable_quantity() {
+            *share += 1;
+            remainder -= 1;
+        }
+    }
+    shares
+}
+
+/// The configured priority of `order`'s participant, 0 if it has none.
+fn participant_priority(config: &AuctionConfig, order: &Order) -> i64 {
+    config
+        .participant_priority
+        .get(&order.participant_id)
+        .copied()
+        .unwrap_or(0)
+}
+
+/// Splits `volume` across one side's orders, sorted by priority, under the
+/// config's allocation rule.
+fn allocate_side(orders: &[&Order], volume: u64, config: &AuctionConfig) -> Vec<u64> {
+    let mut remaining = volume;
+    match config.allocation_rule {
+        AllocationRule::PriceTime => orders
+            .iter()
+            .map(|order| {
+                let quantity = remaining.min(order.matchable_quantity());
+                remaining -= quantity;
+                quantity
+            })
+            .collect(),
+        AllocationRule::ProRata => orders
+            .chunk_by(|a, b| {
+                a.limit_price == b.limit_price
+                    && participant_priority(config, a) == participant_priority(config, b)
+            })
+            .flat_map(|level| {
+                let quantities = prorated_quantities(level, remaining);
+                remaining -= quantities.iter().sum::<u64>();
+                quantities
+            })
+            .collect(),
+    }
+}
+
+/// Creates tentative fills for orders by the config's allocation rule, or pro
+/// rata across each whole side when `prorate` is set.
 fn create_tentative_fills(
     sorted_bids: Vec<&Order>,
     asks: Vec<&Order>,
     clearing_price: Decimal,
     matched_volume: u64,
     order_map: &HashMap<OrderId, Order>,
+    prorate: bool,
+    config: &AuctionConfig,
 ) -> Result<Vec<TentativeFill>, String> {
     let mut tentative_fills = Vec::new();
 
@@ -639,31 +1562,22 @@ fn create_tentative_fills(
         .filter(|o| o.limit_price <= clearing_price)
         .collect();
 
-    let mut current_fills = HashMap::<OrderId, u64>::new();
-
-    // Fill bids up to matched volume
-    let mut bid_filled_volume = 0u64;
-    for bid_order in &eligible_bids {
-        if bid_filled_volume >= matched_volume {
-            break;
-        }
-        let fill_amount = (matched_volume - bid_filled_volume).min(bid_order.effective_quantity);
-        if fill_amount > 0 {
-            *current_fills.entry(bid_order.id).or_insert(0) += fill_amount;
-            bid_filled_volume += fill_amount;
-        }
-    }
+    // Kept in side order (bids, then asks) so fills come out in the same
+    // order on every run
+    let mut current_fills = Vec::<(OrderId, u64)>::new();
 
-    // Fill asks up to matched volume
-    let mut ask_filled_volume = 0u64;
-    for ask_order in &eligible_asks {
-        if ask_filled_volume >= matched_volume {
-            break;
-        }
-        let fill_amount = (matched_volume - ask_filled_volume).min(ask_order.effective_quantity);
-        if fill_amount > 0 {
-            *current_fills.entry(ask_order.id).or_insert(0) += fill_amount;
-            ask_filled_volume += fill_amount;
+    // Fill each side up to matched volume. Under rationing every eligible
+    // order on the long side gets its share, whatever its price
+    for side in [&eligible_bids, &eligible_asks] {
+        let quantities = if prorate {
+            prorated_quantities(side, matched_volume)
+        } else {
+            allocate_side(side, matched_volume, config)
+        };
+        for (order, quantity) in side.iter().zip(quantities) {
+            if quantity > 0 {
+                current_fills.push((order.id, quantity));
+            }
         }
     }
 
@@ -698,6 +1612,7 @@ fn create_tentative_fills(
 /// * `orders` - All orders for this specific resource (both bids and asks)
 /// * `last_price` - Previous clearing price for tie-breaking
 /// * `order_map` - Complete order lookup map for fill tracking
+/// * `config` - Matching mode and tie-break seed
 ///
 /// # Returns
 ///
@@ -719,37 +1634,231 @@ fn create_tentative_fills(
 ///
 /// Once clearing price is found, orders are filled using price-time priority:
 /// - Orders with better prices filled first
-/// - Among same price, earlier orders (lower timestamp) filled first
+/// - Among same price, participants with higher `config.participant_priority` filled first
+/// - Then earlier orders (lower timestamp) filled first
+/// - Remaining ties follow the seeded order when `config.tie_break_seed` is set
 /// - Partial fills allowed to match exact volume
+///
+/// With `AllocationRule::ProRata`, orders at the same price and participant
+/// priority share what is left at that level in proportion to their size
+/// instead of by timestamp.
+///
+/// The eligible quantity left over on each side at the clearing price is reported as
+/// `excess_demand` / `excess_supply`; at most one of them is non-zero.
+///
+/// # Price Controls
+///
+/// Candidate prices are limited to the resource's ceiling and floor in `config`. When
+/// the unconstrained price would lie outside them, the resource clears at the bound,
+/// the short side is filled completely and the long side is rationed pro rata, and
+/// `price_control` records the binding.
+///
+/// # All-or-Nothing Orders
+///
+/// Choosing which all-or-nothing orders to include is a knapsack-style problem, so a
+/// greedy heuristic is used instead: after each clearing, any all-or-nothing order that
+/// would be only partially filled is excluded and the resource is cleared again. Price-time
+/// priority means only the marginal order on each side can be partial, so the lowest
+/// priority orders are dropped first and the loop ends after at most one pass per order
+/// (pro rata allocation may drop several orders from the marginal price at once).
 pub fn find_clearing_for_resource(
     orders: &[&Order],
     last_price: Option<Decimal>,
     order_map: &HashMap<OrderId, Order>, // Pass map ref
+    config: &AuctionConfig,
+) -> Result<Option<ResourceClearing>, String> {
+    let mut candidates: Vec<&Order> = orders.to_vec();
+
+    loop {
+        let clearing = match clear_resource_once(&candidates, last_price, order_map, config)? {
+            Some(clearing) => clearing,
+            None => return Ok(None),
+        };
+
+        let partial_aon: Vec<OrderId> = clearing
+            .tentative_fills
+            .iter()
+            .filter(|fill| {
+                order_map.get(&fill.order_id).is_some_and(|o| {
+                    o.fills_whole_or_nothing() && fill.filled_quantity < o.original_quantity
+                })
+            })
+            .map(|fill| fill.order_id)
+            .collect();
+
+        if partial_aon.is_empty() {
+            return Ok(Some(clearing));
+        }
+        candidates.retain(|o| !partial_aon.contains(&o.id));
+    }
+}
+
+/// Clears a single resource once, with no all-or-nothing handling.
+fn clear_resource_once(
+    orders: &[&Order],
+    last_price: Option<Decimal>,
+    order_map: &HashMap<OrderId, Order>,
+    config: &AuctionConfig,
 ) -> Result<Option<ResourceClearing>, String> {
     // Return Result<Option<...>, ErrorString>
 
     // Collect and sort eligible orders
-    let (sorted_bids, asks) = collect_eligible_orders(orders);
+    let (sorted_bids, asks) = collect_eligible_orders(orders, config);
+    let bounds = orders
+        .first()
+        .map(|o| PriceBounds::for_resource(config, &o.resource_id))
+        .unwrap_or_default();
+
+    if sorted_bids.is_empty() || asks.is_empty() {
+        let oracle_price = orders
+            .first()
+            .and_then(|o| config.oracle_prices.get(&o.resource_id));
+        let max_volume = orders
+            .first()
+            .and_then(|o| config.max_market_volume.get(&o.resource_id))
+            .copied();
+        return Ok(oracle_price.and_then(|&price| {
+            clear_against_oracle(&sorted_bids, &asks, price, bounds, max_volume, config.trace)
+        }));
+    }
 
-    // Find the best clearing price and volume
-    let clearing_result = find_best_clearing(&sorted_bids, &asks, last_price)?;
+    // Find the clearing price and volume
+    let find_clearing = |bounds: PriceBounds| match config.matching_mode {
+        MatchingMode::DiscretePrice => find_best_clearing(&sorted_bids, &asks, last_price, bounds),
+        MatchingMode::Tatonnement { step, iterations } => Ok(find_tatonnement_clearing(
+            &sorted_bids,
+            &asks,
+            last_price,
+            step,
+            iterations,
+            bounds,
+        )
+        .map(|(price, volume)| (price, volume, PriceChoice::Tatonnement))),
+    };
+    let clearing_result = find_clearing(bounds)?;
 
-    let (clearing_price, matched_volume) = match clearing_result {
-        Some((price, volume)) => (price, volume),
+    let (clearing_price, matched_volume, choice) = match clearing_result {
+        Some(clearing) => clearing,
         None => return Ok(None), // No trade possible
     };
+    let matched_volume = orders
+        .first()
+        .and_then(|o| config.max_market_volume.get(&o.resource_id))
+        .map_or(matched_volume, |&cap| matched_volume.min(cap));
+
+    // A control binds when the market would clear outside it without one
+    let price_control = if bounds.is_unbounded() {
+        None
+    } else {
+        find_clearing(PriceBounds::default())?.and_then(|(unconstrained_price, _, _)| {
+            bounds
+                .violated_by(unconstrained_price)
+                .map(|kind| PriceControlBinding {
+                    kind,
+                    unconstrained_price,
+                })
+        })
+    };
+
+    // Unmet quantity on each side at the clearing price shows which side is constrained
+    let eligible_demand = sorted_bids
+        .iter()
+        .filter(|o| o.limit_price >= clearing_price)
+        .map(|o| o.matchable_quantity())
+        .sum::<u64>();
+    let eligible_supply = asks
+        .iter()
+        .filter(|o| o.limit_price <= clearing_price)
+        .map(|o| o.matchable_quantity())
+        .sum::<u64>();
+
+    let levels = if config.trace {
+        price_levels(&sorted_bids, &asks, bounds)
+    } else {
+        Vec::new()
+    };
 
     // Create tentative fills based on price-time priority
-    let tentative_fills =
-        create_tentative_fills(sorted_bids, asks, clearing_price, matched_volume, order_map)?;
+    let tentative_fills = create_tentative_fills(
+        sorted_bids,
+        asks,
+        clearing_price,
+        matched_volume,
+        order_map,
+        price_control.is_some(),
+        config,
+    )?;
 
     Ok(Some(ResourceClearing {
         clearing_price,
         matched_volume,
         tentative_fills,
+        excess_demand: eligible_demand - matched_volume,
+        excess_supply: eligible_supply - matched_volume,
+        price_control,
+        choice,
+        levels,
     }))
 }
 
+/// Fills a one-sided market at the oracle price, held within `bounds`: every
+/// ask at or below it, or every bid at or above it, in priority order until
+/// `max_volume` runs out. `None` if nothing qualifies.
+fn clear_against_oracle(
+    bids: &[&Order],
+    asks: &[&Order],
+    oracle_price: Decimal,
+    bounds: PriceBounds,
+    max_volume: Option<u64>,
+    trace: bool,
+) -> Option<ResourceClearing> {
+    let price = bounds.clamp(oracle_price);
+    let eligible: Vec<&Order> = bids
+        .iter()
+        .filter(|o| o.limit_price >= price)
+        .chain(asks.iter().filter(|o| o.limit_price <= price))
+        .copied()
+        .collect();
+    let mut remaining = max_volume.unwrap_or(u64::MAX);
+    let tentative_fills: Vec<TentativeFill> = eligible
+        .iter()
+        .map(|o| {
+            let filled_quantity = o.matchable_quantity().min(remaining);
+            remaining -= filled_quantity;
+            TentativeFill {
+                order_id: o.id,
+                filled_quantity,
+            }
+        })
+        .filter(|fill| fill.filled_quantity > 0)
+        .collect();
+    if tentative_fills.is_empty() {
+        return None;
+    }
+
+    let matched_volume = tentative_fills.iter().map(|f| f.filled_quantity).sum();
+    let unfilled = eligible.iter().map(|o| o.matchable_quantity()).sum::<u64>() - matched_volume;
+    Some(ResourceClearing {
+        clearing_price: price,
+        matched_volume,
+        tentative_fills,
+        excess_demand: if bids.is_empty() { 0 } else { unfilled },
+        excess_supply: if asks.is_empty() { 0 } else { unfilled },
+        price_control: bounds
+            .violated_by(oracle_price)
+            .map(|kind| PriceControlBinding {
+                kind,
+                unconstrained_price: oracle_price,
+            }),
+        choice: PriceChoice::Oracle,
+        levels: if trace {
+            price_levels(bids, asks, bounds)
+        } else {
+            Vec::new()
+        },
+    })
+}
+
 // --- Unit Tests (Updated for Decimal) ---
 #[cfg(test)]
 mod tests {
@@ -792,6 +1901,9 @@ mod tests {
             effective_quantity: qty,
             limit_price: price,
             timestamp: ts,
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            visible_quantity: None,
         }
     }
 
@@ -1050,7 +2162,7 @@ mod tests {
                 panic!("Auction should have failed with max_iterations = 0")
             }
             Err(e) => {
-                assert!(matches!(e, AuctionError::MaxIterationsReached));
+                assert!(matches!(e, AuctionError::MaxIterationsReached { .. }));
             }
         }
     }
@@ -1307,4 +2419,979 @@ mod tests {
             Err(e) => panic!("Multi-resource budget constraint test failed: {:?}", e),
         }
     }
+
+    #[test]
+    fn test_all_or_nothing_bid_dropped_when_partial() {
+        let mut aon_bid = create_order(2, BOB, "wood", OrderType::Bid, 10, dec!(12), 2);
+        aon_bid.all_or_nothing = true;
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Ask, 6, dec!(10), 1),
+            aon_bid,
+            create_order(3, CAROL, "wood", OrderType::Bid, 4, dec!(11), 3),
+        ];
+        let participants = create_participants(vec![
+            (ALICE, dec!(1000)),
+            (BOB, dec!(1000)),
+            (CAROL, dec!(1000)),
+        ]);
+
+        let success = run_auction(orders, participants, 5, HashMap::new()).unwrap();
+
+        // Only 6 of the AON bid's 10 could fill, so it is dropped entirely
+        assert!(success.final_fills.iter().all(|f| f.order_id != OrderId(2)));
+        let carol_fill = success
+            .final_fills
+            .iter()
+            .find(|f| f.order_id == OrderId(3))
+            .unwrap();
+        assert_eq!(carol_fill.filled_quantity, 4);
+        assert_eq!(
+            success.clearing_prices[&ResourceId("wood".to_string())],
+            dec!(11)
+        );
+    }
+
+    #[test]
+    fn test_fill_or_kill_bid_killed_when_partial() {
+        let fok_bid = Order {
+            time_in_force: TimeInForce::FillOrKill,
+            ..create_order(2, BOB, "wood", OrderType::Bid, 10, dec!(12), 2)
+        };
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Ask, 6, dec!(10), 1),
+            fok_bid,
+            create_order(3, CAROL, "wood", OrderType::Bid, 8, dec!(11), 3),
+        ];
+        let participants = create_participants(vec![
+            (ALICE, dec!(1000)),
+            (BOB, dec!(1000)),
+            (CAROL, dec!(1000)),
+        ]);
+
+        let success = run_auction(orders.clone(), participants, 5, HashMap::new()).unwrap();
+
+        // Only 6 of the FOK bid's 10 could fill, so it is cancelled and
+        // Carol's bid takes a partial fill of the 6 on offer instead
+        assert!(success.final_fills.iter().all(|f| f.order_id != OrderId(2)));
+        assert_eq!(success.killed_orders, vec![OrderId(2)]);
+        let carol_fill = success
+            .final_fills
+            .iter()
+            .find(|f| f.order_id == OrderId(3))
+            .unwrap();
+        assert_eq!(carol_fill.filled_quantity, 6);
+        // A killed order never rests, even as an iceberg
+        let iceberg_orders: Vec<Order> = orders
+            .into_iter()
+            .map(|o| Order {
+                visible_quantity: Some(o.original_quantity),
+                ..o
+            })
+            .collect();
+        assert!(
+            replenish_icebergs(&iceberg_orders, &success.final_fills)
+                .iter()
+                .all(|o| o.id != OrderId(2))
+        );
+    }
+
+    #[test]
+    fn test_tatonnement_converges_near_linear_equilibrium() {
+        // One unit bid at each price 1..=10 and one unit asked at each price 1..=10:
+        // demand(p) = 11 - p and supply(p) = p cross at p = 5.5
+        let mut orders = Vec::new();
+        for price in 1..=10u64 {
+            orders.push(create_order(
+                price as usize,
+                ALICE,
+                "wood",
+                OrderType::Bid,
+                1,
+                Decimal::from(price),
+                price,
+            ));
+            orders.push(create_order(
+                100 + price as usize,
+                BOB,
+                "wood",
+                OrderType::Ask,
+                1,
+                Decimal::from(price),
+                100 + price,
+            ));
+        }
+        let participants = create_participants(vec![(ALICE, dec!(1000)), (BOB, dec!(1000))]);
+        let last_prices = HashMap::from([(ResourceId("wood".to_string()), dec!(1))]);
+        let config = AuctionConfig {
+            matching_mode: MatchingMode::Tatonnement {
+                step: dec!(0.25),
+                iterations: 100,
+            },
+            ..Default::default()
+        };
+
+        let success =
+            run_auction_with_config(orders, participants, 5, last_prices, &config).unwrap();
+
+        let price = success.clearing_prices[&ResourceId("wood".to_string())];
+        assert!((price - dec!(5.5)).abs() <= dec!(0.5), "price {}", price);
+        let bought: u64 = success
+            .final_fills
+            .iter()
+            .filter(|f| f.order_type == OrderType::Bid)
+            .map(|f| f.filled_quantity)
+            .sum();
+        assert_eq!(bought, 5);
+    }
+
+    #[test]
+    fn test_seeded_tie_break_decides_equal_bids() {
+        let winner = |seed: u64| {
+            let orders = vec![
+                create_order(1, ALICE, "wood", OrderType::Bid, 5, dec!(10), 1),
+                create_order(2, BOB, "wood", OrderType::Bid, 5, dec!(10), 1),
+                create_order(3, CAROL, "wood", OrderType::Ask, 5, dec!(10), 2),
+            ];
+            let participants =
+                create_participants(vec![(ALICE, dec!(100)), (BOB, dec!(100)), (CAROL, dec!(0))]);
+            let config = AuctionConfig {
+                tie_break_seed: Some(seed),
+                ..Default::default()
+            };
+            let success =
+                run_auction_with_config(orders, participants, 5, HashMap::new(), &config).unwrap();
+            let bid_fills: Vec<_> = success
+                .final_fills
+                .iter()
+                .filter(|f| f.order_type == OrderType::Bid)
+                .collect();
+            assert_eq!(bid_fills.len(), 1, "the limited fill goes to one bid");
+            assert_eq!(bid_fills[0].filled_quantity, 5);
+            bid_fills[0].participant_id.clone()
+        };
+
+        let winners: Vec<ParticipantId> = (0..16).map(winner).collect();
+        assert!(winners.contains(&ParticipantId(ALICE)));
+        assert!(winners.contains(&ParticipantId(BOB)));
+        // Same seed, same outcome
+        for (seed, expected) in winners.iter().enumerate() {
+            assert_eq!(&winner(seed as u64), expected);
+        }
+    }
+
+    #[test]
+    fn test_participant_in_debt_that_only_sells_does_not_stall_auction() {
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Ask, 5, dec!(10), 1),
+            create_order(2, BOB, "wood", OrderType::Bid, 5, dec!(10), 2),
+        ];
+        // Alice is below zero but spends nothing, so she is never short
+        let participants = create_participants(vec![(ALICE, dec!(-20)), (BOB, dec!(100))]);
+
+        let success = run_auction(orders, participants, 5, HashMap::new()).unwrap();
+
+        let alice = success
+            .final_balances
+            .iter()
+            .find(|b| b.participant_id == ParticipantId(ALICE))
+            .unwrap();
+        assert_eq!(alice.final_currency, dec!(30));
+    }
+
+    #[test]
+    fn test_degrade_mode_returns_feasible_result_past_iteration_budget() {
+        let book = || {
+            (
+                vec![
+                    create_order(1, ALICE, "wood", OrderType::Bid, 10, dec!(10), 1),
+                    create_order(2, BOB, "wood", OrderType::Bid, 10, dec!(10), 2),
+                    create_order(3, CAROL, "wood", OrderType::Ask, 20, dec!(10), 3),
+                ],
+                create_participants(vec![(ALICE, dec!(25)), (BOB, dec!(200)), (CAROL, dec!(0))]),
+            )
+        };
+
+        // Alice is over budget until she is pruned, which one iteration can't finish
+        let (orders, participants) = book();
+        let result = run_auction(orders, participants, 1, HashMap::new());
+        assert!(matches!(
+            result,
+            Err(AuctionError::MaxIterationsReached { .. })
+        ));
+
+        // With tracing, the failure shows how far clearing got
+        let (orders, participants) = book();
+        let traced = AuctionConfig {
+            trace: true,
+            ..Default::default()
+        };
+        let result = run_auction_with_config(orders, participants, 1, HashMap::new(), &traced);
+        let Err(AuctionError::MaxIterationsReached { trace }) = result else {
+            panic!("One iteration should not be enough");
+        };
+        assert!(matches!(
+            trace.first(),
+            Some(AuctionTraceStep::Cleared { iteration: 0, .. })
+        ));
+        assert!(
+            trace
+                .iter()
+                .any(|step| matches!(step, AuctionTraceStep::Pruned { .. }))
+        );
+
+        let config = AuctionConfig {
+            degrade_on_max_iterations: true,
+            ..Default::default()
+        };
+        for max_iterations in [0, 1] {
+            let (orders, participants) = book();
+            let success = run_auction_with_config(
+                orders,
+                participants,
+                max_iterations,
+                HashMap::new(),
+                &config,
+            )
+            .unwrap();
+            for balance in &success.final_balances {
+                assert!(
+                    balance.final_currency >= Decimal::ZERO,
+                    "{:?} overspent",
+                    balance.participant_id
+                );
+            }
+            let bought = |id: u32| -> u64 {
+                success
+                    .final_fills
+                    .iter()
+                    .filter(|f| f.participant_id == ParticipantId(id))
+                    .map(|f| f.filled_quantity)
+                    .sum()
+            };
+            assert_eq!(bought(BOB), 10);
+            // Alice's bid shrinks to the 2 wood her 25 covers instead of being dropped
+            assert_eq!(bought(ALICE), 2);
+        }
+    }
+
+    #[test]
+    fn test_listing_fee_charged_per_order_regardless_of_fills() {
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Bid, 5, dec!(10), 1),
+            // Too low to fill
+            create_order(2, ALICE, "wood", OrderType::Bid, 5, dec!(1), 2),
+            create_order(3, BOB, "wood", OrderType::Ask, 5, dec!(10), 3),
+        ];
+        let participants = create_participants(vec![(ALICE, dec!(100)), (BOB, dec!(0.2))]);
+        let config = AuctionConfig {
+            listing_fee: dec!(0.5),
+            ..Default::default()
+        };
+        let success =
+            run_auction_with_config(orders, participants, 5, HashMap::new(), &config).unwrap();
+
+        assert_eq!(success.listing_fees[&ParticipantId(ALICE)], dec!(1.0));
+        // Never more than the participant holds
+        assert_eq!(success.listing_fees[&ParticipantId(BOB)], dec!(0.2));
+        let balance = |id: u32| {
+            success
+                .final_balances
+                .iter()
+                .find(|b| b.participant_id == ParticipantId(id))
+                .unwrap()
+                .final_currency
+        };
+        assert_eq!(balance(ALICE), dec!(49.0));
+        assert_eq!(balance(BOB), dec!(50.0));
+    }
+
+    #[test]
+    fn test_surplus_matches_maximum_when_clearing_is_efficient() {
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Bid, 5, dec!(10), 1),
+            create_order(2, BOB, "wood", OrderType::Bid, 5, dec!(4), 2),
+            create_order(3, CAROL, "wood", OrderType::Ask, 5, dec!(2), 3),
+            create_order(4, CAROL, "wood", OrderType::Ask, 5, dec!(8), 4),
+        ];
+        let participants =
+            create_participants(vec![(ALICE, dec!(100)), (BOB, dec!(100)), (CAROL, dec!(0))]);
+        let success = run_auction(orders, participants, 5, HashMap::new()).unwrap();
+
+        // Only Alice's bid and the cheap ask cross: (10 - 2) × 5
+        assert_eq!(success.max_surplus, dec!(40));
+        assert_eq!(success.realized_surplus, success.max_surplus);
+    }
+
+    #[test]
+    fn test_budget_pruning_realizes_less_than_maximum_surplus() {
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Bid, 10, dec!(10), 1),
+            create_order(2, BOB, "wood", OrderType::Ask, 10, dec!(2), 2),
+        ];
+        // Alice can afford only part of what she bids for
+        let participants = create_participants(vec![(ALICE, dec!(30)), (BOB, dec!(0))]);
+        let success = run_auction(orders, participants, 10, HashMap::new()).unwrap();
+
+        assert_eq!(success.max_surplus, dec!(80));
+        assert!(success.realized_surplus > Decimal::ZERO);
+        assert!(success.realized_surplus < success.max_surplus);
+    }
+
+    #[test]
+    fn test_max_market_volume_caps_aggregate_fills() {
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Bid, 25, dec!(12.0), 1),
+            create_order(2, BOB, "wood", OrderType::Bid, 25, dec!(10.0), 2),
+            create_order(3, CAROL, "wood", OrderType::Ask, 25, dec!(4.0), 3),
+            create_order(4, DAVID, "wood", OrderType::Ask, 25, dec!(8.0), 4),
+        ];
+        let participants = create_participants(vec![
+            (ALICE, dec!(1000.0)),
+            (BOB, dec!(1000.0)),
+            (CAROL, dec!(1000.0)),
+            (DAVID, dec!(1000.0)),
+        ]);
+        let filled = |config: &AuctionConfig| {
+            let success = run_auction_with_config(
+                orders.clone(),
+                participants.clone(),
+                5,
+                HashMap::new(),
+                config,
+            )
+            .unwrap();
+            let mut fills: Vec<_> = success
+                .final_fills
+                .iter()
+                .map(|f| (f.order_id.0, f.filled_quantity))
+                .collect();
+            fills.sort();
+            fills
+        };
+
+        assert_eq!(
+            filled(&AuctionConfig::default()),
+            vec![(1, 25), (2, 25), (3, 25), (4, 25)]
+        );
+
+        // The best bid and the cheapest ask fill first
+        let config = AuctionConfig {
+            max_market_volume: HashMap::from([(ResourceId("wood".to_string()), 30)]),
+            ..Default::default()
+        };
+        assert_eq!(filled(&config), vec![(1, 25), (2, 5), (3, 25), (4, 5)]);
+    }
+
+    #[test]
+    fn test_participant_priority_fills_higher_reputation_first() {
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Bid, 5, dec!(10), 1),
+            create_order(2, BOB, "wood", OrderType::Bid, 5, dec!(10), 2),
+            create_order(3, CAROL, "wood", OrderType::Ask, 5, dec!(10), 3),
+        ];
+        let participants =
+            create_participants(vec![(ALICE, dec!(100)), (BOB, dec!(100)), (CAROL, dec!(0))]);
+
+        let buyer = |config: &AuctionConfig| {
+            let success = run_auction_with_config(
+                orders.clone(),
+                participants.clone(),
+                5,
+                HashMap::new(),
+                config,
+            )
+            .unwrap();
+            let bid_fills: Vec<_> = success
+                .final_fills
+                .iter()
+                .filter(|f| f.order_type == OrderType::Bid)
+                .collect();
+            assert_eq!(bid_fills.len(), 1, "the limited fill goes to one bid");
+            bid_fills[0].participant_id.clone()
+        };
+
+        // Equal priority falls back to timestamp
+        assert_eq!(buyer(&AuctionConfig::default()), ParticipantId(ALICE));
+
+        let config = AuctionConfig {
+            participant_priority: HashMap::from([(ParticipantId(BOB), 10)]),
+            ..Default::default()
+        };
+        assert_eq!(buyer(&config), ParticipantId(BOB));
+    }
+
+    #[test]
+    fn test_settlement_rounding_conserves_money() {
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Bid, 1, dec!(3.333), 1),
+            create_order(2, BOB, "wood", OrderType::Bid, 1, dec!(3.333), 2),
+            create_order(3, CAROL, "wood", OrderType::Bid, 1, dec!(3.333), 3),
+            create_order(4, DAVID, "wood", OrderType::Ask, 3, dec!(3.333), 4),
+        ];
+        let participants = create_participants(vec![
+            (ALICE, dec!(100)),
+            (BOB, dec!(100)),
+            (CAROL, dec!(100)),
+            (DAVID, dec!(0)),
+        ]);
+        let config = AuctionConfig {
+            settlement_rounding: Some(2),
+            ..Default::default()
+        };
+
+        let success =
+            run_auction_with_config(orders, participants, 5, HashMap::new(), &config).unwrap();
+
+        // 3 × 3.333 = 9.999 settles as 10.00; the cent lost rounding each bid
+        // to 3.33 goes to one bid; all rounded equally, so the lowest order ID
+        let value_of = |order: usize| {
+            success
+                .final_fills
+                .iter()
+                .find(|f| f.order_id == OrderId(order))
+                .unwrap()
+                .value
+        };
+        assert_eq!(value_of(1), dec!(3.34));
+        assert_eq!(value_of(2), dec!(3.33));
+        assert_eq!(value_of(3), dec!(3.33));
+        assert_eq!(value_of(4), dec!(10.00));
+
+        let balance_of = |id: u32| {
+            success
+                .final_balances
+                .iter()
+                .find(|b| b.participant_id == ParticipantId(id))
+                .unwrap()
+                .final_currency
+        };
+        assert_eq!(balance_of(DAVID), dec!(10.00));
+        let total: Decimal = success
+            .final_balances
+            .iter()
+            .map(|b| b.final_currency)
+            .sum();
+        assert_eq!(total, dec!(300));
+        assert_eq!(success.rounding_adjustments.len(), 1);
+    }
+
+    #[test]
+    fn test_rounding_remainder_is_spread_within_budgets() {
+        // Five buyers of one unit at 1.004 each; the first can't afford a cent more
+        let mut orders: Vec<Order> = (1..=5)
+            .map(|id| {
+                create_order(
+                    id,
+                    10 + id as u32,
+                    "wood",
+                    OrderType::Bid,
+                    1,
+                    dec!(1.004),
+                    1,
+                )
+            })
+            .collect();
+        orders.push(create_order(
+            6,
+            20,
+            "wood",
+            OrderType::Ask,
+            5,
+            dec!(1.004),
+            2,
+        ));
+        let mut budgets = vec![(11, dec!(1.004)), (20, dec!(0))];
+        budgets.extend((12..=15).map(|id| (id, dec!(100))));
+        let participants = create_participants(budgets);
+        let config = AuctionConfig {
+            settlement_rounding: Some(2),
+            ..Default::default()
+        };
+
+        let success =
+            run_auction_with_config(orders, participants, 5, HashMap::new(), &config).unwrap();
+
+        // 5.02 is settled: each bid rounds to 1.00 and the two cents left over
+        // go one apiece to the next bids in line, skipping the first buyer
+        let mut values: Vec<(usize, Decimal)> = success
+            .final_fills
+            .iter()
+            .filter(|f| f.order_type == OrderType::Bid)
+            .map(|f| (f.order_id.0, f.value))
+            .collect();
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                (1, dec!(1.00)),
+                (2, dec!(1.01)),
+                (3, dec!(1.01)),
+                (4, dec!(1.00)),
+                (5, dec!(1.00)),
+            ]
+        );
+        let adjusted: Vec<usize> = success
+            .rounding_adjustments
+            .iter()
+            .map(|a| a.order_id.0)
+            .collect();
+        assert_eq!(adjusted, vec![2, 3]);
+        assert!(
+            success
+                .final_balances
+                .iter()
+                .all(|b| b.final_currency >= Decimal::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_excess_demand_reported() {
+        // 12 units bid at or above 10, only 5 offered
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Ask, 5, dec!(8.0), 1),
+            create_order(2, BOB, "wood", OrderType::Bid, 8, dec!(10.0), 2),
+            create_order(3, CAROL, "wood", OrderType::Bid, 4, dec!(12.0), 3),
+        ];
+        let participants = create_participants(vec![
+            (ALICE, dec!(1000.0)),
+            (BOB, dec!(1000.0)),
+            (CAROL, dec!(1000.0)),
+        ]);
+        let success = run_auction(orders, participants, 5, HashMap::new()).unwrap();
+
+        // Volume of 5 is maximal at both 8 and 10; tie-break picks the higher price
+        let wood = ResourceId("wood".to_string());
+        assert_eq!(success.clearing_prices[&wood], dec!(10.0));
+
+        // Both bids (12 units) are eligible at 10, but only 5 units matched
+        assert_eq!(success.excess_demand[&wood], 7);
+        assert_eq!(success.excess_supply[&wood], 0);
+    }
+
+    #[test]
+    fn test_one_sided_asks_fill_at_oracle_price() {
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Ask, 4, dec!(3.0), 1),
+            create_order(2, BOB, "wood", OrderType::Ask, 6, dec!(4.5), 2),
+        ];
+        let participants = create_participants(vec![(ALICE, dec!(0.0)), (BOB, dec!(0.0))]);
+        let wood = ResourceId("wood".to_string());
+
+        // Without an oracle nobody is buying
+        let success = run_auction(orders.clone(), participants.clone(), 5, HashMap::new()).unwrap();
+        assert!(success.final_fills.is_empty());
+
+        let config = AuctionConfig {
+            oracle_prices: HashMap::from([(wood.clone(), dec!(5.0))]),
+            ..Default::default()
+        };
+        let success =
+            run_auction_with_config(orders, participants, 5, HashMap::new(), &config).unwrap();
+
+        assert_eq!(success.clearing_prices[&wood], dec!(5.0));
+        let mut fills: Vec<(OrderId, u64, Decimal)> = success
+            .final_fills
+            .iter()
+            .map(|f| (f.order_id, f.filled_quantity, f.price))
+            .collect();
+        fills.sort_by_key(|f| f.0.0);
+        assert_eq!(
+            fills,
+            vec![(OrderId(1), 4, dec!(5.0)), (OrderId(2), 6, dec!(5.0))]
+        );
+        let balance = |id: u32| {
+            success
+                .final_balances
+                .iter()
+                .find(|b| b.participant_id == ParticipantId(id))
+                .unwrap()
+                .final_currency
+        };
+        assert_eq!(balance(ALICE), dec!(20.0));
+        assert_eq!(balance(BOB), dec!(30.0));
+        assert_eq!(success.oracle_resources, HashSet::from([wood]));
+    }
+
+    #[test]
+    fn test_oracle_clearing_respects_price_controls_and_volume_cap() {
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Ask, 4, dec!(3.0), 1),
+            create_order(2, BOB, "wood", OrderType::Ask, 6, dec!(3.5), 2),
+            create_order(3, CAROL, "wood", OrderType::Ask, 5, dec!(4.5), 3),
+        ];
+        let participants = create_participants(vec![
+            (ALICE, dec!(0.0)),
+            (BOB, dec!(0.0)),
+            (CAROL, dec!(0.0)),
+        ]);
+        let wood = ResourceId("wood".to_string());
+        let config = AuctionConfig {
+            oracle_prices: HashMap::from([(wood.clone(), dec!(5.0))]),
+            price_ceilings: HashMap::from([(wood.clone(), dec!(4.0))]),
+            max_market_volume: HashMap::from([(wood.clone(), 7)]),
+            ..Default::default()
+        };
+        let success =
+            run_auction_with_config(orders, participants, 5, HashMap::new(), &config).unwrap();
+
+        // The ceiling holds the oracle at 4, which shuts Carol out, and the
+        // cap leaves Bob only what Alice doesn't take
+        assert_eq!(success.clearing_prices[&wood], dec!(4.0));
+        assert_eq!(
+            success.price_controls[&wood],
+            PriceControlBinding {
+                kind: PriceControlKind::Ceiling,
+                unconstrained_price: dec!(5.0),
+            }
+        );
+        let mut fills: Vec<(OrderId, u64)> = success
+            .final_fills
+            .iter()
+            .map(|f| (f.order_id, f.filled_quantity))
+            .collect();
+        fills.sort_by_key(|f| f.0.0);
+        assert_eq!(fills, vec![(OrderId(1), 4), (OrderId(2), 3)]);
+        assert_eq!(success.excess_supply[&wood], 3);
+    }
+
+    #[test]
+    fn test_budget_tolerance_skips_pruning_tiny_overages() {
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Bid, 100, dec!(1.0), 1),
+            create_order(2, BOB, "food", OrderType::Bid, 50, dec!(2.0), 2),
+            create_order(3, CAROL, "wood", OrderType::Ask, 100, dec!(1.0), 3),
+            create_order(4, CAROL, "food", OrderType::Ask, 50, dec!(2.0), 4),
+        ];
+        // Each buyer is a cent or two short of its order's cost
+        let participants = create_participants(vec![
+            (ALICE, dec!(99.99)),
+            (BOB, dec!(99.98)),
+            (CAROL, dec!(0.0)),
+        ]);
+        let run = |budget_tolerance| {
+            let config = AuctionConfig {
+                budget_tolerance,
+                trace: true,
+                ..Default::default()
+            };
+            run_auction_with_config(
+                orders.clone(),
+                participants.clone(),
+                5,
+                HashMap::new(),
+                &config,
+            )
+            .unwrap()
+        };
+        let prunes = |success: &AuctionSuccess| {
+            success
+                .trace
+                .iter()
+                .filter(|step| matches!(step, AuctionTraceStep::Pruned { .. }))
+                .count()
+        };
+
+        let exact = run(Decimal::ZERO);
+        let tolerant = run(dec!(0.05));
+
+        assert_eq!(prunes(&exact), 2);
+        assert_eq!(prunes(&tolerant), 0);
+        let total_filled = |s: &AuctionSuccess| -> u64 {
+            s.final_fills
+                .iter()
+                .filter(|f| f.order_type == OrderType::Bid)
+                .map(|f| f.filled_quantity)
+                .sum()
+        };
+        assert_eq!(total_filled(&tolerant), 150);
+        assert!(total_filled(&exact) < 150);
+        for balance in &tolerant.final_balances {
+            assert!(balance.final_currency >= dec!(-0.05));
+        }
+    }
+
+    #[test]
+    fn test_skip_insolvent_bids_keeps_only_barter_bids() {
+        let orders = vec![
+            // Bob has no money: a food bid his wood ask pays for, then a plain wood bid
+            create_order(1, BOB, "food", OrderType::Bid, 10, dec!(1.0), 1),
+            create_order(2, BOB, "wood", OrderType::Bid, 5, dec!(2.0), 2),
+            create_order(3, BOB, "wood", OrderType::Ask, 5, dec!(2.0), 3),
+            create_order(4, ALICE, "wood", OrderType::Bid, 5, dec!(2.0), 4),
+            create_order(5, CAROL, "food", OrderType::Ask, 10, dec!(1.0), 5),
+            create_order(6, CAROL, "wood", OrderType::Ask, 5, dec!(2.0), 6),
+        ];
+        let participants = create_participants(vec![
+            (ALICE, dec!(100.0)),
+            (BOB, dec!(0.0)),
+            (CAROL, dec!(0.0)),
+        ]);
+        let config = AuctionConfig {
+            skip_insolvent_bids: true,
+            trace: true,
+            ..Default::default()
+        };
+        let success =
+            run_auction_with_config(orders, participants, 10, HashMap::new(), &config).unwrap();
+
+        let filled = |order_id: usize| {
+            success
+                .final_fills
+                .iter()
+                .filter(|f| f.order_id == OrderId(order_id))
+                .map(|f| f.filled_quantity)
+                .sum::<u64>()
+        };
+        assert_eq!(filled(1), 10, "Barter bid paid for by the wood ask clears");
+        assert_eq!(filled(2), 0, "Standalone bid with no money is dropped");
+        assert_eq!(filled(4), 5);
+        assert!(
+            !success
+                .trace
+                .iter()
+                .any(|step| matches!(step, AuctionTraceStep::Pruned { .. })),
+            "Nothing is left to prune"
+        );
+        let bob = success
+            .final_balances
+            .iter()
+            .find(|b| b.participant_id == ParticipantId(BOB))
+            .unwrap();
+        assert!(bob.final_currency >= Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_max_price_move_clamps_bid_above_last_price() {
+        let orders = vec![
+            create_order(1, BOB, "wood", OrderType::Bid, 10, dec!(15.0), 1),
+            create_order(2, ALICE, "wood", OrderType::Ask, 5, dec!(11.0), 2),
+            create_order(3, DAVID, "wood", OrderType::Ask, 5, dec!(14.0), 3),
+        ];
+        let participants = create_participants(vec![
+            (ALICE, dec!(1000.0)),
+            (BOB, dec!(1000.0)),
+            (DAVID, dec!(1000.0)),
+        ]);
+        let wood = ResourceId("wood".to_string());
+        let config = AuctionConfig {
+            max_price_move: Some(dec!(1.2)),
+            ..Default::default()
+        };
+        let last_prices = HashMap::from([(wood.clone(), dec!(10.0))]);
+        let success =
+            run_auction_with_config(orders, participants, 5, last_prices, &config).unwrap();
+
+        assert_eq!(
+            success.price_limits,
+            vec![PriceLimitHit {
+                order_id: OrderId(1),
+                participant_id: ParticipantId(BOB),
+                resource_id: wood.clone(),
+                limit_price: dec!(15.0),
+                clamped_price: dec!(12.0),
+            }]
+        );
+        // At 12 the bid can no longer reach David's ask at 14
+        assert!(success.clearing_prices[&wood] <= dec!(12.0));
+        assert_eq!(success.excess_demand[&wood], 5);
+    }
+
+    #[test]
+    fn test_price_ceiling_caps_price_and_rations_demand() {
+        // Without a ceiling 12 units clear at 10
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Ask, 6, dec!(4.0), 1),
+            create_order(2, DAVID, "wood", OrderType::Ask, 6, dec!(9.0), 2),
+            create_order(3, BOB, "wood", OrderType::Bid, 6, dec!(12.0), 3),
+            create_order(4, CAROL, "wood", OrderType::Bid, 6, dec!(10.0), 4),
+        ];
+        let participants = create_participants(vec![
+            (ALICE, dec!(1000.0)),
+            (BOB, dec!(1000.0)),
+            (CAROL, dec!(1000.0)),
+            (DAVID, dec!(1000.0)),
+        ]);
+        let wood = ResourceId("wood".to_string());
+        let config = AuctionConfig {
+            price_ceilings: HashMap::from([(wood.clone(), dec!(5.0))]),
+            ..Default::default()
+        };
+        let success =
+            run_auction_with_config(orders, participants, 5, HashMap::new(), &config).unwrap();
+
+        assert_eq!(success.clearing_prices[&wood], dec!(5.0));
+        assert_eq!(
+            success.price_controls[&wood],
+            PriceControlBinding {
+                kind: PriceControlKind::Ceiling,
+                unconstrained_price: dec!(10.0),
+            }
+        );
+
+        // Only Alice's 6 units are offered at 5; both bids get half instead of
+        // Bob's higher bid taking everything
+        let filled = |id: usize| {
+            success
+                .final_fills
+                .iter()
+                .find(|f| f.order_id == OrderId(id))
+                .map_or(0, |f| f.filled_quantity)
+        };
+        assert_eq!((filled(1), filled(2)), (6, 0));
+        assert_eq!((filled(3), filled(4)), (3, 3));
+        assert_eq!(success.excess_demand[&wood], 6);
+    }
+
+    #[test]
+    fn test_trace_records_clearing_and_budget_pruning() {
+        let orders = vec![
+            create_order(1, ALICE, "wood", OrderType::Ask, 10, dec!(5.0), 1),
+            create_order(2, BOB, "wood", OrderType::Bid, 10, dec!(10.0), 2),
+        ];
+        // Bob can only afford half of his bid at 10
+        let participants = create_participants(vec![(ALICE, dec!(0.0)), (BOB, dec!(50.0))]);
+        let wood = ResourceId("wood".to_string());
+        let config = AuctionConfig {
+            trace: true,
+            ..Default::default()
+        };
+        let success =
+            run_auction_with_config(orders, participants, 5, HashMap::new(), &config).unwrap();
+
+        let AuctionTraceStep::Cleared {
+            iteration,
+            price,
+            volume,
+            choice,
+            levels,
+            ..
+        } = &success.trace[0]
+        else {
+            panic!("First step should be a clearing: {:?}", success.trace[0]);
+        };
+        assert_eq!((*iteration, *price, *volume), (0, dec!(10.0), 10));
+        // 5 and 10 both trade 10 units; with no last price the highest wins
+        assert_eq!(*choice, PriceChoice::HighestPrice);
+        assert_eq!(levels.len(), 2);
+
+        assert!(success.trace.contains(&AuctionTraceStep::Pruned {
+            iteration: 0,
+            participant: ParticipantId(BOB),
+            shortfall: dec!(50.0),
+            reductions: vec![OrderReduction {
+                order_id: OrderId(2),
+                from: 10,
+                to: 5,
+            }],
+        }));
+        assert_eq!(success.clearing_prices[&wood], dec!(10.0));
+        assert!(matches!(
+            success.trace.last(),
+            Some(AuctionTraceStep::Cleared {
+                iteration: 1,
+                volume: 5,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_pro_rata_splits_marginal_price_by_size() {
+        let orders = [
+            create_order(1, ALICE, "wood", OrderType::Ask, 5, dec!(4.0), 1),
+            create_order(2, BOB, "wood", OrderType::Bid, 6, dec!(5.0), 2),
+            create_order(3, CAROL, "wood", OrderType::Bid, 4, dec!(5.0), 3),
+        ];
+        let order_refs: Vec<&Order> = orders.iter().collect();
+        let order_map: HashMap<OrderId, Order> = orders.iter().map(|o| (o.id, o.clone())).collect();
+        let filled = |rule| {
+            let config = AuctionConfig {
+                allocation_rule: rule,
+                ..Default::default()
+            };
+            let clearing = find_clearing_for_resource(&order_refs, None, &order_map, &config)
+                .unwrap()
+                .unwrap();
+            let fill = |id: usize| {
+                clearing
+                    .tentative_fills
+                    .iter()
+                    .find(|f| f.order_id == OrderId(id))
+                    .map_or(0, |f| f.filled_quantity)
+            };
+            (fill(2), fill(3))
+        };
+
+        // Price-time: Bob's earlier bid takes all 5; pro rata: 6:4 of 5
+        assert_eq!(filled(AllocationRule::PriceTime), (5, 0));
+        assert_eq!(filled(AllocationRule::ProRata), (3, 2));
+    }
+
+    #[test]
+    fn test_pro_rata_fills_higher_priority_first() {
+        let orders = [
+            create_order(1, ALICE, "wood", OrderType::Ask, 5, dec!(4.0), 1),
+            create_order(2, BOB, "wood", OrderType::Bid, 6, dec!(5.0), 2),
+            create_order(3, CAROL, "wood", OrderType::Bid, 4, dec!(5.0), 3),
+        ];
+        let order_refs: Vec<&Order> = orders.iter().collect();
+        let order_map: HashMap<OrderId, Order> = orders.iter().map(|o| (o.id, o.clone())).collect();
+        let config = AuctionConfig {
+            allocation_rule: AllocationRule::ProRata,
+            participant_priority: HashMap::from([(ParticipantId(CAROL), 1)]),
+            ..Default::default()
+        };
+        let clearing = find_clearing_for_resource(&order_refs, None, &order_map, &config)
+            .unwrap()
+            .unwrap();
+        let fill = |id: usize| {
+            clearing
+                .tentative_fills
+                .iter()
+                .find(|f| f.order_id == OrderId(id))
+                .map_or(0, |f| f.filled_quantity)
+        };
+
+        // Carol outranks Bob, so only the remainder is prorated to him
+        assert_eq!((fill(2), fill(3)), (1, 4));
+    }
+
+    #[test]
+    fn test_iceberg_ask_exposes_visible_quantity_only() {
+        let iceberg = Order {
+            visible_quantity: Some(10),
+            ..create_order(1, ALICE, "wood", OrderType::Ask, 100, dec!(5.0), 1)
+        };
+        let bid = create_order(2, BOB, "wood", OrderType::Bid, 50, dec!(6.0), 2);
+        let participants = create_participants(vec![(ALICE, dec!(0.0)), (BOB, dec!(1000.0))]);
+        let wood = ResourceId("wood".to_string());
+
+        let success = run_auction(
+            vec![iceberg.clone(), bid.clone()],
+            participants.clone(),
+            5,
+            HashMap::new(),
+        )
+        .unwrap();
+        let ask_fill = |success: &AuctionSuccess| {
+            success
+                .final_fills
+                .iter()
+                .find(|f| f.order_id == OrderId(1))
+                .map_or(0, |f| f.filled_quantity)
+        };
+        assert_eq!(ask_fill(&success), 10);
+        assert_eq!(success.excess_demand[&wood], 40);
+
+        // The next round shows another 10 from the hidden reserve
+        let resting = replenish_icebergs(&[iceberg, bid.clone()], &success.final_fills);
+        assert_eq!(resting.len(), 1);
+        assert_eq!(resting[0].original_quantity, 90);
+        assert_eq!(resting[0].matchable_quantity(), 10);
+
+        let mut orders = resting;
+        orders.push(bid);
+        let success = run_auction(orders, participants, 5, HashMap::new()).unwrap();
+        assert_eq!(ask_fill(&success), 10);
+    }
 } // end tests mod
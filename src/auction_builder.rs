@@ -1,4 +1,7 @@
-use crate::auction::{Order, OrderId, OrderType, Participant, ParticipantId, ResourceId};
+use crate::auction::{
+    AllocationRule, AuctionConfig, Order, OrderId, OrderType, Participant, ParticipantId,
+    ResourceId,
+};
 use crate::types::{OrderRequest, ResourceTypeExt, VillageId};
 use rust_decimal::Decimal;
 use std::collections::HashMap;
@@ -8,16 +11,64 @@ pub struct AuctionBuilder {
     orders: Vec<Order>,
     participants: HashMap<ParticipantId, Participant>,
     order_counter: usize,
-    timestamp_counter: u64,
+    priorities: Vec<u64>,
+    tick: u64,
+    participant_priority: HashMap<ParticipantId, i64>,
+    allocation_rule: AllocationRule,
+    skip_insolvent_bids: bool,
+    listing_fee: Decimal,
 }
 
 impl AuctionBuilder {
     pub fn new() -> Self {
+        Self::with_tick(0)
+    }
+
+    /// Builder for an auction held at `tick`, the default order priority
+    pub fn with_tick(tick: usize) -> Self {
         Self {
             orders: Vec::new(),
             participants: HashMap::new(),
             order_counter: 0,
-            timestamp_counter: 0,
+            priorities: Vec::new(),
+            tick: tick as u64,
+            participant_priority: HashMap::new(),
+            allocation_rule: AllocationRule::default(),
+            skip_insolvent_bids: false,
+            listing_fee: Decimal::ZERO,
+        }
+    }
+
+    /// Rank a village's orders above lower-priority ones at the same price
+    pub fn set_participant_priority(&mut self, village_id: &VillageId, priority: i64) {
+        self.participant_priority
+            .insert(ParticipantId(village_id.to_participant_id()), priority);
+    }
+
+    /// How volume is shared among orders at the same price
+    pub fn set_allocation_rule(&mut self, rule: AllocationRule) {
+        self.allocation_rule = rule;
+    }
+
+    /// Drop bids from villages with no money up front, keeping bids their
+    /// own asks pay for
+    pub fn set_skip_insolvent_bids(&mut self, skip: bool) {
+        self.skip_insolvent_bids = skip;
+    }
+
+    /// Flat fee each order added costs its village, filled or not
+    pub fn set_listing_fee(&mut self, fee: Decimal) {
+        self.listing_fee = fee;
+    }
+
+    /// Auction settings chosen on the builder; everything else is default.
+    pub fn auction_config(&self) -> AuctionConfig {
+        AuctionConfig {
+            allocation_rule: self.allocation_rule,
+            skip_insolvent_bids: self.skip_insolvent_bids,
+            listing_fee: self.listing_fee,
+            participant_priority: self.participant_priority.clone(),
+            ..Default::default()
         }
     }
 
@@ -47,16 +98,28 @@ impl AuctionBuilder {
             original_quantity: request.quantity as u64,
             effective_quantity: request.quantity as u64,
             limit_price: request.price,
-            timestamp: self.timestamp_counter,
+            timestamp: 0,
+            all_or_nothing: request.all_or_nothing,
+            time_in_force: request.time_in_force,
+            visible_quantity: request.visible_quantity.map(u64::from),
         };
 
         self.orders.push(order);
+        self.priorities.push(request.priority.unwrap_or(self.tick));
         self.order_counter += 1;
-        self.timestamp_counter += 1;
     }
 
     /// Get the built orders and participants
-    pub fn build(self) -> (Vec<Order>, HashMap<ParticipantId, Participant>) {
+    ///
+    /// Timestamps rank orders by priority. Orders sharing a priority get the
+    /// same timestamp, so the auction's tie-break decides between them.
+    pub fn build(mut self) -> (Vec<Order>, HashMap<ParticipantId, Participant>) {
+        let mut ranks = self.priorities.clone();
+        ranks.sort_unstable();
+        ranks.dedup();
+        for (order, priority) in self.orders.iter_mut().zip(&self.priorities) {
+            order.timestamp = ranks.binary_search(priority).unwrap_or_default() as u64;
+        }
         (self.orders, self.participants)
     }
 }
@@ -66,3 +129,174 @@ impl Default for AuctionBuilder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auction::{AuctionConfig, run_auction, run_auction_with_config};
+    use crate::types::{ResourceType, TimeInForce};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_explicit_priority_fills_before_earlier_same_price_order() {
+        let (early, urgent, seller) = (
+            VillageId("early".to_string()),
+            VillageId("urgent".to_string()),
+            VillageId("seller".to_string()),
+        );
+        let order = |is_buy, quantity, priority| OrderRequest {
+            resource: ResourceType::Food,
+            is_buy,
+            quantity,
+            price: dec!(1),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority,
+            visible_quantity: None,
+        };
+
+        let mut builder = AuctionBuilder::with_tick(5);
+        for village in [&early, &urgent, &seller] {
+            builder.add_village(village, dec!(100));
+        }
+        builder.add_order(&early, order(true, 5, None));
+        builder.add_order(&urgent, order(true, 5, Some(0)));
+        builder.add_order(&seller, order(false, 5, None));
+        let (orders, participants) = builder.build();
+
+        let result = run_auction(orders, participants, 10, HashMap::new()).unwrap();
+        let bought = |village: &VillageId| -> u64 {
+            let participant = ParticipantId(village.to_participant_id());
+            result
+                .final_fills
+                .iter()
+                .filter(|f| f.participant_id == participant && f.order_type == OrderType::Bid)
+                .map(|f| f.filled_quantity)
+                .sum()
+        };
+        assert_eq!(bought(&urgent), 5);
+        assert_eq!(bought(&early), 0);
+    }
+
+    #[test]
+    fn test_seeded_tie_break_decides_same_priority_bids() {
+        let (first, second, seller) = (
+            VillageId("first".to_string()),
+            VillageId("second".to_string()),
+            VillageId("seller".to_string()),
+        );
+        let order = |is_buy| OrderRequest {
+            resource: ResourceType::Food,
+            is_buy,
+            quantity: 5,
+            price: dec!(1),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        };
+        let winner = |seed: u64| {
+            let mut builder = AuctionBuilder::with_tick(3);
+            for village in [&first, &second, &seller] {
+                builder.add_village(village, dec!(100));
+            }
+            builder.add_order(&first, order(true));
+            builder.add_order(&second, order(true));
+            builder.add_order(&seller, order(false));
+            let (orders, participants) = builder.build();
+            let config = AuctionConfig {
+                tie_break_seed: Some(seed),
+                ..Default::default()
+            };
+            let result =
+                run_auction_with_config(orders, participants, 10, HashMap::new(), &config).unwrap();
+            let bids: Vec<_> = result
+                .final_fills
+                .iter()
+                .filter(|f| f.order_type == OrderType::Bid)
+                .collect();
+            assert_eq!(bids.len(), 1, "the limited supply goes to one bid");
+            bids[0].participant_id.clone()
+        };
+
+        let winners: Vec<ParticipantId> = (0..16).map(winner).collect();
+        // Submission order doesn't decide: either bid can win
+        for village in [&first, &second] {
+            assert!(winners.contains(&ParticipantId(village.to_participant_id())));
+        }
+        for (seed, expected) in winners.iter().enumerate() {
+            assert_eq!(&winner(seed as u64), expected);
+        }
+    }
+
+    #[test]
+    fn test_builder_skips_insolvent_bids_but_keeps_barter() {
+        let (broke, seller) = (
+            VillageId("broke".to_string()),
+            VillageId("seller".to_string()),
+        );
+        let order = |resource, is_buy, quantity| OrderRequest {
+            resource,
+            is_buy,
+            quantity,
+            price: dec!(1),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        };
+
+        let mut builder = AuctionBuilder::with_tick(0);
+        builder.set_skip_insolvent_bids(true);
+        builder.add_village(&broke, dec!(0));
+        builder.add_village(&seller, dec!(100));
+        // Selling 3 wood pays for 3 food; the standalone wood bid can't be paid for
+        builder.add_order(&broke, order(ResourceType::Wood, false, 3));
+        builder.add_order(&broke, order(ResourceType::Food, true, 3));
+        builder.add_order(&broke, order(ResourceType::Wood, true, 2));
+        builder.add_order(&seller, order(ResourceType::Food, false, 3));
+        builder.add_order(&seller, order(ResourceType::Wood, true, 3));
+        builder.add_order(&seller, order(ResourceType::Wood, false, 2));
+        let config = builder.auction_config();
+        assert!(config.skip_insolvent_bids);
+        let (orders, participants) = builder.build();
+
+        let result =
+            run_auction_with_config(orders, participants, 1, HashMap::new(), &config).unwrap();
+        let broke_bids: Vec<_> = result
+            .final_fills
+            .iter()
+            .filter(|f| {
+                f.participant_id == ParticipantId(broke.to_participant_id())
+                    && f.order_type == OrderType::Bid
+            })
+            .map(|f| (f.order_id, f.filled_quantity))
+            .collect();
+        assert_eq!(broke_bids, vec![(OrderId(1), 3)]);
+    }
+
+    #[test]
+    fn test_orders_sharing_a_priority_share_a_timestamp() {
+        let village = VillageId("village".to_string());
+        let order = |priority| OrderRequest {
+            resource: ResourceType::Food,
+            is_buy: true,
+            quantity: 1,
+            price: dec!(1),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority,
+            visible_quantity: None,
+        };
+
+        let mut builder = AuctionBuilder::with_tick(5);
+        builder.add_village(&village, dec!(100));
+        for priority in [None, Some(9), Some(2), None, Some(2)] {
+            builder.add_order(&village, order(priority));
+        }
+        let (orders, _) = builder.build();
+
+        let timestamps: Vec<u64> = orders.iter().map(|o| o.timestamp).collect();
+        assert_eq!(timestamps, vec![1, 2, 0, 1, 0]);
+    }
+}
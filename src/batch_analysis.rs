@@ -1,6 +1,7 @@
 //! Batch analysis tools for comparing multiple simulation results.
 
 use crate::analysis::analyze_simulation;
+use crate::metrics::MetricsCalculator;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::ToPrimitive;
 use serde::{Deserialize, Serialize};
@@ -14,6 +15,9 @@ pub struct BatchAnalysisReport {
     pub simulations: Vec<SimulationSummary>,
     pub aggregate_stats: AggregateStatistics,
     pub strategy_performance: HashMap<String, StrategyStats>,
+    /// Fraction of co-occurring pairings where the first strategy's village
+    /// ended with more workers than the second's (ties count as half a win)
+    pub head_to_head: HashMap<(String, String), f64>,
     pub insights: Vec<String>,
 }
 
@@ -133,7 +137,7 @@ pub fn analyze_batch(files: &[PathBuf]) -> Result<BatchAnalysisReport, String> {
             });
         }
 
-        // Calculate Gini coefficient (placeholder - would need actual wealth distribution)
+        // Gini coefficient of the villages' final wealth
         let gini = calculate_gini_from_villages(&analysis.villages);
         all_gini_coeffs.push(gini);
 
@@ -184,6 +188,8 @@ pub fn analyze_batch(files: &[PathBuf]) -> Result<BatchAnalysisReport, String> {
         );
     }
 
+    let head_to_head = compute_head_to_head(&simulations);
+
     // Generate insights
     let insights = generate_batch_insights(&simulations, &aggregate_stats, &strategy_performance);
 
@@ -191,10 +197,52 @@ pub fn analyze_batch(files: &[PathBuf]) -> Result<BatchAnalysisReport, String> {
         simulations,
         aggregate_stats,
         strategy_performance,
+        head_to_head,
         insights,
     })
 }
 
+/// Pairwise win rates on final population between strategies that ran in the
+/// same simulation. Both orderings of each pair are present.
+fn compute_head_to_head(simulations: &[SimulationSummary]) -> HashMap<(String, String), f64> {
+    // (strategy, opponent) -> (wins, contests)
+    let mut tallies: HashMap<(String, String), (f64, usize)> = HashMap::new();
+
+    for sim in simulations {
+        let entrants: Vec<(&str, usize)> = sim
+            .villages
+            .iter()
+            .filter_map(|v| v.strategy.as_deref().map(|s| (s, v.final_population)))
+            .collect();
+
+        for (i, &(a, pop_a)) in entrants.iter().enumerate() {
+            for &(b, pop_b) in &entrants[i + 1..] {
+                if a == b {
+                    continue;
+                }
+                let score_a = match pop_a.cmp(&pop_b) {
+                    std::cmp::Ordering::Greater => 1.0,
+                    std::cmp::Ordering::Equal => 0.5,
+                    std::cmp::Ordering::Less => 0.0,
+                };
+                for (key, score) in [
+                    ((a.to_string(), b.to_string()), score_a),
+                    ((b.to_string(), a.to_string()), 1.0 - score_a),
+                ] {
+                    let tally = tallies.entry(key).or_insert((0.0, 0));
+                    tally.0 += score;
+                    tally.1 += 1;
+                }
+            }
+        }
+    }
+
+    tallies
+        .into_iter()
+        .map(|(pair, (wins, contests))| (pair, wins / contests as f64))
+        .collect()
+}
+
 /// Export batch analysis to CSV
 pub fn export_batch_to_csv(report: &BatchAnalysisReport, output: &Path) -> Result<(), String> {
     use std::io::Write;
@@ -257,34 +305,13 @@ fn extract_strategy_from_id(id: &str) -> Option<String> {
     }
 }
 
+/// Gini coefficient of the villages' final wealth.
 fn calculate_gini_from_villages(villages: &[crate::analysis::VillageAnalysis]) -> f64 {
-    // Simple Gini calculation based on final populations
-    let mut populations: Vec<f64> = villages.iter().map(|v| v.final_population as f64).collect();
-    populations.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-    if populations.is_empty() || populations.iter().all(|&p| p == 0.0) {
-        return 0.0;
-    }
-
-    let n = populations.len() as f64;
-    let sum_of_absolute_differences: f64 = populations
+    let wealth: Vec<f64> = villages
         .iter()
-        .enumerate()
-        .flat_map(|(i, &xi)| {
-            populations
-                .iter()
-                .skip(i + 1)
-                .map(move |&xj| (xi - xj).abs())
-        })
-        .sum();
-
-    let mean_pop = populations.iter().sum::<f64>() / n;
-
-    if mean_pop == 0.0 {
-        return 0.0;
-    }
-
-    sum_of_absolute_differences / (n * n * mean_pop)
+        .map(|v| v.final_wealth.to_f64().unwrap_or(0.0))
+        .collect();
+    MetricsCalculator::calculate_gini_coefficient(&wealth)
 }
 
 fn generate_batch_insights(
@@ -332,3 +359,45 @@ fn generate_batch_insights(
 
     insights
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn village(strategy: &str, final_population: usize) -> VillageSummary {
+        VillageSummary {
+            id: format!("village_0_{}", strategy),
+            strategy: Some(strategy.to_string()),
+            growth_multiplier: 1.0,
+            final_population,
+            trade_profit: Decimal::ZERO,
+            efficiency: 0.0,
+        }
+    }
+
+    fn simulation(villages: Vec<VillageSummary>) -> SimulationSummary {
+        SimulationSummary {
+            file_name: "sim.json".to_string(),
+            total_days: 100,
+            villages,
+            aggregate_survival_rate: 1.0,
+            aggregate_growth_rate: 0.0,
+            total_trades: 0,
+            gini_coefficient: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_head_to_head_win_rate() {
+        let simulations = vec![
+            simulation(vec![village("trading", 12), village("survival", 8)]),
+            simulation(vec![village("survival", 3), village("trading", 7)]),
+        ];
+
+        let head_to_head = compute_head_to_head(&simulations);
+        let rate = |a: &str, b: &str| head_to_head[&(a.to_string(), b.to_string())];
+        assert_eq!(rate("trading", "survival"), 1.0);
+        assert_eq!(rate("survival", "trading"), 0.0);
+        assert_eq!(head_to_head.len(), 2);
+    }
+}
@@ -1,6 +1,9 @@
 //! Enhanced command-line interface for the village model simulation.
 
-use crate::scenario::Scenario;
+use crate::analysis::estimate_carrying_capacity;
+use crate::scenario::{Scenario, SimError, load_scenarios};
+use crate::strategies::{STRATEGY_NAMES, is_known_strategy};
+use crate::visualization::ChartMetric;
 use lexopt::prelude::*;
 use rust_decimal::Decimal;
 use std::path::PathBuf;
@@ -15,6 +18,9 @@ pub struct CliArgs {
     pub days: Option<usize>,
     pub growth_delay: Option<usize>,
     pub random_seed: Option<u64>,
+    /// Seed for scenarios that don't set their own
+    pub default_seed: Option<u64>,
+    pub no_seed: bool,
     pub initial_food: Option<Decimal>,
     pub initial_wood: Option<Decimal>,
     pub initial_money: Option<Decimal>,
@@ -22,7 +28,13 @@ pub struct CliArgs {
     pub verbose: bool,
     pub quiet: bool,
     pub output_file: Option<PathBuf>,
+    pub metrics_json: Option<PathBuf>,
+    pub warm_start: Option<PathBuf>,
+    pub save_snapshot: Option<PathBuf>,
+    /// Print a status line to stderr every this many ticks
+    pub report_interval: Option<usize>,
     pub debug_decisions: Option<String>,
+    pub strict_strategies: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -30,9 +42,12 @@ pub enum Command {
     Run,
     Ui {
         file: PathBuf,
+        break_on: Option<String>,
     },
     Analyze {
         file: PathBuf,
+        /// Write the per-tick Gini of money and wealth to this CSV
+        inequality: Option<PathBuf>,
     },
     Compare {
         files: Vec<PathBuf>,
@@ -51,6 +66,36 @@ pub enum Command {
         file: PathBuf,
         filters: QueryFilters,
     },
+    Summary {
+        file: PathBuf,
+        out: Option<PathBuf>,
+    },
+    TradeFlows {
+        file: PathBuf,
+        out: Option<PathBuf>,
+    },
+    Compact {
+        file: PathBuf,
+        out: Option<PathBuf>,
+        keyframe_interval: Option<usize>,
+    },
+    Rollup {
+        file: PathBuf,
+        bucket_size: usize,
+        out: Option<PathBuf>,
+    },
+    Plot {
+        file: PathBuf,
+        metric: ChartMetric,
+        out: PathBuf,
+    },
+    Validate {
+        dir: PathBuf,
+    },
+    Bench {
+        villages: usize,
+        ticks: usize,
+    },
 }
 
 #[derive(Debug, Clone, Default)]
@@ -71,6 +116,8 @@ impl Default for CliArgs {
             days: None,
             growth_delay: None,
             random_seed: None,
+            default_seed: None,
+            no_seed: false,
             initial_food: None,
             initial_wood: None,
             initial_money: None,
@@ -78,7 +125,12 @@ impl Default for CliArgs {
             verbose: false,
             quiet: false,
             output_file: None,
+            metrics_json: None,
+            warm_start: None,
+            save_snapshot: None,
+            report_interval: None,
             debug_decisions: None,
+            strict_strategies: false,
         }
     }
 }
@@ -88,7 +140,9 @@ pub fn parse_args() -> Result<CliArgs, lexopt::Error> {
     let mut cli_args = CliArgs::default();
     let mut subcommand = None;
     let mut ui_file = None;
+    let mut ui_break_on = None;
     let mut analyze_file = None;
+    let mut analyze_inequality = None;
     let mut explain_file = None;
     let mut compare_files = Vec::new();
     let mut batch_config = None;
@@ -96,6 +150,21 @@ pub fn parse_args() -> Result<CliArgs, lexopt::Error> {
     let mut analyze_batch_output = None;
     let mut query_file = None;
     let mut query_filters = QueryFilters::default();
+    let mut summary_file = None;
+    let mut summary_output = None;
+    let mut trade_flows_file = None;
+    let mut trade_flows_output = None;
+    let mut validate_dir = None;
+    let mut compact_file = None;
+    let mut compact_output = None;
+    let mut compact_keyframes = None;
+    let mut plot_file = None;
+    let mut rollup_file = None;
+    let mut rollup_bucket = None;
+    let mut rollup_output = None;
+    let mut plot_metric = None;
+    let mut plot_output = None;
+    let mut bench_villages = None;
 
     while let Some(arg) = args.next()? {
         match arg {
@@ -112,6 +181,12 @@ pub fn parse_args() -> Result<CliArgs, lexopt::Error> {
                         Some("batch") => batch_config = Some(PathBuf::from(val_str)),
                         Some("analyze-batch") => analyze_batch_files.push(PathBuf::from(val_str)),
                         Some("query") => query_file = Some(PathBuf::from(val_str)),
+                        Some("summary") => summary_file = Some(PathBuf::from(val_str)),
+                        Some("trade-flows") => trade_flows_file = Some(PathBuf::from(val_str)),
+                        Some("validate") => validate_dir = Some(PathBuf::from(val_str)),
+                        Some("compact") => compact_file = Some(PathBuf::from(val_str)),
+                        Some("plot") => plot_file = Some(PathBuf::from(val_str)),
+                        Some("rollup") => rollup_file = Some(PathBuf::from(val_str)),
                         _ => {}
                     }
                 }
@@ -146,6 +221,12 @@ pub fn parse_args() -> Result<CliArgs, lexopt::Error> {
                     cli_args.random_seed = Some(val.parse()?);
                 }
             }
+            Long("default-seed") => {
+                if let Some(Value(val)) = args.next()? {
+                    cli_args.default_seed = Some(val.parse()?);
+                }
+            }
+            Long("no-seed") => cli_args.no_seed = true,
             Long("initial-food") => {
                 if let Some(Value(val)) = args.next()? {
                     cli_args.initial_food = Some(val.parse()?);
@@ -161,6 +242,7 @@ pub fn parse_args() -> Result<CliArgs, lexopt::Error> {
                     cli_args.initial_money = Some(val.parse()?);
                 }
             }
+            Long("strict-strategies") => cli_args.strict_strategies = true,
             Long("debug") => cli_args.debug = true,
             Long("verbose") | Short('v') => cli_args.verbose = true,
             Long("quiet") | Short('q') => cli_args.quiet = true,
@@ -174,11 +256,71 @@ pub fn parse_args() -> Result<CliArgs, lexopt::Error> {
                     let path = PathBuf::from(val.string()?);
                     if subcommand.as_deref() == Some("analyze-batch") {
                         analyze_batch_output = Some(path);
+                    } else if subcommand.as_deref() == Some("summary") {
+                        summary_output = Some(path);
+                    } else if subcommand.as_deref() == Some("trade-flows") {
+                        trade_flows_output = Some(path);
+                    } else if subcommand.as_deref() == Some("compact") {
+                        compact_output = Some(path);
+                    } else if subcommand.as_deref() == Some("plot") {
+                        plot_output = Some(path);
+                    } else if subcommand.as_deref() == Some("rollup") {
+                        rollup_output = Some(path);
                     } else {
                         cli_args.output_file = Some(path);
                     }
                 }
             }
+            Long("metrics-json") => {
+                if let Some(Value(val)) = args.next()? {
+                    cli_args.metrics_json = Some(PathBuf::from(val.string()?));
+                }
+            }
+            Long("warm-start") => {
+                if let Some(Value(val)) = args.next()? {
+                    cli_args.warm_start = Some(PathBuf::from(val.string()?));
+                }
+            }
+            Long("save-snapshot") => {
+                if let Some(Value(val)) = args.next()? {
+                    cli_args.save_snapshot = Some(PathBuf::from(val.string()?));
+                }
+            }
+            Long("report-interval") => {
+                if let Some(Value(val)) = args.next()? {
+                    cli_args.report_interval = Some(val.parse()?);
+                }
+            }
+            Long("inequality") => {
+                if let Some(Value(val)) = args.next()? {
+                    analyze_inequality = Some(PathBuf::from(val.string()?));
+                }
+            }
+            Long("villages") => {
+                if let Some(Value(val)) = args.next()? {
+                    bench_villages = Some(val.parse()?);
+                }
+            }
+            Long("keyframe-every") => {
+                if let Some(Value(val)) = args.next()? {
+                    compact_keyframes = Some(val.parse()?);
+                }
+            }
+            Long("bucket") => {
+                if let Some(Value(val)) = args.next()? {
+                    rollup_bucket = Some(val.parse()?);
+                }
+            }
+            Long("metric") => {
+                if let Some(Value(val)) = args.next()? {
+                    plot_metric = Some(val.parse()?);
+                }
+            }
+            Long("break-on") => {
+                if let Some(Value(val)) = args.next()? {
+                    ui_break_on = Some(val.string()?);
+                }
+            }
             Long("village") => {
                 if let Some(Value(val)) = args.next()? {
                     query_filters.village = Some(val.string()?);
@@ -217,9 +359,11 @@ pub fn parse_args() -> Result<CliArgs, lexopt::Error> {
     cli_args.command = match subcommand.as_deref() {
         Some("ui") => Command::Ui {
             file: ui_file.unwrap_or_else(|| PathBuf::from("simulation_events.json")),
+            break_on: ui_break_on,
         },
         Some("analyze") => Command::Analyze {
             file: analyze_file.unwrap_or_else(|| PathBuf::from("simulation_events.json")),
+            inequality: analyze_inequality,
         },
         Some("compare") => {
             if compare_files.is_empty() {
@@ -262,6 +406,36 @@ pub fn parse_args() -> Result<CliArgs, lexopt::Error> {
                 std::process::exit(1);
             }
         }
+        Some("summary") => Command::Summary {
+            file: summary_file.unwrap_or_else(|| PathBuf::from("simulation_events.json")),
+            out: summary_output,
+        },
+        Some("trade-flows") => Command::TradeFlows {
+            file: trade_flows_file.unwrap_or_else(|| PathBuf::from("simulation_events.json")),
+            out: trade_flows_output,
+        },
+        Some("compact") => Command::Compact {
+            file: compact_file.unwrap_or_else(|| PathBuf::from("simulation_events.json")),
+            out: compact_output,
+            keyframe_interval: compact_keyframes,
+        },
+        Some("rollup") => Command::Rollup {
+            file: rollup_file.unwrap_or_else(|| PathBuf::from("simulation_events.json")),
+            bucket_size: rollup_bucket.unwrap_or(50),
+            out: rollup_output,
+        },
+        Some("plot") => Command::Plot {
+            file: plot_file.unwrap_or_else(|| PathBuf::from("simulation_events.json")),
+            metric: plot_metric.unwrap_or(ChartMetric::Population),
+            out: plot_output.unwrap_or_else(|| PathBuf::from("chart.png")),
+        },
+        Some("validate") => Command::Validate {
+            dir: validate_dir.unwrap_or_else(|| PathBuf::from("scenarios")),
+        },
+        Some("bench") => Command::Bench {
+            villages: bench_villages.unwrap_or(10),
+            ticks: cli_args.days.unwrap_or(5000),
+        },
         Some("run") | None => Command::Run,
         Some(cmd) => {
             eprintln!("Unknown command: {}", cmd);
@@ -283,8 +457,14 @@ pub fn apply_overrides(scenario: &mut Scenario, args: &CliArgs) {
         scenario.parameters.days_before_growth_chance = delay;
     }
 
-    if let Some(seed) = args.random_seed {
+    // --no-seed wins over both --seed and the scenario's own seed, which
+    // in turn win over --default-seed
+    if args.no_seed {
+        scenario.random_seed = None;
+    } else if let Some(seed) = args.random_seed {
         scenario.random_seed = Some(seed);
+    } else if scenario.random_seed.is_none() {
+        scenario.random_seed = args.default_seed;
     }
 
     // Apply initial resource overrides to all villages
@@ -301,10 +481,65 @@ pub fn apply_overrides(scenario: &mut Scenario, args: &CliArgs) {
     }
 }
 
+/// Load the scenarios selected by `args` (a scenario file may hold several),
+/// apply CLI overrides to each and validate them.
+pub fn load_configured_scenarios(args: &CliArgs) -> Result<Vec<Scenario>, SimError> {
+    let mut scenarios = load_scenarios(args.scenario_file.as_deref(), &args.scenario_name)?;
+    for scenario in &mut scenarios {
+        apply_overrides(scenario, args);
+        scenario.validate().map_err(SimError::Validation)?;
+        // Reject unknown strategies (and mismatched counts in strict mode) before running
+        check_strategy_assignment(scenario, args).map_err(SimError::Validation)?;
+    }
+    Ok(scenarios)
+}
+
+/// Check `--strategy` flags against the scenario's villages.
+///
+/// Unknown strategy names are always an error. A strategy count that doesn't
+/// match the village count produces a warning (strategies are cycled), or an
+/// error when `--strict-strategies` is set.
+pub fn check_strategy_assignment(
+    scenario: &Scenario,
+    args: &CliArgs,
+) -> Result<Vec<String>, String> {
+    let mut warnings = Vec::new();
+
+    if let Some(unknown) = args.strategies.iter().find(|s| !is_known_strategy(s)) {
+        return Err(format!(
+            "Unknown strategy '{}'. Available: {}",
+            unknown,
+            STRATEGY_NAMES.join(", ")
+        ));
+    }
+
+    let village_count = scenario.villages.len();
+    if !args.strategies.is_empty() && args.strategies.len() != village_count {
+        let message = format!(
+            "{} strategies given for {} villages",
+            args.strategies.len(),
+            village_count
+        );
+        if args.strict_strategies {
+            return Err(format!("{} (--strict-strategies)", message));
+        }
+        warnings.push(format!("{}; strategies will be cycled", message));
+    }
+
+    Ok(warnings)
+}
+
 /// Validate scenario configuration and print warnings.
 pub fn validate_scenario(scenario: &Scenario, args: &CliArgs) {
     let params = &scenario.parameters;
 
+    // Check strategy assignment (errors are reported by the caller)
+    if let Ok(warnings) = check_strategy_assignment(scenario, args) {
+        for warning in warnings {
+            println!("⚠️  WARNING: {}\n", warning);
+        }
+    }
+
     // Check growth timing issue
     if params.days_before_growth_chance >= params.days_to_simulate {
         println!(
@@ -332,6 +567,18 @@ pub fn validate_scenario(scenario: &Scenario, args: &CliArgs) {
         }
     }
 
+    // Check for villages starting above what their land can sustain
+    for village in scenario.villages.iter().filter(|v| !v.is_market_agent) {
+        let capacity = estimate_carrying_capacity(village, params);
+        if village.initial_workers > capacity {
+            println!(
+                "⚠️  WARNING: Village {}'s carrying capacity is ~{} but it starts with {} workers",
+                village.id, capacity, village.initial_workers
+            );
+            println!("   Expect starvation or exposure unless it trades for food and wood\n");
+        }
+    }
+
     // Check for insufficient starting resources
     for village in scenario.villages.iter() {
         let min_food_needed = Decimal::from(village.initial_workers * 10);
@@ -360,13 +607,35 @@ fn print_help() {
 
     println!("COMMANDS:");
     println!("    run              Run the simulation (default)");
-    println!("    ui [FILE]        View simulation events in TUI");
-    println!("    analyze [FILE]   Analyze simulation results");
+    println!("    ui [FILE] [--break-on TYPE]  View simulation events in TUI");
+    println!("    analyze [FILE] [--inequality CSV]  Analyze simulation results");
+    println!("                     (--inequality exports the per-tick Gini of money and wealth)");
     println!("    compare FILE...  Compare multiple simulation results");
     println!("    explain [FILE]   Generate narrative explanation of events");
+    println!(
+        "                     (with --scenario-file, compares villages to their carrying capacity)"
+    );
     println!("    batch CONFIG     Run batch experiments from YAML config");
     println!("    analyze-batch FILE... [-o OUTPUT]  Analyze multiple results and export");
-    println!("    query FILE [OPTIONS]  Query and filter simulation events\n");
+    println!("    query FILE [OPTIONS]  Query and filter simulation events");
+    println!(
+        "    summary [FILE] [-o OUTPUT]  Final state of each village (CSV, or JSON for .json)"
+    );
+    println!("    trade-flows [FILE] [-o OUTPUT]  Inferred buyer/seller pairs per trade (CSV)");
+    println!(
+        "    compact [FILE] [-o OUTPUT] [--keyframe-every N]  Drop unchanged snapshots (default OUTPUT FILE.compact.json)"
+    );
+    println!(
+        "    rollup [FILE] [--bucket N] [-o OUTPUT]  Per-village totals every N ticks (CSV, default 50)"
+    );
+    println!(
+        "    plot [FILE] [--metric M] [-o OUTPUT]  Line chart of population, houses, food, wood,"
+    );
+    println!("                     money or prices (PNG, or SVG for .svg; needs --features plot)");
+    println!("    validate [DIR]   Check every scenario file in DIR (default: scenarios)");
+    println!(
+        "    bench [--villages N] [--days N]  Time a fixed seeded run (default 10 villages, 5000 ticks)\n"
+    );
 
     println!("SIMULATION OPTIONS:");
     println!("    -s, --strategy <NAME>      Strategy for villages (can be used multiple times)");
@@ -376,13 +645,25 @@ fn print_help() {
     println!("    --scenario-file <FILE>     Load scenario from JSON file");
     println!("    -d, --days <N>             Number of days to simulate");
     println!("    --growth-delay <N>         Days before population growth possible");
-    println!("    --seed <N>                 Random seed for reproducible runs");
+    println!("    --strict-strategies        Error if strategy count doesn't match villages");
+    println!(
+        "    --seed <N>                 Random seed for reproducible runs (overrides scenario)"
+    );
+    println!("    --default-seed <N>         Random seed for scenarios that don't set one");
+    println!("    --no-seed                  Ignore any seed and run nondeterministically");
     println!("    --initial-food <N>         Override initial food for all villages");
     println!("    --initial-wood <N>         Override initial wood for all villages");
-    println!("    --initial-money <N>        Override initial money for all villages\n");
+    println!("    --initial-money <N>        Override initial money for all villages");
+    println!(
+        "    --warm-start <FILE>        Start villages from their final state in an events file,"
+    );
+    println!("                               or resume exactly from a saved snapshot\n");
 
     println!("OUTPUT OPTIONS:");
     println!("    -o, --output <FILE>        Output events to specified file");
+    println!("    --metrics-json <FILE>      Save the run's metrics as JSON");
+    println!("    --save-snapshot <FILE>     Save the final state of every village as JSON");
+    println!("    --report-interval <N>      Print population and prices to stderr every N ticks");
     println!("    --debug                    Enable debug output");
     println!("    -v, --verbose              Enable verbose output");
     println!("    -q, --quiet                Suppress non-essential output");
@@ -395,11 +676,16 @@ fn print_help() {
     println!("    --resource <TYPE>          Filter by resource type (food/wood)");
     println!("    --tick-range <START-END>   Filter by tick range (e.g., 0-100)\n");
 
+    println!("UI OPTIONS:");
+    println!("    --break-on <TYPE>          Pause playback when an event of TYPE occurs");
+    println!("                               (query event types, or starvation)\n");
+
     println!("UI CONTROLS:");
     println!("    Space            Pause/Resume playback");
     println!("    ←/→              Step backward/forward through events");
     println!("    Home/End         Jump to beginning/end");
     println!("    +/-              Faster/slower playback");
+    println!("    B                Cycle breakpoint (starvation, died, completed, off)");
     println!("    Tab              Switch between views");
     println!("    Q                Quit\n");
 
@@ -416,3 +702,78 @@ fn print_help() {
     println!("    # Compare different strategies");
     println!("    village-model-sim compare survival.json growth.json trading.json");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::create_standard_scenarios;
+
+    fn basic_scenario() -> Scenario {
+        create_standard_scenarios()["basic"].clone()
+    }
+
+    #[test]
+    fn test_strategy_count_mismatch_warns() {
+        let scenario = basic_scenario();
+        let args = CliArgs {
+            strategies: vec!["survival".to_string()],
+            ..Default::default()
+        };
+        assert!(scenario.villages.len() > 1);
+
+        let warnings = check_strategy_assignment(&scenario, &args).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("cycled"));
+    }
+
+    #[test]
+    fn test_strategy_count_mismatch_errors_when_strict() {
+        let scenario = basic_scenario();
+        let args = CliArgs {
+            strategies: vec!["survival".to_string()],
+            strict_strategies: true,
+            ..Default::default()
+        };
+
+        assert!(check_strategy_assignment(&scenario, &args).is_err());
+    }
+
+    #[test]
+    fn test_matching_strategy_count_has_no_warnings() {
+        let scenario = basic_scenario();
+        let args = CliArgs {
+            strategies: vec!["Trading".to_string(); scenario.villages.len()],
+            strict_strategies: true,
+            ..Default::default()
+        };
+
+        assert_eq!(check_strategy_assignment(&scenario, &args), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_unknown_strategy_name_errors() {
+        let scenario = basic_scenario();
+        let args = CliArgs {
+            strategies: vec!["survival".to_string(), "hoarding".to_string()],
+            ..Default::default()
+        };
+
+        let err = check_strategy_assignment(&scenario, &args).unwrap_err();
+        assert!(err.contains("hoarding"));
+    }
+
+    #[test]
+    fn test_no_seed_clears_scenario_and_cli_seed() {
+        let mut scenario = basic_scenario();
+        scenario.random_seed = Some(3);
+        let args = CliArgs {
+            random_seed: Some(7),
+            no_seed: true,
+            ..Default::default()
+        };
+
+        apply_overrides(&mut scenario, &args);
+
+        assert_eq!(scenario.random_seed, None);
+    }
+}
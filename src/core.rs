@@ -1,20 +1,29 @@
+use crate::events::ResourceType;
+use crate::scenario::{Good, LaborEfficiency};
+use crate::strategies::{DefaultPrices, OrderBook};
 use rand;
 use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Worker {
     pub id: usize,
-    pub days_without_food: u32,
+    /// Fractional with `partial_rations`, where a half ration adds half a day
+    pub days_without_food: Decimal,
     pub days_without_shelter: u32,
     pub days_with_both: u32,
     pub spawn_eligible: bool,
+    /// Days lived (initial workers start at 0 when the run begins)
+    pub age: u32,
 }
 
 impl Worker {
     pub fn productivity(&self) -> Decimal {
         let mut productivity = dec!(1.0);
-        if self.days_without_food > 0 {
+        if self.days_without_food > Decimal::ZERO {
             productivity -= dec!(0.2);
         }
         if self.days_without_shelter > 0 {
@@ -24,11 +33,11 @@ impl Worker {
     }
 }
 
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct House {
     pub id: usize,
     /// Negative means wood is still needed for full repair in whole units.
-    /// Positive or zero never exceeds 5 total capacity.
+    /// Positive levels come from upgrades and add 1 capacity per whole unit.
     /// Decreases by 0.1 per day if unmaintained.
     pub maintenance_level: Decimal,
 }
@@ -41,16 +50,121 @@ impl House {
             let lost_capacity = needed.min(dec!(5));
             dec!(5) - lost_capacity
         } else {
-            dec!(5)
+            // Each full positive point of upgrades adds 1 capacity
+            // (the upgrade path caps the level, so no cap is needed here)
+            dec!(5) + self.maintenance_level.floor()
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Allocation {
     pub wood: Decimal,
     pub food: Decimal,
     pub house_construction: Decimal,
+    /// Wood (not labor) to spend upgrading houses this tick
+    pub house_upgrade_wood: Decimal,
+    /// Worker-days spent running the scenario's recipes
+    pub processing: Decimal,
+    /// Worker-days spent clearing land or building workshops for a new slot
+    pub slot_expansion: Decimal,
+    /// Resource whose full-productivity slots the expansion adds to
+    pub slot_expansion_target: ResourceType,
+    /// Worker-days spent repairing decayed houses, on top of passive upkeep
+    pub repair: Decimal,
+}
+
+impl Allocation {
+    /// The same allocation with every labor amount multiplied by `factor`
+    /// (wood for upgrades is not labor and is left unchanged).
+    pub fn scaled(&self, factor: Decimal) -> Allocation {
+        Allocation {
+            wood: self.wood * factor,
+            food: self.food * factor,
+            house_construction: self.house_construction * factor,
+            house_upgrade_wood: self.house_upgrade_wood,
+            processing: self.processing * factor,
+            slot_expansion: self.slot_expansion * factor,
+            slot_expansion_target: self.slot_expansion_target,
+            repair: self.repair * factor,
+        }
+    }
+
+    /// Total worker-days across every labor use.
+    pub fn labor(&self) -> Decimal {
+        self.wood
+            + self.food
+            + self.house_construction
+            + self.processing
+            + self.slot_expansion
+            + self.repair
+    }
+
+    /// The same allocation with negative amounts zeroed and the labor scaled
+    /// proportionally to sum to exactly `worker_days`. An allocation with no
+    /// labor at all splits the worker-days evenly between food and wood.
+    pub fn normalized(&self, worker_days: Decimal) -> Allocation {
+        let clamped = Allocation {
+            wood: self.wood.max(Decimal::ZERO),
+            food: self.food.max(Decimal::ZERO),
+            house_construction: self.house_construction.max(Decimal::ZERO),
+            house_upgrade_wood: self.house_upgrade_wood.max(Decimal::ZERO),
+            processing: self.processing.max(Decimal::ZERO),
+            slot_expansion: self.slot_expansion.max(Decimal::ZERO),
+            slot_expansion_target: self.slot_expansion_target,
+            repair: self.repair.max(Decimal::ZERO),
+        };
+        let total = clamped.labor();
+        if total.is_zero() {
+            let half = worker_days / Decimal::TWO;
+            return Allocation {
+                wood: half,
+                food: worker_days - half,
+                house_construction: Decimal::ZERO,
+                processing: Decimal::ZERO,
+                slot_expansion: Decimal::ZERO,
+                repair: Decimal::ZERO,
+                ..clamped
+            };
+        }
+
+        let scale = |amount: Decimal| amount * worker_days / total;
+        let mut normalized = Allocation {
+            wood: scale(clamped.wood),
+            food: scale(clamped.food),
+            house_construction: scale(clamped.house_construction),
+            processing: scale(clamped.processing),
+            slot_expansion: scale(clamped.slot_expansion),
+            repair: scale(clamped.repair),
+            ..clamped
+        };
+        // Division can leave a rounding remainder; food absorbs it
+        normalized.food += worker_days - normalized.labor();
+        normalized
+    }
+}
+
+/// Produced output that joins the stockpile at `available_at`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingProduction {
+    pub available_at: usize,
+    pub resource: ResourceType,
+    pub amount: Decimal,
+}
+
+/// Money and goods on hand, the inputs to a wealth valuation
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Holdings {
+    pub money: Decimal,
+    pub food: Decimal,
+    pub wood: Decimal,
+}
+
+impl Holdings {
+    /// Money plus wood and food valued at the given prices
+    pub fn value(&self, wood_price: Decimal, food_price: Decimal) -> Decimal {
+        self.money + self.wood * wood_price + self.food * food_price
+    }
 }
 
 pub struct Village {
@@ -58,12 +172,23 @@ pub struct Village {
     pub id_str: String,
     pub wood: Decimal,
     pub food: Decimal,
+    /// Food set aside by `granary_fraction`; only eaten when `food` runs short
+    /// and out of reach of trading
+    pub granary: Decimal,
+    /// Capital goods that raise worker productivity under `tools`
+    pub tools: Decimal,
+    /// Output still waiting out `production_lag`, oldest first
+    pub pending_production: Vec<PendingProduction>,
     pub money: Decimal,
     pub wood_slots: (u32, u32),
     pub food_slots: (u32, u32),
     pub workers: Vec<Worker>,
     pub houses: Vec<House>,
     pub construction_progress: Decimal,
+    pub slot_expansion_progress: Decimal,
+    /// Multipliers on everything the food and wood slots produce
+    pub food_quality: Decimal,
+    pub wood_quality: Decimal,
 
     // For tracking births/deaths
     pub next_worker_id: usize,
@@ -73,24 +198,333 @@ pub struct Village {
     pub rng: Option<rand::rngs::StdRng>,
 }
 
+/// The village totals a `VillageStateSnapshot` event records; enough to
+/// rebuild an approximate `Village` with [`Village::from_snapshot`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct VillageTotals {
+    pub population: usize,
+    pub houses: usize,
+    pub food: Decimal,
+    pub wood: Decimal,
+    pub money: Decimal,
+}
+
+/// Everything needed to restore a `Village` exactly, except its lifecycle RNG.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VillageSnapshot {
+    pub id: usize,
+    pub id_str: String,
+    pub wood: Decimal,
+    pub food: Decimal,
+    pub granary: Decimal,
+    #[serde(default)]
+    pub tools: Decimal,
+    pub pending_production: Vec<PendingProduction>,
+    pub money: Decimal,
+    pub wood_slots: (u32, u32),
+    pub food_slots: (u32, u32),
+    pub workers: Vec<Worker>,
+    pub houses: Vec<House>,
+    pub construction_progress: Decimal,
+    pub slot_expansion_progress: Decimal,
+    pub food_quality: Decimal,
+    pub wood_quality: Decimal,
+    pub next_worker_id: usize,
+    pub next_house_id: usize,
+}
+
+/// Every village's state plus the market's, taken between ticks so a run
+/// can be saved and resumed.
+///
+/// RNGs aren't saved: seeded runs derive them afresh each tick, so a
+/// resumed run draws what the uninterrupted run would have.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationSnapshot {
+    /// The tick a resumed run continues from
+    pub next_tick: usize,
+    /// Villages still running; any others went bankrupt
+    pub villages: Vec<VillageSnapshot>,
+    pub last_clearing_prices: HashMap<ResourceType, Decimal>,
+    /// Order books strategies see on the next tick
+    #[serde(default)]
+    pub wood_book: OrderBook,
+    #[serde(default)]
+    pub food_book: OrderBook,
+    /// Consecutive insolvent ticks per village id, for `bankruptcy`
+    #[serde(default)]
+    pub insolvent_ticks: HashMap<String, usize>,
+    /// Current price of each resource on the external market
+    #[serde(default)]
+    pub external_prices: HashMap<ResourceType, Decimal>,
+}
+
 impl Village {
+    /// Captures the village's full state for `restore`.
+    pub fn snapshot(&self) -> VillageSnapshot {
+        VillageSnapshot {
+            id: self.id,
+            id_str: self.id_str.clone(),
+            wood: self.wood,
+            food: self.food,
+            granary: self.granary,
+            tools: self.tools,
+            pending_production: self.pending_production.clone(),
+            money: self.money,
+            wood_slots: self.wood_slots,
+            food_slots: self.food_slots,
+            workers: self.workers.clone(),
+            houses: self.houses.clone(),
+            construction_progress: self.construction_progress,
+            slot_expansion_progress: self.slot_expansion_progress,
+            food_quality: self.food_quality,
+            wood_quality: self.wood_quality,
+            next_worker_id: self.next_worker_id,
+            next_house_id: self.next_house_id,
+        }
+    }
+
+    /// Rebuilds a village exactly as it was when `snapshot` was taken.
+    ///
+    /// The lifecycle RNG can't be serialized, so the result has none; set
+    /// `rng` to keep births and deaths reproducible.
+    pub fn restore(snapshot: VillageSnapshot) -> Self {
+        Self {
+            id: snapshot.id,
+            id_str: snapshot.id_str,
+            wood: snapshot.wood,
+            food: snapshot.food,
+            granary: snapshot.granary,
+            tools: snapshot.tools,
+            pending_production: snapshot.pending_production,
+            money: snapshot.money,
+            wood_slots: snapshot.wood_slots,
+            food_slots: snapshot.food_slots,
+            workers: snapshot.workers,
+            houses: snapshot.houses,
+            construction_progress: snapshot.construction_progress,
+            slot_expansion_progress: snapshot.slot_expansion_progress,
+            food_quality: snapshot.food_quality,
+            wood_quality: snapshot.wood_quality,
+            next_worker_id: snapshot.next_worker_id,
+            next_house_id: snapshot.next_house_id,
+            rng: None,
+        }
+    }
+
+    /// Rebuilds a village from the totals in its last state snapshot.
+    ///
+    /// Snapshots don't record individual workers or houses, so the result is
+    /// approximate: workers start fed, sheltered and aged 0, houses are fully
+    /// maintained, granary food is returned to the main stock, and construction
+    /// progress and output still waiting out `production_lag` are lost.
+    pub fn from_snapshot(
+        id: usize,
+        id_str: String,
+        wood_slots: (u32, u32),
+        food_slots: (u32, u32),
+        snapshot: &VillageTotals,
+    ) -> Self {
+        Self {
+            id,
+            id_str,
+            wood: snapshot.wood,
+            food: snapshot.food,
+            granary: dec!(0),
+            tools: dec!(0),
+            pending_production: Vec::new(),
+            money: snapshot.money,
+            wood_slots,
+            food_slots,
+            workers: (0..snapshot.population)
+                .map(|id| Worker {
+                    id,
+                    days_without_food: Decimal::ZERO,
+                    days_without_shelter: 0,
+                    days_with_both: 0,
+                    spawn_eligible: false,
+                    age: 0,
+                })
+                .collect(),
+            houses: (0..snapshot.houses)
+                .map(|id| House {
+                    id,
+                    maintenance_level: dec!(0),
+                })
+                .collect(),
+            construction_progress: dec!(0),
+            slot_expansion_progress: dec!(0),
+            food_quality: dec!(1),
+            wood_quality: dec!(1),
+            next_worker_id: snapshot.population,
+            next_house_id: snapshot.houses,
+            rng: None,
+        }
+    }
+
     pub fn worker_days(&self) -> Decimal {
         self.workers.iter().map(|w| w.productivity()).sum()
     }
 
-    /// Check if a new worker should spawn (5% chance)
-    pub fn should_spawn_worker(&mut self) -> bool {
+    /// Worker-days the village can allocate once `efficiency` is applied to
+    /// the raw [`worker_days`](Self::worker_days).
+    pub fn effective_worker_days(&self, efficiency: LaborEfficiency) -> Decimal {
+        efficiency.effective(self.worker_days())
+    }
+
+    /// Current stock of a good
+    pub fn good(&self, good: Good) -> Decimal {
+        match good {
+            Good::Food => self.food,
+            Good::Wood => self.wood,
+            Good::Money => self.money,
+            Good::Tools => self.tools,
+        }
+    }
+
+    /// Mutable stock of a good
+    pub fn good_mut(&mut self, good: Good) -> &mut Decimal {
+        match good {
+            Good::Food => &mut self.food,
+            Good::Wood => &mut self.wood,
+            Good::Money => &mut self.money,
+            Good::Tools => &mut self.tools,
+        }
+    }
+
+    /// Workers the houses can shelter when each occupies `shelter_per_worker`
+    pub fn housing_capacity(&self, shelter_per_worker: Decimal) -> usize {
+        (self
+            .houses
+            .iter()
+            .map(|h| h.shelter_effect())
+            .sum::<Decimal>()
+            / shelter_per_worker)
+            .floor()
+            .to_usize()
+            .unwrap_or(0)
+    }
+
+    /// Money, wood and food, the granary included
+    pub fn holdings(&self) -> Holdings {
+        Holdings {
+            money: self.money,
+            food: self.food + self.granary,
+            wood: self.wood,
+        }
+    }
+
+    /// Money plus wood and food (granary included) valued at the given prices
+    pub fn total_wealth(&self, wood_price: Decimal, food_price: Decimal) -> Decimal {
+        self.holdings().value(wood_price, food_price)
+    }
+
+    /// [`Village::total_wealth`] at a scenario's default prices
+    pub fn net_worth(&self, prices: DefaultPrices) -> Decimal {
+        self.total_wealth(prices.wood, prices.food)
+    }
+
+    /// Moves pending output that has matured by `tick` into the stockpile
+    pub fn release_pending_production(&mut self, tick: usize) {
+        let (matured, pending) = std::mem::take(&mut self.pending_production)
+            .into_iter()
+            .partition(|p| p.available_at <= tick);
+        self.pending_production = pending;
+        for output in matured {
+            match output.resource {
+                ResourceType::Food => self.food += output.amount,
+                ResourceType::Wood => self.wood += output.amount,
+                ResourceType::Labor => {}
+            }
+        }
+    }
+
+    /// Adds a fresh worker and returns its id
+    pub fn add_worker(&mut self) -> usize {
+        let id = self.next_worker_id;
+        self.next_worker_id += 1;
+        self.workers.push(Worker {
+            id,
+            days_without_food: Decimal::ZERO,
+            days_without_shelter: 0,
+            days_with_both: 0,
+            spawn_eligible: false,
+            age: 0,
+        });
+        id
+    }
+
+    /// Days the food stock lasts at 1 food per worker per day
+    pub fn food_days(&self) -> Decimal {
+        if self.workers.is_empty() {
+            return Decimal::ZERO;
+        }
+        self.food / Decimal::from(self.workers.len())
+    }
+
+    /// Days the wood stock covers basic upkeep (0.1 wood per house per day)
+    pub fn wood_days(&self) -> Decimal {
+        if self.houses.is_empty() {
+            return Decimal::ZERO;
+        }
+        self.wood / (dec!(0.1) * Decimal::from(self.houses.len()))
+    }
+
+    /// Roll whether a worker dies of old age, with the given chance
+    pub fn dies_of_old_age(&mut self, chance: f64) -> bool {
         use rand::Rng;
 
+        let chance = chance.clamp(0.0, 1.0);
         if let Some(ref mut rng) = self.rng {
-            rng.random_bool(0.05)
+            rng.random_bool(chance)
+        } else {
+            rand::rng().random_bool(chance)
+        }
+    }
+
+    /// Draw a uniform shock in [-1, 1], e.g. for a random-walk step
+    pub fn random_shock(&mut self) -> f64 {
+        use rand::Rng;
+
+        if let Some(ref mut rng) = self.rng {
+            rng.random_range(-1.0..=1.0)
+        } else {
+            rand::rng().random_range(-1.0..=1.0)
+        }
+    }
+
+    /// Check if a new worker should spawn (5% chance, multiplied by `factor`)
+    pub fn should_spawn_worker(&mut self, factor: f64) -> bool {
+        use rand::Rng;
+
+        let chance = (0.05 * factor).min(1.0);
+        if let Some(ref mut rng) = self.rng {
+            rng.random_bool(chance)
         } else {
             // Fallback to thread_rng if no RNG is set
-            rand::rng().random_bool(0.05)
+            rand::rng().random_bool(chance)
         }
     }
 }
 
+/// Derives an independent seed for `index` within `namespace` from a base seed.
+///
+/// Uses a fixed hash (FNV-1a over the namespace, then a splitmix64 finalizer)
+/// so derived seeds are stable across platforms and Rust releases.
+pub fn derive_seed(base_seed: u64, namespace: &str, index: u64) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in namespace.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    let mut z = base_seed
+        .wrapping_add(hash)
+        .wrapping_add(index.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
 pub trait Strategy {
     fn decide_allocation_and_bids_asks(
         &self,
@@ -99,3 +533,113 @@ pub trait Strategy {
         _asks: &[(Decimal, u32, usize)],
     ) -> (Allocation, (Decimal, u32), (Decimal, u32));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn house(maintenance_level: Decimal) -> House {
+        House {
+            id: 0,
+            maintenance_level,
+        }
+    }
+
+    fn allocation(wood: Decimal, food: Decimal, house_construction: Decimal) -> Allocation {
+        Allocation {
+            wood,
+            food,
+            house_construction,
+            house_upgrade_wood: Decimal::ZERO,
+            processing: Decimal::ZERO,
+            slot_expansion: Decimal::ZERO,
+            slot_expansion_target: ResourceType::Food,
+            repair: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_normalized_scales_overallocation_down_to_worker_days() {
+        // 120% of 10 worker-days
+        let normalized = allocation(dec!(6), dec!(3.6), dec!(2.4)).normalized(dec!(10));
+        assert_eq!(normalized.labor(), dec!(10));
+        assert_eq!(normalized.wood, dec!(5));
+        assert_eq!(normalized.food, dec!(3));
+        assert_eq!(normalized.house_construction, dec!(2));
+    }
+
+    #[test]
+    fn test_normalized_zeroes_negatives_and_defaults_empty_allocation() {
+        let normalized = allocation(dec!(-4), dec!(3), dec!(0)).normalized(dec!(6));
+        assert_eq!(normalized.wood, dec!(0));
+        assert_eq!(normalized.food, dec!(6));
+
+        let fallback = allocation(dec!(0), dec!(0), dec!(0)).normalized(dec!(7));
+        assert_eq!(fallback.labor(), dec!(7));
+        assert_eq!(fallback.wood, dec!(3.5));
+        assert_eq!(fallback.food, dec!(3.5));
+    }
+
+    #[test]
+    fn test_derive_seed_separates_namespaces_and_indices() {
+        assert_eq!(
+            derive_seed(42, "strategy", 0),
+            derive_seed(42, "strategy", 0)
+        );
+        assert_ne!(
+            derive_seed(42, "strategy", 0),
+            derive_seed(42, "strategy", 1)
+        );
+        assert_ne!(
+            derive_seed(42, "strategy", 0),
+            derive_seed(42, "village", 0)
+        );
+        assert_ne!(
+            derive_seed(42, "strategy", 0),
+            derive_seed(43, "strategy", 0)
+        );
+    }
+
+    #[test]
+    fn test_shelter_effect_base_and_decayed() {
+        assert_eq!(house(dec!(0.0)).shelter_effect(), dec!(5));
+        assert_eq!(house(dec!(-0.5)).shelter_effect(), dec!(5));
+        assert_eq!(house(dec!(-2.3)).shelter_effect(), dec!(3));
+        assert_eq!(house(dec!(-9.0)).shelter_effect(), dec!(0));
+    }
+
+    #[test]
+    fn test_shelter_effect_upgraded() {
+        assert_eq!(house(dec!(0.9)).shelter_effect(), dec!(5));
+        assert_eq!(house(dec!(1.0)).shelter_effect(), dec!(6));
+        assert_eq!(house(dec!(2.7)).shelter_effect(), dec!(7));
+    }
+
+    #[test]
+    fn test_total_wealth_values_inventory_at_prices() {
+        let village = Village {
+            id: 0,
+            id_str: "village".to_string(),
+            wood: dec!(10),
+            food: dec!(20),
+            granary: dec!(0),
+            tools: dec!(0),
+            pending_production: Vec::new(),
+            money: dec!(100),
+            wood_slots: (0, 0),
+            food_slots: (0, 0),
+            workers: Vec::new(),
+            houses: Vec::new(),
+            construction_progress: dec!(0),
+            slot_expansion_progress: dec!(0),
+            food_quality: dec!(1),
+            wood_quality: dec!(1),
+            next_worker_id: 0,
+            next_house_id: 0,
+            rng: None,
+        };
+
+        assert_eq!(village.total_wealth(dec!(5), dec!(1)), dec!(170));
+        assert_eq!(village.net_worth(DefaultPrices::default()), dec!(170));
+    }
+}
@@ -11,7 +11,7 @@ pub struct Event {
     pub event_type: EventType,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum EventType {
     ResourceProduced {
@@ -62,6 +62,17 @@ pub enum EventType {
         repair_workers: usize,
         idle_workers: usize,
     },
+    /// Worker-days a strategy allocated, as it returned them, next to the
+    /// worker-days the village actually has
+    AllocationIntent {
+        wood: Decimal,
+        food: Decimal,
+        construction: Decimal,
+        processing: Decimal,
+        slot_expansion: Decimal,
+        repair: Decimal,
+        worker_days: Decimal,
+    },
     VillageStateSnapshot {
         population: usize,
         houses: usize,
@@ -76,28 +87,185 @@ pub enum EventType {
         food_volume: u64,
         total_participants: usize,
     },
+    AgeCensus {
+        ages: Vec<u32>,
+    },
+    SlotAdded {
+        resource: ResourceType,
+        total_slots: u32,
+    },
+    /// Worker-days allocated to a resource beyond what its slots can use
+    LaborWasted {
+        resource: ResourceType,
+        wasted_worker_days: Decimal,
+    },
+    /// A price control held the clearing price at its bound, leaving
+    /// `rationed_quantity` of the long side unfilled
+    PriceControlBinding {
+        resource: ResourceType,
+        control: PriceControlKind,
+        price: Decimal,
+        unconstrained_price: Decimal,
+        rationed_quantity: u64,
+    },
+    /// A dead worker's estate passed to the village
+    EstateInherited {
+        worker_id: usize,
+        food: Decimal,
+    },
+    /// Food bought through the emergency backstop so workers could eat
+    FoodImported {
+        amount: Decimal,
+        cost: Decimal,
+    },
+    /// A global production modifier started (`active`) or ended
+    GlobalModifierActive {
+        resource: ResourceType,
+        multiplier: Decimal,
+        active: bool,
+    },
+    /// The current factor of a seasonal production cycle, logged each quarter period
+    SeasonalFactor {
+        resource: ResourceType,
+        factor: Decimal,
+    },
+    /// Gains from trade the auction realized against the most its orders allowed
+    MarketSurplus {
+        realized: Decimal,
+        potential: Decimal,
+    },
+    /// The external market's price for a resource this tick
+    ExternalPrice {
+        resource: ResourceType,
+        price: Decimal,
+    },
+    /// A bid was clamped to the market's maximum move from the last price
+    PriceLimitHit {
+        resource: ResourceType,
+        limit_price: Decimal,
+        clamped_price: Decimal,
+    },
+    /// A fill's settlement carried one currency unit of the auction's
+    /// rounding remainder; `amount` is negative when it was taken off
+    RoundingRemainder {
+        resource: ResourceType,
+        amount: Decimal,
+    },
+    /// A village's flows for one tick, valued at the prices its strategy saw.
+    /// `trade_cash` is money received from sales less money paid for purchases.
+    CashFlow {
+        production_value: Decimal,
+        consumption_value: Decimal,
+        trade_cash: Decimal,
+        maintenance_cost: Decimal,
+    },
+    /// A house decayed past the collapse threshold and was removed
+    HouseCollapsed {
+        house_id: usize,
+        maintenance_level: Decimal,
+        total_houses: usize,
+    },
+    /// An order for an embargoed resource was dropped before the auction
+    OrderBlocked {
+        resource: ResourceType,
+        quantity: Decimal,
+        price: Decimal,
+        side: TradeSide,
+    },
+    /// A fill-or-kill order couldn't fill completely and was cancelled
+    OrderKilled {
+        resource: ResourceType,
+        quantity: Decimal,
+        price: Decimal,
+        side: TradeSide,
+        order_id: String,
+    },
+    /// A village stayed insolvent past the bankruptcy grace period and was
+    /// removed; its stock went to `heir` ("market" when nobody inherits)
+    VillageBankrupt {
+        heir: String,
+        food: Decimal,
+        wood: Decimal,
+        money: Decimal,
+        workers: usize,
+    },
+    /// A house had enough construction progress to complete but couldn't,
+    /// e.g. for lack of wood; `progress` is kept for later ticks
+    ConstructionStalled {
+        reason: StallReason,
+        progress: Decimal,
+    },
+    /// Money taken or given outside of trades, e.g. the auction's listing fee
+    MoneyAdjusted {
+        amount: Decimal,
+        reason: AdjustmentReason,
+    },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ResourceType {
     Food,
     Wood,
+    /// Workers, traded only with `labor_market` enabled. A filled bid adds a
+    /// worker to the village and a filled ask removes one.
+    Labor,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ResourceType {
+    const ALL: [ResourceType; 3] = [ResourceType::Food, ResourceType::Wood, ResourceType::Labor];
+
+    /// Every resource, in declaration order
+    pub fn all() -> &'static [ResourceType] {
+        &Self::ALL
+    }
+
+    /// Number of resources
+    pub fn count() -> usize {
+        Self::ALL.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ConsumptionPurpose {
     WorkerFeeding,
     HouseConstruction,
     HouseMaintenance,
+    HouseUpgrade,
+    Processing,
+    SlotExpansion,
+    Burial,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DeathCause {
     Starvation,
     NoShelter,
+    OldAge,
+    /// Left the village alive; it no longer counts toward deaths
+    Departed,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PriceControlKind {
+    Ceiling,
+    Floor,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StallReason {
+    InsufficientWood,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AdjustmentReason {
+    ListingFee,
+    /// A bankrupt village's negative balance, cancelled rather than inherited
+    DebtWrittenOff,
+    /// A bankrupt village's money, gone from the economy for want of an heir
+    EstateForfeited,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TradeSide {
     Buy,
     Sell,
@@ -136,6 +304,17 @@ impl fmt::Display for Event {
                     worker_id, total_population
                 )
             }
+            EventType::WorkerDied {
+                worker_id,
+                cause: DeathCause::Departed,
+                total_population,
+            } => {
+                write!(
+                    f,
+                    "Worker {} left the village (population: {})",
+                    worker_id, total_population
+                )
+            }
             EventType::WorkerDied {
                 worker_id,
                 cause,
@@ -202,6 +381,21 @@ impl fmt::Display for Event {
                     food_workers, wood_workers, construction_workers, repair_workers, idle_workers
                 )
             }
+            EventType::AllocationIntent {
+                wood,
+                food,
+                construction,
+                processing,
+                slot_expansion,
+                repair,
+                worker_days,
+            } => {
+                write!(
+                    f,
+                    "Allocation intent - F:{} W:{} C:{} P:{} S:{} R:{} of {} worker-days",
+                    food, wood, construction, processing, slot_expansion, repair, worker_days
+                )
+            }
             EventType::VillageStateSnapshot {
                 population,
                 houses,
@@ -228,6 +422,162 @@ impl fmt::Display for Event {
                     wood_volume, wood_price, food_volume, food_price, total_participants
                 )
             }
+            EventType::AgeCensus { ages } => {
+                write!(
+                    f,
+                    "Age census - {} workers, oldest {}",
+                    ages.len(),
+                    ages.iter().max().copied().unwrap_or(0)
+                )
+            }
+            EventType::SlotAdded {
+                resource,
+                total_slots,
+            } => {
+                write!(f, "{:?} slot added (full slots: {})", resource, total_slots)
+            }
+            EventType::LaborWasted {
+                resource,
+                wasted_worker_days,
+            } => {
+                write!(
+                    f,
+                    "Wasted {} worker-days on {:?} beyond slot capacity",
+                    wasted_worker_days, resource
+                )
+            }
+            EventType::PriceControlBinding {
+                resource,
+                control,
+                price,
+                unconstrained_price,
+                rationed_quantity,
+            } => {
+                write!(
+                    f,
+                    "{:?} price {:?} binding at {} (market price {}), {} units rationed",
+                    resource, control, price, unconstrained_price, rationed_quantity
+                )
+            }
+            EventType::EstateInherited { worker_id, food } => {
+                write!(f, "Inherited {} food from worker {}", food, worker_id)
+            }
+            EventType::FoodImported { amount, cost } => {
+                write!(f, "Imported {} food for {}", amount, cost)
+            }
+            EventType::GlobalModifierActive {
+                resource,
+                multiplier,
+                active,
+            } => {
+                let state = if *active { "started" } else { "ended" };
+                write!(
+                    f,
+                    "Global {:?} production modifier x{} {}",
+                    resource, multiplier, state
+                )
+            }
+            EventType::SeasonalFactor { resource, factor } => {
+                write!(f, "Seasonal {:?} production factor x{}", resource, factor)
+            }
+            EventType::ExternalPrice { resource, price } => {
+                write!(f, "External {:?} price {}", resource, price)
+            }
+            EventType::MarketSurplus {
+                realized,
+                potential,
+            } => {
+                write!(f, "Realized surplus {} of {} possible", realized, potential)
+            }
+            EventType::PriceLimitHit {
+                resource,
+                limit_price,
+                clamped_price,
+            } => {
+                write!(
+                    f,
+                    "{:?} bid at {} clamped to price limit {}",
+                    resource, limit_price, clamped_price
+                )
+            }
+            EventType::RoundingRemainder { resource, amount } => {
+                write!(
+                    f,
+                    "{:?} settlement adjusted by {} of rounding remainder",
+                    resource, amount
+                )
+            }
+            EventType::CashFlow {
+                production_value,
+                consumption_value,
+                trade_cash,
+                maintenance_cost,
+            } => {
+                write!(
+                    f,
+                    "Cash flow: produced {}, consumed {}, trade {}, maintenance {}",
+                    production_value, consumption_value, trade_cash, maintenance_cost
+                )
+            }
+            EventType::HouseCollapsed {
+                house_id,
+                maintenance_level,
+                total_houses,
+            } => {
+                write!(
+                    f,
+                    "House {} collapsed at {} maintenance ({} houses left)",
+                    house_id, maintenance_level, total_houses
+                )
+            }
+            EventType::OrderBlocked {
+                resource,
+                quantity,
+                price,
+                side,
+            } => {
+                write!(
+                    f,
+                    "{:?} order for {} {:?} @ {} blocked by embargo",
+                    side, quantity, resource, price
+                )
+            }
+            EventType::OrderKilled {
+                resource,
+                quantity,
+                price,
+                side,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Fill-or-kill {:?} order for {} {:?} @ {} killed",
+                    side, quantity, resource, price
+                )
+            }
+            EventType::VillageBankrupt {
+                heir,
+                food,
+                wood,
+                money,
+                workers,
+            } => {
+                write!(
+                    f,
+                    "Bankrupt with {} workers; {} food, {} wood and {} money go to {}",
+                    workers, food, wood, money, heir
+                )
+            }
+            EventType::ConstructionStalled { reason, progress } => {
+                write!(
+                    f,
+                    "Construction stalled at {} progress ({:?})",
+                    progress, reason
+                )
+            }
+            EventType::MoneyAdjusted { amount, reason } => {
+                write!(f, "Money adjusted by {} ({:?})", amount, reason)
+            }
         }
     }
 }
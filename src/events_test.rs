@@ -97,4 +97,19 @@ mod tests {
 
         std::fs::remove_file(temp_file).ok();
     }
+
+    #[test]
+    fn test_resource_type_all_lists_every_variant_once() {
+        // Adding a variant breaks this exhaustive match until it is listed here
+        let position = |resource: ResourceType| match resource {
+            ResourceType::Food => 0,
+            ResourceType::Wood => 1,
+            ResourceType::Labor => 2,
+        };
+        assert_eq!(ResourceType::all().len(), ResourceType::count());
+        assert_eq!(ResourceType::count(), 3);
+        for (i, resource) in ResourceType::all().iter().enumerate() {
+            assert_eq!(position(*resource), i);
+        }
+    }
 }
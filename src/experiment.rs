@@ -1,12 +1,15 @@
 //! Batch experiment runner for systematic strategy evaluation.
 
 use crate::cli::CliArgs;
+use crate::core::derive_seed;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
 /// Configuration for a batch of experiments
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,7 +55,7 @@ pub struct ExperimentResult {
 }
 
 /// Summary metrics from an experiment
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExperimentMetrics {
     pub aggregate_survival_rate: f64,
     pub aggregate_growth_rate: f64,
@@ -61,6 +64,68 @@ pub struct ExperimentMetrics {
     pub village_scores: HashMap<String, f64>,
 }
 
+/// Completion progress of a running batch.
+///
+/// Worker threads record completions through a shared atomic counter; the
+/// main thread reads it to report progress and an estimated time remaining.
+#[derive(Debug)]
+pub struct BatchProgress {
+    completed: AtomicUsize,
+    total: usize,
+    start: Instant,
+}
+
+impl BatchProgress {
+    pub fn new(total: usize) -> Self {
+        Self {
+            completed: AtomicUsize::new(0),
+            total,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn completed(&self) -> usize {
+        self.completed.load(Ordering::SeqCst)
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    fn record_completion(&self) {
+        self.completed.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Estimated time remaining, extrapolated from the average time per completed experiment.
+    pub fn eta(&self) -> Option<Duration> {
+        let completed = self.completed();
+        if completed == 0 {
+            return None;
+        }
+        let per_experiment = self.start.elapsed() / completed as u32;
+        Some(per_experiment * self.total.saturating_sub(completed) as u32)
+    }
+
+    /// Format a progress line like `[#####     ] 5/10 (ETA 12s)`.
+    pub fn status_line(&self) -> String {
+        const WIDTH: usize = 20;
+        let completed = self.completed().min(self.total);
+        let filled = (completed * WIDTH).checked_div(self.total).unwrap_or(WIDTH);
+        let eta = match self.eta() {
+            Some(eta) => format!("ETA {}s", eta.as_secs()),
+            None => "ETA --".to_string(),
+        };
+        format!(
+            "[{}{}] {}/{} ({})",
+            "#".repeat(filled),
+            " ".repeat(WIDTH - filled),
+            completed,
+            self.total,
+            eta
+        )
+    }
+}
+
 impl ExperimentBatch {
     /// Load experiment configuration from YAML file
     pub fn load_from_file(path: &Path) -> Result<Self, String> {
@@ -72,17 +137,38 @@ impl ExperimentBatch {
 
     /// Run all experiments in the batch
     pub fn run(&self, quiet: bool) -> Vec<ExperimentResult> {
+        let progress = Arc::new(BatchProgress::new(self.experiments.len()));
+        self.run_with(quiet, &progress, run_single_experiment)
+    }
+
+    /// Run all experiments with the given runner, recording completions in `progress`.
+    ///
+    /// Progress is printed from the calling thread unless `quiet` is set.
+    /// Results come back in the order of `experiments` however many run in
+    /// parallel, so a batch gives the same results serially or in parallel.
+    fn run_with(
+        &self,
+        quiet: bool,
+        progress: &Arc<BatchProgress>,
+        runner: fn(&ExperimentConfig, bool) -> ExperimentResult,
+    ) -> Vec<ExperimentResult> {
         let parallel = self.parallel.unwrap_or(1);
 
         if parallel == 1 {
             // Sequential execution
             self.experiments
                 .iter()
-                .map(|exp| run_single_experiment(exp, quiet))
+                .map(|exp| {
+                    let result = runner(exp, quiet);
+                    progress.record_completion();
+                    if !quiet {
+                        println!("{}", progress.status_line());
+                    }
+                    result
+                })
                 .collect()
         } else {
             // Parallel execution
-            let results = Arc::new(Mutex::new(Vec::new()));
             let mut handles = vec![];
 
             // Create thread pool
@@ -90,8 +176,8 @@ impl ExperimentBatch {
 
             for exp in &self.experiments {
                 let exp_clone = exp.clone();
-                let results_clone = Arc::clone(&results);
                 let sem_clone = Arc::clone(&semaphore);
+                let progress_clone = Arc::clone(progress);
 
                 let handle = thread::spawn(move || {
                     // Wait for available slot
@@ -106,59 +192,71 @@ impl ExperimentBatch {
                     }
 
                     // Run experiment
-                    let result = run_single_experiment(&exp_clone, quiet);
-
-                    // Store result
-                    results_clone.lock().unwrap().push(result);
+                    let result = runner(&exp_clone, quiet);
+                    progress_clone.record_completion();
 
                     // Release slot
                     *sem_clone.lock().unwrap() += 1;
+                    result
                 });
 
                 handles.push(handle);
             }
 
-            // Wait for all threads
-            for handle in handles {
-                handle.join().unwrap();
+            // Report progress from the main thread as experiments finish
+            let mut last_reported = 0;
+            while !handles.iter().all(|h| h.is_finished()) {
+                let completed = progress.completed();
+                if !quiet && completed != last_reported {
+                    println!("{}", progress.status_line());
+                    last_reported = completed;
+                }
+                thread::sleep(Duration::from_millis(100));
+            }
+            if !quiet && progress.completed() != last_reported {
+                println!("{}", progress.status_line());
             }
 
-            Arc::try_unwrap(results).unwrap().into_inner().unwrap()
+            // Join in submission order so results line up with the experiments
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
         }
     }
 }
 
-/// Run a single experiment
-fn run_single_experiment(config: &ExperimentConfig, quiet: bool) -> ExperimentResult {
-    let start = std::time::Instant::now();
+/// Seed an experiment runs with, taken only from its config.
+///
+/// Seed for the experiment's scenarios that don't set their own, derived
+/// from the experiment name so no run falls back to an unseeded RNG.
+fn experiment_seed(config: &ExperimentConfig) -> u64 {
+    derive_seed(0, &config.name, 0)
+}
 
-    // Prepare CLI args
-    let mut args = CliArgs {
+/// CLI arguments that run `config`'s scenarios with its overrides. An
+/// explicit `random_seed` override replaces every scenario's seed; otherwise
+/// only scenarios without one get `experiment_seed`.
+pub fn experiment_args(config: &ExperimentConfig) -> CliArgs {
+    CliArgs {
         scenario_file: Some(config.scenario.clone()),
         strategies: config.strategies.clone(),
         output_file: Some(config.output.clone()),
+        days: config.overrides.days,
+        growth_delay: config.overrides.growth_delay,
+        random_seed: config.overrides.random_seed,
+        default_seed: Some(experiment_seed(config)),
+        initial_food: config.overrides.initial_food,
+        initial_wood: config.overrides.initial_wood,
+        initial_money: config.overrides.initial_money,
         ..Default::default()
-    };
-
-    // Apply overrides
-    if let Some(days) = config.overrides.days {
-        args.days = Some(days);
-    }
-    if let Some(delay) = config.overrides.growth_delay {
-        args.growth_delay = Some(delay);
-    }
-    if let Some(seed) = config.overrides.random_seed {
-        args.random_seed = Some(seed);
-    }
-    if let Some(food) = config.overrides.initial_food {
-        args.initial_food = Some(food);
-    }
-    if let Some(wood) = config.overrides.initial_wood {
-        args.initial_wood = Some(wood);
-    }
-    if let Some(money) = config.overrides.initial_money {
-        args.initial_money = Some(money);
     }
+}
+
+/// Run a single experiment
+fn run_single_experiment(config: &ExperimentConfig, quiet: bool) -> ExperimentResult {
+    let start = std::time::Instant::now();
+    let args = experiment_args(config);
 
     if !quiet {
         println!("Running experiment: {}", config.name);
@@ -218,6 +316,10 @@ fn run_simulation_for_experiment(args: CliArgs, quiet: bool) -> Result<Experimen
         cmd.arg("--seed").arg(seed.to_string());
     }
 
+    if let Some(seed) = args.default_seed {
+        cmd.arg("--default-seed").arg(seed.to_string());
+    }
+
     if quiet {
         cmd.arg("--quiet");
     }
@@ -304,3 +406,99 @@ fn parse_village_score(line: &str) -> Option<(String, f64)> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stub_runner(config: &ExperimentConfig, _quiet: bool) -> ExperimentResult {
+        ExperimentResult {
+            name: config.name.clone(),
+            success: true,
+            error: None,
+            metrics: None,
+            duration_ms: 0,
+        }
+    }
+
+    fn batch(experiments: usize, parallel: Option<usize>) -> ExperimentBatch {
+        ExperimentBatch {
+            name: "test".to_string(),
+            description: String::new(),
+            parallel,
+            experiments: (0..experiments)
+                .map(|i| ExperimentConfig {
+                    name: format!("exp_{}", i),
+                    scenario: PathBuf::from("scenario.json"),
+                    strategies: vec![],
+                    output: PathBuf::from(format!("exp_{}.json", i)),
+                    overrides: ExperimentOverrides::default(),
+                    repeat: 0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_progress_counter_reaches_total() {
+        for parallel in [None, Some(3)] {
+            let batch = batch(5, parallel);
+            let progress = Arc::new(BatchProgress::new(batch.experiments.len()));
+
+            let results = batch.run_with(true, &progress, stub_runner);
+
+            assert_eq!(results.len(), 5);
+            assert_eq!(progress.completed(), progress.total());
+            assert_eq!(progress.eta(), Some(Duration::ZERO));
+        }
+    }
+
+    /// Stands in for a simulation: metrics come only from the experiment's
+    /// seed, and a seed-dependent delay scrambles completion order.
+    fn seeded_runner(config: &ExperimentConfig, _quiet: bool) -> ExperimentResult {
+        use rand::{Rng, SeedableRng, rngs::StdRng};
+
+        let mut rng = StdRng::seed_from_u64(experiment_seed(config));
+        thread::sleep(Duration::from_millis(rng.random_range(0..30)));
+        ExperimentResult {
+            name: config.name.clone(),
+            success: true,
+            error: None,
+            metrics: Some(ExperimentMetrics {
+                aggregate_survival_rate: rng.random(),
+                aggregate_growth_rate: rng.random(),
+                total_trade_volume: rng.random_range(0..1000),
+                economic_inequality: rng.random(),
+                village_scores: HashMap::new(),
+            }),
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_parallel_batch_matches_serial_results() {
+        let run = |parallel| {
+            let batch = batch(8, Some(parallel));
+            let progress = Arc::new(BatchProgress::new(batch.experiments.len()));
+            batch.run_with(true, &progress, seeded_runner)
+        };
+
+        let serial = run(1);
+        let parallel = run(4);
+
+        assert_eq!(serial.len(), parallel.len());
+        for (s, p) in serial.iter().zip(&parallel) {
+            assert_eq!(s.name, p.name);
+            assert_eq!(s.metrics, p.metrics);
+        }
+    }
+
+    #[test]
+    fn test_status_line_before_any_completion() {
+        let progress = BatchProgress::new(4);
+        assert_eq!(
+            progress.status_line(),
+            format!("[{}] 0/4 (ETA --)", " ".repeat(20))
+        );
+    }
+}
@@ -38,21 +38,42 @@ use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
 use rust_decimal_macros::dec;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process;
 use village_model::{
-    analysis::{analyze_simulation, compare_simulations, explain_simulation},
-    auction::{FinalFill, run_auction},
+    analysis::{
+        analyze_simulation, compare_simulations, explain_simulation, format_inequality_csv,
+        inequality_timeseries, set_carrying_capacities,
+    },
+    auction::{
+        AllocationRule, AuctionConfig, AuctionSuccess, FinalFill, ParticipantId, PriceControlKind,
+        ResourceId, replenish_icebergs, run_auction_with_config,
+    },
     auction_builder::AuctionBuilder,
     batch_analysis::{analyze_batch, export_batch_to_csv},
-    cli::{Command, apply_overrides, parse_args, validate_scenario},
-    core::{Allocation, House, Village, Worker},
-    events::{ConsumptionPurpose, DeathCause, EventLogger, EventType, TradeSide},
+    cli::{CliArgs, Command, load_configured_scenarios, parse_args, validate_scenario},
+    core::{
+        Allocation, House, PendingProduction, SimulationSnapshot, Village, Worker, derive_seed,
+    },
+    events::{
+        AdjustmentReason, ConsumptionPurpose, DeathCause, Event, EventLogger, EventType,
+        StallReason, TradeSide,
+    },
     experiment::ExperimentBatch,
-    metrics::MetricsCalculator,
-    query::{export_to_csv as export_query_to_csv, format_query_results, query_events},
-    scenario::{VillageConfig, create_standard_scenarios},
+    metrics::MetricsAccumulator,
+    query::{
+        CompactOptions, FinalVillageState, compact_events, export_final_states,
+        export_to_csv as export_query_to_csv, final_village_states, format_final_states_csv,
+        format_query_results, format_rollup_csv, format_trade_flows_csv, query_events,
+        reconstruct_trade_flows, rollup_events,
+    },
+    scenario::{
+        AgentOrder, Good, InitialWorkerState, PhaseOrder, ProductionCurve, Recipe, Scenario,
+        SimError, SimulationParameters, StrategyConfig, VillageConfig, check_scenario_dir,
+        create_standard_scenarios, format_scenario_checks,
+    },
     strategies,
-    types::{OrderRequest, ResourceType, ResourceTypeExt, VillageId},
+    types::{OrderRequest, ResourceType, ResourceTypeExt, TimeInForce, VillageId},
     ui::run_ui,
 };
 
@@ -68,10 +89,11 @@ fn create_village(
     let workers_vec: Vec<Worker> = (0..workers)
         .map(|i| Worker {
             id: i,
-            days_without_food: 0,
+            days_without_food: Decimal::ZERO,
             days_without_shelter: 0,
             days_with_both: 0,
             spawn_eligible: false,
+            age: 0,
         })
         .collect();
 
@@ -87,12 +109,18 @@ fn create_village(
         id_str: format!("village_{}", id),
         wood: dec!(100.0),
         food: dec!(100.0),
+        granary: dec!(0.0),
+        tools: dec!(0.0),
+        pending_production: Vec::new(),
         money: dec!(100.0),
         wood_slots,
         food_slots,
         workers: workers_vec,
         houses: houses_vec,
         construction_progress: dec!(0.0),
+        slot_expansion_progress: dec!(0.0),
+        food_quality: dec!(1),
+        wood_quality: dec!(1),
         next_worker_id: workers,
         next_house_id: houses,
         rng: None,
@@ -103,10 +131,11 @@ fn village_from_config(id: usize, config: &VillageConfig) -> Village {
     let workers: Vec<Worker> = (0..config.initial_workers)
         .map(|i| Worker {
             id: i,
-            days_without_food: 0,
-            days_without_shelter: 0,
-            days_with_both: 0,
+            days_without_food: Decimal::from(config.initial_worker_state.days_without_food),
+            days_without_shelter: config.initial_worker_state.days_without_shelter,
+            days_with_both: config.initial_worker_state.days_with_both,
             spawn_eligible: false,
+            age: 0,
         })
         .collect();
 
@@ -122,12 +151,18 @@ fn village_from_config(id: usize, config: &VillageConfig) -> Village {
         id_str: config.id.clone(),
         wood: config.initial_wood,
         food: config.initial_food,
+        granary: dec!(0.0),
+        tools: dec!(0.0),
+        pending_production: Vec::new(),
         money: config.initial_money,
         wood_slots: (config.wood_slots.0 as u32, config.wood_slots.1 as u32),
         food_slots: (config.food_slots.0 as u32, config.food_slots.1 as u32),
         workers,
         houses,
-        construction_progress: dec!(0.0),
+        construction_progress: config.initial_construction_progress,
+        slot_expansion_progress: dec!(0.0),
+        food_quality: config.food_quality,
+        wood_quality: config.wood_quality,
         next_worker_id: config.initial_workers,
         next_house_id: config.initial_houses,
         rng: None,
@@ -138,32 +173,68 @@ fn village_from_config(id: usize, config: &VillageConfig) -> Village {
 ///
 /// This is the core update function that processes all village activities:
 /// 1. Validates worker allocation matches available worker-days
-/// 2. Processes resource production based on allocation
+/// 2. Releases output whose `production_lag` has passed, then processes
+///    resource production based on allocation
 /// 3. Advances construction progress and completes houses
-/// 4. Handles worker feeding, shelter, births, and deaths
-/// 5. Maintains houses and handles decay
+/// 4. Spends wood on house upgrades requested by the strategy
+/// 5. Handles worker feeding, shelter, births, and deaths
+/// 6. Maintains houses and handles decay
+///
+/// With `ticks_per_day` above 1, each tick covers that fraction of a day: the
+/// allocation's worker-days, food eaten and house upkeep are scaled down to match.
+/// `intent` is the allocation as the strategy returned it, before
+/// normalization; it is logged if `log_allocation_intent` is set.
 fn update_village(
     village: &mut Village,
+    intent: &Allocation,
     allocation: Allocation,
+    params: &SimulationParameters,
     logger: &mut EventLogger,
     tick: usize,
 ) {
     // Validate allocation matches available worker-days
-    let worker_days = village.worker_days();
+    let worker_days = village.effective_worker_days(params.labor_efficiency);
+    if params.log_allocation_intent {
+        logger.log(
+            tick,
+            village.id_str.clone(),
+            EventType::AllocationIntent {
+                wood: intent.wood,
+                food: intent.food,
+                construction: intent.house_construction,
+                processing: intent.processing,
+                slot_expansion: intent.slot_expansion,
+                repair: intent.repair,
+                worker_days,
+            },
+        );
+    }
     assert!(
-        ((allocation.wood + allocation.food + allocation.house_construction) - worker_days).abs()
-            < dec!(0.001),
+        (allocation.labor() - worker_days).abs() < dec!(0.001),
         "worker_days: {}, allocation: {:?}",
         worker_days,
         allocation
     );
 
     log_worker_allocation(village, &allocation, logger, tick);
-    process_production(village, &allocation, logger, tick);
+    let allocation = allocation.scaled(Decimal::ONE / Decimal::from(params.ticks_per_day));
+    village.release_pending_production(tick);
+    process_production(village, &allocation, params, logger, tick);
+    process_recipes(village, &allocation, &params.recipes, logger, tick);
+    depreciate_tools(village, params);
     process_construction(village, &allocation, logger, tick);
-    let (new_workers, workers_to_remove) = process_worker_lifecycle(village, logger, tick);
-    apply_worker_changes(village, new_workers, workers_to_remove, logger, tick);
-    process_house_maintenance(village, logger, tick);
+    process_slot_expansion(village, &allocation, params, logger, tick);
+    process_house_upgrades(village, &allocation, params, logger, tick);
+    let (new_workers, workers_to_remove) = process_worker_lifecycle(village, params, logger, tick);
+    apply_worker_changes(
+        village,
+        new_workers,
+        workers_to_remove,
+        params,
+        logger,
+        tick,
+    );
+    process_house_maintenance(village, &allocation, params, logger, tick);
 
     // Log village state snapshot
     logger.log(
@@ -172,7 +243,7 @@ fn update_village(
         EventType::VillageStateSnapshot {
             population: village.workers.len(),
             houses: village.houses.len(),
-            food: village.food,
+            food: village.food + village.granary,
             wood: village.wood,
             money: village.money,
         },
@@ -189,10 +260,17 @@ fn log_worker_allocation(
     let food_workers = allocation.food.to_u32().unwrap_or(0) as usize;
     let wood_workers = allocation.wood.to_u32().unwrap_or(0) as usize;
     let construction_workers = allocation.house_construction.to_u32().unwrap_or(0) as usize;
-    let idle_workers = village
-        .workers
-        .len()
-        .saturating_sub(food_workers + wood_workers + construction_workers);
+    let processing_workers = allocation.processing.to_u32().unwrap_or(0) as usize;
+    let expansion_workers = allocation.slot_expansion.to_u32().unwrap_or(0) as usize;
+    let repair_workers = allocation.repair.to_u32().unwrap_or(0) as usize;
+    let idle_workers = village.workers.len().saturating_sub(
+        food_workers
+            + wood_workers
+            + construction_workers
+            + processing_workers
+            + expansion_workers
+            + repair_workers,
+    );
 
     logger.log(
         tick,
@@ -201,7 +279,7 @@ fn log_worker_allocation(
             food_workers,
             wood_workers,
             construction_workers,
-            repair_workers: 0,
+            repair_workers,
             idle_workers,
         },
     );
@@ -209,16 +287,21 @@ fn log_worker_allocation(
 
 /// Processes resource production based on worker allocation and production slots.
 ///
-/// Production uses diminishing returns:
+/// Production uses diminishing returns (shown for the default `Step` curve;
+/// each resource's `ProductionCurve` can replace it):
 /// - First slot workers produce at 100% efficiency
 /// - Second slot workers produce at 50% efficiency  
 /// - Additional workers produce nothing (0% efficiency)
 ///
 /// Wood production: 0.1 units per worker-day
 /// Food production: 2.0 units per worker-day
+///
+/// Worker-days beyond a resource's full plus partial slots are logged as
+/// `LaborWasted` so wasteful strategies stand out.
 fn process_production(
     village: &mut Village,
     allocation: &Allocation,
+    params: &SimulationParameters,
     logger: &mut EventLogger,
     tick: usize,
 ) {
@@ -226,8 +309,48 @@ fn process_production(
     let food_workers = allocation.food.to_u32().unwrap_or(0) as usize;
 
     // Calculate production with diminishing returns
-    let wood_produced = produced(village.wood_slots, dec!(0.1), allocation.wood);
-    let food_produced = produced(village.food_slots, dec!(2.0), allocation.food);
+    let wood_produced = produce_with_curve(
+        params.wood_production_curve,
+        village.wood_slots,
+        dec!(0.1),
+        allocation.wood,
+    );
+    let food_produced = produce_with_curve(
+        params.food_production_curve,
+        village.food_slots,
+        dec!(2.0),
+        allocation.food,
+    );
+    let tool_multiplier = params.tools.map_or(Decimal::ONE, |tools| {
+        tools.multiplier(village.tools, village.workers.len())
+    });
+    let wood_produced = wood_produced
+        * tool_multiplier
+        * village.wood_quality
+        * params.production_multiplier(ResourceType::Wood, tick)
+        * params.seasonal_factor(&village.id_str, ResourceType::Wood, tick);
+    let food_produced = food_produced
+        * tool_multiplier
+        * village.food_quality
+        * params.production_multiplier(ResourceType::Food, tick)
+        * params.seasonal_factor(&village.id_str, ResourceType::Food, tick);
+
+    for (resource, slots, worker_days) in [
+        (ResourceType::Wood, village.wood_slots, allocation.wood),
+        (ResourceType::Food, village.food_slots, allocation.food),
+    ] {
+        let capacity = Decimal::from(slots.0 + slots.1);
+        if worker_days > capacity {
+            logger.log(
+                tick,
+                village.id_str.clone(),
+                EventType::LaborWasted {
+                    resource,
+                    wasted_worker_days: worker_days - capacity,
+                },
+            );
+        }
+    }
 
     // Log and update wood production
     if wood_produced > dec!(0) {
@@ -240,7 +363,7 @@ fn process_production(
                 workers_assigned: wood_workers,
             },
         );
-        village.wood += wood_produced;
+        store_production(village, ResourceType::Wood, wood_produced, params, tick);
     }
 
     // Log and update food production
@@ -254,7 +377,105 @@ fn process_production(
                 workers_assigned: food_workers,
             },
         );
-        village.food += food_produced;
+        store_production(village, ResourceType::Food, food_produced, params, tick);
+    }
+}
+
+/// Adds output to the stockpile, or queues it for `production_lag` ticks.
+fn store_production(
+    village: &mut Village,
+    resource: ResourceType,
+    amount: Decimal,
+    params: &SimulationParameters,
+    tick: usize,
+) {
+    if params.production_lag == 0 {
+        match resource {
+            ResourceType::Food => village.food += amount,
+            ResourceType::Wood => village.wood += amount,
+            ResourceType::Labor => {}
+        }
+        return;
+    }
+    village.pending_production.push(PendingProduction {
+        available_at: tick + params.production_lag,
+        resource,
+        amount,
+    });
+}
+
+/// Wears down the village's tools by the day's `depreciation_rate`, split
+/// across the day's ticks.
+fn depreciate_tools(village: &mut Village, params: &SimulationParameters) {
+    if let Some(tools) = params.tools {
+        let rate = tools.depreciation_rate / Decimal::from(params.ticks_per_day);
+        village.tools -= village.tools * rate;
+    }
+}
+
+/// Runs the scenario's recipes with the worker-days allocated to processing.
+///
+/// Recipes run in the order listed. Each uses as many of the remaining
+/// processing worker-days as its inputs allow, and anything it can't use
+/// passes to the next recipe. Inputs and outputs scale linearly with
+/// worker-days, so partial days convert partial amounts.
+fn process_recipes(
+    village: &mut Village,
+    allocation: &Allocation,
+    recipes: &[Recipe],
+    logger: &mut EventLogger,
+    tick: usize,
+) {
+    let mut remaining_days = allocation.processing;
+
+    for recipe in recipes {
+        if remaining_days <= dec!(0) {
+            break;
+        }
+
+        // Limit worker-days by the scarcest input
+        let days = recipe
+            .inputs
+            .iter()
+            .filter(|input| input.amount > dec!(0))
+            .map(|input| village.good(input.good) / input.amount)
+            .fold(remaining_days, Decimal::min);
+        if days <= dec!(0) {
+            continue;
+        }
+        remaining_days -= days;
+
+        for input in &recipe.inputs {
+            let amount = input.amount * days;
+            *village.good_mut(input.good) -= amount;
+            if let Some(resource) = input.good.resource() {
+                logger.log(
+                    tick,
+                    village.id_str.clone(),
+                    EventType::ResourceConsumed {
+                        resource,
+                        amount,
+                        purpose: ConsumptionPurpose::Processing,
+                    },
+                );
+            }
+        }
+
+        for output in &recipe.outputs {
+            let amount = output.amount * days;
+            *village.good_mut(output.good) += amount;
+            if let Some(resource) = output.good.resource() {
+                logger.log(
+                    tick,
+                    village.id_str.clone(),
+                    EventType::ResourceProduced {
+                        resource,
+                        amount,
+                        workers_assigned: days.to_u32().unwrap_or(0) as usize,
+                    },
+                );
+            }
+        }
     }
 }
 
@@ -266,6 +487,7 @@ fn process_production(
 /// - Completion requires 10 wood (consumed immediately)
 /// - Multiple houses can complete in one tick if resources allow
 /// - Excess progress carries over to next house
+/// - A house with enough progress but too little wood logs `ConstructionStalled`
 fn process_construction(
     village: &mut Village,
     allocation: &Allocation,
@@ -312,28 +534,166 @@ fn process_construction(
             village.construction_progress -= dec!(60.0);
         } else {
             // Not enough wood, stop construction
+            logger.log(
+                tick,
+                village.id_str.clone(),
+                EventType::ConstructionStalled {
+                    reason: StallReason::InsufficientWood,
+                    progress: village.construction_progress,
+                },
+            );
             break;
         }
     }
 }
 
+/// Processes slot expansion progress.
+///
+/// Mirrors house construction: each worker-day adds 1 progress point, and once
+/// `slot_expansion_days` points have accumulated the expansion completes by
+/// consuming `slot_expansion_wood`, adding one full-productivity slot for the
+/// allocation's target resource. Without enough wood, progress is kept.
+fn process_slot_expansion(
+    village: &mut Village,
+    allocation: &Allocation,
+    params: &SimulationParameters,
+    logger: &mut EventLogger,
+    tick: usize,
+) {
+    if allocation.slot_expansion <= dec!(0.0) {
+        return;
+    }
+
+    village.slot_expansion_progress += allocation.slot_expansion;
+    let slots = match allocation.slot_expansion_target {
+        ResourceType::Food => &mut village.food_slots,
+        ResourceType::Wood => &mut village.wood_slots,
+        // Labor has no production slots to expand
+        ResourceType::Labor => return,
+    };
+
+    while village.slot_expansion_progress >= params.slot_expansion_days
+        && village.wood >= params.slot_expansion_wood
+    {
+        village.wood -= params.slot_expansion_wood;
+        logger.log(
+            tick,
+            village.id_str.clone(),
+            EventType::ResourceConsumed {
+                resource: ResourceType::Wood,
+                amount: params.slot_expansion_wood,
+                purpose: ConsumptionPurpose::SlotExpansion,
+            },
+        );
+
+        slots.0 += 1;
+        logger.log(
+            tick,
+            village.id_str.clone(),
+            EventType::SlotAdded {
+                resource: allocation.slot_expansion_target,
+                total_slots: slots.0,
+            },
+        );
+
+        village.slot_expansion_progress -= params.slot_expansion_days;
+    }
+}
+
+/// Spends wood requested by the strategy on upgrading houses above base capacity.
+///
+/// Upgrade mechanics:
+/// - Each `house_upgrade_wood` of wood raises one house's maintenance level by 1
+/// - Each whole positive level adds 1 shelter capacity
+/// - The least-upgraded house is upgraded first; decayed houses are skipped
+/// - No house is raised above `max_house_upgrade_level`
+fn process_house_upgrades(
+    village: &mut Village,
+    allocation: &Allocation,
+    params: &SimulationParameters,
+    logger: &mut EventLogger,
+    tick: usize,
+) {
+    let cost = params.house_upgrade_wood;
+    if allocation.house_upgrade_wood <= dec!(0) || cost <= dec!(0) {
+        return;
+    }
+
+    let max_level = Decimal::from(params.max_house_upgrade_level);
+    let mut budget = allocation.house_upgrade_wood.min(village.wood);
+    let mut wood_spent = dec!(0);
+
+    while budget >= cost {
+        let Some(house) = village
+            .houses
+            .iter_mut()
+            .filter(|h| {
+                h.maintenance_level >= dec!(0) && h.maintenance_level + dec!(1) <= max_level
+            })
+            .min_by_key(|h| h.maintenance_level)
+        else {
+            break;
+        };
+
+        house.maintenance_level += dec!(1);
+        village.wood -= cost;
+        budget -= cost;
+        wood_spent += cost;
+    }
+
+    if wood_spent > dec!(0) {
+        logger.log(
+            tick,
+            village.id_str.clone(),
+            EventType::ResourceConsumed {
+                resource: ResourceType::Wood,
+                amount: wood_spent,
+                purpose: ConsumptionPurpose::HouseUpgrade,
+            },
+        );
+    }
+}
+
 /// Processes worker lifecycle: feeding, shelter, births, and deaths.
 ///
 /// Worker needs and consequences:
 /// - Food: 1 unit/day, starve after 10 days without
-/// - Shelter: 1 capacity/worker, die from exposure after 30 days without
+/// - Shelter: `shelter_per_worker` capacity/worker, die from exposure after 30 days without
+/// - Age: with `old_age` set, each worker rolls daily against its old-age death chance
+///
+/// Food is eaten in `1 / ticks_per_day` rations every tick, but ages and the
+/// day counters only advance on the last tick of each day.
+///
+/// Recovery: a fed (or sheltered) day resets the matching counter to 0, or
+/// with `recovery_rate` only lowers it by that many days.
+///
+/// Partial rations: with `partial_rations`, food that can't feed everyone is
+/// split evenly and a short day adds only the unfed fraction of a day.
+///
+/// Granary: when `food` can't feed everyone the shortfall is released from
+/// the granary; afterwards `granary_fraction` of all food is set aside again.
 ///
 /// Reproduction:
 /// - Requires 100+ consecutive days with both food and shelter
-/// - 5% daily chance to spawn new worker when conditions met
+/// - 5% daily chance to spawn new worker when conditions met, multiplied by
+///   the scenario's prosperity bonus when food and wood buffers are large
+/// - With `require_housing_for_birth`, rolls are skipped once the houses are full
 /// - Resets counter on successful birth
 ///
 /// Returns (new_workers_count, workers_to_remove).
 fn process_worker_lifecycle(
     village: &mut Village,
+    params: &SimulationParameters,
     logger: &mut EventLogger,
     tick: usize,
 ) -> (usize, Vec<(usize, usize, DeathCause)>) {
+    // Nobody to feed, house or breed, and rations are split per worker
+    if village.workers.is_empty() {
+        return (0, Vec::new());
+    }
+    let shelter_per_worker = params.shelter_per_worker;
+    let ration = Decimal::ONE / Decimal::from(params.ticks_per_day);
+    let day_ends = (tick + 1).is_multiple_of(params.ticks_per_day as usize);
     let mut shelter_effect = village
         .houses
         .iter()
@@ -341,35 +701,83 @@ fn process_worker_lifecycle(
         .sum::<Decimal>();
     let mut new_workers = 0;
     let mut workers_to_remove = Vec::new();
+    let mut old_age_rolls = Vec::new();
     let mut food_consumed = dec!(0);
 
+    let needed = ration * Decimal::from(village.workers.len());
+    if village.food < needed {
+        let released = (needed - village.food).min(village.granary);
+        village.granary -= released;
+        village.food += released;
+    }
+    if let Some(backstop) = params.food_backstop
+        && village.food < needed
+    {
+        let mut amount = needed - village.food;
+        if backstop.price > Decimal::ZERO {
+            amount = amount.min(village.money.max(Decimal::ZERO) / backstop.price);
+        }
+        let cost = amount * backstop.price;
+        if amount > Decimal::ZERO {
+            village.food += amount;
+            village.money -= cost;
+            logger.log(
+                tick,
+                village.id_str.clone(),
+                EventType::FoodImported { amount, cost },
+            );
+        }
+    }
+
+    let share = if params.partial_rations && village.food < needed {
+        village.food.max(Decimal::ZERO) / Decimal::from(village.workers.len())
+    } else {
+        ration
+    };
+
     for (i, worker) in village.workers.iter_mut().enumerate() {
         // Feed workers (1 food per worker per day)
-        let has_food = if village.food >= dec!(1.0) {
-            village.food -= dec!(1.0);
-            food_consumed += dec!(1.0);
-            worker.days_without_food = 0;
-            true
+        let eaten = if village.food >= share {
+            share
+        } else if params.partial_rations {
+            // The last share can come up short by a rounding digit
+            village.food.max(Decimal::ZERO)
         } else {
-            worker.days_without_food += 1;
-            false
+            Decimal::ZERO
         };
+        let has_food = eaten >= ration;
+        village.food -= eaten;
+        food_consumed += eaten;
 
-        // Provide shelter (1 shelter unit per worker)
-        let has_shelter = shelter_effect >= dec!(1.0);
+        // Provide shelter (shelter_per_worker units per worker)
+        let has_shelter = shelter_effect >= shelter_per_worker;
         if has_shelter {
-            shelter_effect -= dec!(1.0);
-            worker.days_without_shelter = 0;
-        } else {
-            worker.days_without_shelter += 1;
+            shelter_effect -= shelter_per_worker;
         }
 
-        // Track days with both food and shelter for reproduction
-        worker.days_with_both = if has_food && has_shelter {
-            worker.days_with_both + 1
-        } else {
-            0
-        };
+        if day_ends {
+            worker.age += 1;
+            worker.days_without_food = if has_food {
+                recover(worker.days_without_food, params.recovery_rate)
+            } else {
+                worker.days_without_food + Decimal::ONE - eaten / ration
+            };
+            worker.days_without_shelter = if has_shelter {
+                recover(worker.days_without_shelter, params.recovery_rate)
+            } else {
+                worker.days_without_shelter + 1
+            };
+
+            // Track days with both food and shelter for reproduction
+            worker.days_with_both = if has_food && has_shelter {
+                worker.days_with_both + 1
+            } else {
+                match params.reproduction_setback {
+                    Some(setback) => worker.days_with_both.saturating_sub(setback),
+                    None => 0,
+                }
+            };
+        }
 
         // Mark workers eligible for spawning
         if worker.days_with_both >= 100 {
@@ -377,13 +785,27 @@ fn process_worker_lifecycle(
         }
 
         // Check for death conditions
-        if worker.days_without_food >= 10 {
+        if worker.days_without_food >= dec!(10) {
             workers_to_remove.push((i, worker.id, DeathCause::Starvation));
         } else if worker.days_without_shelter >= 30 {
             workers_to_remove.push((i, worker.id, DeathCause::NoShelter));
+        } else if day_ends && let Some(old_age) = params.old_age {
+            old_age_rolls.push((i, worker.id, old_age.death_chance(worker.age)));
+        }
+    }
+
+    // Old-age rolls draw from the village RNG, which the loop above borrows
+    for (i, worker_id, chance) in old_age_rolls {
+        if chance > 0.0 && village.dies_of_old_age(chance) {
+            workers_to_remove.push((i, worker_id, DeathCause::OldAge));
         }
     }
 
+    // Refill the granary to its share of the remaining food
+    let total_food = village.food + village.granary;
+    village.granary = total_food * params.granary_fraction;
+    village.food = total_food - village.granary;
+
     // Log food consumption
     if food_consumed > dec!(0) {
         logger.log(
@@ -400,26 +822,59 @@ fn process_worker_lifecycle(
     // Collect eligible workers
     let eligible_count = village.workers.iter().filter(|w| w.spawn_eligible).count();
 
-    // Handle spawning for eligible workers
-    for _ in 0..eligible_count {
-        if village.should_spawn_worker() {
-            // Find the first eligible worker and reset their counter
-            if let Some(worker) = village.workers.iter_mut().find(|w| w.spawn_eligible) {
+    // Handle spawning for eligible workers (the chance is daily)
+    let parents_per_birth = if params.require_pairing { 2 } else { 1 };
+    let spawn_rolls = if day_ends {
+        eligible_count / parents_per_birth
+    } else {
+        0
+    };
+    let prosperity = params
+        .prosperity_bonus
+        .factor(village.food_days(), village.wood_days());
+    let housing_capacity = village.housing_capacity(shelter_per_worker);
+    for _ in 0..spawn_rolls {
+        if params.require_housing_for_birth
+            && village.workers.len() + new_workers >= housing_capacity
+        {
+            break;
+        }
+        if village.should_spawn_worker(prosperity) {
+            // Find the first eligible parents and reset their counters
+            for worker in village
+                .workers
+                .iter_mut()
+                .filter(|w| w.spawn_eligible)
+                .take(parents_per_birth)
+            {
                 worker.days_with_both = 0;
                 worker.spawn_eligible = false;
-                new_workers += 1;
             }
+            new_workers += 1;
         }
     }
 
     (new_workers, workers_to_remove)
 }
 
+/// Counts a deprivation streak down by `recovery_rate` days, or resets it
+/// outright when no rate is set.
+fn recover<T>(days: T, recovery_rate: Option<u32>) -> T
+where
+    T: Copy + Default + Ord + From<u32> + std::ops::Sub<Output = T>,
+{
+    match recovery_rate {
+        Some(rate) if days > T::from(rate) => days - T::from(rate),
+        _ => T::default(),
+    }
+}
+
 /// Applies worker population changes (births and deaths).
 fn apply_worker_changes(
     village: &mut Village,
     new_workers: usize,
     mut workers_to_remove: Vec<(usize, usize, DeathCause)>,
+    params: &SimulationParameters,
     logger: &mut EventLogger,
     tick: usize,
 ) {
@@ -435,6 +890,7 @@ fn apply_worker_changes(
                 total_population: village.workers.len() - 1,
             },
         );
+        settle_estate(village, *worker_id, params, logger, tick);
     }
 
     for (i, _, _) in workers_to_remove {
@@ -443,54 +899,117 @@ fn apply_worker_changes(
 
     // Add new workers
     for _ in 0..new_workers {
-        let new_worker = Worker {
-            id: village.next_worker_id,
-            days_without_food: 0,
-            days_without_shelter: 0,
-            days_with_both: 0,
-            spawn_eligible: false,
-        };
-        village.next_worker_id += 1;
-
+        let worker_id = village.add_worker();
         logger.log(
             tick,
             village.id_str.clone(),
             EventType::WorkerBorn {
-                worker_id: new_worker.id,
-                total_population: village.workers.len() + 1,
+                worker_id,
+                total_population: village.workers.len(),
+            },
+        );
+    }
+}
+
+/// Pays the burial cost for a dead worker and hands their estate to the village.
+///
+/// Burial takes `death_cost_wood`, or whatever wood is left if the village has
+/// less; the estate adds `estate_food`.
+fn settle_estate(
+    village: &mut Village,
+    worker_id: usize,
+    params: &SimulationParameters,
+    logger: &mut EventLogger,
+    tick: usize,
+) {
+    let burial_wood = params.death_cost_wood.min(village.wood);
+    if burial_wood > Decimal::ZERO {
+        village.wood -= burial_wood;
+        logger.log(
+            tick,
+            village.id_str.clone(),
+            EventType::ResourceConsumed {
+                resource: ResourceType::Wood,
+                amount: burial_wood,
+                purpose: ConsumptionPurpose::Burial,
             },
         );
+    }
 
-        village.workers.push(new_worker);
+    if params.estate_food > Decimal::ZERO {
+        village.food += params.estate_food;
+        logger.log(
+            tick,
+            village.id_str.clone(),
+            EventType::EstateInherited {
+                worker_id,
+                food: params.estate_food,
+            },
+        );
     }
 }
 
+/// Maintenance levels one worker-day of repair restores, using as much wood
+const REPAIR_PER_WORKER_DAY: Decimal = dec!(0.5);
+
 /// Processes house maintenance and decay.
 ///
 /// Maintenance mechanics:
-/// - Each house requires 0.1 wood/tick for basic upkeep
-/// - Houses below 0 maintenance level can be repaired with additional 0.1 wood
-/// - Without maintenance, houses decay by 0.1 level/tick
-/// - Shelter capacity = 5 * (1 + maintenance_level) when level >= 0
+/// - Each house requires 0.1 wood/day for basic upkeep
+/// - Houses below 0 maintenance level can be repaired with additional 0.1 wood/day
+/// - Without maintenance, houses decay by 0.1 level/day
+/// - All of these are spread evenly over the day's `ticks_per_day` ticks
+/// - Each repair worker-day restores a further `REPAIR_PER_WORKER_DAY` levels,
+///   for the same amount of wood, worst-damaged houses first
+/// - Shelter capacity = 5 + floor(maintenance_level) when level >= 0, so each
+///   whole upgrade level adds one
 /// - Negative maintenance reduces effective shelter capacity
-fn process_house_maintenance(village: &mut Village, logger: &mut EventLogger, tick: usize) {
+fn process_house_maintenance(
+    village: &mut Village,
+    allocation: &Allocation,
+    params: &SimulationParameters,
+    logger: &mut EventLogger,
+    tick: usize,
+) {
     let mut wood_for_maintenance = dec!(0);
+    let upkeep = dec!(0.1) / Decimal::from(params.ticks_per_day);
+
+    // Active repair (the allocation is already scaled to this tick)
+    let mut repair_capacity = allocation.repair * REPAIR_PER_WORKER_DAY;
+    let mut damaged: Vec<&mut House> = village
+        .houses
+        .iter_mut()
+        .filter(|h| h.maintenance_level < dec!(0.0))
+        .collect();
+    damaged.sort_by_key(|h| h.maintenance_level);
+    for house in damaged {
+        let amount = (-house.maintenance_level)
+            .min(repair_capacity)
+            .min(village.wood);
+        if amount <= dec!(0) {
+            break;
+        }
+        house.maintenance_level += amount;
+        village.wood -= amount;
+        wood_for_maintenance += amount;
+        repair_capacity -= amount;
+    }
 
     for house in village.houses.iter_mut() {
-        if village.wood >= dec!(0.1) {
+        if village.wood >= upkeep {
             // Basic maintenance
-            village.wood -= dec!(0.1);
-            wood_for_maintenance += dec!(0.1);
+            village.wood -= upkeep;
+            wood_for_maintenance += upkeep;
 
             // Repair if needed and wood available
-            if village.wood >= dec!(0.1) && house.maintenance_level < dec!(0.0) {
-                house.maintenance_level += dec!(0.1);
-                village.wood -= dec!(0.1);
-                wood_for_maintenance += dec!(0.1);
+            if village.wood >= upkeep && house.maintenance_level < dec!(0.0) {
+                house.maintenance_level += upkeep;
+                village.wood -= upkeep;
+                wood_for_maintenance += upkeep;
             }
         } else {
             // No wood for maintenance, house decays
-            house.maintenance_level -= dec!(0.1);
+            house.maintenance_level -= upkeep;
             logger.log(
                 tick,
                 village.id_str.clone(),
@@ -514,6 +1033,35 @@ fn process_house_maintenance(village: &mut Village, logger: &mut EventLogger, ti
             },
         );
     }
+
+    if let Some(threshold) = params.collapse_threshold {
+        collapse_decayed_houses(village, threshold, logger, tick);
+    }
+}
+
+/// Removes houses whose maintenance has fallen below `threshold`.
+fn collapse_decayed_houses(
+    village: &mut Village,
+    threshold: Decimal,
+    logger: &mut EventLogger,
+    tick: usize,
+) {
+    let (collapsed, standing): (Vec<House>, Vec<House>) = village
+        .houses
+        .drain(..)
+        .partition(|h| h.maintenance_level < threshold);
+    village.houses = standing;
+    for house in collapsed {
+        logger.log(
+            tick,
+            village.id_str.clone(),
+            EventType::HouseCollapsed {
+                house_id: house.id,
+                maintenance_level: house.maintenance_level,
+                total_houses: village.houses.len(),
+            },
+        );
+    }
 }
 
 /// Calculates resource production based on slot allocation and worker assignment.
@@ -535,22 +1083,56 @@ fn produced(slots: (u32, u32), units_per_slot: Decimal, worker_days: Decimal) ->
     (full_slots + partial_slots * dec!(0.5)) * units_per_slot
 }
 
+/// Calculates production for `worker_days` under the given curve.
+///
+/// `Step` is the slot-based `produced`; the smooth curves apply to the
+/// worker-days that fit in the slots (full plus partial) and ignore the rest.
+fn produce_with_curve(
+    curve: ProductionCurve,
+    slots: (u32, u32),
+    units_per_slot: Decimal,
+    worker_days: Decimal,
+) -> Decimal {
+    let effective_days = worker_days
+        .min(Decimal::from(slots.0 + slots.1))
+        .max(Decimal::ZERO)
+        .to_f64()
+        .unwrap_or(0.0);
+    let effective_output = match curve {
+        ProductionCurve::Step => return produced(slots, units_per_slot, worker_days),
+        // Sum of ratio^i over the worker-days, continuous in fractional days
+        ProductionCurve::ExponentialDecay { ratio } if ratio != 1.0 => {
+            (1.0 - ratio.powf(effective_days)) / (1.0 - ratio)
+        }
+        ProductionCurve::ExponentialDecay { .. } => effective_days,
+        ProductionCurve::PowerLaw { alpha } => effective_days.powf(alpha),
+    };
+    Decimal::from_f64(effective_output).unwrap_or(Decimal::ZERO) * units_per_slot
+}
+
 /// Applies auction results to village inventories.
 ///
 /// Processes each filled order:
 /// - Bids (buys): Decrease money, increase resource
 /// - Asks (sells): Increase money, decrease resource
 ///
-/// All trades are logged for analysis and metrics.
+/// All trades are logged for analysis and metrics. The auction only checks a
+/// village's net spending, so sales are applied before purchases. A purchase
+/// that still leaves money below `money_floor` means fills and balances have
+/// desynced; it is returned as an error and no further fills are applied.
 fn apply_trades(
     villages: &mut [Village],
     village_ids: &HashMap<String, VillageId>,
     fills: &[FinalFill],
+    money_floor: Decimal,
     logger: &mut EventLogger,
     tick: usize,
-) {
-    // Process each fill
-    for fill in fills {
+) -> Result<(), String> {
+    use village_model::auction::OrderType;
+
+    let sales = fills.iter().filter(|f| f.order_type == OrderType::Ask);
+    let purchases = fills.iter().filter(|f| f.order_type == OrderType::Bid);
+    for fill in sales.chain(purchases) {
         // Find the village by matching participant ID
         let village = villages.iter_mut().find(|v| {
             if let Some(vid) = village_ids.get(&v.id_str) {
@@ -562,7 +1144,7 @@ fn apply_trades(
 
         if let Some(village) = village {
             let quantity_dec = Decimal::from(fill.filled_quantity);
-            let total_value = quantity_dec * fill.price;
+            let total_value = fill.value;
 
             // Parse resource type
             let resource =
@@ -576,6 +1158,19 @@ fn apply_trades(
                     match resource {
                         ResourceType::Wood => village.wood += quantity_dec,
                         ResourceType::Food => village.food += quantity_dec,
+                        ResourceType::Labor => {
+                            for _ in 0..fill.filled_quantity {
+                                let worker_id = village.add_worker();
+                                logger.log(
+                                    tick,
+                                    village.id_str.clone(),
+                                    EventType::WorkerBorn {
+                                        worker_id,
+                                        total_population: village.workers.len(),
+                                    },
+                                );
+                            }
+                        }
                     }
 
                     logger.log(
@@ -589,6 +1184,13 @@ fn apply_trades(
                             side: TradeSide::Buy,
                         },
                     );
+                    if village.money < money_floor {
+                        return Err(format!(
+                            "{} money fell to {}, below the floor of {}, buying {} {:?}; \
+                             fills and balances desynced",
+                            village.id_str, village.money, money_floor, quantity_dec, resource
+                        ));
+                    }
                 }
                 village_model::auction::OrderType::Ask => {
                     // Selling: gain money, lose resource
@@ -596,6 +1198,26 @@ fn apply_trades(
                     match resource {
                         ResourceType::Wood => village.wood -= quantity_dec,
                         ResourceType::Food => village.food -= quantity_dec,
+                        ResourceType::Labor => {
+                            let remaining = village
+                                .workers
+                                .len()
+                                .saturating_sub(fill.filled_quantity as usize);
+                            let departed = village.workers.split_off(remaining);
+                            for (i, worker) in departed.iter().enumerate() {
+                                logger.log(
+                                    tick,
+                                    village.id_str.clone(),
+                                    EventType::WorkerDied {
+                                        worker_id: worker.id,
+                                        cause: DeathCause::Departed,
+                                        total_population: village.workers.len() + departed.len()
+                                            - i
+                                            - 1,
+                                    },
+                                );
+                            }
+                        }
                     }
 
                     logger.log(
@@ -613,6 +1235,81 @@ fn apply_trades(
             }
         }
     }
+    Ok(())
+}
+
+/// A strategy's allocation as it decided it, then as it will be applied.
+type DecidedAllocation = (Allocation, Allocation);
+
+/// Money, food and wood held across every village and market agent.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct MarketTotals {
+    money: Decimal,
+    food: Decimal,
+    wood: Decimal,
+}
+
+impl MarketTotals {
+    fn of(villages: &[Village]) -> Self {
+        Self {
+            money: villages.iter().map(|v| v.money).sum(),
+            food: villages.iter().map(|v| v.food).sum(),
+            wood: villages.iter().map(|v| v.wood).sum(),
+        }
+    }
+
+    /// What the oracle counterparty takes out of the market in `success`:
+    /// the money villages pay it and the goods they sell it, net of what it
+    /// pays and delivers.
+    fn oracle(success: &AuctionSuccess) -> Self {
+        let mut totals = Self::default();
+        for fill in &success.final_fills {
+            if !success.oracle_resources.contains(&fill.resource_id) {
+                continue;
+            }
+            let (money, goods) = match fill.order_type {
+                village_model::auction::OrderType::Bid => {
+                    (fill.value, -Decimal::from(fill.filled_quantity))
+                }
+                village_model::auction::OrderType::Ask => {
+                    (-fill.value, Decimal::from(fill.filled_quantity))
+                }
+            };
+            totals.money += money;
+            match ResourceType::from_str(&fill.resource_id.0) {
+                Some(ResourceType::Food) => totals.food += goods,
+                Some(ResourceType::Wood) => totals.wood += goods,
+                _ => {}
+            }
+        }
+        totals
+    }
+}
+
+/// Checks that settling trades only moved money, food and wood between
+/// villages and the `oracle` counterparty. `before` is taken just ahead of
+/// `apply_trades`.
+fn verify_conservation(
+    before: &MarketTotals,
+    oracle: &MarketTotals,
+    villages: &[Village],
+) -> Result<(), String> {
+    let tolerance = dec!(0.000001);
+    let after = MarketTotals::of(villages);
+    let checks = [
+        ("money", before.money, after.money + oracle.money),
+        ("food", before.food, after.food + oracle.food),
+        ("wood", before.wood, after.wood + oracle.wood),
+    ];
+    for (name, expected, actual) in checks {
+        if (expected - actual).abs() > tolerance {
+            return Err(format!(
+                "settlement changed total {} from {} to {}",
+                name, expected, actual
+            ));
+        }
+    }
+    Ok(())
 }
 
 /// Adapter to bridge between the strategies module and village decisions.
@@ -622,848 +1319,5461 @@ fn apply_trades(
 /// implemented without knowledge of internal simulation details.
 struct StrategyAdapter {
     inner: Box<dyn strategies::Strategy>,
+    seed: u64,
 }
 
 impl StrategyAdapter {
-    fn new(strategy: Box<dyn strategies::Strategy>) -> Self {
-        Self { inner: strategy }
+    fn new(mut strategy: Box<dyn strategies::Strategy>, seed: u64) -> Self {
+        strategy.seed_rng(seed);
+        Self {
+            inner: strategy,
+            seed,
+        }
+    }
+
+    /// Reseeds the strategy's RNG for `tick` from its own seed.
+    fn reseed(&mut self, tick: usize) {
+        self.inner
+            .seed_rng(derive_seed(self.seed, STRATEGY_SEED_NAMESPACE, tick as u64));
     }
 
+    /// Returns the strategy's allocation as it was decided, the same
+    /// allocation normalized to the village's worker-days (unless
+    /// `strict_allocations` is set), and its orders.
     fn get_allocation_and_orders(
-        &self,
+        &mut self,
         village: &Village,
         market_state: &strategies::MarketState,
-    ) -> (Allocation, Vec<OrderRequest>) {
-        // Convert Village to strategies::VillageState
-        let village_state = strategies::VillageState {
-            id: village.id_str.clone(),
-            workers: village.workers.len(),
-            wood: village.wood,
-            food: village.food,
-            money: village.money,
-            houses: village.houses.len(),
-            house_capacity: village.houses.len() * 5,
-            wood_slots: village.wood_slots,
-            food_slots: village.food_slots,
-            worker_days: village.worker_days(),
-            days_without_food: village
-                .workers
-                .iter()
-                .map(|w| w.days_without_food)
-                .collect(),
-            days_without_shelter: village
-                .workers
-                .iter()
-                .map(|w| w.days_without_shelter)
-                .collect(),
-            construction_progress: village.construction_progress,
-        };
-
+        params: &SimulationParameters,
+    ) -> (Allocation, Allocation, Vec<OrderRequest>) {
         // Get decision from strategy
         let decision = self
             .inner
-            .decide_allocation_and_orders(&village_state, market_state);
+            .decide_allocation_and_orders(&village_state(village, params), market_state);
 
         // Convert allocation
-        let allocation = Allocation {
+        let intent = Allocation {
             wood: decision.allocation.wood,
             food: decision.allocation.food,
             house_construction: decision.allocation.construction,
+            house_upgrade_wood: decision.house_upgrade_wood,
+            processing: decision.allocation.processing,
+            slot_expansion: decision.allocation.slot_expansion,
+            slot_expansion_target: decision.allocation.slot_expansion_target,
+            repair: decision.allocation.repair,
+        };
+        let allocation = if params.strict_allocations {
+            intent.clone()
+        } else {
+            let worker_days = village.effective_worker_days(params.labor_efficiency);
+            let normalized = intent.normalized(worker_days);
+            if (intent.labor() - worker_days).abs() >= dec!(0.001) {
+                log::warn!(
+                    "{} allocated {} of {} worker-days in village {}; rescaled",
+                    self.inner.name(),
+                    intent.labor(),
+                    worker_days,
+                    village.id_str
+                );
+            }
+            normalized
         };
 
-        // Convert orders to requests
-        let mut orders = Vec::new();
+        (intent, allocation, decision_orders(&decision))
+    }
 
-        if let Some((price, quantity)) = decision.wood_bid {
-            orders.push(OrderRequest {
-                resource: ResourceType::Wood,
-                is_buy: true,
-                quantity,
-                price,
-            });
-        }
+    /// Returns the strategy's orders for a later auction round in the tick,
+    /// given how its orders filled in the round before.
+    fn requote(
+        &mut self,
+        village: &Village,
+        market_state: &strategies::MarketState,
+        params: &SimulationParameters,
+        fills: &[strategies::OrderFill],
+    ) -> Vec<OrderRequest> {
+        let decision =
+            self.inner
+                .on_trade_result(&village_state(village, params), market_state, fills);
+        decision_orders(&decision)
+    }
+}
 
-        if let Some((price, quantity)) = decision.wood_ask {
-            orders.push(OrderRequest {
-                resource: ResourceType::Wood,
-                is_buy: false,
+/// Converts a village into the strategy API's view of it.
+fn village_state(village: &Village, params: &SimulationParameters) -> strategies::VillageState {
+    let max_upgrade_level = Decimal::from(params.max_house_upgrade_level);
+    strategies::VillageState {
+        id: village.id_str.clone(),
+        workers: village.workers.len(),
+        wood: village.wood,
+        food: village.food,
+        money: village.money,
+        houses: village.houses.len(),
+        house_capacity: village.housing_capacity(params.shelter_per_worker),
+        shelter_per_worker: params.shelter_per_worker,
+        wood_slots: village.wood_slots,
+        food_slots: village.food_slots,
+        food_quality: village.food_quality,
+        wood_quality: village.wood_quality,
+        worker_days: village.effective_worker_days(params.labor_efficiency),
+        days_without_food: village
+            .workers
+            .iter()
+            .map(|w| w.days_without_food)
+            .collect(),
+        days_without_shelter: village
+            .workers
+            .iter()
+            .map(|w| w.days_without_shelter)
+            .collect(),
+        construction_progress: village.construction_progress,
+        upgradable_house_levels: village
+            .houses
+            .iter()
+            .filter(|h| h.maintenance_level >= dec!(0))
+            .map(|h| {
+                (max_upgrade_level - h.maintenance_level)
+                    .floor()
+                    .to_u32()
+                    .unwrap_or(0)
+            })
+            .sum(),
+        house_upgrade_cost: params.house_upgrade_wood,
+        tools: village.tools,
+        can_make_tools: params.tools.is_some_and(|tools| {
+            tools.multiplier(village.tools, village.workers.len()) < tools.max_multiplier
+        }) && params.recipes.iter().any(|recipe| {
+            recipe
+                .outputs
+                .iter()
+                .any(|output| output.good == Good::Tools)
+                && recipe
+                    .inputs
+                    .iter()
+                    .all(|input| village.good(input.good) >= input.amount)
+        }),
+    }
+}
+
+/// Converts a strategy decision's bids and asks into order requests.
+fn decision_orders(decision: &strategies::StrategyDecision) -> Vec<OrderRequest> {
+    let mut orders = Vec::new();
+
+    if let Some((price, quantity)) = decision.wood_bid {
+        orders.push(OrderRequest {
+            resource: ResourceType::Wood,
+            is_buy: true,
+            quantity,
+            price,
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        });
+    }
+
+    if let Some((price, quantity)) = decision.wood_ask {
+        orders.push(OrderRequest {
+            resource: ResourceType::Wood,
+            is_buy: false,
+            quantity,
+            price,
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        });
+    }
+
+    if let Some((price, quantity)) = decision.food_bid {
+        orders.push(OrderRequest {
+            resource: ResourceType::Food,
+            is_buy: true,
+            quantity,
+            price,
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        });
+    }
+
+    if let Some((price, quantity)) = decision.food_ask {
+        orders.push(OrderRequest {
+            resource: ResourceType::Food,
+            is_buy: false,
+            quantity,
+            price,
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        });
+    }
+
+    orders
+}
+
+/// Turns a market agent's scripted orders into this tick's order requests.
+///
+/// Asks are capped at the agent's current stock, so a fixed inventory stops
+/// supplying once it has been sold off.
+fn market_agent_orders(village: &Village, agent_orders: &[AgentOrder]) -> Vec<OrderRequest> {
+    agent_orders
+        .iter()
+        .filter_map(|order| {
+            let quantity = if order.is_buy {
+                order.quantity
+            } else {
+                let stock = match order.resource {
+                    ResourceType::Wood => village.wood,
+                    ResourceType::Food => village.food,
+                    // An agent's labor is an outside pool with no stock to run out
+                    ResourceType::Labor => Decimal::from(order.quantity),
+                };
+                order.quantity.min(stock.floor().to_u32().unwrap_or(0))
+            };
+            (quantity > 0).then_some(OrderRequest {
+                resource: order.resource,
+                is_buy: order.is_buy,
                 quantity,
-                price,
-            });
+                price: order.price,
+                all_or_nothing: false,
+                time_in_force: TimeInForce::GoodTillCancel,
+                priority: None,
+                visible_quantity: order.visible_quantity,
+            })
+        })
+        .collect()
+}
+
+/// Formats a progress status line, e.g.
+/// `[tick 100/500] population 42 | wood 5.20 | food -`; prices that
+/// haven't cleared yet show as `-`.
+fn format_progress(
+    tick: usize,
+    days: usize,
+    population: usize,
+    wood_price: Option<Decimal>,
+    food_price: Option<Decimal>,
+) -> String {
+    let price = |p: Option<Decimal>| p.map_or("-".to_string(), |p| format!("{:.2}", p));
+    format!(
+        "[tick {}/{}] population {} | wood {} | food {}",
+        tick + 1,
+        days,
+        population,
+        price(wood_price),
+        price(food_price)
+    )
+}
+
+/// Village ID of the market agent standing in for `external_market`.
+const EXTERNAL_MARKET_ID: &str = "rest_of_world";
+
+/// Units the external market bids and asks for each tick, deep enough that
+/// villages never exhaust it.
+const EXTERNAL_MARKET_DEPTH: u32 = 1_000_000;
+
+/// The market agent that trades on behalf of `external_market`. Its orders,
+/// stock and money are reset every tick by `post_external_orders`.
+fn external_market_village(id: usize) -> Village {
+    village_from_config(
+        id,
+        &VillageConfig {
+            id: EXTERNAL_MARKET_ID.to_string(),
+            initial_workers: 0,
+            initial_houses: 0,
+            initial_food: Decimal::ZERO,
+            initial_wood: Decimal::ZERO,
+            initial_money: Decimal::ZERO,
+            food_slots: (0, 0),
+            wood_slots: (0, 0),
+            strategy: StrategyConfig::default(),
+            is_market_agent: true,
+            agent_orders: Vec::new(),
+            initial_worker_state: InitialWorkerState::default(),
+            initial_construction_progress: Decimal::ZERO,
+            food_quality: Decimal::ONE,
+            wood_quality: Decimal::ONE,
+        },
+    )
+}
+
+/// Namespace for deriving per-village strategy seeds from the scenario seed.
+const STRATEGY_SEED_NAMESPACE: &str = "strategy";
+
+/// Namespace for deriving per-round auction tie-break seeds from the scenario seed.
+const AUCTION_SEED_NAMESPACE: &str = "auction";
+
+/// Namespace for deriving per-village, per-tick lifecycle seeds from the scenario seed.
+const LIFECYCLE_SEED_NAMESPACE: &str = "lifecycle";
+
+/// Seed of village `village_id`'s lifecycle RNG on `tick`.
+fn lifecycle_seed(seed: u64, village_id: usize, tick: usize) -> u64 {
+    let village_seed = derive_seed(seed, LIFECYCLE_SEED_NAMESPACE, village_id as u64);
+    derive_seed(village_seed, LIFECYCLE_SEED_NAMESPACE, tick as u64)
+}
+
+/// Tie-break seed of auction `round` on `tick`. The first round keeps the
+/// tick's seed, so a single round per tick breaks ties as it always has.
+fn auction_seed(seed: u64, tick: usize, round: usize) -> u64 {
+    let tick_seed = derive_seed(seed, AUCTION_SEED_NAMESPACE, tick as u64);
+    if round == 0 {
+        tick_seed
+    } else {
+        derive_seed(tick_seed, AUCTION_SEED_NAMESPACE, round as u64)
+    }
+}
+
+/// Headless simulation engine: villages, their strategies and the market.
+///
+/// `run_simulation` wraps this with scenario loading, console output and
+/// saving events; tests drive it directly.
+struct SimulationRunner {
+    params: SimulationParameters,
+    villages: Vec<Village>,
+    village_ids: HashMap<String, VillageId>,
+    strategies: Vec<StrategyAdapter>,
+    /// Scripted orders for each village that is a market agent
+    market_agents: Vec<Option<Vec<AgentOrder>>>,
+    /// Scenario seed used to derive each tick's auction tie-break seed
+    random_seed: Option<u64>,
+    last_clearing_prices: HashMap<ResourceId, Decimal>,
+    /// Order books from the previous tick's submitted orders
+    last_wood_book: strategies::OrderBook,
+    last_food_book: strategies::OrderBook,
+    /// Iceberg orders left over from the last auction round, per village,
+    /// with their shown part topped up from the hidden rest
+    resting_icebergs: Vec<Vec<OrderRequest>>,
+    /// Consecutive insolvent ticks per village, for `bankruptcy`
+    insolvent_ticks: Vec<usize>,
+    /// Current price of each resource on the external market
+    external_prices: HashMap<ResourceType, Decimal>,
+    /// Auctions run so far, for `bench`
+    auctions_run: usize,
+    logger: EventLogger,
+}
+
+impl SimulationRunner {
+    /// Creates villages from the scenario, pairing each with its strategy.
+    ///
+    /// With a scenario seed, each village's lifecycle RNG and its strategy RNG
+    /// are derived from it under separate namespaces, so changing how much
+    /// randomness a strategy draws never perturbs births and deaths.
+    fn new(scenario: &Scenario, strategies: Vec<Box<dyn strategies::Strategy>>) -> Self {
+        let mut villages: Vec<Village> = scenario
+            .villages
+            .iter()
+            .enumerate()
+            .map(|(i, config)| village_from_config(i, config))
+            .collect();
+
+        let mut market_agents: Vec<_> = scenario
+            .villages
+            .iter()
+            .map(|config| config.is_market_agent.then(|| config.agent_orders.clone()))
+            .collect();
+
+        let mut strategies: Vec<StrategyAdapter> = strategies
+            .into_iter()
+            .enumerate()
+            .map(|(i, strategy)| {
+                let strategy_seed = match scenario.random_seed {
+                    Some(seed) => derive_seed(seed, STRATEGY_SEED_NAMESPACE, i as u64),
+                    None => rand::random(),
+                };
+                StrategyAdapter::new(strategy, strategy_seed)
+            })
+            .collect();
+
+        // The external market trades as one more market agent; its strategy
+        // only keeps the per-village vectors aligned and is never consulted
+        if !scenario.parameters.external_market.is_empty() {
+            villages.push(external_market_village(villages.len()));
+            market_agents.push(Some(Vec::new()));
+            strategies.push(StrategyAdapter::new(
+                strategies::create_strategy(&StrategyConfig::default()),
+                0,
+            ));
         }
 
-        if let Some((price, quantity)) = decision.food_bid {
-            orders.push(OrderRequest {
-                resource: ResourceType::Food,
-                is_buy: true,
-                quantity,
-                price,
-            });
+        let insolvent_ticks = vec![0; villages.len()];
+
+        // Create village ID mapping
+        let village_ids: HashMap<String, VillageId> = villages
+            .iter()
+            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .collect();
+
+        let mut runner = Self {
+            params: scenario.parameters.clone(),
+            villages,
+            village_ids,
+            strategies,
+            market_agents,
+            random_seed: scenario.random_seed,
+            last_clearing_prices: auction_price_map(&scenario.parameters.initial_prices),
+            last_wood_book: strategies::OrderBook::default(),
+            resting_icebergs: Vec::new(),
+            last_food_book: strategies::OrderBook::default(),
+            insolvent_ticks,
+            external_prices: scenario
+                .parameters
+                .external_market
+                .iter()
+                .map(|(resource, market)| (*resource, market.initial_price))
+                .collect(),
+            auctions_run: 0,
+            logger: EventLogger::new(),
+        };
+        runner.reseed(0);
+        runner
+    }
+
+    /// Re-derives every village's lifecycle RNG and every strategy's RNG for
+    /// `tick` from the scenario seed. No RNG state carries from one tick to
+    /// the next, so a run resumed from a snapshot stays exact.
+    fn reseed(&mut self, tick: usize) {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let Some(seed) = self.random_seed else {
+            return;
+        };
+        for village in &mut self.villages {
+            village.rng = Some(StdRng::seed_from_u64(lifecycle_seed(
+                seed, village.id, tick,
+            )));
         }
+        for strategy in &mut self.strategies {
+            strategy.reseed(tick);
+        }
+    }
 
-        if let Some((price, quantity)) = decision.food_ask {
-            orders.push(OrderRequest {
-                resource: ResourceType::Food,
-                is_buy: false,
-                quantity,
-                price,
-            });
+    /// Replaces each village that has a snapshot with one rebuilt from it,
+    /// keeping its production slots and lifecycle RNG.
+    fn warm_start(&mut self, snapshots: &[FinalVillageState]) {
+        for village in &mut self.villages {
+            let Some(snapshot) = snapshots.iter().find(|s| s.village_id == village.id_str) else {
+                continue;
+            };
+            let rng = village.rng.take();
+            let quality = (village.food_quality, village.wood_quality);
+            *village = Village::from_snapshot(
+                village.id,
+                village.id_str.clone(),
+                village.wood_slots,
+                village.food_slots,
+                &snapshot.into(),
+            );
+            village.rng = rng;
+            (village.food_quality, village.wood_quality) = quality;
         }
+    }
 
-        (allocation, orders)
+    /// Captures every village and the market's state, to resume at `next_tick`.
+    fn snapshot(&self, next_tick: usize) -> SimulationSnapshot {
+        SimulationSnapshot {
+            next_tick,
+            villages: self.villages.iter().map(Village::snapshot).collect(),
+            last_clearing_prices: self
+                .last_clearing_prices
+                .iter()
+                .filter_map(|(id, price)| ResourceType::from_str(&id.0).map(|r| (r, *price)))
+                .collect(),
+            wood_book: self.last_wood_book.clone(),
+            food_book: self.last_food_book.clone(),
+            insolvent_ticks: self
+                .villages
+                .iter()
+                .zip(&self.insolvent_ticks)
+                .map(|(village, ticks)| (village.id_str.clone(), *ticks))
+                .collect(),
+            external_prices: self.external_prices.clone(),
+        }
     }
-}
 
-/// Entry point for the village model simulation.
-fn main() {
-    // Parse enhanced command line arguments
-    let args = match parse_args() {
-        Ok(args) => args,
-        Err(e) => {
-            eprintln!("Error parsing arguments: {}", e);
-            process::exit(1);
+    /// Replaces each village with its exact saved state, drops villages the
+    /// snapshot no longer has, and resumes the market from its saved state.
+    fn restore(&mut self, snapshot: &SimulationSnapshot) {
+        let mut village_idx = 0;
+        while village_idx < self.villages.len() {
+            let village = &mut self.villages[village_idx];
+            let Some(saved) = snapshot
+                .villages
+                .iter()
+                .find(|s| s.id_str == village.id_str)
+            else {
+                self.remove_village(village_idx);
+                continue;
+            };
+            let rng = village.rng.take();
+            *village = Village::restore(saved.clone());
+            village.rng = rng;
+            self.insolvent_ticks[village_idx] = snapshot
+                .insolvent_ticks
+                .get(&village.id_str)
+                .copied()
+                .unwrap_or_default();
+            village_idx += 1;
         }
-    };
+        self.last_clearing_prices = auction_price_map(&snapshot.last_clearing_prices);
+        self.last_wood_book = snapshot.wood_book.clone();
+        self.last_food_book = snapshot.food_book.clone();
+        self.external_prices
+            .extend(snapshot.external_prices.iter().map(|(r, p)| (*r, *p)));
+    }
 
-    // Set up logging if debug mode is enabled
-    if args.debug {
-        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug")).init();
-    } else if args.verbose {
-        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    /// Removes the village at `village_idx` along with its strategy and
+    /// market bookkeeping.
+    fn remove_village(&mut self, village_idx: usize) -> Village {
+        let village = self.villages.remove(village_idx);
+        self.strategies.remove(village_idx);
+        self.market_agents.remove(village_idx);
+        self.insolvent_ticks.remove(village_idx);
+        self.village_ids.remove(&village.id_str);
+        village
     }
 
-    // Execute command
-    match args.command {
-        Command::Run => {
-            run_simulation(args);
+    /// Steps each external price along its random walk (from the second
+    /// tick on), logs it, and restocks the external market agent to quote
+    /// deep bids and asks around it.
+    fn post_external_orders(&mut self, tick: usize) {
+        let Some(agent_idx) = self
+            .villages
+            .iter()
+            .position(|v| v.id_str == EXTERNAL_MARKET_ID)
+        else {
+            return;
+        };
+        let agent = &mut self.villages[agent_idx];
+        let depth = Decimal::from(EXTERNAL_MARKET_DEPTH);
+        let mut orders = Vec::new();
+        agent.money = Decimal::ZERO;
+        // Fixed order, so the walk draws from the agent's RNG reproducibly
+        for resource in [ResourceType::Food, ResourceType::Wood] {
+            let Some(market) = self.params.external_market.get(&resource) else {
+                continue;
+            };
+            let price = self
+                .external_prices
+                .entry(resource)
+                .or_insert(market.initial_price);
+            if tick > 0 {
+                *price = market.next_price(*price, agent.random_shock());
+            }
+            self.logger.log(
+                tick,
+                agent.id_str.clone(),
+                EventType::ExternalPrice {
+                    resource,
+                    price: *price,
+                },
+            );
+
+            let (bid, ask) = market.quotes(*price);
+            agent.money += bid * depth;
+            match resource {
+                ResourceType::Food => agent.food = depth,
+                _ => agent.wood = depth,
+            }
+            for (is_buy, price) in [(true, bid), (false, ask)] {
+                orders.push(AgentOrder {
+                    resource,
+                    is_buy,
+                    quantity: EXTERNAL_MARKET_DEPTH,
+                    price,
+                    visible_quantity: None,
+                });
+            }
         }
-        Command::Ui { file } => {
-            if let Err(e) = run_ui(&file.to_string_lossy()) {
-                eprintln!("Error running UI: {}", e);
-                process::exit(1);
+        self.market_agents[agent_idx] = Some(orders);
+    }
+
+    /// One-line status for `--report-interval`: the tick, living population
+    /// and last clearing prices.
+    fn progress_line(&self, tick: usize, days: usize) -> String {
+        let population = self
+            .villages
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.is_market_agent(*i))
+            .map(|(_, v)| v.workers.len())
+            .sum();
+        let price = |resource: &str| {
+            self.last_clearing_prices
+                .get(&ResourceId(resource.to_string()))
+                .copied()
+        };
+        format_progress(tick, days, population, price("wood"), price("food"))
+    }
+
+    /// Logs every village's worker ages, for end-of-run age metrics.
+    fn log_age_census(&mut self, tick: usize) {
+        for (village, agent) in self.villages.iter().zip(&self.market_agents) {
+            if agent.is_some() {
+                continue;
             }
+            self.logger.log(
+                tick,
+                village.id_str.clone(),
+                EventType::AgeCensus {
+                    ages: village.workers.iter().map(|w| w.age).collect(),
+                },
+            );
         }
-        Command::Analyze { file } => match analyze_simulation(&file) {
-            Ok(analysis) => {
-                println!("\n=== Simulation Analysis ===");
-                println!("Total Events: {}", analysis.total_events);
-                println!("Duration: {} days", analysis.total_days);
-                println!("\nVillage Performance:");
-                for village in &analysis.villages {
-                    println!(
-                        "  {}: {} -> {} workers ({:+.1}% growth)",
-                        village.id,
-                        village.initial_population,
-                        village.final_population,
-                        village.growth_rate * 100.0
-                    );
-                }
-                println!("\nMarket Activity:");
-                println!("  Orders: {}", analysis.market.total_orders);
-                println!(
-                    "  Trades: {} ({:.1}% success rate)",
-                    analysis.market.total_trades,
-                    analysis.market.trade_success_rate * 100.0
-                );
-                if !analysis.insights.is_empty() {
-                    println!("\nInsights:");
-                    for insight in &analysis.insights {
-                        println!("  - {}", insight);
-                    }
-                }
+    }
+
+    /// Whether every living village has died out (market agents never do).
+    fn all_villages_dead(&self) -> bool {
+        self.villages
+            .iter()
+            .zip(&self.market_agents)
+            .filter(|(_, agent)| agent.is_none())
+            .all(|(v, _)| v.workers.is_empty())
+    }
+
+    /// Whether the village at `village_idx` is a market agent.
+    fn is_market_agent(&self, village_idx: usize) -> bool {
+        self.market_agents[village_idx].is_some()
+    }
+
+    /// Runs one tick: strategy decisions, then village updates and the
+    /// auction in the order set by `phase_order`.
+    fn step(&mut self, tick: usize) {
+        let first_event = self.logger.get_events().len();
+        self.reseed(tick);
+        log_global_modifiers(&self.params, &mut self.logger, tick);
+        log_seasonality(&self.params, &mut self.logger, tick);
+        self.post_external_orders(tick);
+
+        // Strategy phase: Each village decides worker allocation and trading orders
+        let market_state = self.market_state();
+        let (allocations, village_orders) = self.decide(&market_state);
+
+        match self.params.phase_order {
+            PhaseOrder::ConsumeFirst => {
+                self.update_villages(allocations, tick);
+                self.run_market_rounds(village_orders, tick);
             }
-            Err(e) => {
-                eprintln!("Error analyzing simulation: {}", e);
-                process::exit(1);
+            PhaseOrder::TradeFirst => {
+                self.run_market_rounds(village_orders, tick);
+                self.update_villages(allocations, tick);
             }
-        },
-        Command::Compare { files } => {
-            let mut analyses = Vec::new();
-            for file in &files {
-                match analyze_simulation(file) {
-                    Ok(analysis) => analyses.push(analysis),
-                    Err(e) => {
-                        eprintln!("Error analyzing {}: {}", file.display(), e);
-                        process::exit(1);
-                    }
-                }
+        }
+
+        self.log_cash_flows(
+            first_event,
+            market_state.wood_price(),
+            market_state.food_price(),
+            tick,
+        );
+        self.wind_up_bankrupt_villages(tick);
+    }
+
+    /// Removes villages that have been insolvent for the bankruptcy rule's
+    /// grace period, handing what they hold to the heir.
+    ///
+    /// Each of its workers is logged as departing. A negative balance is
+    /// written off rather than passed on, and without an heir the estate
+    /// leaves the economy; both are logged as `MoneyAdjusted` so the money
+    /// can be accounted for.
+    fn wind_up_bankrupt_villages(&mut self, tick: usize) {
+        let Some(rule) = self.params.bankruptcy.clone() else {
+            return;
+        };
+        let mut village_idx = 0;
+        while village_idx < self.villages.len() {
+            let village = &self.villages[village_idx];
+            let insolvent = !self.is_market_agent(village_idx)
+                && village.money <= Decimal::ZERO
+                && village.food_days() < rule.food_days_threshold;
+            if insolvent {
+                self.insolvent_ticks[village_idx] += 1;
+            } else {
+                self.insolvent_ticks[village_idx] = 0;
+            }
+            if self.insolvent_ticks[village_idx] < rule.grace_ticks {
+                village_idx += 1;
+                continue;
             }
 
-            let report = compare_simulations(&analyses);
-            println!("\n=== Simulation Comparison ===");
-            for (i, summary) in report.simulation_summaries.iter().enumerate() {
-                println!("\nSimulation {} ({}):", i + 1, files[i].display());
-                println!(
-                    "  Avg Growth Rate: {:+.1}%",
-                    summary.avg_growth_rate * 100.0
-                );
-                println!(
-                    "  Avg Survival Rate: {:.1}%",
-                    summary.avg_survival_rate * 100.0
+            let bankrupt = self.remove_village(village_idx);
+            let workers = bankrupt.workers.len();
+            for (i, worker) in bankrupt.workers.iter().enumerate() {
+                self.logger.log(
+                    tick,
+                    bankrupt.id_str.clone(),
+                    EventType::WorkerDied {
+                        worker_id: worker.id,
+                        cause: DeathCause::Departed,
+                        total_population: workers - i - 1,
+                    },
                 );
-                println!("  Total Trades: {}", summary.total_trades);
-                println!(
-                    "  Trade Success Rate: {:.1}%",
-                    summary.trade_success_rate * 100.0
+            }
+            if bankrupt.money < Decimal::ZERO {
+                self.logger.log(
+                    tick,
+                    bankrupt.id_str.clone(),
+                    EventType::MoneyAdjusted {
+                        amount: -bankrupt.money,
+                        reason: AdjustmentReason::DebtWrittenOff,
+                    },
                 );
             }
 
-            if !report.strategy_rankings.is_empty() {
-                println!("\nStrategy Rankings:");
-                for (rank, (strategy, score)) in report.strategy_rankings.iter().enumerate() {
-                    println!("  {}. {} (score: {:.2})", rank + 1, strategy, score);
+            let food = bankrupt.food + bankrupt.granary;
+            let money = bankrupt.money.max(Decimal::ZERO);
+            let heir = rule
+                .heir
+                .as_ref()
+                .and_then(|heir| self.villages.iter_mut().find(|v| &v.id_str == heir));
+            let heir_id = match heir {
+                Some(heir) => {
+                    heir.food += food;
+                    heir.wood += bankrupt.wood;
+                    heir.money += money;
+                    heir.id_str.clone()
+                }
+                None => {
+                    if money > Decimal::ZERO {
+                        self.logger.log(
+                            tick,
+                            bankrupt.id_str.clone(),
+                            EventType::MoneyAdjusted {
+                                amount: -money,
+                                reason: AdjustmentReason::EstateForfeited,
+                            },
+                        );
+                    }
+                    "market".to_string()
+                }
+            };
+            self.logger.log(
+                tick,
+                bankrupt.id_str.clone(),
+                EventType::VillageBankrupt {
+                    heir: heir_id,
+                    food,
+                    wood: bankrupt.wood,
+                    money,
+                    workers,
+                },
+            );
+        }
+    }
+
+    /// Market state strategies see, from the last clearing prices and books.
+    fn market_state(&self) -> strategies::MarketState {
+        strategies::MarketState {
+            last_wood_price: self
+                .last_clearing_prices
+                .get(&village_model::auction::ResourceId("wood".to_string()))
+                .cloned(),
+            last_food_price: self
+                .last_clearing_prices
+                .get(&village_model::auction::ResourceId("food".to_string()))
+                .cloned(),
+            default_prices: strategies::DefaultPrices {
+                wood: self.params.default_wood_price,
+                food: self.params.default_food_price,
+            },
+            wood_book: self.last_wood_book.clone(),
+            food_book: self.last_food_book.clone(),
+        }
+    }
+
+    /// Asks each village's strategy for its allocation and orders. Market
+    /// agents get no allocation and post their standing orders.
+    fn decide(
+        &mut self,
+        market_state: &strategies::MarketState,
+    ) -> (Vec<Option<DecidedAllocation>>, Vec<Vec<OrderRequest>>) {
+        let mut allocations = Vec::with_capacity(self.villages.len());
+        let mut village_orders = Vec::with_capacity(self.villages.len());
+        for (village_idx, village) in self.villages.iter().enumerate() {
+            match &self.market_agents[village_idx] {
+                // Market agents skip production and the lifecycle entirely
+                Some(agent_orders) => {
+                    allocations.push(None);
+                    village_orders.push(market_agent_orders(village, agent_orders));
+                }
+                None => {
+                    let (intent, allocation, orders) = self.strategies[village_idx]
+                        .get_allocation_and_orders(village, market_state, &self.params);
+                    allocations.push(Some((intent, allocation)));
+                    village_orders.push(orders);
                 }
             }
         }
-        Command::Explain { file } => match analyze_simulation(&file) {
-            Ok(analysis) => {
-                let explanation = explain_simulation(&analysis);
-                println!("{}", explanation);
+        (allocations, village_orders)
+    }
+
+    /// Runs `rounds_per_tick` auctions. The first uses the orders decided at
+    /// the start of the tick. Before each later round strategies re-quote
+    /// through `on_trade_result`, while market agents only carry over what is
+    /// still unfilled, so their orders are posted once per tick.
+    fn run_market_rounds(&mut self, mut village_orders: Vec<Vec<OrderRequest>>, tick: usize) {
+        let rounds = self.params.rounds_per_tick as usize;
+        // `max_market_volume` caps the whole tick, so each round gets what
+        // earlier rounds left
+        let mut volume_left = self.params.max_market_volume.clone();
+        for round in 0..rounds {
+            let filled = self.run_market(village_orders.clone(), tick, round, &volume_left);
+            for (resource, left) in &mut volume_left {
+                let traded = |is_buy: bool| -> u64 {
+                    village_orders
+                        .iter()
+                        .zip(&filled)
+                        .flat_map(|(orders, filled)| orders.iter().zip(filled))
+                        .filter(|(order, _)| order.resource == *resource && order.is_buy == is_buy)
+                        .map(|(_, filled)| u64::from(*filled))
+                        .sum()
+                };
+                // Against the oracle only one side trades
+                *left = left.saturating_sub(traded(true).max(traded(false)));
             }
-            Err(e) => {
-                eprintln!("Error analyzing simulation: {}", e);
-                process::exit(1);
+            if round + 1 == rounds {
+                break;
             }
-        },
-        Command::Batch { config } => {
-            match ExperimentBatch::load_from_file(&config) {
-                Ok(batch) => {
-                    println!("Running {} experiments", batch.experiments.len());
-                    if let Some(parallel) = batch.parallel {
-                        println!("Parallel execution with {} threads", parallel);
-                    }
+            let market_state = self.market_state();
+            for (village_idx, (orders, filled)) in village_orders.iter_mut().zip(filled).enumerate()
+            {
+                let fills: Vec<strategies::OrderFill> = orders
+                    .drain(..)
+                    .zip(filled)
+                    .map(|(order, filled)| strategies::OrderFill { order, filled })
+                    .collect();
+                *orders = if self.market_agents[village_idx].is_some() {
+                    let mut resting: Vec<OrderRequest> = fills
+                        .into_iter()
+                        .filter(|fill| fill.filled < fill.order.quantity)
+                        // A fill-or-kill order never rests into a later round
+                        .filter(|fill| fill.order.time_in_force != TimeInForce::FillOrKill)
+                        // Icebergs rest as the auction replenished them
+                        .filter(|fill| fill.order.visible_quantity.is_none())
+                        .map(|fill| OrderRequest {
+                            quantity: fill.order.quantity - fill.filled,
+                            ..fill.order
+                        })
+                        .collect();
+                    resting.append(&mut self.resting_icebergs[village_idx]);
+                    resting
+                } else {
+                    self.strategies[village_idx].requote(
+                        &self.villages[village_idx],
+                        &market_state,
+                        &self.params,
+                        &fills,
+                    )
+                };
+            }
+        }
+    }
 
-                    let results = batch.run(args.quiet);
+    /// Logs a `CashFlow` for each village, summarizing its events since
+    /// `first_event` at the given prices.
+    fn log_cash_flows(
+        &mut self,
+        first_event: usize,
+        wood_price: Decimal,
+        food_price: Decimal,
+        tick: usize,
+    ) {
+        let flows: Vec<(String, EventType)> = self
+            .villages
+            .iter()
+            .zip(&self.market_agents)
+            .filter(|(_, agent)| agent.is_none())
+            .map(|(village, _)| {
+                let events = self.logger.get_events()[first_event..]
+                    .iter()
+                    .filter(|e| e.village_id == village.id_str);
+                let flow = cash_flow(events, wood_price, food_price);
+                (village.id_str.clone(), flow)
+            })
+            .collect();
+        for (village_id, flow) in flows {
+            self.logger.log(tick, village_id, flow);
+        }
+    }
 
-                    // Print summary
-                    let successful = results.iter().filter(|r| r.success).count();
-                    println!("\n=== Experiment Results ===");
-                    println!(
-                        "Total: {} | Success: {} | Failed: {}",
-                        results.len(),
-                        successful,
-                        results.len() - successful
-                    );
+    /// Applies each living village's allocation: production, consumption,
+    /// births and deaths, and house upkeep.
+    fn update_villages(&mut self, allocations: Vec<Option<DecidedAllocation>>, tick: usize) {
+        for (village, allocation) in self.villages.iter_mut().zip(allocations) {
+            if let Some((intent, allocation)) = allocation {
+                update_village(
+                    village,
+                    &intent,
+                    allocation,
+                    &self.params,
+                    &mut self.logger,
+                    tick,
+                );
+            }
+        }
+    }
 
-                    for result in &results {
-                        if result.success {
-                            println!("✓ {} ({} ms)", result.name, result.duration_ms);
-                        } else {
-                            println!(
-                                "✗ {} - {}",
-                                result.name,
-                                result
-                                    .error
-                                    .as_ref()
-                                    .unwrap_or(&"Unknown error".to_string())
-                            );
-                        }
+    /// Runs one auction round: places every village's orders, runs the
+    /// auction and applies the trades, trading at most `volume_left` of each
+    /// capped resource. Returns how much of each order filled, indexed like
+    /// `village_orders`.
+    fn run_market(
+        &mut self,
+        village_orders: Vec<Vec<OrderRequest>>,
+        tick: usize,
+        round: usize,
+        volume_left: &HashMap<ResourceType, u64>,
+    ) -> Vec<Vec<u32>> {
+        let params = &self.params;
+        let logger = &mut self.logger;
+        let last_clearing_prices = &mut self.last_clearing_prices;
+        let village_ids = &self.village_ids;
+
+        let mut auction_builder = AuctionBuilder::with_tick(tick);
+        auction_builder.set_skip_insolvent_bids(params.skip_insolvent_bids);
+        if params.pro_rata_allocation {
+            auction_builder.set_allocation_rule(AllocationRule::ProRata);
+        }
+        // Orders resting into later rounds were already charged for
+        if round == 0 {
+            auction_builder.set_listing_fee(params.listing_fee);
+        }
+        let mut submitted_orders = Vec::new();
+        let mut filled: Vec<Vec<u32>> = village_orders.iter().map(|o| vec![0; o.len()]).collect();
+        self.resting_icebergs = vec![Vec::new(); village_orders.len()];
+        // The village, order index and logged id behind each auction order id
+        let mut placed = Vec::new();
+        // Later rounds in a tick get their own order ids
+        let round_suffix = if round == 0 {
+            String::new()
+        } else {
+            format!("_r{}", round)
+        };
+
+        for (village_idx, (village, orders)) in self.villages.iter().zip(village_orders).enumerate()
+        {
+            // Without a market, orders are discarded before they are placed
+            if params.autarky {
+                continue;
+            }
+
+            // Add village to auction
+            let village_id = &village_ids[&village.id_str];
+            auction_builder.add_village(village_id, village.money);
+            if let Some(&priority) = params.participant_priority.get(&village.id_str) {
+                auction_builder.set_participant_priority(village_id, priority);
+            }
+
+            // Add orders to auction
+            for (order_idx, order) in orders.into_iter().enumerate() {
+                if order.resource == ResourceType::Labor && !params.labor_market {
+                    continue;
+                }
+                // A village can only sell the workers it has; an agent's
+                // labor is an outside pool
+                let order = if order.resource == ResourceType::Labor
+                    && !order.is_buy
+                    && self.market_agents[village_idx].is_none()
+                {
+                    OrderRequest {
+                        quantity: order.quantity.min(village.workers.len() as u32),
+                        ..order
                     }
+                } else {
+                    order
+                };
+                if order.quantity == 0 {
+                    continue;
                 }
-                Err(e) => {
-                    eprintln!("Error loading experiment batch: {}", e);
-                    process::exit(1);
+                let side = if order.is_buy {
+                    TradeSide::Buy
+                } else {
+                    TradeSide::Sell
+                };
+                if params
+                    .embargoes
+                    .iter()
+                    .any(|e| e.village == village.id_str && e.resource == order.resource)
+                {
+                    logger.log(
+                        tick,
+                        village.id_str.clone(),
+                        EventType::OrderBlocked {
+                            resource: order.resource,
+                            quantity: order.quantity.into(),
+                            price: order.price,
+                            side,
+                        },
+                    );
+                    continue;
                 }
+                // Log order
+                let order_id = format!(
+                    "{}_{}_{}_{}{}",
+                    village.id_str,
+                    order.resource.as_str(),
+                    if order.is_buy { "bid" } else { "ask" },
+                    tick,
+                    round_suffix
+                );
+                logger.log(
+                    tick,
+                    village.id_str.clone(),
+                    EventType::OrderPlaced {
+                        resource: order.resource,
+                        quantity: order.quantity.into(),
+                        price: order.price,
+                        side,
+                        order_id: order_id.clone(),
+                    },
+                );
+
+                submitted_orders.push(order.clone());
+                placed.push((village_idx, order_idx, order_id));
+                auction_builder.add_order(village_id, order);
             }
         }
-        Command::AnalyzeBatch { files, output } => {
-            match analyze_batch(&files) {
-                Ok(report) => {
-                    // Print summary
-                    println!("\n=== Batch Analysis Report ===");
-                    println!("Analyzed {} simulations", report.simulations.len());
-                    println!("\nAggregate Statistics:");
-                    println!(
-                        "  Mean Growth Rate: {:+.1}% (σ={:.1}%)",
-                        report.aggregate_stats.mean_growth_rate * 100.0,
-                        report.aggregate_stats.std_growth_rate * 100.0
-                    );
-                    println!(
-                        "  Mean Survival Rate: {:.1}% (σ={:.1}%)",
-                        report.aggregate_stats.mean_survival_rate * 100.0,
-                        report.aggregate_stats.std_survival_rate * 100.0
-                    );
-                    println!(
-                        "  Mean Trade Volume: {:.1}",
-                        report.aggregate_stats.mean_trade_volume
-                    );
-                    println!(
-                        "  Mean Gini Coefficient: {:.3}",
-                        report.aggregate_stats.mean_gini
-                    );
 
-                    if !report.strategy_performance.is_empty() {
-                        println!("\nStrategy Performance:");
-                        for (strategy, stats) in &report.strategy_performance {
-                            println!(
-                                "  {}: {:.1}% growth (n={})",
-                                strategy,
-                                stats.mean_growth * 100.0,
-                                stats.occurrences
-                            );
-                        }
-                    }
+        // Books remember who posted what, so each village can see the
+        // market without its own orders
+        let book_orders: Vec<_> = placed
+            .iter()
+            .zip(&submitted_orders)
+            .map(|((village_idx, _, _), order)| {
+                (Some(self.villages[*village_idx].id_str.as_str()), order)
+            })
+            .collect();
+        self.last_wood_book = strategies::OrderBook::from_village_orders(
+            book_orders.iter().copied(),
+            ResourceType::Wood,
+        );
+        self.last_food_book = strategies::OrderBook::from_village_orders(
+            book_orders.iter().copied(),
+            ResourceType::Food,
+        );
 
-                    if !report.insights.is_empty() {
-                        println!("\nInsights:");
-                        for insight in &report.insights {
-                            println!("  - {}", insight);
-                        }
-                    }
+        if params.autarky {
+            return filled;
+        }
 
-                    // Export to CSV if requested
-                    if let Some(output_path) = output {
-                        match export_batch_to_csv(&report, &output_path) {
-                            Ok(_) => println!("\nResults exported to {}", output_path.display()),
-                            Err(e) => eprintln!("Error exporting to CSV: {}", e),
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Error analyzing batch: {}", e);
-                    process::exit(1);
-                }
+        // Run double auction to match buy/sell orders across all villages
+        let builder_config = auction_builder.auction_config();
+        let (orders, participants) = auction_builder.build();
+        let auction_orders = orders.clone();
+        // A fresh tie-break order each round keeps equal orders from always
+        // resolving the same way, while staying reproducible for a seed
+        let auction_config = AuctionConfig {
+            tie_break_seed: self.random_seed.map(|seed| auction_seed(seed, tick, round)),
+            settlement_rounding: params.settlement_rounding,
+            price_ceilings: auction_price_map(&params.price_ceilings),
+            price_floors: auction_price_map(&params.price_floors),
+            max_market_volume: volume_left
+                .iter()
+                .map(|(resource, cap)| (ResourceId(resource.as_str().to_string()), *cap))
+                .collect(),
+            max_price_move: params.max_price_move,
+            oracle_prices: auction_price_map(&params.oracle_prices),
+            budget_tolerance: params.budget_tolerance,
+            degrade_on_max_iterations: params.degrade_on_max_iterations,
+            ..builder_config
+        };
+        self.auctions_run += 1;
+        let auction_result = run_auction_with_config(
+            orders,
+            participants,
+            10, // max iterations for price discovery
+            last_clearing_prices.clone(),
+            &auction_config,
+        );
+
+        let mut final_fills = Vec::new();
+        if let Ok(success) = auction_result {
+            // Update last clearing prices for the next round or tick. A later
+            // round that doesn't clear a resource keeps its earlier price
+            if round == 0 {
+                *last_clearing_prices = success.clearing_prices.clone();
+            } else {
+                last_clearing_prices.extend(success.clearing_prices.clone());
+            }
+
+            // Log auction clearing event
+            let wood_volume = success
+                .final_fills
+                .iter()
+                .filter(|f| {
+                    f.resource_id == village_model::auction::ResourceId("wood".to_string())
+                        && f.order_type == village_model::auction::OrderType::Bid
+                })
+                .map(|f| f.filled_quantity)
+                .sum::<u64>();
+            let food_volume = success
+                .final_fills
+                .iter()
+                .filter(|f| {
+                    f.resource_id == village_model::auction::ResourceId("food".to_string())
+                        && f.order_type == village_model::auction::OrderType::Bid
+                })
+                .map(|f| f.filled_quantity)
+                .sum::<u64>();
+
+            logger.log(
+                tick,
+                "market".to_string(),
+                EventType::AuctionCleared {
+                    wood_price: success
+                        .clearing_prices
+                        .get(&village_model::auction::ResourceId("wood".to_string()))
+                        .cloned(),
+                    food_price: success
+                        .clearing_prices
+                        .get(&village_model::auction::ResourceId("food".to_string()))
+                        .cloned(),
+                    wood_volume,
+                    food_volume,
+                    total_participants: success.final_balances.len(),
+                },
+            );
+
+            logger.log(
+                tick,
+                "market".to_string(),
+                EventType::MarketSurplus {
+                    realized: success.realized_surplus,
+                    potential: success.max_surplus,
+                },
+            );
+            for killed in &success.killed_orders {
+                let (village_idx, _, order_id) = &placed[killed.0];
+                let order = &submitted_orders[killed.0];
+                logger.log(
+                    tick,
+                    self.villages[*village_idx].id_str.clone(),
+                    EventType::OrderKilled {
+                        resource: order.resource,
+                        quantity: order.quantity.into(),
+                        price: order.price,
+                        side: if order.is_buy {
+                            TradeSide::Buy
+                        } else {
+                            TradeSide::Sell
+                        },
+                        order_id: order_id.clone(),
+                    },
+                );
+            }
+            log_price_controls(&success, logger, tick);
+            log_price_limits(&success, village_ids, logger, tick);
+            log_rounding_adjustments(&success, village_ids, logger, tick);
+            charge_listing_fees(&mut self.villages, village_ids, &success, logger, tick);
+
+            // Apply trades to villages
+            let totals = MarketTotals::of(&self.villages);
+            // Purchases the auction let run over budget become debt
+            if let Err(message) = apply_trades(
+                &mut self.villages,
+                village_ids,
+                &success.final_fills,
+                params.money_floor - params.budget_tolerance,
+                logger,
+                tick,
+            ) {
+                panic!("tick {}: {}", tick, message);
+            }
+            if (cfg!(debug_assertions) || params.verify_conservation)
+                && let Err(message) =
+                    verify_conservation(&totals, &MarketTotals::oracle(&success), &self.villages)
+            {
+                panic!("tick {}: {}", tick, message);
+            }
+
+            for fill in &success.final_fills {
+                let (village_idx, order_idx, _) = placed[fill.order_id.0];
+                filled[village_idx][order_idx] += fill.filled_quantity as u32;
             }
+            final_fills = success.final_fills;
         }
-        Command::Query { file, filters } => {
-            match query_events(&file, &filters) {
-                Ok(events) => {
-                    let output = format_query_results(&events, args.verbose);
-                    println!("{}", output);
 
-                    // Export to CSV if output file specified
-                    if let Some(output_path) = args.output_file {
-                        match export_query_to_csv(&events, &output_path) {
-                            Ok(_) => {
-                                println!("\nQuery results exported to {}", output_path.display())
-                            }
-                            Err(e) => eprintln!("Error exporting to CSV: {}", e),
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Error querying events: {}", e);
-                    process::exit(1);
-                }
+        // An iceberg rests with its shown part topped up, even if the
+        // auction failed and nothing filled
+        for order in replenish_icebergs(&auction_orders, &final_fills) {
+            let (village_idx, _, _) = placed[order.id.0];
+            self.resting_icebergs[village_idx].push(OrderRequest {
+                quantity: order.original_quantity as u32,
+                ..submitted_orders[order.id.0].clone()
+            });
+        }
+        filled
+    }
+}
+
+/// Totals a village's production, consumption, trades and house maintenance
+/// from its events into a `CashFlow`, valuing goods at the given prices.
+fn cash_flow<'a>(
+    events: impl Iterator<Item = &'a Event>,
+    wood_price: Decimal,
+    food_price: Decimal,
+) -> EventType {
+    let value = |resource: &ResourceType, amount: Decimal| match resource {
+        ResourceType::Wood => amount * wood_price,
+        ResourceType::Food => amount * food_price,
+        ResourceType::Labor => Decimal::ZERO,
+    };
+    let mut production_value = Decimal::ZERO;
+    let mut consumption_value = Decimal::ZERO;
+    let mut trade_cash = Decimal::ZERO;
+    let mut maintenance_cost = Decimal::ZERO;
+    for event in events {
+        match &event.event_type {
+            EventType::ResourceProduced {
+                resource, amount, ..
+            } => production_value += value(resource, *amount),
+            EventType::ResourceConsumed {
+                resource,
+                amount,
+                purpose: ConsumptionPurpose::HouseMaintenance,
+            } => maintenance_cost += value(resource, *amount),
+            EventType::ResourceConsumed {
+                resource, amount, ..
+            } => consumption_value += value(resource, *amount),
+            EventType::TradeExecuted {
+                quantity,
+                price,
+                side,
+                ..
+            } => match side {
+                TradeSide::Sell => trade_cash += quantity * price,
+                TradeSide::Buy => trade_cash -= quantity * price,
+            },
+            EventType::MoneyAdjusted { amount, .. } => trade_cash += amount,
+            EventType::FoodImported { cost, .. } => trade_cash -= cost,
+            _ => {}
+        }
+    }
+    EventType::CashFlow {
+        production_value,
+        consumption_value,
+        trade_cash,
+        maintenance_cost,
+    }
+}
+
+/// Keys per-resource prices by the auction's resource IDs.
+fn auction_price_map(prices: &HashMap<ResourceType, Decimal>) -> HashMap<ResourceId, Decimal> {
+    prices
+        .iter()
+        .map(|(resource, price)| (ResourceId(resource.as_str().to_string()), *price))
+        .collect()
+}
+
+/// Logs a `GlobalModifierActive` event for each global modifier starting or
+/// ending at `tick`.
+fn log_global_modifiers(params: &SimulationParameters, logger: &mut EventLogger, tick: usize) {
+    for modifier in &params.global_modifiers {
+        let active = if tick == modifier.start_tick {
+            true
+        } else if tick == modifier.end_tick {
+            false
+        } else {
+            continue;
+        };
+        logger.log(
+            tick,
+            "global".to_string(),
+            EventType::GlobalModifierActive {
+                resource: modifier.resource,
+                multiplier: modifier.multiplier,
+                active,
+            },
+        );
+    }
+}
+
+/// Logs a `SeasonalFactor` event for each seasonal cycle at every quarter of
+/// its period: the start, peak, midpoint and trough of a sine wave.
+fn log_seasonality(params: &SimulationParameters, logger: &mut EventLogger, tick: usize) {
+    for season in &params.seasonality {
+        if !tick.is_multiple_of((season.period / 4).max(1)) {
+            continue;
+        }
+        logger.log(
+            tick,
+            season
+                .village
+                .clone()
+                .unwrap_or_else(|| "global".to_string()),
+            EventType::SeasonalFactor {
+                resource: season.resource,
+                factor: season.factor(tick),
+            },
+        );
+    }
+}
+
+/// Logs a `PriceControlBinding` event for each resource held at a price control.
+fn log_price_controls(success: &AuctionSuccess, logger: &mut EventLogger, tick: usize) {
+    let mut bindings: Vec<_> = success.price_controls.iter().collect();
+    bindings.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+    for (resource_id, binding) in bindings {
+        let Some(resource) = ResourceType::from_str(&resource_id.0) else {
+            continue;
+        };
+        let rationed = match binding.kind {
+            PriceControlKind::Ceiling => &success.excess_demand,
+            PriceControlKind::Floor => &success.excess_supply,
+        };
+        logger.log(
+            tick,
+            "market".to_string(),
+            EventType::PriceControlBinding {
+                resource,
+                control: binding.kind,
+                price: success.clearing_prices[resource_id],
+                unconstrained_price: binding.unconstrained_price,
+                rationed_quantity: rationed.get(resource_id).copied().unwrap_or(0),
+            },
+        );
+    }
+}
+
+/// Logs a `PriceLimitHit` event for each bid clamped by the price move limit.
+fn log_price_limits(
+    success: &AuctionSuccess,
+    village_ids: &HashMap<String, VillageId>,
+    logger: &mut EventLogger,
+    tick: usize,
+) {
+    for hit in &success.price_limits {
+        let Some(resource) = ResourceType::from_str(&hit.resource_id.0) else {
+            continue;
+        };
+        let Some(village) = village_ids
+            .iter()
+            .find(|(_, id)| id.to_participant_id() == hit.participant_id.0)
+            .map(|(name, _)| name.clone())
+        else {
+            continue;
+        };
+        logger.log(
+            tick,
+            village,
+            EventType::PriceLimitHit {
+                resource,
+                limit_price: hit.limit_price,
+                clamped_price: hit.clamped_price,
+            },
+        );
+    }
+}
+
+/// Logs a `RoundingRemainder` event for each fill that carried part of the
+/// auction's settlement rounding remainder.
+fn log_rounding_adjustments(
+    success: &AuctionSuccess,
+    village_ids: &HashMap<String, VillageId>,
+    logger: &mut EventLogger,
+    tick: usize,
+) {
+    for adjustment in &success.rounding_adjustments {
+        let Some(resource) = ResourceType::from_str(&adjustment.resource_id.0) else {
+            continue;
+        };
+        let Some(village) = village_ids
+            .iter()
+            .find(|(_, id)| id.to_participant_id() == adjustment.participant_id.0)
+            .map(|(name, _)| name.clone())
+        else {
+            continue;
+        };
+        logger.log(
+            tick,
+            village,
+            EventType::RoundingRemainder {
+                resource,
+                amount: adjustment.amount,
+            },
+        );
+    }
+}
+
+/// Deducts the listing fees the auction charged from each village's money.
+fn charge_listing_fees(
+    villages: &mut [Village],
+    village_ids: &HashMap<String, VillageId>,
+    success: &AuctionSuccess,
+    logger: &mut EventLogger,
+    tick: usize,
+) {
+    for village in villages {
+        let Some(&fee) = village_ids.get(&village.id_str).and_then(|id| {
+            success
+                .listing_fees
+                .get(&ParticipantId(id.to_participant_id()))
+        }) else {
+            continue;
+        };
+        village.money -= fee;
+        logger.log(
+            tick,
+            village.id_str.clone(),
+            EventType::MoneyAdjusted {
+                amount: -fee,
+                reason: AdjustmentReason::ListingFee,
+            },
+        );
+    }
+}
+
+/// Entry point for the village model simulation.
+fn main() {
+    // Parse enhanced command line arguments
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error parsing arguments: {}", e);
+            process::exit(1);
+        }
+    };
+
+    // Set up logging if debug mode is enabled
+    if args.debug {
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug")).init();
+    } else if args.verbose {
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    }
+
+    // Execute command
+    match args.command.clone() {
+        Command::Run => {
+            if let Err(e) = run_simulation(args) {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+        Command::Ui { file, break_on } => {
+            if let Err(e) = run_ui(&file.to_string_lossy(), break_on.as_deref()) {
+                eprintln!("Error running UI: {}", e);
+                process::exit(1);
+            }
+        }
+        Command::Analyze { file, inequality } => match analyze_simulation(&file) {
+            Ok(analysis) => {
+                println!("\n=== Simulation Analysis ===");
+                println!("Total Events: {}", analysis.total_events);
+                println!("Duration: {} days", analysis.total_days);
+                println!("\nVillage Performance:");
+                for village in &analysis.villages {
+                    println!(
+                        "  {}: {} -> {} workers ({:+.1}% growth)",
+                        village.id,
+                        village.initial_population,
+                        village.final_population,
+                        village.growth_rate * 100.0
+                    );
+                }
+                println!("\nMarket Activity:");
+                println!("  Orders: {}", analysis.market.total_orders);
+                println!(
+                    "  Trades: {} ({:.1}% success rate)",
+                    analysis.market.total_trades,
+                    analysis.market.trade_success_rate * 100.0
+                );
+                if !analysis.fairness.villages.is_empty() {
+                    println!("\nFill Rates:");
+                    for rate in &analysis.fairness.villages {
+                        let flag = if analysis.fairness.flagged.contains(&rate.village_id) {
+                            " ⚠️"
+                        } else {
+                            ""
+                        };
+                        println!(
+                            "  {}: {:.1}% of {} ordered (z={:+.2}){}",
+                            rate.village_id,
+                            rate.fill_rate * 100.0,
+                            rate.ordered,
+                            rate.z_score,
+                            flag
+                        );
+                    }
+                }
+                if !analysis.insights.is_empty() {
+                    println!("\nInsights:");
+                    for insight in &analysis.insights {
+                        println!("  - {}", insight);
+                    }
+                }
+                if let Some(output_path) = inequality {
+                    let exported = EventLogger::load_from_file(&file.to_string_lossy())
+                        .map(|logger| {
+                            format_inequality_csv(&inequality_timeseries(logger.get_events()))
+                        })
+                        .map_err(|e| e.to_string())
+                        .and_then(|csv| {
+                            std::fs::write(&output_path, csv).map_err(|e| e.to_string())
+                        });
+                    match exported {
+                        Ok(_) => {
+                            println!("\nInequality series exported to {}", output_path.display())
+                        }
+                        Err(e) => {
+                            eprintln!("Error exporting inequality series: {}", e);
+                            process::exit(1);
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("Error analyzing simulation: {}", e);
+                process::exit(1);
+            }
+        },
+        Command::Compare { files } => {
+            let mut analyses = Vec::new();
+            for file in &files {
+                match analyze_simulation(file) {
+                    Ok(analysis) => analyses.push(analysis),
+                    Err(e) => {
+                        eprintln!("Error analyzing {}: {}", file.display(), e);
+                        process::exit(1);
+                    }
+                }
+            }
+
+            let report = compare_simulations(&analyses);
+            println!("\n=== Simulation Comparison ===");
+            for (i, summary) in report.simulation_summaries.iter().enumerate() {
+                println!("\nSimulation {} ({}):", i + 1, files[i].display());
+                println!(
+                    "  Avg Growth Rate: {:+.1}%",
+                    summary.avg_growth_rate * 100.0
+                );
+                println!(
+                    "  Avg Survival Rate: {:.1}%",
+                    summary.avg_survival_rate * 100.0
+                );
+                println!("  Total Trades: {}", summary.total_trades);
+                println!(
+                    "  Trade Success Rate: {:.1}%",
+                    summary.trade_success_rate * 100.0
+                );
+            }
+
+            if !report.strategy_rankings.is_empty() {
+                println!("\nStrategy Rankings:");
+                for (rank, (strategy, score)) in report.strategy_rankings.iter().enumerate() {
+                    println!("  {}. {} (score: {:.2})", rank + 1, strategy, score);
+                }
+            }
+        }
+        Command::Explain { file } => match analyze_simulation(&file) {
+            Ok(mut analysis) => {
+                // Carrying capacities need the villages' configuration
+                if args.scenario_file.is_some() {
+                    match load_configured_scenarios(&args) {
+                        Ok(scenarios) => {
+                            if let Some(scenario) = scenarios.first() {
+                                set_carrying_capacities(&mut analysis, scenario);
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            process::exit(1);
+                        }
+                    }
+                }
+                let explanation = explain_simulation(&analysis);
+                println!("{}", explanation);
+            }
+            Err(e) => {
+                eprintln!("Error analyzing simulation: {}", e);
+                process::exit(1);
+            }
+        },
+        Command::Batch { config } => {
+            match ExperimentBatch::load_from_file(&config) {
+                Ok(batch) => {
+                    println!("Running {} experiments", batch.experiments.len());
+                    if let Some(parallel) = batch.parallel {
+                        println!("Parallel execution with {} threads", parallel);
+                    }
+
+                    let results = batch.run(args.quiet);
+
+                    // Print summary
+                    let successful = results.iter().filter(|r| r.success).count();
+                    println!("\n=== Experiment Results ===");
+                    println!(
+                        "Total: {} | Success: {} | Failed: {}",
+                        results.len(),
+                        successful,
+                        results.len() - successful
+                    );
+
+                    for result in &results {
+                        if result.success {
+                            println!("✓ {} ({} ms)", result.name, result.duration_ms);
+                        } else {
+                            println!(
+                                "✗ {} - {}",
+                                result.name,
+                                result
+                                    .error
+                                    .as_ref()
+                                    .unwrap_or(&"Unknown error".to_string())
+                            );
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error loading experiment batch: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::AnalyzeBatch { files, output } => {
+            match analyze_batch(&files) {
+                Ok(report) => {
+                    // Print summary
+                    println!("\n=== Batch Analysis Report ===");
+                    println!("Analyzed {} simulations", report.simulations.len());
+                    println!("\nAggregate Statistics:");
+                    println!(
+                        "  Mean Growth Rate: {:+.1}% (σ={:.1}%)",
+                        report.aggregate_stats.mean_growth_rate * 100.0,
+                        report.aggregate_stats.std_growth_rate * 100.0
+                    );
+                    println!(
+                        "  Mean Survival Rate: {:.1}% (σ={:.1}%)",
+                        report.aggregate_stats.mean_survival_rate * 100.0,
+                        report.aggregate_stats.std_survival_rate * 100.0
+                    );
+                    println!(
+                        "  Mean Trade Volume: {:.1}",
+                        report.aggregate_stats.mean_trade_volume
+                    );
+                    println!(
+                        "  Mean Gini Coefficient: {:.3}",
+                        report.aggregate_stats.mean_gini
+                    );
+
+                    if !report.strategy_performance.is_empty() {
+                        println!("\nStrategy Performance:");
+                        for (strategy, stats) in &report.strategy_performance {
+                            println!(
+                                "  {}: {:.1}% growth (n={})",
+                                strategy,
+                                stats.mean_growth * 100.0,
+                                stats.occurrences
+                            );
+                        }
+                    }
+
+                    if !report.head_to_head.is_empty() {
+                        println!("\nHead-to-Head (final population win rate):");
+                        let mut pairs: Vec<_> = report.head_to_head.iter().collect();
+                        pairs.sort_by(|a, b| a.0.cmp(b.0));
+                        for ((strategy, opponent), rate) in pairs {
+                            println!("  {} vs {}: {:.0}%", strategy, opponent, rate * 100.0);
+                        }
+                    }
+
+                    if !report.insights.is_empty() {
+                        println!("\nInsights:");
+                        for insight in &report.insights {
+                            println!("  - {}", insight);
+                        }
+                    }
+
+                    // Export to CSV if requested
+                    if let Some(output_path) = output {
+                        match export_batch_to_csv(&report, &output_path) {
+                            Ok(_) => println!("\nResults exported to {}", output_path.display()),
+                            Err(e) => eprintln!("Error exporting to CSV: {}", e),
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error analyzing batch: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Query { file, filters } => {
+            match query_events(&file, &filters) {
+                Ok(events) => {
+                    let output = format_query_results(&events, args.verbose);
+                    println!("{}", output);
+
+                    // Export to CSV if output file specified
+                    if let Some(output_path) = args.output_file {
+                        match export_query_to_csv(&events, &output_path) {
+                            Ok(_) => {
+                                println!("\nQuery results exported to {}", output_path.display())
+                            }
+                            Err(e) => eprintln!("Error exporting to CSV: {}", e),
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error querying events: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Summary { file, out } => {
+            match EventLogger::load_from_file(&file.to_string_lossy()) {
+                Ok(logger) => {
+                    let states = final_village_states(logger.get_events());
+                    match out {
+                        Some(output_path) => match export_final_states(&states, &output_path) {
+                            Ok(_) => println!("Summary exported to {}", output_path.display()),
+                            Err(e) => {
+                                eprintln!("Error exporting summary: {}", e);
+                                process::exit(1);
+                            }
+                        },
+                        None => print!("{}", format_final_states_csv(&states)),
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error loading events: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::TradeFlows { file, out } => {
+            match EventLogger::load_from_file(&file.to_string_lossy()) {
+                Ok(logger) => {
+                    let csv = format_trade_flows_csv(&reconstruct_trade_flows(logger.get_events()));
+                    match out {
+                        Some(output_path) => match std::fs::write(&output_path, csv) {
+                            Ok(_) => println!("Trade flows exported to {}", output_path.display()),
+                            Err(e) => {
+                                eprintln!("Error exporting trade flows: {}", e);
+                                process::exit(1);
+                            }
+                        },
+                        None => print!("{}", csv),
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error loading events: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Compact {
+            file,
+            out,
+            keyframe_interval,
+        } => match EventLogger::load_from_file(&file.to_string_lossy()) {
+            Ok(logger) => {
+                let events = logger.get_events();
+                let output_path = match compact_output_path(&file, out) {
+                    Ok(path) => path,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        process::exit(1);
+                    }
+                };
+                let compacted = compact_events(events, &CompactOptions { keyframe_interval });
+                let written = serde_json::to_string_pretty(&compacted)
+                    .map_err(std::io::Error::from)
+                    .and_then(|json| std::fs::write(&output_path, json));
+                match written {
+                    Ok(_) => println!(
+                        "Compacted {} events to {} in {}",
+                        events.len(),
+                        compacted.len(),
+                        output_path.display()
+                    ),
+                    Err(e) => {
+                        eprintln!("Error writing compacted events: {}", e);
+                        process::exit(1);
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("Error loading events: {}", e);
+                process::exit(1);
+            }
+        },
+        Command::Rollup {
+            file,
+            bucket_size,
+            out,
+        } => match EventLogger::load_from_file(&file.to_string_lossy()) {
+            Ok(logger) => {
+                let csv = format_rollup_csv(&rollup_events(logger.get_events(), bucket_size));
+                match out {
+                    Some(output_path) => match std::fs::write(&output_path, csv) {
+                        Ok(_) => println!("Rollup exported to {}", output_path.display()),
+                        Err(e) => {
+                            eprintln!("Error writing rollup: {}", e);
+                            process::exit(1);
+                        }
+                    },
+                    None => print!("{}", csv),
+                }
+            }
+            Err(e) => {
+                eprintln!("Error loading events: {}", e);
+                process::exit(1);
+            }
+        },
+        Command::Plot { file, metric, out } => {
+            #[cfg(feature = "plot")]
+            match EventLogger::load_from_file(&file.to_string_lossy()) {
+                Ok(logger) => {
+                    match village_model::visualization::render_chart(
+                        logger.get_events(),
+                        metric,
+                        &out,
+                    ) {
+                        Ok(()) => println!("{:?} chart written to {}", metric, out.display()),
+                        Err(e) => {
+                            eprintln!("Error drawing chart: {}", e);
+                            process::exit(1);
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error loading events: {}", e);
+                    process::exit(1);
+                }
+            }
+            #[cfg(not(feature = "plot"))]
+            {
+                let _ = (file, metric, out);
+                eprintln!("Plotting needs the `plot` feature: cargo run --features plot -- plot");
+                process::exit(1);
+            }
+        }
+        Command::Bench { villages, ticks } => println!("{}", run_bench(villages, ticks)),
+        Command::Validate { dir } => match check_scenario_dir(&dir) {
+            Ok(checks) => {
+                print!("{}", format_scenario_checks(&checks));
+                if !checks.iter().all(|c| c.passed()) {
+                    process::exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        },
+    }
+}
+
+/// Throughput of a `bench` run.
+struct BenchReport {
+    villages: usize,
+    ticks: usize,
+    auctions: usize,
+    peak_events: usize,
+    elapsed: std::time::Duration,
+}
+
+impl BenchReport {
+    fn ticks_per_second(&self) -> f64 {
+        self.ticks as f64 / self.elapsed.as_secs_f64()
+    }
+
+    fn auctions_per_second(&self) -> f64 {
+        self.auctions as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl std::fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Bench: {} villages, {} ticks in {:.2}s",
+            self.villages,
+            self.ticks,
+            self.elapsed.as_secs_f64()
+        )?;
+        writeln!(f, "  Ticks/s:    {:.1}", self.ticks_per_second())?;
+        writeln!(f, "  Auctions/s: {:.1}", self.auctions_per_second())?;
+        write!(f, "  Peak events: {}", self.peak_events)
+    }
+}
+
+/// Seed of the `bench` workload, fixed so runs are comparable.
+const BENCH_SEED: u64 = 42;
+
+/// Times a fixed workload on the headless runner: `villages` copies of the
+/// basic scenario's villages cycling through every strategy, run for `ticks`
+/// ticks with a fixed seed. Nothing is written to disk.
+fn run_bench(villages: usize, ticks: usize) -> BenchReport {
+    let basic = &create_standard_scenarios()["basic"];
+    let mut scenario = basic.clone();
+    scenario.random_seed = Some(BENCH_SEED);
+    scenario.parameters.days_to_simulate = ticks;
+    scenario.villages = (0..villages)
+        .map(|i| VillageConfig {
+            id: format!("village_{}", i),
+            ..basic.villages[i % basic.villages.len()].clone()
+        })
+        .collect();
+    let strategies = (0..villages)
+        .map(|i| {
+            strategies::create_strategy_by_name(
+                strategies::STRATEGY_NAMES[i % strategies::STRATEGY_NAMES.len()],
+            )
+        })
+        .collect();
+
+    let started = std::time::Instant::now();
+    let mut runner = SimulationRunner::new(&scenario, strategies);
+    let mut ticks_run = 0;
+    for tick in 0..ticks {
+        runner.step(tick);
+        ticks_run += 1;
+        if runner.all_villages_dead() {
+            break;
+        }
+    }
+    BenchReport {
+        villages,
+        ticks: ticks_run,
+        auctions: runner.auctions_run,
+        peak_events: runner.logger.get_events().len(),
+        elapsed: started.elapsed(),
+    }
+}
+
+/// Runs the main simulation loop.
+///
+/// # Simulation Flow
+///
+/// 1. **Initialization**: Load scenario, create villages, assign strategies
+/// 2. **Main Loop**: For each tick:
+///    - Villages decide allocations and trading orders via strategies
+///    - Update villages (production, construction, population)
+///    - Run double auction to match orders
+///    - Apply trade results to village inventories
+/// 3. **Termination**: After N ticks or when all villages die
+/// 4. **Output**: Save events to JSON, calculate and display metrics
+///
+/// A scenario file holding an array of scenarios runs each in turn, saving
+/// each one's events to the output file name suffixed with the scenario name.
+/// Names that would share an output file are rejected before anything runs.
+fn run_simulation(args: CliArgs) -> Result<(), SimError> {
+    log::info!("Starting simulation with args: {:?}", args);
+    // Load, override and validate the scenarios
+    let scenarios = load_configured_scenarios(&args)?;
+    let warm_start = match &args.warm_start {
+        Some(path) => Some(
+            load_warm_start(path).map_err(|source| SimError::WarmStartRead {
+                path: path.clone(),
+                source,
+            })?,
+        ),
+        None => None,
+    };
+
+    let events_file = args
+        .output_file
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("simulation_events.json"));
+    let multiple = scenarios.len() > 1;
+    // Scenarios whose names suffix to the same file would overwrite each other
+    let mut outputs: HashMap<PathBuf, &str> = HashMap::new();
+    for scenario in scenarios.iter().filter(|_| multiple) {
+        let output = suffixed_events_file(&events_file, &scenario.name);
+        if let Some(other) = outputs.insert(output.clone(), &scenario.name) {
+            return Err(SimError::Validation(format!(
+                "Scenarios '{}' and '{}' would both write {}; give them distinct names",
+                other,
+                scenario.name,
+                output.display()
+            )));
+        }
+    }
+    for scenario in &scenarios {
+        let output = if multiple {
+            suffixed_events_file(&events_file, &scenario.name)
+        } else {
+            events_file.clone()
+        };
+        let suffixed = |file: &PathBuf| {
+            if multiple {
+                suffixed_events_file(file, &scenario.name)
+            } else {
+                file.clone()
+            }
+        };
+        let metrics_output = args.metrics_json.as_ref().map(suffixed);
+        let snapshot_output = args.save_snapshot.as_ref().map(suffixed);
+        run_scenario(
+            scenario,
+            &args,
+            ScenarioOutputs {
+                events: &output,
+                metrics: metrics_output.as_deref(),
+                snapshot: snapshot_output.as_deref(),
+            },
+            warm_start.as_ref(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Where a run starts from instead of its configured villages.
+enum WarmStart {
+    /// Village totals from the end of an earlier run's events
+    FinalStates(Vec<FinalVillageState>),
+    /// An exact snapshot saved with `--save-snapshot`
+    Snapshot(Box<SimulationSnapshot>),
+}
+
+/// Reads a warm-start file, which holds either a saved `SimulationSnapshot`
+/// or an events log.
+fn load_warm_start(path: &Path) -> std::io::Result<WarmStart> {
+    let json = std::fs::read_to_string(path)?;
+    if let Ok(snapshot) = serde_json::from_str::<SimulationSnapshot>(&json) {
+        return Ok(WarmStart::Snapshot(Box::new(snapshot)));
+    }
+    let events: Vec<Event> = serde_json::from_str(&json)?;
+    Ok(WarmStart::FinalStates(final_village_states(&events)))
+}
+
+/// Files a scenario's run is saved to.
+struct ScenarioOutputs<'a> {
+    events: &'a Path,
+    metrics: Option<&'a Path>,
+    snapshot: Option<&'a Path>,
+}
+
+/// Where `compact` writes: `out` if given, else `<file stem>.compact.json`
+/// beside `file`. Refuses to overwrite the input, which compaction can't undo.
+fn compact_output_path(file: &Path, out: Option<PathBuf>) -> Result<PathBuf, String> {
+    let output = out.unwrap_or_else(|| {
+        let stem = file
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        file.with_file_name(format!("{}.compact.json", stem))
+    });
+    let same_file = output == file
+        || matches!(
+            (std::fs::canonicalize(file), std::fs::canonicalize(&output)),
+            (Ok(a), Ok(b)) if a == b
+        );
+    if same_file {
+        return Err(format!(
+            "refusing to overwrite {} with its compacted events; pick another -o",
+            file.display()
+        ));
+    }
+    Ok(output)
+}
+
+/// `base` with `_<scenario_name>` inserted before its extension.
+fn suffixed_events_file(base: &Path, scenario_name: &str) -> PathBuf {
+    let stem = base
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name: String = scenario_name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let mut file_name = format!("{}_{}", stem, name);
+    if let Some(ext) = base.extension() {
+        file_name.push('.');
+        file_name.push_str(&ext.to_string_lossy());
+    }
+    base.with_file_name(file_name)
+}
+
+/// Runs one scenario to completion, saving its events and, if given, its
+/// metrics and final snapshot as JSON. Villages in `warm_start` begin from it
+/// instead of their configured state; a full snapshot also resumes the run at
+/// its saved tick.
+fn run_scenario(
+    scenario: &Scenario,
+    args: &CliArgs,
+    outputs: ScenarioOutputs,
+    warm_start: Option<&WarmStart>,
+) {
+    // Validate scenario configuration
+    if !args.quiet {
+        validate_scenario(scenario, args);
+        println!("{}", scenario);
+    }
+
+    // Print villages with their strategies
+    if !args.quiet {
+        println!("\nVillages with strategies:");
+    }
+
+    // Create strategies for each village
+    let strategies: Vec<Box<dyn strategies::Strategy>> = if args.strategies.is_empty() {
+        // Use strategies from scenario configuration
+        scenario
+            .villages
+            .iter()
+            .map(|config| {
+                let strategy = strategies::create_strategy(&config.strategy);
+                if !args.quiet {
+                    println!("  {}: {} (from scenario)", config.id, strategy.name());
+                }
+                strategy
+            })
+            .collect()
+    } else {
+        // Assign strategies in order, cycling if needed
+        scenario
+            .villages
+            .iter()
+            .enumerate()
+            .map(|(i, config)| {
+                let strategy_name = &args.strategies[i % args.strategies.len()];
+                if !args.quiet {
+                    println!("  {}: {}", config.id, strategy_name);
+                }
+                strategies::create_strategy_by_name(strategy_name)
+            })
+            .collect()
+    };
+
+    if let Some(seed) = scenario.random_seed {
+        log::info!("Using random seed: {}", seed);
+    }
+    let mut runner = SimulationRunner::new(scenario, strategies);
+    let mut start_tick = 0;
+    match warm_start {
+        Some(WarmStart::FinalStates(states)) => runner.warm_start(states),
+        Some(WarmStart::Snapshot(snapshot)) => {
+            runner.restore(snapshot);
+            start_tick = snapshot.next_tick;
+        }
+        None => {}
+    }
+
+    // Track initial populations for metrics
+    let village_configs: Vec<(String, usize)> = runner
+        .villages
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !runner.is_market_agent(*i))
+        .map(|(_, v)| (v.id_str.clone(), v.workers.len()))
+        .collect();
+
+    // Metrics are accumulated as events are logged rather than from the full log
+    let mut metrics_accumulator =
+        MetricsAccumulator::new(&village_configs, scenario.parameters.days_to_simulate);
+    let mut events_observed = 0;
+
+    // Run simulation for configured number of days
+    let mut last_tick = start_tick;
+    let mut next_tick = start_tick;
+    for tick in start_tick..scenario.parameters.days_to_simulate {
+        runner.step(tick);
+        last_tick = tick;
+        next_tick = tick + 1;
+        if let Some(interval) = args.report_interval
+            && interval > 0
+            && !args.quiet
+            && (tick + 1).is_multiple_of(interval)
+        {
+            eprintln!(
+                "{}",
+                runner.progress_line(tick, scenario.parameters.days_to_simulate)
+            );
+        }
+
+        let events = runner.logger.get_events();
+        for event in &events[events_observed..] {
+            metrics_accumulator.observe(event);
+        }
+        events_observed = events.len();
+
+        // Check for early termination if all villages have died
+        if runner.all_villages_dead() {
+            if !args.quiet {
+                println!("All villages have died at tick {}", tick);
+            }
+            break;
+        }
+    }
+    runner.log_age_census(last_tick);
+    if let Some(path) = outputs.snapshot {
+        let written = serde_json::to_string_pretty(&runner.snapshot(next_tick))
+            .map_err(std::io::Error::from)
+            .and_then(|json| std::fs::write(path, json));
+        match written {
+            Ok(()) if !args.quiet => println!("Snapshot saved to {}", path.display()),
+            Ok(()) => {}
+            Err(e) => eprintln!("Error saving snapshot to {}: {}", path.display(), e),
+        }
+    }
+    let logger = runner.logger;
+    for event in &logger.get_events()[events_observed..] {
+        metrics_accumulator.observe(event);
+    }
+
+    // Save events
+    let filename = outputs.events.to_string_lossy();
+    logger.save_to_file(&filename).unwrap();
+    if !args.quiet {
+        println!("\nEvents saved to {}", filename);
+    }
+
+    // Calculate and display metrics
+    let metrics = metrics_accumulator.finalize();
+
+    if let Some(path) = outputs.metrics {
+        let written = metrics
+            .to_json()
+            .map_err(std::io::Error::from)
+            .and_then(|json| std::fs::write(path, json));
+        match written {
+            Ok(()) if !args.quiet => println!("Metrics saved to {}", path.display()),
+            Ok(()) => {}
+            Err(e) => eprintln!("Error saving metrics to {}: {}", path.display(), e),
+        }
+    }
+
+    if !args.quiet {
+        println!("\n{}", metrics);
+
+        // Display individual village metrics
+        for village_metrics in metrics.villages.values() {
+            println!("\n{}", village_metrics);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+    use village_model::cli::apply_overrides;
+    use village_model::scenario::{
+        BankruptcyRule, Embargo, InitialWorkerState, Modifier, OldAgeMortality, SeasonalShape,
+        Seasonality, create_standard_scenarios,
+    };
+
+    use super::*;
+
+    /// Runs `scenario` headless with `seed` and returns its event log, one
+    /// line per event. Wall-clock timestamps are left out.
+    fn seeded_event_log(scenario: &Scenario, seed: u64) -> Vec<String> {
+        let mut scenario = scenario.clone();
+        scenario.random_seed = Some(seed);
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let mut last_tick = 0;
+        for tick in 0..scenario.parameters.days_to_simulate {
+            runner.step(tick);
+            last_tick = tick;
+            if runner.all_villages_dead() {
+                break;
+            }
+        }
+        runner.log_age_census(last_tick);
+        runner
+            .logger
+            .get_events()
+            .iter()
+            .map(|e| {
+                let event_type = serde_json::to_string(&e.event_type).unwrap();
+                format!("{} {} {}", e.tick, e.village_id, event_type)
+            })
+            .collect()
+    }
+
+    /// Runs `scenario` twice with `seed` and reports whether the event logs
+    /// match, printing the first event where they diverge.
+    fn run_twice_and_compare(scenario: &Scenario, seed: u64) -> bool {
+        let first = seeded_event_log(scenario, seed);
+        let second = seeded_event_log(scenario, seed);
+        if let Some(i) = (0..first.len().max(second.len())).find(|&i| first.get(i) != second.get(i))
+        {
+            eprintln!(
+                "Scenario {} diverges at event {}:\n  first:  {}\n  second: {}",
+                scenario.name,
+                i,
+                first.get(i).map_or("<end of log>", String::as_str),
+                second.get(i).map_or("<end of log>", String::as_str)
+            );
+            return false;
+        }
+        true
+    }
+
+    #[test]
+    fn test_standard_scenarios_are_deterministic_for_a_seed() {
+        let mut names: Vec<_> = create_standard_scenarios().into_iter().collect();
+        names.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, scenario) in names {
+            assert!(
+                run_twice_and_compare(&scenario, 42),
+                "Scenario {} is not deterministic",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_apply_trades_wood_buy() {
+        let mut villages = vec![create_village(0, (2, 1), (2, 1), 5, 1)];
+        let mut logger = EventLogger::new();
+
+        let village_ids: HashMap<String, VillageId> = villages
+            .iter()
+            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .collect();
+
+        // Create a fill for buying wood
+        let fills = vec![FinalFill {
+            order_id: village_model::auction::OrderId(1),
+            participant_id: village_model::auction::ParticipantId(
+                village_ids["village_0"].to_participant_id(),
+            ),
+            resource_id: village_model::auction::ResourceId("wood".to_string()),
+            order_type: village_model::auction::OrderType::Bid,
+            filled_quantity: 10,
+            price: dec!(15.0),
+            value: dec!(150.0),
+        }];
+
+        let initial_wood = villages[0].wood;
+        let initial_money = villages[0].money;
+
+        apply_trades(
+            &mut villages,
+            &village_ids,
+            &fills,
+            dec!(-50),
+            &mut logger,
+            0,
+        )
+        .unwrap();
+
+        // Should have gained 10 wood and lost 150 money
+        assert_eq!(villages[0].wood, initial_wood + dec!(10));
+        assert_eq!(villages[0].money, initial_money - dec!(150));
+    }
+
+    #[test]
+    fn test_verify_conservation_catches_desynced_fills() {
+        use village_model::auction::OrderType::{Ask, Bid};
+
+        let mut villages = vec![
+            create_village(0, (2, 1), (2, 1), 5, 1),
+            create_village(1, (2, 1), (2, 1), 5, 1),
+        ];
+        let mut logger = EventLogger::new();
+        let village_ids: HashMap<String, VillageId> = villages
+            .iter()
+            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .collect();
+        let fill = |village: &str, resource: &str, order_type| FinalFill {
+            order_id: village_model::auction::OrderId(1),
+            participant_id: village_model::auction::ParticipantId(
+                village_ids[village].to_participant_id(),
+            ),
+            resource_id: village_model::auction::ResourceId(resource.to_string()),
+            order_type,
+            filled_quantity: 2,
+            price: dec!(3),
+            value: dec!(6),
+        };
+        let totals = MarketTotals::of(&villages);
+        let matched = [
+            fill("village_0", "wood", Bid),
+            fill("village_1", "wood", Ask),
+        ];
+        apply_trades(
+            &mut villages,
+            &village_ids,
+            &matched,
+            dec!(0),
+            &mut logger,
+            0,
+        )
+        .unwrap();
+        let no_oracle = MarketTotals::default();
+        assert_eq!(verify_conservation(&totals, &no_oracle, &villages), Ok(()));
+
+        // The buyer receives wood while the seller gives up food
+        let totals = MarketTotals::of(&villages);
+        let desynced = [
+            fill("village_0", "wood", Bid),
+            fill("village_1", "food", Ask),
+        ];
+        apply_trades(
+            &mut villages,
+            &village_ids,
+            &desynced,
+            dec!(0),
+            &mut logger,
+            1,
+        )
+        .unwrap();
+        let error = verify_conservation(&totals, &no_oracle, &villages).unwrap_err();
+        assert!(error.contains("food"), "{}", error);
+    }
+
+    #[test]
+    fn test_pairing_leaves_odd_worker_out_and_births_once_per_pair() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        scenario.villages[0].initial_workers = 3;
+        scenario.villages[0].initial_food = dec!(1000);
+        scenario.villages[0].initial_houses = 10;
+        scenario.villages[0].initial_worker_state = InitialWorkerState {
+            days_with_both: 100,
+            ..Default::default()
+        };
+        scenario.random_seed = Some(11);
+        scenario.parameters.require_pairing = true;
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        // Shorter than the 100 days a reset pair needs to become eligible again
+        for tick in 0..60 {
+            runner.step(tick);
+        }
+
+        let births = runner
+            .logger
+            .get_events()
+            .iter()
+            .filter(|e| matches!(e.event_type, EventType::WorkerBorn { .. }))
+            .count();
+        assert_eq!(births, 1, "Three eligible workers form a single pair");
+        let still_eligible = runner.villages[0]
+            .workers
+            .iter()
+            .filter(|w| w.spawn_eligible)
+            .count();
+        assert_eq!(still_eligible, 1, "The unpaired worker keeps waiting");
+    }
+
+    #[test]
+    fn test_insolvent_village_is_removed_after_grace_period() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        // No trading, so the penniless village can't earn its way out
+        scenario.parameters.autarky = true;
+        scenario.villages[0].initial_money = dec!(0);
+        scenario.villages[0].initial_wood = dec!(500);
+        let heir = scenario.villages[1].id.clone();
+        scenario.parameters.bankruptcy = Some(BankruptcyRule {
+            food_days_threshold: dec!(1000),
+            grace_ticks: 5,
+            heir: Some(heir.clone()),
+        });
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+
+        for tick in 0..4 {
+            runner.step(tick);
+        }
+        assert_eq!(runner.villages.len(), 2, "Still within the grace period");
+        // Debt is written off, not inherited
+        runner.villages[0].money = dec!(-20);
+        let workers = runner.villages[0].workers.len();
+        let heir_money = runner.villages[1].money;
+        let first_event = runner.logger.get_events().len();
+        runner.step(4);
+        assert_eq!(runner.villages.len(), 1);
+        assert_eq!(runner.villages[0].id_str, heir);
+        assert_eq!(runner.villages[0].money, heir_money);
+
+        let bankrupt_events: Vec<_> = runner.logger.get_events()[first_event..]
+            .iter()
+            .filter(|e| e.village_id == scenario.villages[0].id)
+            .collect();
+        let departed = bankrupt_events
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e.event_type,
+                    EventType::WorkerDied {
+                        cause: DeathCause::Departed,
+                        ..
+                    }
+                )
+            })
+            .count();
+        assert_eq!(departed, workers);
+        assert!(bankrupt_events.iter().any(|e| e.event_type
+            == EventType::MoneyAdjusted {
+                amount: dec!(20),
+                reason: AdjustmentReason::DebtWrittenOff,
+            }));
+
+        let bankruptcies: Vec<_> = runner
+            .logger
+            .get_events()
+            .iter()
+            .filter_map(|e| match &e.event_type {
+                EventType::VillageBankrupt { heir, wood, .. } => {
+                    Some((e.tick, e.village_id.clone(), heir.clone(), *wood))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(bankruptcies.len(), 1);
+        let (tick, village_id, recorded_heir, wood) = &bankruptcies[0];
+        assert_eq!(*tick, 4);
+        assert_eq!(village_id, &scenario.villages[0].id);
+        assert_eq!(recorded_heir, &heir);
+        // The heir's own wood stock is far smaller than the inherited pile
+        assert!(*wood > dec!(400));
+        assert!(runner.villages[0].wood > *wood);
+
+        // The run carries on without it
+        runner.step(5);
+    }
+
+    #[test]
+    fn test_workers_die_of_old_age_by_max_age() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        scenario.villages[0].initial_food = dec!(1000);
+        scenario.villages[0].initial_houses = 10;
+        scenario.random_seed = Some(7);
+        scenario.parameters.old_age = Some(OldAgeMortality {
+            onset_age: 20,
+            max_age: 30,
+        });
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let initial_workers = runner.villages[0].workers.len();
+        for tick in 0..40 {
+            runner.step(tick);
+        }
+
+        let old_age_death_ticks: Vec<usize> = runner
+            .logger
+            .get_events()
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e.event_type,
+                    EventType::WorkerDied {
+                        cause: DeathCause::OldAge,
+                        ..
+                    }
+                )
+            })
+            .map(|e| e.tick)
+            .collect();
+        assert_eq!(old_age_death_ticks.len(), initial_workers);
+        // Ages reach `tick + 1` at the end of each tick
+        assert!(
+            old_age_death_ticks
+                .iter()
+                .all(|&tick| (20..30).contains(&tick))
+        );
+        assert!(runner.villages[0].workers.is_empty());
+    }
+
+    #[test]
+    fn test_initial_prices_break_first_tick_ties() {
+        let first_wood_price = |initial_prices: HashMap<ResourceType, Decimal>| {
+            let mut scenario = create_standard_scenarios()["basic"].clone();
+            scenario.parameters.initial_prices = initial_prices;
+            let strategies = scenario
+                .villages
+                .iter()
+                .map(|c| strategies::create_strategy(&c.strategy))
+                .collect();
+            let mut runner = SimulationRunner::new(&scenario, strategies);
+            let order = |is_buy, price| OrderRequest {
+                resource: ResourceType::Wood,
+                is_buy,
+                quantity: 2,
+                price,
+                all_or_nothing: false,
+                time_in_force: TimeInForce::GoodTillCancel,
+                priority: None,
+                visible_quantity: None,
+            };
+            // Both limit prices clear the full 2 units, so the tie-break decides
+            runner.run_market(
+                vec![vec![order(true, dec!(9))], vec![order(false, dec!(7.5))]],
+                0,
+                0,
+                &HashMap::new(),
+            );
+            runner.last_clearing_prices[&ResourceId("wood".to_string())]
+        };
+
+        assert_eq!(first_wood_price(HashMap::new()), dec!(9));
+        assert_eq!(
+            first_wood_price(HashMap::from([(ResourceType::Wood, dec!(8))])),
+            dec!(7.5)
+        );
+    }
+
+    #[test]
+    fn test_bench_reports_positive_throughput() {
+        let report = run_bench(3, 20);
+        assert_eq!(report.ticks, 20);
+        assert_eq!(report.auctions, 20);
+        assert!(report.peak_events > 0);
+        assert!(report.ticks_per_second() > 0.0);
+        assert!(report.auctions_per_second() > 0.0);
+        assert!(report.to_string().contains("3 villages, 20 ticks"));
+    }
+
+    #[test]
+    fn test_format_progress_status_line() {
+        assert_eq!(
+            format_progress(99, 500, 42, Some(dec!(5.2)), Some(dec!(1.125))),
+            "[tick 100/500] population 42 | wood 5.20 | food 1.12"
+        );
+        assert_eq!(
+            format_progress(0, 10, 7, None, Some(dec!(2))),
+            "[tick 1/10] population 7 | wood - | food 2.00"
+        );
+    }
+
+    fn external_market_runner(seed: u64) -> SimulationRunner {
+        use village_model::scenario::ExternalMarket;
+
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.random_seed = Some(seed);
+        for (resource, initial_price) in
+            [(ResourceType::Food, dec!(1)), (ResourceType::Wood, dec!(5))]
+        {
+            scenario.parameters.external_market.insert(
+                resource,
+                ExternalMarket {
+                    initial_price,
+                    volatility: dec!(0.1),
+                    spread: dec!(0.1),
+                },
+            );
+        }
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        SimulationRunner::new(&scenario, strategies)
+    }
+
+    #[test]
+    fn test_external_price_follows_seeded_walk() {
+        use rand::{Rng, SeedableRng};
+
+        let mut runner = external_market_runner(7);
+        for tick in 0..6 {
+            runner.step(tick);
+        }
+        let prices: Vec<(ResourceType, Decimal)> = runner
+            .logger
+            .get_events()
+            .iter()
+            .filter_map(|e| match e.event_type {
+                EventType::ExternalPrice { resource, price } => Some((resource, price)),
+                _ => None,
+            })
+            .collect();
+
+        // The agent is appended after the two scenario villages, so its RNG
+        // is seeded like a third village's
+        let market = &runner.params.external_market;
+        let mut current = [dec!(1), dec!(5)];
+        let mut expected = Vec::new();
+        for tick in 0..6 {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(lifecycle_seed(7, 2, tick));
+            for (i, resource) in [ResourceType::Food, ResourceType::Wood]
+                .into_iter()
+                .enumerate()
+            {
+                if tick > 0 {
+                    let shock = rng.random_range(-1.0..=1.0);
+                    current[i] = market[&resource].next_price(current[i], shock);
+                }
+                expected.push((resource, current[i]));
+            }
+        }
+        assert_eq!(prices, expected);
+        assert!(
+            prices[2..]
+                .iter()
+                .any(|&(_, p)| p != dec!(1) && p != dec!(5))
+        );
+
+        let mut rerun = external_market_runner(7);
+        for tick in 0..6 {
+            rerun.step(tick);
+        }
+        assert_eq!(rerun.external_prices, runner.external_prices);
+    }
+
+    #[test]
+    fn test_villages_trade_against_external_market() {
+        let mut runner = external_market_runner(3);
+        runner.villages[0].money = dec!(100);
+        runner.villages[0].wood = dec!(10);
+        let food_before = runner.villages[0].food;
+
+        runner.post_external_orders(0);
+        let (_, mut orders) = runner.decide(&runner.market_state());
+        // Nobody but the external market is on the other side of either order
+        orders[0] = vec![
+            OrderRequest {
+                resource: ResourceType::Food,
+                is_buy: true,
+                quantity: 5,
+                price: dec!(2),
+                all_or_nothing: false,
+                time_in_force: TimeInForce::GoodTillCancel,
+                priority: None,
+                visible_quantity: None,
+            },
+            OrderRequest {
+                resource: ResourceType::Wood,
+                is_buy: false,
+                quantity: 3,
+                price: dec!(1),
+                all_or_nothing: false,
+                time_in_force: TimeInForce::GoodTillCancel,
+                priority: None,
+                visible_quantity: None,
+            },
+        ];
+        orders[1].clear();
+        runner.run_market(orders, 0, 0, &HashMap::new());
+
+        assert_eq!(runner.villages[0].food, food_before + dec!(5));
+        assert_eq!(runner.villages[0].wood, dec!(7));
+        assert!(runner.villages[0].money < dec!(100) + dec!(3) * dec!(5));
+    }
+
+    #[test]
+    fn test_participant_priority_outranks_pro_rata_share() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        let ask = AgentOrder {
+            resource: ResourceType::Wood,
+            is_buy: false,
+            quantity: 5,
+            price: dec!(1),
+            visible_quantity: None,
+        };
+        scenario.add_village(market_agent("wood_seller", dec!(10), dec!(0), ask.clone()));
+        scenario.parameters.pro_rata_allocation = true;
+        scenario.parameters.participant_priority =
+            HashMap::from([(scenario.villages[1].id.clone(), 1)]);
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let order = |is_buy| OrderRequest {
+            resource: ResourceType::Wood,
+            is_buy,
+            quantity: if is_buy { 4 } else { 5 },
+            price: dec!(1),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        };
+
+        let filled = runner.run_market(
+            vec![vec![order(true)], vec![order(true)], vec![order(false)]],
+            0,
+            0,
+            &HashMap::new(),
+        );
+
+        // The priority village fills completely; the rest is all that's left
+        assert_eq!(filled, vec![vec![1], vec![4], vec![5]]);
+    }
+
+    #[test]
+    fn test_pro_rata_allocation_shares_tied_bids_by_size() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        let ask = AgentOrder {
+            resource: ResourceType::Wood,
+            is_buy: false,
+            quantity: 5,
+            price: dec!(1),
+            visible_quantity: None,
+        };
+        scenario.add_village(market_agent("wood_seller", dec!(10), dec!(0), ask.clone()));
+        scenario.parameters.pro_rata_allocation = true;
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let order = |is_buy, quantity| OrderRequest {
+            resource: ResourceType::Wood,
+            is_buy,
+            quantity,
+            price: dec!(1),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        };
+
+        let filled = runner.run_market(
+            vec![
+                vec![order(true, 6)],
+                vec![order(true, 4)],
+                vec![order(false, 5)],
+            ],
+            0,
+            0,
+            &HashMap::new(),
+        );
+
+        // 6:4 of the 5 on offer, rather than all 5 to the first bid
+        assert_eq!(filled, vec![vec![3], vec![2], vec![5]]);
+    }
+
+    #[test]
+    fn test_listing_fee_charged_for_unfilled_orders() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.parameters.listing_fee = dec!(0.25);
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let money_before = runner.villages[0].money;
+        let bid = |resource| OrderRequest {
+            resource,
+            is_buy: true,
+            quantity: 2,
+            price: dec!(1),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        };
+        // Nobody sells, so neither bid fills
+        runner.run_market(
+            vec![
+                vec![bid(ResourceType::Wood), bid(ResourceType::Food)],
+                vec![],
+            ],
+            0,
+            0,
+            &HashMap::new(),
+        );
+
+        assert_eq!(runner.villages[0].money, money_before - dec!(0.5));
+        let fees: Vec<_> = runner
+            .logger
+            .get_events()
+            .iter()
+            .filter_map(|e| match &e.event_type {
+                EventType::MoneyAdjusted {
+                    amount,
+                    reason: AdjustmentReason::ListingFee,
+                } => Some((e.village_id.clone(), *amount)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(fees, vec![(runner.villages[0].id_str.clone(), dec!(-0.5))]);
+    }
+
+    #[test]
+    fn test_listing_fee_charged_once_per_tick_across_rounds() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.parameters.listing_fee = dec!(0.25);
+        scenario.parameters.rounds_per_tick = 3;
+        let bid = AgentOrder {
+            resource: ResourceType::Wood,
+            is_buy: true,
+            quantity: 2,
+            price: dec!(0.5),
+            visible_quantity: None,
+        };
+        scenario.add_village(market_agent("wood_buyer", dec!(0), dec!(10), bid.clone()));
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let orders = market_agent_orders(&runner.villages[2], &[bid]);
+
+        // Nobody sells, so the bid rests unfilled through all three rounds
+        runner.run_market_rounds(vec![vec![], vec![], orders], 0);
+
+        assert_eq!(runner.villages[2].money, dec!(9.75));
+    }
+
+    #[test]
+    fn test_agent_iceberg_ask_shows_its_visible_part_each_round() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.parameters.rounds_per_tick = 3;
+        let ask = AgentOrder {
+            resource: ResourceType::Wood,
+            is_buy: false,
+            quantity: 10,
+            price: dec!(1),
+            visible_quantity: Some(3),
+        };
+        let bid = AgentOrder {
+            resource: ResourceType::Wood,
+            is_buy: true,
+            quantity: 10,
+            price: dec!(1),
+            visible_quantity: None,
+        };
+        // Only the two agents trade, so no strategy re-quotes between rounds
+        scenario.villages.clear();
+        scenario.add_village(market_agent("iceberg", dec!(10), dec!(0), ask.clone()));
+        scenario.add_village(market_agent("wood_buyer", dec!(0), dec!(100), bid.clone()));
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let seller_orders = market_agent_orders(&runner.villages[0], &[ask]);
+        let buyer_orders = market_agent_orders(&runner.villages[1], &[bid]);
+
+        runner.run_market_rounds(vec![seller_orders, buyer_orders], 0);
+
+        // Three rounds of three shown units each, out of the ten on offer
+        assert_eq!(runner.villages[1].wood, dec!(9));
+        assert_eq!(runner.villages[0].wood, dec!(1));
+    }
+
+    #[test]
+    fn test_one_sided_market_settles_against_oracle_within_controls() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        scenario.parameters.verify_conservation = true;
+        scenario.parameters.oracle_prices = HashMap::from([(ResourceType::Wood, dec!(5))]);
+        scenario.parameters.price_ceilings = HashMap::from([(ResourceType::Wood, dec!(4))]);
+        scenario.parameters.max_market_volume = HashMap::from([(ResourceType::Wood, 3)]);
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let (money_before, wood_before) = (runner.villages[0].money, runner.villages[0].wood);
+
+        let ask = OrderRequest {
+            resource: ResourceType::Wood,
+            is_buy: false,
+            quantity: 10,
+            price: dec!(1),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        };
+        let filled = runner.run_market(
+            vec![vec![ask]],
+            0,
+            0,
+            &scenario.parameters.max_market_volume,
+        );
+
+        // Conservation is checked against the oracle's side of the fill
+        assert_eq!(filled, vec![vec![3]]);
+        assert_eq!(runner.villages[0].money, money_before + dec!(12));
+        assert_eq!(runner.villages[0].wood, wood_before - dec!(3));
+    }
+
+    #[test]
+    fn test_max_market_volume_caps_the_whole_tick_across_rounds() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.clear();
+        let ask = AgentOrder {
+            resource: ResourceType::Wood,
+            is_buy: false,
+            quantity: 10,
+            price: dec!(1),
+            visible_quantity: None,
+        };
+        scenario.add_village(market_agent("wood_seller", dec!(10), dec!(0), ask.clone()));
+        scenario.parameters.rounds_per_tick = 3;
+        scenario.parameters.oracle_prices = HashMap::from([(ResourceType::Wood, dec!(2))]);
+        scenario.parameters.max_market_volume = HashMap::from([(ResourceType::Wood, 4)]);
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+
+        let orders = market_agent_orders(&runner.villages[0], &[ask]);
+        runner.run_market_rounds(vec![orders], 0);
+
+        // The unfilled rest of the ask rests into later rounds, which find
+        // the tick's allowance already used up
+        assert_eq!(runner.villages[0].wood, dec!(6));
+        assert_eq!(runner.villages[0].money, dec!(8));
+    }
+
+    #[test]
+    fn test_partly_fillable_fill_or_kill_order_is_logged_as_killed() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        let ask = AgentOrder {
+            resource: ResourceType::Wood,
+            is_buy: false,
+            quantity: 6,
+            price: dec!(1),
+            visible_quantity: None,
+        };
+        scenario.add_village(market_agent("wood_seller", dec!(6), dec!(0), ask.clone()));
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let wood_before = runner.villages[0].wood;
+
+        let fok_bid = OrderRequest {
+            resource: ResourceType::Wood,
+            is_buy: true,
+            quantity: 10,
+            price: dec!(5),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::FillOrKill,
+            priority: None,
+            visible_quantity: None,
+        };
+        let seller_orders = market_agent_orders(&runner.villages[1], &[ask]);
+        let filled = runner.run_market(vec![vec![fok_bid], seller_orders], 0, 0, &HashMap::new());
+
+        assert_eq!(filled, vec![vec![0], vec![0]]);
+        assert_eq!(runner.villages[0].wood, wood_before);
+        let village_id = runner.villages[0].id_str.clone();
+        let killed: Vec<&str> = runner
+            .logger
+            .get_events()
+            .iter()
+            .filter(|e| e.village_id == village_id)
+            .filter_map(|e| match &e.event_type {
+                EventType::OrderKilled { order_id, .. } => Some(order_id.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(killed, vec![format!("{}_wood_bid_0", village_id)]);
+    }
+
+    #[test]
+    fn test_budget_tolerance_overrun_is_carried_as_debt() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        scenario.parameters.budget_tolerance = dec!(2);
+        let ask = AgentOrder {
+            resource: ResourceType::Wood,
+            is_buy: false,
+            quantity: 20,
+            price: dec!(10.1),
+            visible_quantity: None,
+        };
+        scenario.add_village(market_agent("wood_seller", dec!(20), dec!(0), ask.clone()));
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        runner.villages[0].money = dec!(100);
+        let wood_before = runner.villages[0].wood;
+        let bid = OrderRequest {
+            resource: ResourceType::Wood,
+            is_buy: true,
+            quantity: 10,
+            price: dec!(10.1),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        };
+
+        for tick in 0..2 {
+            let seller_orders =
+                market_agent_orders(&runner.villages[1], std::slice::from_ref(&ask));
+            runner.run_market(
+                vec![vec![bid.clone()], seller_orders],
+                tick,
+                0,
+                &HashMap::new(),
+            );
+        }
+
+        // The first purchase ran 1 over budget; the debt then blocks the second
+        assert_eq!(runner.villages[0].money, dec!(-1));
+        assert_eq!(runner.villages[0].wood, wood_before + dec!(10));
+    }
+
+    #[test]
+    fn test_embargoed_orders_never_reach_the_auction() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        scenario.parameters.embargoes = vec![Embargo {
+            village: scenario.villages[0].id.clone(),
+            resource: ResourceType::Wood,
+        }];
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+
+        let bid = |resource| OrderRequest {
+            resource,
+            is_buy: true,
+            quantity: 2,
+            price: dec!(3),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        };
+        runner.run_market(
+            vec![vec![bid(ResourceType::Wood), bid(ResourceType::Food)]],
+            0,
+            0,
+            &HashMap::new(),
+        );
+
+        assert!(runner.last_wood_book.bids.is_empty());
+        assert_eq!(runner.last_food_book.bids.len(), 1);
+        let events = runner.logger.get_events();
+        assert!(events.iter().any(|e| matches!(
+            e.event_type,
+            EventType::OrderBlocked {
+                resource: ResourceType::Wood,
+                ..
+            }
+        )));
+        assert!(!events.iter().any(|e| matches!(
+            e.event_type,
+            EventType::OrderPlaced {
+                resource: ResourceType::Wood,
+                ..
+            }
+        )));
+        assert!(events.iter().any(|e| matches!(
+            e.event_type,
+            EventType::OrderPlaced {
+                resource: ResourceType::Food,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_buying_labor_adds_workers_at_clearing_price() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        scenario.parameters.labor_market = true;
+        let labor_ask = AgentOrder {
+            resource: ResourceType::Labor,
+            is_buy: false,
+            quantity: 5,
+            price: dec!(3),
+            visible_quantity: None,
+        };
+        scenario.add_village(market_agent(
+            "labor_pool",
+            dec!(0),
+            dec!(0),
+            labor_ask.clone(),
+        ));
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let (workers, money) = (runner.villages[0].workers.len(), runner.villages[0].money);
+
+        let labor_bid = OrderRequest {
+            resource: ResourceType::Labor,
+            is_buy: true,
+            quantity: 2,
+            price: dec!(4),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        };
+        let pool_orders = market_agent_orders(&runner.villages[1], &[labor_ask]);
+        runner.run_market(vec![vec![labor_bid], pool_orders], 0, 0, &HashMap::new());
+
+        let price = runner.last_clearing_prices[&ResourceId("labor".to_string())];
+        assert_eq!(runner.villages[0].workers.len(), workers + 2);
+        assert_eq!(runner.villages[0].money, money - price * dec!(2));
+        let born = runner
+            .logger
+            .get_events()
+            .iter()
+            .filter(|e| matches!(e.event_type, EventType::WorkerBorn { .. }))
+            .count();
+        assert_eq!(born, 2);
+        assert!(runner.logger.get_events().iter().any(|e| matches!(
+            e.event_type,
+            EventType::TradeExecuted {
+                resource: ResourceType::Labor,
+                side: TradeSide::Buy,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_selling_labor_is_capped_at_the_workforce() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        scenario.parameters.labor_market = true;
+        let labor_bid = AgentOrder {
+            resource: ResourceType::Labor,
+            is_buy: true,
+            quantity: 50,
+            price: dec!(3),
+            visible_quantity: None,
+        };
+        scenario.add_village(market_agent(
+            "labor_pool",
+            dec!(0),
+            dec!(1000),
+            labor_bid.clone(),
+        ));
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let workers = runner.villages[0].workers.len();
+
+        let labor_ask = OrderRequest {
+            resource: ResourceType::Labor,
+            is_buy: false,
+            quantity: workers as u32 + 3,
+            price: dec!(1),
+            all_or_nothing: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            priority: None,
+            visible_quantity: None,
+        };
+        let pool_orders = market_agent_orders(&runner.villages[1], &[labor_bid]);
+        let filled = runner.run_market(vec![vec![labor_ask], pool_orders], 0, 0, &HashMap::new());
+
+        assert_eq!(filled[0], vec![workers as u32]);
+        assert!(runner.villages[0].workers.is_empty());
+        let populations: Vec<usize> = runner
+            .logger
+            .get_events()
+            .iter()
+            .filter_map(|e| match e.event_type {
+                EventType::WorkerDied {
+                    cause: DeathCause::Departed,
+                    total_population,
+                    ..
+                } => Some(total_population),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(populations, (0..workers).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_apply_trades_rejects_purchase_below_money_floor() {
+        let mut villages = vec![create_village(0, (2, 1), (2, 1), 5, 1)];
+        let mut logger = EventLogger::new();
+        let village_ids: HashMap<String, VillageId> = villages
+            .iter()
+            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .collect();
+
+        // A fill costing more than the village has, as if balances had desynced
+        let cost = villages[0].money + dec!(50);
+        let fills = vec![FinalFill {
+            order_id: village_model::auction::OrderId(1),
+            participant_id: village_model::auction::ParticipantId(
+                village_ids["village_0"].to_participant_id(),
+            ),
+            resource_id: village_model::auction::ResourceId("wood".to_string()),
+            order_type: village_model::auction::OrderType::Bid,
+            filled_quantity: 1,
+            price: cost,
+            value: cost,
+        }];
+
+        let error =
+            apply_trades(&mut villages, &village_ids, &fills, dec!(0), &mut logger, 0).unwrap_err();
+
+        assert!(error.contains("desynced"), "{}", error);
+        // The shortfall is reported, not covered with new money
+        assert_eq!(villages[0].money, dec!(-50));
+    }
+
+    #[test]
+    fn test_apply_trades_wood_sell() {
+        let mut villages = vec![create_village(0, (2, 1), (2, 1), 5, 1)];
+        let mut logger = EventLogger::new();
+
+        let village_ids: HashMap<String, VillageId> = villages
+            .iter()
+            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .collect();
+
+        // Create a fill for selling wood
+        let fills = vec![FinalFill {
+            order_id: village_model::auction::OrderId(1),
+            participant_id: village_model::auction::ParticipantId(
+                village_ids["village_0"].to_participant_id(),
+            ),
+            resource_id: village_model::auction::ResourceId("wood".to_string()),
+            order_type: village_model::auction::OrderType::Ask,
+            filled_quantity: 5,
+            price: dec!(20.0),
+            value: dec!(100.0),
+        }];
+
+        let initial_wood = villages[0].wood;
+        let initial_money = villages[0].money;
+
+        apply_trades(&mut villages, &village_ids, &fills, dec!(0), &mut logger, 0).unwrap();
+
+        // Should have lost 5 wood and gained 100 money
+        assert_eq!(villages[0].wood, initial_wood - dec!(5));
+        assert_eq!(villages[0].money, initial_money + dec!(100));
+    }
+
+    #[test]
+    fn test_apply_trades_food_buy() {
+        let mut villages = vec![create_village(0, (2, 1), (2, 1), 5, 1)];
+        let mut logger = EventLogger::new();
+
+        let village_ids: HashMap<String, VillageId> = villages
+            .iter()
+            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .collect();
+
+        // Create a fill for buying food
+        let fills = vec![FinalFill {
+            order_id: village_model::auction::OrderId(1),
+            participant_id: village_model::auction::ParticipantId(
+                village_ids["village_0"].to_participant_id(),
+            ),
+            resource_id: village_model::auction::ResourceId("food".to_string()),
+            order_type: village_model::auction::OrderType::Bid,
+            filled_quantity: 8,
+            price: dec!(12.0),
+            value: dec!(96.0),
+        }];
+
+        let initial_food = villages[0].food;
+        let initial_money = villages[0].money;
+
+        apply_trades(&mut villages, &village_ids, &fills, dec!(0), &mut logger, 0).unwrap();
+
+        // Should have gained 8 food and lost 96 money
+        assert_eq!(villages[0].food, initial_food + dec!(8));
+        assert_eq!(villages[0].money, initial_money - dec!(96));
+    }
+
+    #[test]
+    fn test_apply_trades_food_sell() {
+        let mut villages = vec![create_village(0, (2, 1), (2, 1), 5, 1)];
+        let mut logger = EventLogger::new();
+
+        let village_ids: HashMap<String, VillageId> = villages
+            .iter()
+            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .collect();
+
+        // Create a fill for selling food
+        let fills = vec![FinalFill {
+            order_id: village_model::auction::OrderId(1),
+            participant_id: village_model::auction::ParticipantId(
+                village_ids["village_0"].to_participant_id(),
+            ),
+            resource_id: village_model::auction::ResourceId("food".to_string()),
+            order_type: village_model::auction::OrderType::Ask,
+            filled_quantity: 15,
+            price: dec!(10.0),
+            value: dec!(150.0),
+        }];
+
+        let initial_food = villages[0].food;
+        let initial_money = villages[0].money;
+
+        apply_trades(&mut villages, &village_ids, &fills, dec!(0), &mut logger, 0).unwrap();
+
+        // Should have lost 15 food and gained 150 money
+        assert_eq!(villages[0].food, initial_food - dec!(15));
+        assert_eq!(villages[0].money, initial_money + dec!(150));
+    }
+
+    #[test]
+    fn test_apply_trades_multiple_resources() {
+        let mut villages = vec![
+            create_village(0, (2, 1), (2, 1), 5, 1),
+            create_village(1, (2, 1), (2, 1), 5, 1),
+        ];
+        let mut logger = EventLogger::new();
+
+        let village_ids: HashMap<String, VillageId> = villages
+            .iter()
+            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .collect();
+
+        // Create fills for multiple trades
+        let fills = vec![
+            // Village 0 buys wood
+            FinalFill {
+                order_id: village_model::auction::OrderId(1),
+                participant_id: village_model::auction::ParticipantId(
+                    village_ids["village_0"].to_participant_id(),
+                ),
+                resource_id: village_model::auction::ResourceId("wood".to_string()),
+                order_type: village_model::auction::OrderType::Bid,
+                filled_quantity: 10,
+                price: dec!(15.0),
+                value: dec!(150.0),
+            },
+            // Village 1 sells wood
+            FinalFill {
+                order_id: village_model::auction::OrderId(2),
+                participant_id: village_model::auction::ParticipantId(
+                    village_ids["village_1"].to_participant_id(),
+                ),
+                resource_id: village_model::auction::ResourceId("wood".to_string()),
+                order_type: village_model::auction::OrderType::Ask,
+                filled_quantity: 10,
+                price: dec!(15.0),
+                value: dec!(150.0),
+            },
+            // Village 0 sells food
+            FinalFill {
+                order_id: village_model::auction::OrderId(3),
+                participant_id: village_model::auction::ParticipantId(
+                    village_ids["village_0"].to_participant_id(),
+                ),
+                resource_id: village_model::auction::ResourceId("food".to_string()),
+                order_type: village_model::auction::OrderType::Ask,
+                filled_quantity: 5,
+                price: dec!(20.0),
+                value: dec!(100.0),
+            },
+            // Village 1 buys food
+            FinalFill {
+                order_id: village_model::auction::OrderId(4),
+                participant_id: village_model::auction::ParticipantId(
+                    village_ids["village_1"].to_participant_id(),
+                ),
+                resource_id: village_model::auction::ResourceId("food".to_string()),
+                order_type: village_model::auction::OrderType::Bid,
+                filled_quantity: 5,
+                price: dec!(20.0),
+                value: dec!(100.0),
+            },
+        ];
+
+        let v0_initial_wood = villages[0].wood;
+        let v0_initial_food = villages[0].food;
+        let v0_initial_money = villages[0].money;
+        let v1_initial_wood = villages[1].wood;
+        let v1_initial_food = villages[1].food;
+        let v1_initial_money = villages[1].money;
+
+        apply_trades(&mut villages, &village_ids, &fills, dec!(0), &mut logger, 0).unwrap();
+
+        // Village 0: +10 wood (-150 money), -5 food (+100 money) = net -50 money
+        assert_eq!(villages[0].wood, v0_initial_wood + dec!(10));
+        assert_eq!(villages[0].food, v0_initial_food - dec!(5));
+        assert_eq!(villages[0].money, v0_initial_money - dec!(50));
+
+        // Village 1: -10 wood (+150 money), +5 food (-100 money) = net +50 money
+        assert_eq!(villages[1].wood, v1_initial_wood - dec!(10));
+        assert_eq!(villages[1].food, v1_initial_food + dec!(5));
+        assert_eq!(villages[1].money, v1_initial_money + dec!(50));
+    }
+
+    #[test]
+    fn test_initial_construction_progress_completes_house_on_first_tick() {
+        let mut config = create_standard_scenarios()["basic"].villages[0].clone();
+        config.initial_houses = 1;
+        config.initial_wood = dec!(20);
+        config.initial_construction_progress = dec!(55);
+        let mut village = village_from_config(0, &config);
+        let mut logger = EventLogger::new();
+        let allocation = Allocation {
+            wood: dec!(0),
+            food: dec!(0),
+            house_construction: dec!(10),
+            house_upgrade_wood: dec!(0),
+            processing: dec!(0),
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
+        };
+
+        process_construction(&mut village, &allocation, &mut logger, 0);
+
+        assert_eq!(village.houses.len(), 2);
+        assert_eq!(village.wood, dec!(10));
+        // 55 + 10 passes the 60 worker-day threshold with 5 to spare
+        assert_eq!(village.construction_progress, dec!(5));
+        assert!(logger.get_events().iter().any(|e| e.tick == 0
+            && matches!(
+                e.event_type,
+                EventType::HouseCompleted {
+                    total_houses: 2,
+                    ..
+                }
+            )));
+    }
+
+    #[test]
+    fn test_construction_stalls_when_wood_runs_out() {
+        let mut village = create_village(0, (2, 1), (2, 1), 5, 1);
+        village.wood = dec!(15);
+        village.construction_progress = dec!(179);
+        let mut logger = EventLogger::new();
+        let allocation = Allocation {
+            wood: dec!(0),
+            food: dec!(0),
+            house_construction: dec!(1),
+            house_upgrade_wood: dec!(0),
+            processing: dec!(0),
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
+        };
+
+        process_construction(&mut village, &allocation, &mut logger, 0);
+
+        assert_eq!(village.houses.len(), 2);
+        assert_eq!(village.wood, dec!(5));
+        assert_eq!(village.construction_progress, dec!(120));
+        let stalls: Vec<_> = logger
+            .get_events()
+            .iter()
+            .filter_map(|e| match &e.event_type {
+                EventType::ConstructionStalled { reason, progress } => Some((reason, *progress)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(stalls, vec![(&StallReason::InsufficientWood, dec!(120))]);
+    }
+
+    #[test]
+    fn test_house_upgrades_add_capacity_up_to_cap() {
+        let mut village = create_village(0, (2, 1), (2, 1), 5, 2);
+        village.wood = dec!(100);
+        let mut logger = EventLogger::new();
+        let params = SimulationParameters {
+            max_house_upgrade_level: 2,
+            house_upgrade_wood: dec!(5),
+            ..Default::default()
+        };
+        let allocation = Allocation {
+            wood: dec!(0),
+            food: dec!(0),
+            house_construction: dec!(0),
+            house_upgrade_wood: dec!(50),
+            processing: dec!(0),
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
+        };
+
+        process_house_upgrades(&mut village, &allocation, &params, &mut logger, 0);
+
+        // Two houses can each gain 2 levels, so only 20 wood is spent
+        assert_eq!(village.wood, dec!(80));
+        let capacity: Decimal = village.houses.iter().map(|h| h.shelter_effect()).sum();
+        assert_eq!(capacity, dec!(14));
+    }
+
+    #[test]
+    fn test_house_upgrades_disabled_by_default() {
+        let mut village = create_village(0, (2, 1), (2, 1), 5, 1);
+        let mut logger = EventLogger::new();
+        let allocation = Allocation {
+            wood: dec!(0),
+            food: dec!(0),
+            house_construction: dec!(0),
+            house_upgrade_wood: dec!(50),
+            processing: dec!(0),
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
+        };
+
+        process_house_upgrades(
+            &mut village,
+            &allocation,
+            &SimulationParameters::default(),
+            &mut logger,
+            0,
+        );
+
+        assert_eq!(village.wood, dec!(100));
+        assert_eq!(village.houses[0].maintenance_level, dec!(0));
+    }
+
+    #[test]
+    fn test_apply_trades_no_matching_village() {
+        let mut villages = vec![create_village(0, (2, 1), (2, 1), 5, 1)];
+        let mut logger = EventLogger::new();
+
+        let village_ids: HashMap<String, VillageId> = villages
+            .iter()
+            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .collect();
+
+        // Create a fill for a non-existent village
+        let fills = vec![FinalFill {
+            order_id: village_model::auction::OrderId(1),
+            participant_id: village_model::auction::ParticipantId(999), // Non-existent
+            resource_id: village_model::auction::ResourceId("wood".to_string()),
+            order_type: village_model::auction::OrderType::Bid,
+            filled_quantity: 10,
+            price: dec!(15.0),
+            value: dec!(150.0),
+        }];
+
+        let initial_wood = villages[0].wood;
+        let initial_money = villages[0].money;
+
+        apply_trades(&mut villages, &village_ids, &fills, dec!(0), &mut logger, 0).unwrap();
+
+        // Village 0 should be unchanged
+        assert_eq!(villages[0].wood, initial_wood);
+        assert_eq!(villages[0].money, initial_money);
+    }
+
+    #[test]
+    fn test_production_curves_at_same_allocation() {
+        let slots = (5, 5);
+        let output = |curve| produce_with_curve(curve, slots, dec!(2.0), dec!(4)).round_dp(3);
+
+        // Step: 4 full-slot worker-days at 2.0 each
+        assert_eq!(output(ProductionCurve::Step), dec!(8));
+        // Exponential: 2.0 * (1 + 0.9 + 0.81 + 0.729)
+        assert_eq!(
+            output(ProductionCurve::ExponentialDecay { ratio: 0.9 }),
+            dec!(6.878)
+        );
+        // Power law: 2.0 * 4^0.5
+        assert_eq!(output(ProductionCurve::PowerLaw { alpha: 0.5 }), dec!(4));
+        // Without decay the smooth curves match the full slots
+        assert_eq!(
+            output(ProductionCurve::ExponentialDecay { ratio: 1.0 }),
+            dec!(8)
+        );
+        assert_eq!(output(ProductionCurve::PowerLaw { alpha: 1.0 }), dec!(8));
+
+        // Labor beyond the 10 slots is wasted under every curve
+        for curve in [
+            ProductionCurve::Step,
+            ProductionCurve::ExponentialDecay { ratio: 0.9 },
+            ProductionCurve::PowerLaw { alpha: 0.5 },
+        ] {
+            assert_eq!(
+                produce_with_curve(curve, slots, dec!(2.0), dec!(10)),
+                produce_with_curve(curve, slots, dec!(2.0), dec!(14)),
+            );
+        }
+    }
+
+    #[test]
+    fn test_tools_raise_output_and_depreciate_without_investment() {
+        use village_model::scenario::{Good, RecipeAmount, ToolProductivity};
+
+        let tools = ToolProductivity {
+            boost_per_tool: dec!(0.5),
+            max_multiplier: dec!(1.5),
+            depreciation_rate: dec!(0.1),
+        };
+        let params = SimulationParameters {
+            tools: Some(tools),
+            recipes: vec![Recipe {
+                name: "toolmaking".to_string(),
+                inputs: vec![RecipeAmount {
+                    good: Good::Wood,
+                    amount: dec!(2),
+                }],
+                outputs: vec![RecipeAmount {
+                    good: Good::Tools,
+                    amount: dec!(1),
+                }],
+            }],
+            ..Default::default()
+        };
+        let allocation = |food, processing| Allocation {
+            wood: dec!(0),
+            food,
+            house_construction: dec!(0),
+            house_upgrade_wood: dec!(0),
+            processing,
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
+        };
+        let food_with_tools = |tools| {
+            let mut village = create_village(0, (5, 5), (5, 5), 4, 1);
+            village.food = dec!(0);
+            village.tools = tools;
+            process_production(
+                &mut village,
+                &allocation(dec!(4), dec!(0)),
+                &params,
+                &mut EventLogger::new(),
+                0,
+            );
+            village.food
+        };
+
+        assert_eq!(food_with_tools(dec!(0)), dec!(8));
+        // Half a tool per worker: +25%
+        assert_eq!(food_with_tools(dec!(2)), dec!(10));
+        // Capped at max_multiplier
+        assert_eq!(food_with_tools(dec!(40)), dec!(12));
+
+        // Processing turns wood into tools
+        let mut village = create_village(0, (5, 5), (5, 5), 4, 1);
+        process_recipes(
+            &mut village,
+            &allocation(dec!(0), dec!(3)),
+            &params.recipes,
+            &mut EventLogger::new(),
+            0,
+        );
+        assert_eq!(village.tools, dec!(3));
+        assert_eq!(village.wood, dec!(94));
+
+        // Without further investment they wear away
+        let mut stock = Vec::new();
+        for _ in 0..50 {
+            depreciate_tools(&mut village, &params);
+            stock.push(village.tools);
+        }
+        assert!(stock.windows(2).all(|w| w[1] < w[0]));
+        assert!(village.tools < dec!(0.02));
+    }
+
+    #[test]
+    fn test_seasonal_peak_and_trough_scale_food_output() {
+        let params = SimulationParameters {
+            seasonality: vec![Seasonality {
+                period: 40,
+                amplitude: dec!(0.5),
+                resource: ResourceType::Food,
+                shape: SeasonalShape::Sine,
+                village: None,
+            }],
+            ..Default::default()
+        };
+        let food_at = |params: &SimulationParameters, tick| {
+            let mut village = create_village(0, (5, 5), (5, 5), 5, 1);
+            village.food = dec!(0);
+            let allocation = Allocation {
+                wood: dec!(0),
+                food: dec!(4),
+                house_construction: dec!(0),
+                house_upgrade_wood: dec!(0),
+                processing: dec!(0),
+                slot_expansion: dec!(0),
+                slot_expansion_target: ResourceType::Food,
+                repair: dec!(0),
+            };
+            process_production(
+                &mut village,
+                &allocation,
+                params,
+                &mut EventLogger::new(),
+                tick,
+            );
+            village.food
+        };
+
+        let base = food_at(&SimulationParameters::default(), 10);
+        assert_eq!(base, dec!(8));
+        // sin peaks a quarter of the way through the period and bottoms out at three quarters
+        assert_eq!(food_at(&params, 10), base * dec!(1.5));
+        assert_eq!(food_at(&params, 30), base * dec!(0.5));
+        assert_eq!(food_at(&params, 0), base);
+        assert_eq!(food_at(&params, 50), base * dec!(1.5));
+
+        // An unvalidated zero period leaves output alone instead of panicking
+        let mut flat = params.clone();
+        flat.seasonality[0].period = 0;
+        assert_eq!(food_at(&flat, 10), base);
+    }
+
+    #[test]
+    fn test_global_drought_halves_food_output_while_active() {
+        let params = SimulationParameters {
+            global_modifiers: vec![Modifier {
+                start_tick: 5,
+                end_tick: 10,
+                resource: ResourceType::Food,
+                multiplier: dec!(0.5),
+            }],
+            ..Default::default()
+        };
+        let food_at = |tick| {
+            let mut village = create_village(0, (5, 5), (5, 5), 5, 1);
+            village.food = dec!(0);
+            let allocation = Allocation {
+                wood: dec!(0),
+                food: dec!(4),
+                house_construction: dec!(0),
+                house_upgrade_wood: dec!(0),
+                processing: dec!(0),
+                slot_expansion: dec!(0),
+                slot_expansion_target: ResourceType::Food,
+                repair: dec!(0),
+            };
+            process_production(
+                &mut village,
+                &allocation,
+                &params,
+                &mut EventLogger::new(),
+                tick,
+            );
+            village.food
+        };
+
+        assert_eq!(food_at(4), dec!(8));
+        assert_eq!(food_at(5), dec!(4));
+        assert_eq!(food_at(9), dec!(4));
+        assert_eq!(food_at(10), dec!(8));
+    }
+
+    #[test]
+    fn test_food_quality_scales_output_for_the_same_slots() {
+        let food_with_quality = |food_quality| {
+            let mut config = create_standard_scenarios()["basic"].villages[0].clone();
+            config.food_slots = (5, 5);
+            config.initial_food = dec!(0);
+            config.food_quality = food_quality;
+            let mut village = village_from_config(0, &config);
+            let allocation = Allocation {
+                wood: dec!(0),
+                food: dec!(4),
+                house_construction: dec!(0),
+                house_upgrade_wood: dec!(0),
+                processing: dec!(0),
+                slot_expansion: dec!(0),
+                slot_expansion_target: ResourceType::Food,
+                repair: dec!(0),
+            };
+            process_production(
+                &mut village,
+                &allocation,
+                &SimulationParameters::default(),
+                &mut EventLogger::new(),
+                0,
+            );
+            village.food
+        };
+
+        assert_eq!(food_with_quality(dec!(1)), dec!(8));
+        assert_eq!(food_with_quality(dec!(1.5)), dec!(12));
+    }
+
+    #[test]
+    fn test_production_lag_delays_food_by_one_tick() {
+        let params = SimulationParameters {
+            production_lag: 1,
+            ..Default::default()
+        };
+        let mut village = create_village(0, (5, 5), (5, 5), 5, 1);
+        village.food = dec!(0);
+        let allocation = Allocation {
+            wood: dec!(0),
+            food: dec!(4),
+            house_construction: dec!(0),
+            house_upgrade_wood: dec!(0),
+            processing: dec!(0),
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
+        };
+
+        village.release_pending_production(0);
+        process_production(
+            &mut village,
+            &allocation,
+            &params,
+            &mut EventLogger::new(),
+            0,
+        );
+        assert_eq!(village.food, dec!(0), "Tick 0 output isn't available yet");
+
+        village.release_pending_production(1);
+        assert_eq!(village.food, dec!(8));
+        assert!(village.pending_production.is_empty());
+    }
+
+    #[test]
+    fn test_repair_labor_restores_houses_faster_than_upkeep() {
+        let level_after = |repair: Decimal| {
+            let mut village = create_village(0, (2, 1), (2, 1), 5, 1);
+            village.houses[0].maintenance_level = dec!(-2);
+            let allocation = Allocation {
+                wood: dec!(0),
+                food: dec!(0),
+                house_construction: dec!(0),
+                house_upgrade_wood: dec!(0),
+                processing: dec!(0),
+                slot_expansion: dec!(0),
+                slot_expansion_target: ResourceType::Food,
+                repair,
+            };
+            let mut logger = EventLogger::new();
+            let params = SimulationParameters::default();
+            process_house_maintenance(&mut village, &allocation, &params, &mut logger, 0);
+            (village.houses[0].maintenance_level, village.wood)
+        };
+
+        // Passive upkeep pays 0.1 and repairs 0.1
+        assert_eq!(level_after(dec!(0)), (dec!(-1.9), dec!(99.8)));
+        // Two repair worker-days add a full level for a further 1 wood
+        assert_eq!(level_after(dec!(2)), (dec!(-0.9), dec!(98.8)));
+    }
+
+    #[test]
+    fn test_house_decaying_past_collapse_threshold_is_removed() {
+        let mut village = create_village(0, (2, 1), (2, 1), 5, 2);
+        village.wood = dec!(0);
+        village.houses[0].maintenance_level = dec!(-0.95);
+        let allocation = Allocation {
+            wood: dec!(0),
+            food: dec!(0),
+            house_construction: dec!(0),
+            house_upgrade_wood: dec!(0),
+            processing: dec!(0),
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
+        };
+        let params = SimulationParameters {
+            collapse_threshold: Some(dec!(-1)),
+            ..Default::default()
+        };
+        let mut logger = EventLogger::new();
+
+        process_house_maintenance(&mut village, &allocation, &params, &mut logger, 0);
+
+        assert_eq!(village.houses.len(), 1);
+        assert_eq!(village.houses[0].maintenance_level, dec!(-0.1));
+        let collapses: Vec<_> = logger
+            .get_events()
+            .iter()
+            .filter_map(|e| match e.event_type {
+                EventType::HouseCollapsed {
+                    maintenance_level,
+                    total_houses,
+                    ..
+                } => Some((maintenance_level, total_houses)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(collapses, vec![(dec!(-1.05), 1)]);
+    }
+
+    #[test]
+    fn test_allocation_beyond_slots_logs_wasted_labor() {
+        let mut village = create_village(0, (2, 1), (2, 1), 5, 1);
+        let mut logger = EventLogger::new();
+        let allocation = Allocation {
+            wood: dec!(0),
+            food: dec!(5),
+            house_construction: dec!(0),
+            house_upgrade_wood: dec!(0),
+            processing: dec!(0),
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
+        };
+
+        process_production(
+            &mut village,
+            &allocation,
+            &SimulationParameters::default(),
+            &mut logger,
+            0,
+        );
+
+        let wasted: Vec<_> = logger
+            .get_events()
+            .iter()
+            .filter_map(|e| match e.event_type {
+                EventType::LaborWasted {
+                    resource,
+                    wasted_worker_days,
+                } => Some((resource, wasted_worker_days)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(wasted, vec![(ResourceType::Food, dec!(2))]);
+    }
+
+    #[test]
+    fn test_slot_expansion_adds_food_capacity() {
+        let mut village = create_village(0, (2, 1), (2, 1), 5, 1);
+        let mut logger = EventLogger::new();
+        let params = SimulationParameters::default();
+        let allocation = Allocation {
+            wood: dec!(0),
+            food: dec!(0),
+            house_construction: dec!(0),
+            house_upgrade_wood: dec!(0),
+            processing: dec!(0),
+            slot_expansion: params.slot_expansion_days,
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
+        };
+        let capacity_before = produced(village.food_slots, dec!(2.0), dec!(10));
+
+        process_slot_expansion(&mut village, &allocation, &params, &mut logger, 0);
+
+        assert_eq!(village.food_slots, (3, 1));
+        assert_eq!(village.wood_slots, (2, 1));
+        assert_eq!(village.wood, dec!(100) - params.slot_expansion_wood);
+        assert!(produced(village.food_slots, dec!(2.0), dec!(10)) > capacity_before);
+        assert!(logger.get_events().iter().any(|e| matches!(
+            e.event_type,
+            EventType::SlotAdded {
+                resource: ResourceType::Food,
+                total_slots: 3
+            }
+        )));
+    }
+
+    #[test]
+    fn test_half_shelter_per_worker_doubles_house_occupancy() {
+        let mut village = create_village(0, (2, 1), (2, 1), 10, 1);
+        let mut logger = EventLogger::new();
+        let crowded = SimulationParameters {
+            shelter_per_worker: dec!(0.5),
+            ..Default::default()
+        };
+
+        process_worker_lifecycle(&mut village, &crowded, &mut logger, 0);
+
+        assert!(village.workers.iter().all(|w| w.days_without_shelter == 0));
+
+        // At the default of 1.0 the same house leaves 5 workers unsheltered
+        let mut village = create_village(0, (2, 1), (2, 1), 10, 1);
+        process_worker_lifecycle(
+            &mut village,
+            &SimulationParameters::default(),
+            &mut logger,
+            0,
+        );
+        let unsheltered = village
+            .workers
+            .iter()
+            .filter(|w| w.days_without_shelter > 0)
+            .count();
+        assert_eq!(unsheltered, 5);
+    }
+
+    #[test]
+    fn test_lifecycle_of_an_empty_village_is_a_no_op() {
+        let mut village = create_village(0, (2, 1), (2, 1), 1, 1);
+        village.workers.clear();
+        village.food = dec!(0);
+        let mut logger = EventLogger::new();
+        let params = SimulationParameters {
+            partial_rations: true,
+            ..Default::default()
+        };
+
+        let (born, died) = process_worker_lifecycle(&mut village, &params, &mut logger, 0);
+        assert_eq!(born, 0);
+        assert!(died.is_empty());
+        assert_eq!(village.food, dec!(0));
+    }
+
+    #[test]
+    fn test_two_ticks_per_day_halves_rations_and_slows_day_counters() {
+        let mut village = create_village(0, (2, 1), (2, 1), 1, 1);
+        village.food = dec!(1);
+        let mut logger = EventLogger::new();
+        let params = SimulationParameters {
+            ticks_per_day: 2,
+            ..Default::default()
+        };
+
+        process_worker_lifecycle(&mut village, &params, &mut logger, 0);
+        assert_eq!(village.food, dec!(0.5));
+        process_worker_lifecycle(&mut village, &params, &mut logger, 1);
+        assert_eq!(village.food, dec!(0));
+        assert_eq!(village.workers[0].days_without_food, dec!(0));
+        assert_eq!(village.workers[0].age, 1);
+
+        // Out of food, the starvation count only advances at the end of each day
+        let mut counts = Vec::new();
+        for tick in 2..6 {
+            process_worker_lifecycle(&mut village, &params, &mut logger, tick);
+            counts.push(village.workers[0].days_without_food);
+        }
+        assert_eq!(counts, vec![dec!(0), dec!(1), dec!(1), dec!(2)]);
+        assert_eq!(village.workers[0].age, 3);
+    }
+
+    #[test]
+    fn test_recovery_rate_heals_starvation_gradually() {
+        let mut village = create_village(0, (2, 1), (2, 1), 1, 1);
+        village.workers[0].days_without_food = dec!(9);
+        let mut logger = EventLogger::new();
+        let params = SimulationParameters {
+            recovery_rate: Some(3),
+            ..Default::default()
+        };
+
+        let mut counts = Vec::new();
+        for tick in 0..3 {
+            process_worker_lifecycle(&mut village, &params, &mut logger, tick);
+            counts.push(village.workers[0].days_without_food);
+        }
+        assert_eq!(counts, vec![dec!(6), dec!(3), dec!(0)]);
+
+        // Without a rate, one meal heals completely
+        village.workers[0].days_without_food = dec!(9);
+        process_worker_lifecycle(
+            &mut village,
+            &SimulationParameters::default(),
+            &mut logger,
+            3,
+        );
+        assert_eq!(village.workers[0].days_without_food, dec!(0));
+    }
+
+    #[test]
+    fn test_partial_rations_accrue_fractional_hunger() {
+        let mut village = create_village(0, (2, 1), (2, 1), 5, 1);
+        village.food = dec!(2.5);
+        let mut logger = EventLogger::new();
+        let params = SimulationParameters {
+            partial_rations: true,
+            ..Default::default()
+        };
+
+        process_worker_lifecycle(&mut village, &params, &mut logger, 0);
+        assert_eq!(village.food, dec!(0));
+        assert!(
+            village
+                .workers
+                .iter()
+                .all(|w| w.days_without_food == dec!(0.5))
+        );
+
+        // Without the option the first two workers eat and the rest go hungry
+        let mut village = create_village(0, (2, 1), (2, 1), 5, 1);
+        village.food = dec!(2.5);
+        process_worker_lifecycle(
+            &mut village,
+            &SimulationParameters::default(),
+            &mut logger,
+            0,
+        );
+        let hunger: Vec<_> = village
+            .workers
+            .iter()
+            .map(|w| w.days_without_food)
+            .collect();
+        assert_eq!(hunger, vec![dec!(0), dec!(0), dec!(1), dec!(1), dec!(1)]);
+    }
+
+    #[test]
+    fn test_reproduction_setback_decrements_instead_of_resetting() {
+        let params = SimulationParameters {
+            reproduction_setback: Some(10),
+            ..Default::default()
+        };
+        let deprived_day = |params: &SimulationParameters| {
+            // Fed, but the only house is gone
+            let mut village = create_village(0, (2, 1), (2, 1), 1, 0);
+            village.workers[0].days_with_both = 99;
+            process_worker_lifecycle(&mut village, params, &mut EventLogger::new(), 0);
+            village.workers[0].days_with_both
+        };
+
+        assert_eq!(deprived_day(&params), 89);
+        assert_eq!(deprived_day(&SimulationParameters::default()), 0);
+    }
+
+    #[test]
+    fn test_food_backstop_keeps_workers_fed() {
+        use village_model::scenario::FoodBackstop;
+
+        let mut village = create_village(0, (2, 1), (2, 1), 4, 1);
+        village.food = dec!(0);
+        village.granary = dec!(0);
+        let mut logger = EventLogger::new();
+        let params = SimulationParameters {
+            food_backstop: Some(FoodBackstop { price: dec!(0) }),
+            ..Default::default()
+        };
+
+        for tick in 0..20 {
+            process_worker_lifecycle(&mut village, &params, &mut logger, tick);
+            assert!(
+                village
+                    .workers
+                    .iter()
+                    .all(|w| w.days_without_food == dec!(0))
+            );
+        }
+        let imported: Decimal = logger
+            .get_events()
+            .iter()
+            .filter_map(|e| match e.event_type {
+                EventType::FoodImported { amount, cost } => {
+                    assert_eq!(cost, dec!(0));
+                    Some(amount)
+                }
+                _ => None,
+            })
+            .sum();
+        assert_eq!(imported, dec!(80));
+
+        // A priced backstop buys only what the money covers
+        village.money = dec!(3);
+        let params = SimulationParameters {
+            food_backstop: Some(FoodBackstop { price: dec!(1.5) }),
+            ..Default::default()
+        };
+        process_worker_lifecycle(&mut village, &params, &mut logger, 20);
+        assert_eq!(village.money, dec!(0));
+        let fed = village
+            .workers
+            .iter()
+            .filter(|w| w.days_without_food == dec!(0))
+            .count();
+        assert_eq!(fed, 2);
+    }
+
+    #[test]
+    fn test_prosperity_bonus_raises_birth_rate() {
+        use rand::SeedableRng;
+        use village_model::scenario::ProsperityBonus;
+
+        let bonus = ProsperityBonus {
+            food_days_threshold: dec!(10),
+            wood_days_threshold: dec!(10),
+            bonus_per_day: 0.1,
+            max_factor: 3.0,
+        };
+        let births = |food: Decimal, wood: Decimal| {
+            let mut village = create_village(0, (2, 1), (2, 1), 5, 1);
+            village.food = food;
+            village.wood = wood;
+            village.rng = Some(rand::rngs::StdRng::seed_from_u64(11));
+            let factor = bonus.factor(village.food_days(), village.wood_days());
+            (0..2000)
+                .filter(|_| village.should_spawn_worker(factor))
+                .count()
+        };
+
+        // 5 workers and 1 house: 500 food is 100 days, 10 wood is 100 days
+        let abundant = births(dec!(500), dec!(10));
+        let marginal = births(dec!(20), dec!(0.5));
+        assert_eq!(
+            bonus.factor(dec!(100), dec!(100)),
+            3.0,
+            "the factor is capped"
+        );
+        assert_eq!(bonus.factor(dec!(4), dec!(5)), 1.0);
+        assert!(
+            abundant > marginal * 2,
+            "abundant: {}, marginal: {}",
+            abundant,
+            marginal
+        );
+    }
+
+    #[test]
+    fn test_require_housing_for_birth_blocks_full_village() {
+        use village_model::scenario::ProsperityBonus;
+
+        // A bonus large enough to make every roll succeed
+        let certain = ProsperityBonus {
+            food_days_threshold: dec!(0),
+            wood_days_threshold: dec!(0),
+            bonus_per_day: 100.0,
+            max_factor: 20.0,
+        };
+        let births = |require_housing: bool, houses: usize| {
+            // 5 workers fill one house exactly
+            let mut village = create_village(0, (2, 1), (2, 1), 5, houses);
+            for worker in &mut village.workers {
+                worker.days_with_both = 100;
+            }
+            let params = SimulationParameters {
+                prosperity_bonus: certain.clone(),
+                require_housing_for_birth: require_housing,
+                ..Default::default()
+            };
+            let mut logger = EventLogger::new();
+            process_worker_lifecycle(&mut village, &params, &mut logger, 0).0
+        };
+
+        assert_eq!(births(false, 1), 5);
+        assert_eq!(births(true, 1), 0);
+        // A second house leaves room for exactly five more
+        assert_eq!(births(true, 2), 5);
+    }
+
+    #[test]
+    fn test_granary_bridges_one_tick_food_gap() {
+        let starved = |granary_fraction: Decimal| {
+            let mut village = create_village(0, (2, 1), (2, 1), 2, 1);
+            village.food = dec!(6);
+            let mut logger = EventLogger::new();
+            let params = SimulationParameters {
+                granary_fraction,
+                ..Default::default()
+            };
+
+            // A normal day: 2 eaten, half of the remaining 4 set aside
+            process_worker_lifecycle(&mut village, &params, &mut logger, 0);
+
+            // The granary is out of reach when the open stock is lost
+            village.food = dec!(0);
+            village.workers[0].days_without_food = dec!(9);
+            let (_, deaths) = process_worker_lifecycle(&mut village, &params, &mut logger, 1);
+            deaths.len()
+        };
+
+        assert_eq!(starved(dec!(0)), 1);
+        assert_eq!(starved(dec!(0.5)), 0);
+    }
+
+    #[test]
+    fn test_recipe_converts_food_to_money() {
+        use village_model::scenario::{Good, RecipeAmount};
+
+        let mut village = create_village(0, (2, 1), (2, 1), 3, 1);
+        let mut logger = EventLogger::new();
+        let recipes = vec![Recipe {
+            name: "market_stall".to_string(),
+            inputs: vec![RecipeAmount {
+                good: Good::Food,
+                amount: dec!(2),
+            }],
+            outputs: vec![RecipeAmount {
+                good: Good::Money,
+                amount: dec!(1),
+            }],
+        }];
+        let allocation = Allocation {
+            wood: dec!(0),
+            food: dec!(0),
+            house_construction: dec!(0),
+            house_upgrade_wood: dec!(0),
+            processing: dec!(3),
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
+        };
+        let food_before = village.food;
+        let money_before = village.money;
+
+        process_recipes(&mut village, &allocation, &recipes, &mut logger, 0);
+
+        assert_eq!(village.food, food_before - dec!(6));
+        assert_eq!(village.money, money_before + dec!(3));
+        assert!(logger.get_events().iter().any(|e| matches!(
+            e.event_type,
+            EventType::ResourceConsumed {
+                purpose: ConsumptionPurpose::Processing,
+                ..
             }
+        )));
+    }
+
+    fn market_agent(id: &str, wood: Decimal, money: Decimal, order: AgentOrder) -> VillageConfig {
+        VillageConfig {
+            id: id.to_string(),
+            initial_workers: 0,
+            initial_houses: 0,
+            initial_food: dec!(0),
+            initial_wood: wood,
+            initial_money: money,
+            food_slots: (0, 0),
+            wood_slots: (0, 0),
+            strategy: Default::default(),
+            is_market_agent: true,
+            agent_orders: vec![order],
+            initial_worker_state: InitialWorkerState::default(),
+            initial_construction_progress: Decimal::ZERO,
+            food_quality: Decimal::ONE,
+            wood_quality: Decimal::ONE,
         }
     }
-}
 
-/// Runs the main simulation loop.
-///
-/// # Simulation Flow
-///
-/// 1. **Initialization**: Load scenario, create villages, assign strategies
-/// 2. **Main Loop**: For each tick:
-///    - Villages decide allocations and trading orders via strategies
-///    - Update villages (production, construction, population)
-///    - Run double auction to match orders
-///    - Apply trade results to village inventories
-/// 3. **Termination**: After N ticks or when all villages die
-/// 4. **Output**: Save events to JSON, calculate and display metrics
-fn run_simulation(args: village_model::cli::CliArgs) {
-    log::info!("Starting simulation with args: {:?}", args);
-    // Load scenario
-    let mut scenario = if let Some(ref file) = args.scenario_file {
-        // Load from file
-        match std::fs::read_to_string(file) {
-            Ok(contents) => {
-                match serde_json::from_str::<village_model::scenario::Scenario>(&contents) {
-                    Ok(scenario) => scenario,
-                    Err(e) => {
-                        eprintln!("Error parsing scenario file {}: {}", file.display(), e);
-                        process::exit(1);
-                    }
-                }
-            }
-            Err(e) => {
-                eprintln!("Error reading scenario file {}: {}", file.display(), e);
-                process::exit(1);
+    /// Strategy that works food and offers one wood a unit above the last
+    /// wood price, so its ask walks up toward the best bid round by round.
+    struct MarkupSeller;
+
+    impl strategies::Strategy for MarkupSeller {
+        fn decide_allocation_and_orders(
+            &mut self,
+            village: &strategies::VillageState,
+            market: &strategies::MarketState,
+        ) -> strategies::StrategyDecision {
+            strategies::StrategyDecision {
+                allocation: strategies::WorkerAllocation {
+                    food: village.worker_days,
+                    wood: dec!(0),
+                    construction: dec!(0),
+                    processing: dec!(0),
+                    slot_expansion: dec!(0),
+                    slot_expansion_target: ResourceType::Food,
+                    repair: dec!(0),
+                },
+                wood_bid: None,
+                wood_ask: Some((market.wood_price() + dec!(1), 1)),
+                food_bid: None,
+                food_ask: None,
+                house_upgrade_wood: dec!(0),
             }
         }
-    } else {
-        // Use built-in scenario
-        let scenarios = create_standard_scenarios();
-        scenarios
-            .get(&args.scenario_name)
-            .cloned()
-            .unwrap_or_else(|| {
-                eprintln!("Unknown scenario: {}", args.scenario_name);
-                eprintln!(
-                    "Available scenarios: {:?}",
-                    scenarios.keys().collect::<Vec<_>>()
-                );
-                process::exit(1);
-            })
-    };
 
-    // Apply CLI overrides to scenario
-    apply_overrides(&mut scenario, &args);
+        fn name(&self) -> &str {
+            "MarkupSeller"
+        }
+    }
 
-    // Validate scenario configuration
-    if !args.quiet {
-        validate_scenario(&scenario, &args);
-        println!("{}", scenario);
+    #[test]
+    fn test_more_trade_rounds_move_price_closer_to_equilibrium() {
+        // The buyer takes any wood up to 10, so the seller's ask keeps rising
+        // until it meets that bid
+        let equilibrium = dec!(10);
+        let wood_price_after_one_tick = |rounds: u32| {
+            let mut scenario = create_standard_scenarios()["basic"].clone();
+            scenario.villages.truncate(1);
+            scenario.villages[0].initial_wood = dec!(50);
+            scenario.parameters.rounds_per_tick = rounds;
+            scenario.add_village(market_agent(
+                "wood_buyer",
+                dec!(0),
+                dec!(1000),
+                AgentOrder {
+                    resource: ResourceType::Wood,
+                    is_buy: true,
+                    quantity: 10,
+                    price: equilibrium,
+                    visible_quantity: None,
+                },
+            ));
+            let strategies: Vec<Box<dyn strategies::Strategy>> = vec![
+                Box::new(MarkupSeller),
+                strategies::create_strategy(&scenario.villages[1].strategy),
+            ];
+            let mut runner = SimulationRunner::new(&scenario, strategies);
+            runner
+                .last_clearing_prices
+                .insert(ResourceId("wood".to_string()), dec!(5));
+            runner.step(0);
+            runner.last_clearing_prices[&ResourceId("wood".to_string())]
+        };
+
+        let one_round = wood_price_after_one_tick(1);
+        let three_rounds = wood_price_after_one_tick(3);
+        assert_eq!(one_round, dec!(6));
+        assert!((equilibrium - three_rounds).abs() < (equilibrium - one_round).abs());
     }
 
-    // Initialize villages from scenario
-    let mut villages: Vec<Village> = scenario
-        .villages
-        .iter()
-        .enumerate()
-        .map(|(i, config)| village_from_config(i, config))
-        .collect();
+    /// Strategy that works food, bids for four wood every round and records
+    /// the fills it is told about between rounds.
+    struct RoundBuyer {
+        fills: std::sync::Arc<std::sync::Mutex<Vec<u32>>>,
+    }
 
-    // Initialize random number generator if seed provided
-    if let Some(seed) = scenario.random_seed {
-        log::info!("Using random seed: {}", seed);
-        use rand::SeedableRng;
-        use rand::rngs::StdRng;
+    impl strategies::Strategy for RoundBuyer {
+        fn decide_allocation_and_orders(
+            &mut self,
+            village: &strategies::VillageState,
+            _market: &strategies::MarketState,
+        ) -> strategies::StrategyDecision {
+            strategies::StrategyDecision {
+                allocation: strategies::WorkerAllocation {
+                    food: village.worker_days,
+                    wood: dec!(0),
+                    construction: dec!(0),
+                    processing: dec!(0),
+                    slot_expansion: dec!(0),
+                    slot_expansion_target: ResourceType::Food,
+                    repair: dec!(0),
+                },
+                wood_bid: Some((dec!(5), 4)),
+                wood_ask: None,
+                food_bid: None,
+                food_ask: None,
+                house_upgrade_wood: dec!(0),
+            }
+        }
+
+        fn on_trade_result(
+            &mut self,
+            village: &strategies::VillageState,
+            market: &strategies::MarketState,
+            fills: &[strategies::OrderFill],
+        ) -> strategies::StrategyDecision {
+            let mut recorded = self.fills.lock().unwrap();
+            recorded.extend(fills.iter().map(|fill| fill.filled));
+            drop(recorded);
+            self.decide_allocation_and_orders(village, market)
+        }
 
-        // Set up RNG for each village with deterministic seeds
-        for (i, village) in villages.iter_mut().enumerate() {
-            // Create a unique seed for each village based on the base seed
-            let village_seed = seed.wrapping_add(i as u64);
-            village.rng = Some(StdRng::seed_from_u64(village_seed));
+        fn name(&self) -> &str {
+            "RoundBuyer"
         }
     }
 
-    // Create village ID mapping
-    let village_ids: HashMap<String, VillageId> = villages
-        .iter()
-        .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
-        .collect();
+    #[test]
+    fn test_later_round_without_trades_keeps_cleared_price() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.clear();
+        scenario.parameters.rounds_per_tick = 2;
+        let order = |is_buy| AgentOrder {
+            resource: ResourceType::Wood,
+            is_buy,
+            quantity: 5,
+            price: dec!(2),
+            visible_quantity: None,
+        };
+        scenario.add_village(market_agent("wood_seller", dec!(5), dec!(0), order(false)));
+        scenario.add_village(market_agent("wood_buyer", dec!(0), dec!(100), order(true)));
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let orders = vec![
+            market_agent_orders(&runner.villages[0], &[order(false)]),
+            market_agent_orders(&runner.villages[1], &[order(true)]),
+        ];
 
-    // Track initial populations for metrics
-    let village_configs: Vec<(String, usize)> = villages
-        .iter()
-        .map(|v| (v.id_str.clone(), v.workers.len()))
-        .collect();
+        // Everything trades in the first round, leaving the second empty
+        runner.run_market_rounds(orders, 0);
 
-    // Print villages with their strategies
-    if !args.quiet {
-        println!("\nVillages with strategies:");
+        assert_eq!(
+            runner
+                .last_clearing_prices
+                .get(&ResourceId("wood".to_string())),
+            Some(&dec!(2))
+        );
     }
 
-    // Create strategies for each village
-    let strategies: Vec<StrategyAdapter> = if args.strategies.is_empty() {
-        // Use strategies from scenario configuration
-        villages
+    #[test]
+    fn test_auction_seed_differs_per_round() {
+        let seeds: Vec<u64> = (0..3).map(|round| auction_seed(7, 4, round)).collect();
+        assert_eq!(seeds[0], derive_seed(7, AUCTION_SEED_NAMESPACE, 4));
+        assert_ne!(seeds[0], seeds[1]);
+        assert_ne!(seeds[1], seeds[2]);
+        assert_ne!(seeds[1], auction_seed(7, 5, 1));
+    }
+
+    #[test]
+    fn test_later_rounds_post_only_unfilled_market_agent_orders() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        scenario.villages[0].initial_money = dec!(1000);
+        scenario.parameters.rounds_per_tick = 3;
+        scenario.add_village(market_agent(
+            "wood_seller",
+            dec!(10),
+            dec!(0),
+            AgentOrder {
+                resource: ResourceType::Wood,
+                is_buy: false,
+                quantity: 10,
+                price: dec!(1),
+                visible_quantity: None,
+            },
+        ));
+        let fills = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let strategies: Vec<Box<dyn strategies::Strategy>> = vec![
+            Box::new(RoundBuyer {
+                fills: fills.clone(),
+            }),
+            strategies::create_strategy(&scenario.villages[1].strategy),
+        ];
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        runner.step(0);
+
+        let placed: Vec<(&str, Decimal, &str)> = runner
+            .logger
+            .get_events()
             .iter()
-            .enumerate()
-            .map(|(i, v)| {
-                let strategy = strategies::create_strategy(&scenario.villages[i].strategy);
-                if !args.quiet {
-                    println!("  {}: {} (from scenario)", v.id_str, strategy.name());
-                }
-                StrategyAdapter::new(strategy)
+            .filter_map(|e| match &e.event_type {
+                EventType::OrderPlaced {
+                    quantity, order_id, ..
+                } => Some((e.village_id.as_str(), *quantity, order_id.as_str())),
+                _ => None,
             })
-            .collect()
-    } else {
-        // Assign strategies in order, cycling if needed
-        villages
+            .collect();
+        let seller_quantities: Vec<Decimal> = placed
             .iter()
-            .enumerate()
-            .map(|(i, v)| {
-                let strategy_name = &args.strategies[i % args.strategies.len()];
-                if !args.quiet {
-                    println!("  {}: {}", v.id_str, strategy_name);
-                }
-                let strategy = strategies::create_strategy_by_name(strategy_name);
-                StrategyAdapter::new(strategy)
-            })
-            .collect()
-    };
+            .filter(|(village, ..)| *village == "wood_seller")
+            .map(|(_, quantity, _)| *quantity)
+            .collect();
+        // The seller's ten wood is offered once, then only what is left
+        assert_eq!(seller_quantities, vec![dec!(10), dec!(6), dec!(2)]);
+        let ids: std::collections::HashSet<&str> = placed.iter().map(|(.., id)| *id).collect();
+        assert_eq!(ids.len(), placed.len(), "Order ids repeat within a tick");
+        assert_eq!(*fills.lock().unwrap(), vec![4, 4]);
+    }
 
-    // Create event logger
-    let mut logger = EventLogger::new();
+    #[test]
+    fn test_cash_flow_trade_cash_matches_tick_trades() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        scenario.villages[0].initial_food = dec!(0);
+        let mut seller = market_agent(
+            "food_seller",
+            dec!(0),
+            dec!(0),
+            AgentOrder {
+                resource: ResourceType::Food,
+                is_buy: false,
+                quantity: 20,
+                price: dec!(0.5),
+                visible_quantity: None,
+            },
+        );
+        seller.initial_food = dec!(100);
+        scenario.add_village(seller);
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        for tick in 0..3 {
+            runner.step(tick);
+        }
 
-    // Track last clearing prices for strategies
-    let mut last_clearing_prices = HashMap::<village_model::auction::ResourceId, Decimal>::new();
+        let village_id = &runner.villages[0].id_str;
+        let events: Vec<&Event> = runner
+            .logger
+            .get_events()
+            .iter()
+            .filter(|e| &e.village_id == village_id)
+            .collect();
+        let mut traded = false;
+        for tick in 0..3 {
+            let trades: Decimal = events
+                .iter()
+                .filter(|e| e.tick == tick)
+                .filter_map(|e| match e.event_type {
+                    EventType::TradeExecuted {
+                        quantity,
+                        price,
+                        side: TradeSide::Sell,
+                        ..
+                    } => Some(quantity * price),
+                    EventType::TradeExecuted {
+                        quantity, price, ..
+                    } => Some(-quantity * price),
+                    _ => None,
+                })
+                .sum();
+            let flows: Vec<Decimal> = events
+                .iter()
+                .filter(|e| e.tick == tick)
+                .filter_map(|e| match e.event_type {
+                    EventType::CashFlow { trade_cash, .. } => Some(trade_cash),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(flows, vec![trades], "One cash flow per tick");
+            traded |= trades != dec!(0);
+        }
+        assert!(traded, "The village should have bought food");
+        // Market agents get no cash-flow statement
+        assert!(runner.logger.get_events().iter().all(|e| {
+            e.village_id != "food_seller" || !matches!(e.event_type, EventType::CashFlow { .. })
+        }));
+    }
 
-    // Run simulation for configured number of days
-    for tick in 0..scenario.parameters.days_to_simulate {
-        let mut auction_builder = AuctionBuilder::new();
+    #[test]
+    fn test_trade_first_lets_starving_village_buy_food_in_time() {
+        let survivors = |phase_order| {
+            let mut scenario = create_standard_scenarios()["basic"].clone();
+            scenario.villages.truncate(1);
+            scenario.parameters.phase_order = phase_order;
+            let village = &mut scenario.villages[0];
+            // No food of its own: it eats only what it buys
+            village.initial_food = dec!(0);
+            village.food_slots = (0, 0);
+            village.initial_money = dec!(1000);
+            village.initial_worker_state = InitialWorkerState {
+                days_without_food: 9,
+                ..Default::default()
+            };
+            let mut seller = market_agent(
+                "food_seller",
+                dec!(0),
+                dec!(0),
+                AgentOrder {
+                    resource: ResourceType::Food,
+                    is_buy: false,
+                    quantity: 100,
+                    price: dec!(0.5),
+                    visible_quantity: None,
+                },
+            );
+            seller.initial_food = dec!(100);
+            scenario.add_village(seller);
 
-        // Create market state from last clearing prices
-        let market_state = strategies::MarketState {
-            last_wood_price: last_clearing_prices
-                .get(&village_model::auction::ResourceId("wood".to_string()))
-                .cloned(),
-            last_food_price: last_clearing_prices
-                .get(&village_model::auction::ResourceId("food".to_string()))
-                .cloned(),
+            let strategies = scenario
+                .villages
+                .iter()
+                .map(|c| strategies::create_strategy(&c.strategy))
+                .collect();
+            let mut runner = SimulationRunner::new(&scenario, strategies);
+            runner.step(0);
+            runner.villages[0].workers.len()
         };
 
-        // Strategy phase: Each village decides worker allocation and trading orders
-        for (village_idx, village) in villages.iter_mut().enumerate() {
-            // Get allocation and orders from strategy
-            let (allocation, orders) =
-                strategies[village_idx].get_allocation_and_orders(village, &market_state);
-
-            // Update village with event logging
-            update_village(village, allocation, &mut logger, tick);
-
-            // Add village to auction
-            let village_id = &village_ids[&village.id_str];
-            auction_builder.add_village(village_id, village.money);
+        assert!(survivors(PhaseOrder::TradeFirst) > 0);
+        assert_eq!(survivors(PhaseOrder::ConsumeFirst), 0);
+    }
 
-            // Add orders to auction
-            for order in orders {
-                // Log order
-                logger.log(
-                    tick,
-                    village.id_str.clone(),
-                    EventType::OrderPlaced {
-                        resource: order.resource,
-                        quantity: order.quantity.into(),
-                        price: order.price,
-                        side: if order.is_buy {
-                            TradeSide::Buy
-                        } else {
-                            TradeSide::Sell
-                        },
-                        order_id: format!(
-                            "{}_{}_{}_{}",
-                            village.id_str,
-                            order.resource.as_str(),
-                            if order.is_buy { "bid" } else { "ask" },
-                            tick
-                        ),
-                    },
-                );
+    #[test]
+    fn test_initial_worker_state_starts_village_on_the_brink() {
+        let mut config = create_standard_scenarios()["basic"].villages[0].clone();
+        config.initial_food = dec!(0);
+        config.initial_worker_state = InitialWorkerState {
+            days_without_food: 9,
+            ..Default::default()
+        };
+        let mut village = village_from_config(0, &config);
+        assert!(
+            village
+                .workers
+                .iter()
+                .all(|w| w.days_without_food == dec!(9))
+        );
 
-                auction_builder.add_order(village_id, order);
-            }
-        }
+        let mut logger = EventLogger::new();
+        let params = SimulationParameters::default();
+        let (new_workers, deaths) = process_worker_lifecycle(&mut village, &params, &mut logger, 0);
+        apply_worker_changes(&mut village, new_workers, deaths, &params, &mut logger, 0);
+        assert!(village.workers.is_empty());
+    }
 
-        // Run double auction to match buy/sell orders across all villages
-        let (orders, participants) = auction_builder.build();
-        let auction_result = run_auction(
-            orders,
-            participants,
-            10, // max iterations for price discovery
-            last_clearing_prices.clone(),
-        );
+    #[test]
+    fn test_burial_cost_takes_one_wood_per_death() {
+        let mut village = village_from_config(0, &create_standard_scenarios()["basic"].villages[0]);
+        village.wood = dec!(10);
+        let params = SimulationParameters {
+            death_cost_wood: dec!(1),
+            ..Default::default()
+        };
+        let deaths = vec![
+            (0, village.workers[0].id, DeathCause::Starvation),
+            (1, village.workers[1].id, DeathCause::NoShelter),
+        ];
 
-        if let Ok(success) = auction_result {
-            // Update last clearing prices for next tick
-            last_clearing_prices = success.clearing_prices.clone();
+        let mut logger = EventLogger::new();
+        apply_worker_changes(&mut village, 0, deaths, &params, &mut logger, 0);
+        assert_eq!(village.wood, dec!(8));
+    }
 
-            // Log auction clearing event
-            let wood_volume = success.final_fills.iter()
-                .filter(|f| f.resource_id == village_model::auction::ResourceId("wood".to_string()) && 
-                           f.order_type == village_model::auction::OrderType::Bid)
-                .map(|f| f.filled_quantity)
-                .sum::<u64>();
-            let food_volume = success.final_fills.iter()
-                .filter(|f| f.resource_id == village_model::auction::ResourceId("food".to_string()) && 
-                           f.order_type == village_model::auction::OrderType::Bid)
-                .map(|f| f.filled_quantity)
-                .sum::<u64>();
-            
-            logger.log(
-                tick,
-                "market".to_string(),
-                EventType::AuctionCleared {
-                    wood_price: success.clearing_prices.get(&village_model::auction::ResourceId("wood".to_string())).cloned(),
-                    food_price: success.clearing_prices.get(&village_model::auction::ResourceId("food".to_string())).cloned(),
-                    wood_volume,
-                    food_volume,
-                    total_participants: success.final_balances.len(),
-                },
-            );
+    #[test]
+    fn test_market_agent_supplies_wood_from_fixed_inventory() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        scenario.add_village(market_agent(
+            "wood_seller",
+            dec!(50),
+            dec!(0),
+            AgentOrder {
+                resource: ResourceType::Wood,
+                is_buy: false,
+                quantity: 5,
+                price: dec!(1),
+                visible_quantity: None,
+            },
+        ));
+        scenario.add_village(market_agent(
+            "wood_buyer",
+            dec!(0),
+            dec!(1000),
+            AgentOrder {
+                resource: ResourceType::Wood,
+                is_buy: true,
+                quantity: 5,
+                price: dec!(2),
+                visible_quantity: None,
+            },
+        ));
+        scenario.validate().unwrap();
 
-            // Apply trades to villages
-            apply_trades(
-                &mut villages,
-                &village_ids,
-                &success.final_fills,
-                &mut logger,
-                tick,
-            );
+        let strategies = scenario
+            .villages
+            .iter()
+            .map(|c| strategies::create_strategy(&c.strategy))
+            .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        for tick in 0..5 {
+            runner.step(tick);
         }
 
-        // Check for early termination if all villages have died
-        if villages.iter().all(|v| v.workers.is_empty()) {
-            if !args.quiet {
-                println!("All villages have died at tick {}", tick);
-            }
-            break;
+        let seller_events: Vec<_> = runner
+            .logger
+            .get_events()
+            .iter()
+            .filter(|e| e.village_id == "wood_seller")
+            .collect();
+        for tick in 0..5 {
+            assert!(seller_events.iter().any(|e| e.tick == tick
+                && matches!(
+                    e.event_type,
+                    EventType::OrderPlaced {
+                        resource: ResourceType::Wood,
+                        side: TradeSide::Sell,
+                        ..
+                    }
+                )));
         }
-    }
+        assert!(seller_events.iter().all(|e| matches!(
+            e.event_type,
+            EventType::OrderPlaced { .. } | EventType::TradeExecuted { .. }
+        )));
 
-    // Save events
-    let filename = args
-        .output_file
-        .as_ref()
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|| "simulation_events.json".to_string());
-    logger.save_to_file(&filename).unwrap();
-    if !args.quiet {
-        println!("\nEvents saved to {}", filename);
+        let sold: Decimal = seller_events
+            .iter()
+            .filter_map(|e| match e.event_type {
+                EventType::TradeExecuted {
+                    quantity,
+                    side: TradeSide::Sell,
+                    ..
+                } => Some(quantity),
+                _ => None,
+            })
+            .sum();
+        let seller = &runner.villages[1];
+        assert!(sold > dec!(0));
+        assert!(seller.workers.is_empty());
+        assert_eq!(seller.wood, dec!(50) - sold);
+        assert!(seller.money > dec!(0));
     }
 
-    // Calculate and display metrics
-    let metrics = MetricsCalculator::calculate_scenario_metrics(
-        logger.get_events(),
-        &village_configs,
-        scenario.parameters.days_to_simulate,
-    );
-
-    if !args.quiet {
-        println!("\n{}", metrics);
+    #[test]
+    fn test_warm_start_resumes_from_final_snapshot() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.random_seed = Some(3);
+        let new_runner = |scenario: &Scenario| {
+            let strategies = scenario
+                .villages
+                .iter()
+                .map(|c| strategies::create_strategy(&c.strategy))
+                .collect();
+            SimulationRunner::new(scenario, strategies)
+        };
 
-        // Display individual village metrics
-        for village_metrics in metrics.villages.values() {
-            println!("\n{}", village_metrics);
+        let mut first = new_runner(&scenario);
+        for tick in 0..30 {
+            first.step(tick);
         }
+        let dir = std::env::temp_dir().join("village_model_warm_start");
+        std::fs::create_dir_all(&dir).unwrap();
+        let events_file = dir.join("events.json");
+        first
+            .logger
+            .save_to_file(&events_file.to_string_lossy())
+            .unwrap();
+
+        let logged = EventLogger::load_from_file(&events_file.to_string_lossy()).unwrap();
+        let snapshots = final_village_states(logged.get_events());
+        let mut second = new_runner(&scenario);
+        second.warm_start(&snapshots);
+
+        assert_eq!(snapshots.len(), first.villages.len());
+        for (resumed, finished) in second.villages.iter().zip(&first.villages) {
+            assert_eq!(resumed.workers.len(), finished.workers.len());
+            assert_eq!(resumed.houses.len(), finished.houses.len());
+            assert_eq!(resumed.food, finished.food + finished.granary);
+            assert_eq!(resumed.wood, finished.wood);
+            assert_eq!(resumed.money, finished.money);
+        }
+        std::fs::remove_dir_all(&dir).ok();
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use rust_decimal_macros::dec;
+    #[test]
+    fn test_snapshot_resumes_identically_in_fresh_run() {
+        use village_model::scenario::ExternalMarket;
+
+        let dir = std::env::temp_dir().join("village_model_snapshot_resume");
+        std::fs::create_dir_all(&dir).unwrap();
+        let args = CliArgs {
+            quiet: true,
+            ..Default::default()
+        };
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.random_seed = Some(5);
+        scenario.parameters.external_market.insert(
+            ResourceType::Food,
+            ExternalMarket {
+                initial_price: dec!(1),
+                volatility: dec!(0.1),
+                spread: dec!(0.1),
+            },
+        );
+        let run = |days: usize, warm_start: Option<WarmStart>, name: &str| {
+            let mut scenario = scenario.clone();
+            scenario.parameters.days_to_simulate = days;
+            let snapshot = dir.join(format!("{}_snapshot.json", name));
+            run_scenario(
+                &scenario,
+                &args,
+                ScenarioOutputs {
+                    events: &dir.join(format!("{}_events.json", name)),
+                    metrics: None,
+                    snapshot: Some(&snapshot),
+                },
+                warm_start.as_ref(),
+            );
+            let json = std::fs::read_to_string(&snapshot).unwrap();
+            serde_json::from_str::<SimulationSnapshot>(&json).unwrap()
+        };
 
-    use super::*;
+        let uninterrupted = run(100, None, "uninterrupted");
+        let halfway = run(50, None, "halfway");
+        assert_eq!(halfway.next_tick, 50);
+        let resumed = run(100, Some(WarmStart::Snapshot(Box::new(halfway))), "resumed");
+        assert_eq!(resumed, uninterrupted);
+
+        // With no ticks left to run, the snapshot is saved back unchanged
+        let idle = run(
+            100,
+            Some(WarmStart::Snapshot(Box::new(resumed.clone()))),
+            "idle",
+        );
+        assert_eq!(idle, resumed);
+        std::fs::remove_dir_all(&dir).ok();
+    }
 
     #[test]
-    fn test_apply_trades_wood_buy() {
-        let mut villages = vec![create_village(0, (2, 1), (2, 1), 5, 1)];
-        let mut logger = EventLogger::new();
-
-        let village_ids: HashMap<String, VillageId> = villages
+    fn test_autarky_runs_without_trades() {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.parameters.autarky = true;
+        scenario.random_seed = Some(1);
+        let strategies = scenario
+            .villages
             .iter()
-            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .map(|c| strategies::create_strategy(&c.strategy))
             .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        let initial_money: Vec<Decimal> = runner.villages.iter().map(|v| v.money).collect();
 
-        // Create a fill for buying wood
-        let fills = vec![FinalFill {
-            order_id: village_model::auction::OrderId(1),
-            participant_id: village_model::auction::ParticipantId(
-                village_ids["village_0"].to_participant_id(),
-            ),
-            resource_id: village_model::auction::ResourceId("wood".to_string()),
-            order_type: village_model::auction::OrderType::Bid,
-            filled_quantity: 10,
-            price: dec!(15.0),
-        }];
-
-        let initial_wood = villages[0].wood;
-        let initial_money = villages[0].money;
+        for tick in 0..50 {
+            runner.step(tick);
+        }
 
-        apply_trades(&mut villages, &village_ids, &fills, &mut logger, 0);
+        assert!(!runner.logger.get_events().iter().any(|e| matches!(
+            e.event_type,
+            EventType::TradeExecuted { .. }
+                | EventType::OrderPlaced { .. }
+                | EventType::AuctionCleared { .. }
+        )));
+        assert!(
+            runner
+                .logger
+                .get_events()
+                .iter()
+                .any(|e| matches!(e.event_type, EventType::ResourceProduced { .. }))
+        );
+        let money: Vec<Decimal> = runner.villages.iter().map(|v| v.money).collect();
+        assert_eq!(money, initial_money);
+    }
 
-        // Should have gained 10 wood and lost 150 money
-        assert_eq!(villages[0].wood, initial_wood + dec!(10));
-        assert_eq!(villages[0].money, initial_money - dec!(150));
+    #[test]
+    fn test_market_agent_asks_capped_at_stock() {
+        let order = AgentOrder {
+            resource: ResourceType::Wood,
+            is_buy: false,
+            quantity: 5,
+            price: dec!(1),
+            visible_quantity: None,
+        };
+        let mut village = create_village(0, (0, 0), (0, 0), 0, 0);
+        village.wood = dec!(3.5);
+        let orders = market_agent_orders(&village, std::slice::from_ref(&order));
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].quantity, 3);
+
+        village.wood = dec!(0.5);
+        assert!(market_agent_orders(&village, &[order]).is_empty());
     }
 
     #[test]
-    fn test_apply_trades_wood_sell() {
-        let mut villages = vec![create_village(0, (2, 1), (2, 1), 5, 1)];
-        let mut logger = EventLogger::new();
+    fn test_compact_never_overwrites_its_input() {
+        let input = Path::new("runs/events.json");
+        assert_eq!(
+            compact_output_path(input, None),
+            Ok(PathBuf::from("runs/events.compact.json"))
+        );
+        assert_eq!(
+            compact_output_path(input, Some(PathBuf::from("small.json"))),
+            Ok(PathBuf::from("small.json"))
+        );
+        assert!(compact_output_path(input, Some(input.to_path_buf())).is_err());
+
+        // The same file reached by another path is still refused
+        let dir = std::env::temp_dir().join("village_model_compact_output");
+        std::fs::create_dir_all(&dir).unwrap();
+        let events = dir.join("events.json");
+        std::fs::write(&events, "[]").unwrap();
+        let roundabout = dir.join(".").join("events.json");
+        assert!(compact_output_path(&events, Some(roundabout)).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
 
-        let village_ids: HashMap<String, VillageId> = villages
+    #[test]
+    fn test_scenario_array_file_writes_log_per_scenario() {
+        let dir = std::env::temp_dir().join("village_model_multi_scenario");
+        std::fs::create_dir_all(&dir).unwrap();
+        let standard = create_standard_scenarios();
+        let scenarios: Vec<Scenario> = ["basic", "custom"]
             .iter()
-            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .map(|name| {
+                let mut scenario = standard[*name].clone();
+                scenario.name = name.to_string();
+                scenario.parameters.days_to_simulate = 3;
+                scenario
+            })
             .collect();
+        let scenario_file = dir.join("suite.json");
+        std::fs::write(&scenario_file, serde_json::to_string(&scenarios).unwrap()).unwrap();
+
+        let args = CliArgs {
+            scenario_file: Some(scenario_file.clone()),
+            output_file: Some(dir.join("events.json")),
+            quiet: true,
+            ..Default::default()
+        };
+        run_simulation(args).unwrap();
 
-        // Create a fill for selling wood
-        let fills = vec![FinalFill {
-            order_id: village_model::auction::OrderId(1),
-            participant_id: village_model::auction::ParticipantId(
-                village_ids["village_0"].to_participant_id(),
-            ),
-            resource_id: village_model::auction::ResourceId("wood".to_string()),
-            order_type: village_model::auction::OrderType::Ask,
-            filled_quantity: 5,
-            price: dec!(20.0),
-        }];
-
-        let initial_wood = villages[0].wood;
-        let initial_money = villages[0].money;
-
-        apply_trades(&mut villages, &village_ids, &fills, &mut logger, 0);
+        for name in ["basic", "custom"] {
+            let path = dir.join(format!("events_{}.json", name));
+            let logger = EventLogger::load_from_file(&path.to_string_lossy()).unwrap();
+            assert!(!logger.get_events().is_empty(), "{} has no events", name);
+        }
 
-        // Should have lost 5 wood and gained 100 money
-        assert_eq!(villages[0].wood, initial_wood - dec!(5));
-        assert_eq!(villages[0].money, initial_money + dec!(100));
+        // Names that suffix to the same file are rejected rather than overwritten
+        let clashing: Vec<Scenario> = ["same name", "same_name"]
+            .iter()
+            .map(|name| Scenario {
+                name: name.to_string(),
+                ..scenarios[0].clone()
+            })
+            .collect();
+        std::fs::write(&scenario_file, serde_json::to_string(&clashing).unwrap()).unwrap();
+        let args = CliArgs {
+            scenario_file: Some(scenario_file),
+            output_file: Some(dir.join("events.json")),
+            quiet: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            run_simulation(args),
+            Err(SimError::Validation(message)) if message.contains("same name")
+        ));
+        assert!(!dir.join("events_same_name.json").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
     #[test]
-    fn test_apply_trades_food_buy() {
-        let mut villages = vec![create_village(0, (2, 1), (2, 1), 5, 1)];
-        let mut logger = EventLogger::new();
+    fn test_cli_seed_overrides_scenario_seed() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
 
-        let village_ids: HashMap<String, VillageId> = villages
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.random_seed = Some(3);
+        let args = village_model::cli::CliArgs {
+            random_seed: Some(7),
+            ..Default::default()
+        };
+        apply_overrides(&mut scenario, &args);
+
+        let strategies = scenario
+            .villages
             .iter()
-            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .map(|c| strategies::create_strategy(&c.strategy))
             .collect();
+        let mut runner = SimulationRunner::new(&scenario, strategies);
 
-        // Create a fill for buying food
-        let fills = vec![FinalFill {
-            order_id: village_model::auction::OrderId(1),
-            participant_id: village_model::auction::ParticipantId(
-                village_ids["village_0"].to_participant_id(),
-            ),
-            resource_id: village_model::auction::ResourceId("food".to_string()),
-            order_type: village_model::auction::OrderType::Bid,
-            filled_quantity: 8,
-            price: dec!(12.0),
-        }];
+        for (i, village) in runner.villages.iter_mut().enumerate() {
+            let mut expected = StdRng::seed_from_u64(lifecycle_seed(7, i, 0));
+            let rng = village.rng.as_mut().expect("village should be seeded");
+            assert_eq!(rng.random::<u64>(), expected.random::<u64>());
+        }
+    }
 
-        let initial_food = villages[0].food;
-        let initial_money = villages[0].money;
+    #[test]
+    fn test_experiment_seed_only_fills_in_missing_scenario_seeds() {
+        use village_model::experiment::{ExperimentConfig, ExperimentOverrides, experiment_args};
+
+        let config = |random_seed| ExperimentConfig {
+            name: "exp".to_string(),
+            scenario: PathBuf::from("suite.json"),
+            strategies: Vec::new(),
+            output: PathBuf::from("exp.json"),
+            overrides: ExperimentOverrides {
+                random_seed,
+                ..Default::default()
+            },
+            repeat: 0,
+        };
+        let runner_seed = |args: &CliArgs, seed| {
+            let mut scenario = create_standard_scenarios()["basic"].clone();
+            scenario.random_seed = seed;
+            apply_overrides(&mut scenario, args);
+            let strategies = scenario
+                .villages
+                .iter()
+                .map(|c| strategies::create_strategy(&c.strategy))
+                .collect();
+            SimulationRunner::new(&scenario, strategies).random_seed
+        };
 
-        apply_trades(&mut villages, &village_ids, &fills, &mut logger, 0);
+        let args = experiment_args(&config(None));
+        assert_eq!(runner_seed(&args, Some(3)), Some(3));
+        let filled = runner_seed(&args, None);
+        assert!(filled.is_some());
+        assert_eq!(runner_seed(&args, None), filled);
 
-        // Should have gained 8 food and lost 96 money
-        assert_eq!(villages[0].food, initial_food + dec!(8));
-        assert_eq!(villages[0].money, initial_money - dec!(96));
+        // An explicit override still replaces the scenario's own seed
+        let args = experiment_args(&config(Some(9)));
+        assert_eq!(runner_seed(&args, Some(3)), Some(9));
     }
 
-    #[test]
-    fn test_apply_trades_food_sell() {
-        let mut villages = vec![create_village(0, (2, 1), (2, 1), 5, 1)];
-        let mut logger = EventLogger::new();
+    /// Strategy that splits its worker-days by fixed fractions, whether or not
+    /// they add up to one.
+    struct FixedShareStrategy {
+        wood: Decimal,
+        food: Decimal,
+        construction: Decimal,
+    }
 
-        let village_ids: HashMap<String, VillageId> = villages
-            .iter()
-            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
-            .collect();
+    impl strategies::Strategy for FixedShareStrategy {
+        fn decide_allocation_and_orders(
+            &mut self,
+            village: &strategies::VillageState,
+            _market: &strategies::MarketState,
+        ) -> strategies::StrategyDecision {
+            strategies::StrategyDecision {
+                allocation: strategies::WorkerAllocation {
+                    food: village.worker_days * self.food,
+                    wood: village.worker_days * self.wood,
+                    construction: village.worker_days * self.construction,
+                    processing: dec!(0),
+                    slot_expansion: dec!(0),
+                    slot_expansion_target: ResourceType::Food,
+                    repair: dec!(0),
+                },
+                wood_bid: None,
+                wood_ask: None,
+                food_bid: None,
+                food_ask: None,
+                house_upgrade_wood: dec!(0),
+            }
+        }
 
-        // Create a fill for selling food
-        let fills = vec![FinalFill {
-            order_id: village_model::auction::OrderId(1),
-            participant_id: village_model::auction::ParticipantId(
-                village_ids["village_0"].to_participant_id(),
-            ),
-            resource_id: village_model::auction::ResourceId("food".to_string()),
-            order_type: village_model::auction::OrderType::Ask,
-            filled_quantity: 15,
-            price: dec!(10.0),
-        }];
+        fn name(&self) -> &str {
+            "FixedShare"
+        }
+    }
 
-        let initial_food = villages[0].food;
-        let initial_money = villages[0].money;
+    /// The `AllocationIntent` logged when `strategy` runs a lone village for one tick.
+    fn first_allocation_intent(strategy: FixedShareStrategy) -> EventType {
+        first_allocation_intent_with(strategy, |_| {})
+    }
 
-        apply_trades(&mut villages, &village_ids, &fills, &mut logger, 0);
+    fn first_allocation_intent_with(
+        strategy: FixedShareStrategy,
+        configure: impl FnOnce(&mut SimulationParameters),
+    ) -> EventType {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        scenario.parameters.autarky = true;
+        scenario.parameters.log_allocation_intent = true;
+        configure(&mut scenario.parameters);
+        let mut runner = SimulationRunner::new(&scenario, vec![Box::new(strategy)]);
+        runner.step(0);
+        runner
+            .logger
+            .get_events()
+            .iter()
+            .find(|e| matches!(e.event_type, EventType::AllocationIntent { .. }))
+            .map(|e| e.event_type.clone())
+            .expect("an allocation intent is logged every tick when asked for")
+    }
 
-        // Should have lost 15 food and gained 150 money
-        assert_eq!(villages[0].food, initial_food - dec!(15));
-        assert_eq!(villages[0].money, initial_money + dec!(150));
+    #[test]
+    fn test_allocation_intent_logs_strategy_values() {
+        let intent = first_allocation_intent(FixedShareStrategy {
+            wood: dec!(0.25),
+            food: dec!(0.5),
+            construction: dec!(0.25),
+        });
+        let EventType::AllocationIntent {
+            wood,
+            food,
+            construction,
+            worker_days,
+            ..
+        } = intent
+        else {
+            unreachable!()
+        };
+        assert!(worker_days > dec!(0));
+        assert_eq!(wood, worker_days * dec!(0.25));
+        assert_eq!(food, worker_days * dec!(0.5));
+        assert_eq!(construction, worker_days * dec!(0.25));
     }
 
     #[test]
-    fn test_apply_trades_multiple_resources() {
-        let mut villages = vec![
-            create_village(0, (2, 1), (2, 1), 5, 1),
-            create_village(1, (2, 1), (2, 1), 5, 1),
-        ];
-        let mut logger = EventLogger::new();
+    fn test_overallocation_is_rescaled_but_logged_as_decided() {
+        // 120% of the village's worker-days; the tick runs instead of panicking
+        let intent = first_allocation_intent(FixedShareStrategy {
+            wood: dec!(0.6),
+            food: dec!(0.36),
+            construction: dec!(0.24),
+        });
+        let EventType::AllocationIntent {
+            wood,
+            food,
+            construction,
+            worker_days,
+            ..
+        } = intent
+        else {
+            unreachable!()
+        };
+        assert_eq!(wood + food + construction, worker_days * dec!(1.2));
+    }
 
-        let village_ids: HashMap<String, VillageId> = villages
-            .iter()
-            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
-            .collect();
+    #[test]
+    fn test_zero_allocation_falls_back_instead_of_panicking() {
+        let strategy = || FixedShareStrategy {
+            wood: dec!(0),
+            food: dec!(0),
+            construction: dec!(0),
+        };
+        first_allocation_intent(strategy());
+
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.villages.truncate(1);
+        let mut runner = SimulationRunner::new(&scenario, vec![Box::new(strategy())]);
+        let market_state = runner.market_state();
+        let (intent, allocation, _) = runner.strategies[0].get_allocation_and_orders(
+            &runner.villages[0],
+            &market_state,
+            &runner.params,
+        );
+        assert_eq!(intent.labor(), dec!(0));
+
+        // The fallback splits every worker-day between food and wood
+        let worker_days = runner.villages[0].effective_worker_days(runner.params.labor_efficiency);
+        assert!(worker_days > dec!(0));
+        assert_eq!(allocation.labor(), worker_days);
+        assert_eq!(allocation.wood, worker_days / dec!(2));
+        assert_eq!(allocation.food, worker_days - allocation.wood);
+        assert_eq!(allocation.house_construction, dec!(0));
+    }
 
-        // Create fills for multiple trades
-        let fills = vec![
-            // Village 0 buys wood
-            FinalFill {
-                order_id: village_model::auction::OrderId(1),
-                participant_id: village_model::auction::ParticipantId(
-                    village_ids["village_0"].to_participant_id(),
-                ),
-                resource_id: village_model::auction::ResourceId("wood".to_string()),
-                order_type: village_model::auction::OrderType::Bid,
-                filled_quantity: 10,
-                price: dec!(15.0),
-            },
-            // Village 1 sells wood
-            FinalFill {
-                order_id: village_model::auction::OrderId(2),
-                participant_id: village_model::auction::ParticipantId(
-                    village_ids["village_1"].to_participant_id(),
-                ),
-                resource_id: village_model::auction::ResourceId("wood".to_string()),
-                order_type: village_model::auction::OrderType::Ask,
-                filled_quantity: 10,
-                price: dec!(15.0),
+    #[test]
+    #[should_panic(expected = "worker_days")]
+    fn test_strict_allocations_panics_on_overallocation() {
+        first_allocation_intent_with(
+            FixedShareStrategy {
+                wood: dec!(0.6),
+                food: dec!(0.36),
+                construction: dec!(0.24),
             },
-            // Village 0 sells food
-            FinalFill {
-                order_id: village_model::auction::OrderId(3),
-                participant_id: village_model::auction::ParticipantId(
-                    village_ids["village_0"].to_participant_id(),
-                ),
-                resource_id: village_model::auction::ResourceId("food".to_string()),
-                order_type: village_model::auction::OrderType::Ask,
-                filled_quantity: 5,
-                price: dec!(20.0),
+            |params| params.strict_allocations = true,
+        );
+    }
+
+    #[test]
+    fn test_labor_efficiency_shrinks_allocatable_worker_days() {
+        use village_model::scenario::LaborEfficiency;
+
+        // Strict, so the assertion must hold against the effective value
+        let intent = first_allocation_intent_with(
+            FixedShareStrategy {
+                wood: dec!(0.5),
+                food: dec!(0.5),
+                construction: dec!(0),
             },
-            // Village 1 buys food
-            FinalFill {
-                order_id: village_model::auction::OrderId(4),
-                participant_id: village_model::auction::ParticipantId(
-                    village_ids["village_1"].to_participant_id(),
-                ),
-                resource_id: village_model::auction::ResourceId("food".to_string()),
-                order_type: village_model::auction::OrderType::Bid,
-                filled_quantity: 5,
-                price: dec!(20.0),
+            |params| {
+                params.strict_allocations = true;
+                params.labor_efficiency = LaborEfficiency::PowerLaw { alpha: 0.8 };
             },
-        ];
+        );
+        let EventType::AllocationIntent {
+            wood,
+            food,
+            worker_days,
+            ..
+        } = intent
+        else {
+            unreachable!()
+        };
+        let scenario = &create_standard_scenarios()["basic"];
+        let raw = Decimal::from(scenario.villages[0].initial_workers);
+        assert!(worker_days < raw);
+        assert_eq!(wood + food, worker_days);
+    }
 
-        let v0_initial_wood = villages[0].wood;
-        let v0_initial_food = villages[0].food;
-        let v0_initial_money = villages[0].money;
-        let v1_initial_wood = villages[1].wood;
-        let v1_initial_food = villages[1].food;
-        let v1_initial_money = villages[1].money;
+    /// Strategy that burns a configurable number of random draws per tick but
+    /// always allocates the same way.
+    struct RandomDrawStrategy {
+        rng: rand::rngs::StdRng,
+        draws: usize,
+    }
 
-        apply_trades(&mut villages, &village_ids, &fills, &mut logger, 0);
+    impl RandomDrawStrategy {
+        fn new(draws: usize) -> Self {
+            use rand::SeedableRng;
+            Self {
+                rng: rand::rngs::StdRng::seed_from_u64(0),
+                draws,
+            }
+        }
+    }
 
-        // Village 0: +10 wood (-150 money), -5 food (+100 money) = net -50 money
-        assert_eq!(villages[0].wood, v0_initial_wood + dec!(10));
-        assert_eq!(villages[0].food, v0_initial_food - dec!(5));
-        assert_eq!(villages[0].money, v0_initial_money - dec!(50));
+    impl strategies::Strategy for RandomDrawStrategy {
+        fn decide_allocation_and_orders(
+            &mut self,
+            village: &strategies::VillageState,
+            _market: &strategies::MarketState,
+        ) -> strategies::StrategyDecision {
+            use rand::Rng;
+            for _ in 0..self.draws {
+                let _: u64 = self.rng.random();
+            }
 
-        // Village 1: -10 wood (+150 money), +5 food (-100 money) = net +50 money
-        assert_eq!(villages[1].wood, v1_initial_wood - dec!(10));
-        assert_eq!(villages[1].food, v1_initial_food + dec!(5));
-        assert_eq!(villages[1].money, v1_initial_money + dec!(50));
-    }
+            let wood = (village.worker_days * dec!(0.2)).round_dp(2);
+            let construction = (village.worker_days * dec!(0.2)).round_dp(2);
+            strategies::StrategyDecision {
+                allocation: strategies::WorkerAllocation {
+                    food: village.worker_days - wood - construction,
+                    wood,
+                    construction,
+                    processing: dec!(0),
+                    slot_expansion: dec!(0),
+                    slot_expansion_target: ResourceType::Food,
+                    repair: dec!(0),
+                },
+                wood_bid: None,
+                wood_ask: None,
+                food_bid: None,
+                food_ask: None,
+                house_upgrade_wood: dec!(0),
+            }
+        }
 
-    #[test]
-    fn test_apply_trades_no_matching_village() {
-        let mut villages = vec![create_village(0, (2, 1), (2, 1), 5, 1)];
-        let mut logger = EventLogger::new();
+        fn name(&self) -> &str {
+            "RandomDraw"
+        }
 
-        let village_ids: HashMap<String, VillageId> = villages
+        fn seed_rng(&mut self, seed: u64) {
+            use rand::SeedableRng;
+            self.rng = rand::rngs::StdRng::seed_from_u64(seed);
+        }
+    }
+
+    fn lifecycle_events(draws: usize) -> Vec<(usize, String, String)> {
+        let mut scenario = create_standard_scenarios()["basic"].clone();
+        scenario.random_seed = Some(7);
+        let strategies = scenario
+            .villages
             .iter()
-            .map(|v| (v.id_str.clone(), VillageId::new(&v.id_str)))
+            .map(|_| Box::new(RandomDrawStrategy::new(draws)) as Box<dyn strategies::Strategy>)
             .collect();
 
-        // Create a fill for a non-existent village
-        let fills = vec![FinalFill {
-            order_id: village_model::auction::OrderId(1),
-            participant_id: village_model::auction::ParticipantId(999), // Non-existent
-            resource_id: village_model::auction::ResourceId("wood".to_string()),
-            order_type: village_model::auction::OrderType::Bid,
-            filled_quantity: 10,
-            price: dec!(15.0),
-        }];
+        let mut runner = SimulationRunner::new(&scenario, strategies);
+        for tick in 0..300 {
+            runner.step(tick);
+        }
 
-        let initial_wood = villages[0].wood;
-        let initial_money = villages[0].money;
+        runner
+            .logger
+            .get_events()
+            .iter()
+            .filter_map(|e| match &e.event_type {
+                EventType::WorkerBorn { worker_id, .. } => {
+                    Some((e.tick, e.village_id.clone(), format!("born {}", worker_id)))
+                }
+                EventType::WorkerDied {
+                    worker_id, cause, ..
+                } => Some((
+                    e.tick,
+                    e.village_id.clone(),
+                    format!("died {} {:?}", worker_id, cause),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
 
-        apply_trades(&mut villages, &village_ids, &fills, &mut logger, 0);
+    #[test]
+    fn test_strategy_draws_do_not_perturb_lifecycle() {
+        let few_draws = lifecycle_events(1);
+        let many_draws = lifecycle_events(5);
 
-        // Village 0 should be unchanged
-        assert_eq!(villages[0].wood, initial_wood);
-        assert_eq!(villages[0].money, initial_money);
+        assert!(
+            few_draws
+                .iter()
+                .any(|(_, _, what)| what.starts_with("born")),
+            "scenario should produce births for the comparison to be meaningful"
+        );
+        assert_eq!(few_draws, many_draws);
     }
 }
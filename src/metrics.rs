@@ -2,7 +2,9 @@ use rust_decimal::Decimal;
 use rust_decimal::prelude::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::ops::Range;
 
+use crate::core::Holdings;
 use crate::events::{DeathCause, Event, EventType, ResourceType};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,13 +24,21 @@ pub struct VillageMetrics {
     pub total_deaths: usize,
     pub starvation_deaths: usize,
     pub shelter_deaths: usize,
+    pub old_age_deaths: usize,
 
     pub total_food_produced: Decimal,
     pub total_wood_produced: Decimal,
     pub total_food_consumed: Decimal,
     pub total_wood_consumed: Decimal,
+    /// Own production over consumption; above 1 means a net producer.
+    /// `None` when nothing was consumed.
+    pub food_self_sufficiency: Option<f64>,
+    pub wood_self_sufficiency: Option<f64>,
 
     pub houses_built: usize,
+    /// Missing from metrics saved before houses could collapse
+    #[serde(default)]
+    pub houses_collapsed: usize,
     pub final_houses: usize,
     pub average_house_maintenance: Decimal,
 
@@ -38,8 +48,18 @@ pub struct VillageMetrics {
 
     pub days_survived: usize,
     pub population_variance: f64,
+    /// Final worker counts by age (days), from the end-of-run age census.
+    /// Logs without a census report one bucket covering all ages.
+    pub age_distribution: Vec<(Range<u32>, usize)>,
+    /// Money, food and wood at the last snapshot, valued at the last
+    /// clearing prices
+    #[serde(default)]
+    pub final_wealth: Decimal,
 }
 
+/// Width in days of each age-distribution bucket.
+pub const AGE_BUCKET_WIDTH: u32 = 50;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScenarioMetrics {
     pub total_days: usize,
@@ -48,135 +68,228 @@ pub struct ScenarioMetrics {
     pub aggregate_growth_rate: f64,
     pub total_trade_volume: Decimal,
     pub economic_inequality: f64,
+    /// Realized over potential gains from trade, summed over every auction;
+    /// 1 is a fully efficient market. `None` when no orders ever crossed
+    #[serde(default)]
+    pub market_efficiency: Option<f64>,
+}
+
+impl ScenarioMetrics {
+    /// Scenario totals and every village's metrics as pretty-printed JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
 }
 
 pub struct MetricsCalculator;
 
-impl MetricsCalculator {
-    pub fn calculate_village_metrics(
-        village_id: &str,
-        events: &[Event],
-        initial_population: usize,
-        days_simulated: usize,
-    ) -> VillageMetrics {
-        let village_events: Vec<&Event> = events
-            .iter()
-            .filter(|e| e.village_id == village_id)
-            .collect();
+/// Latest clearing price of each good from `AuctionCleared` events; a good
+/// that has never cleared is priced at zero.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct LastPrices {
+    pub(crate) wood: Decimal,
+    pub(crate) food: Decimal,
+}
 
-        let mut metrics = VillageMetrics {
-            village_id: village_id.to_string(),
-            initial_population,
-            survival_score: 0.0,
-            growth_score: 0.0,
-            economic_efficiency: 0.0,
-            trade_effectiveness: 0.0,
-            stability_score: 0.0,
-            overall_score: 0.0,
-            final_population: 0,
-            peak_population: initial_population,
-            total_births: 0,
-            total_deaths: 0,
-            starvation_deaths: 0,
-            shelter_deaths: 0,
-            total_food_produced: Decimal::ZERO,
-            total_wood_produced: Decimal::ZERO,
-            total_food_consumed: Decimal::ZERO,
-            total_wood_consumed: Decimal::ZERO,
-            houses_built: 0,
-            final_houses: 0,
-            average_house_maintenance: Decimal::ZERO,
-            trades_executed: 0,
-            trade_volume: Decimal::ZERO,
-            trade_profit: Decimal::ZERO,
-            days_survived: days_simulated,
-            population_variance: 0.0,
-        };
+impl LastPrices {
+    pub(crate) fn observe(&mut self, event: &Event) {
+        if let EventType::AuctionCleared {
+            wood_price,
+            food_price,
+            ..
+        } = &event.event_type
+        {
+            self.wood = wood_price.unwrap_or(self.wood);
+            self.food = food_price.unwrap_or(self.food);
+        }
+    }
 
-        let mut population_history = vec![initial_population];
-        let mut money_history = Vec::new();
-        let mut house_maintenance_sum = Decimal::ZERO;
-        let mut house_maintenance_count = 0;
-
-        for event in &village_events {
-            match &event.event_type {
-                EventType::WorkerBorn {
-                    total_population, ..
-                } => {
-                    metrics.total_births += 1;
-                    if *total_population > metrics.peak_population {
-                        metrics.peak_population = *total_population;
-                    }
-                    population_history.push(*total_population);
-                }
-                EventType::WorkerDied {
-                    cause,
-                    total_population,
-                    ..
-                } => {
-                    metrics.total_deaths += 1;
-                    match cause {
-                        DeathCause::Starvation => metrics.starvation_deaths += 1,
-                        DeathCause::NoShelter => metrics.shelter_deaths += 1,
-                    }
-                    population_history.push(*total_population);
+    pub(crate) fn wealth(&self, holdings: &Holdings) -> Decimal {
+        holdings.value(self.wood, self.food)
+    }
+}
+
+/// Running metrics for one village, built up one event at a time.
+struct VillageAccumulator {
+    metrics: VillageMetrics,
+    days_simulated: usize,
+    final_ages: Option<Vec<u32>>,
+    holdings: Holdings,
+    population_history: Vec<usize>,
+    house_maintenance_sum: Decimal,
+    house_maintenance_count: usize,
+}
+
+impl VillageAccumulator {
+    fn new(village_id: &str, initial_population: usize, days_simulated: usize) -> Self {
+        Self {
+            metrics: VillageMetrics {
+                village_id: village_id.to_string(),
+                initial_population,
+                survival_score: 0.0,
+                growth_score: 0.0,
+                economic_efficiency: 0.0,
+                trade_effectiveness: 0.0,
+                stability_score: 0.0,
+                overall_score: 0.0,
+                final_population: 0,
+                peak_population: initial_population,
+                total_births: 0,
+                total_deaths: 0,
+                starvation_deaths: 0,
+                shelter_deaths: 0,
+                old_age_deaths: 0,
+                total_food_produced: Decimal::ZERO,
+                total_wood_produced: Decimal::ZERO,
+                total_food_consumed: Decimal::ZERO,
+                total_wood_consumed: Decimal::ZERO,
+                food_self_sufficiency: None,
+                wood_self_sufficiency: None,
+                houses_built: 0,
+                houses_collapsed: 0,
+                final_houses: 0,
+                average_house_maintenance: Decimal::ZERO,
+                trades_executed: 0,
+                trade_volume: Decimal::ZERO,
+                trade_profit: Decimal::ZERO,
+                days_survived: days_simulated,
+                population_variance: 0.0,
+                age_distribution: Vec::new(),
+                final_wealth: Decimal::ZERO,
+            },
+            days_simulated,
+            final_ages: None,
+            holdings: Holdings::default(),
+            population_history: vec![initial_population],
+            house_maintenance_sum: Decimal::ZERO,
+            house_maintenance_count: 0,
+        }
+    }
+
+    /// Folds in one of this village's events.
+    fn observe(&mut self, event: &Event) {
+        let metrics = &mut self.metrics;
+        match &event.event_type {
+            EventType::WorkerBorn {
+                total_population, ..
+            } => {
+                metrics.total_births += 1;
+                if *total_population > metrics.peak_population {
+                    metrics.peak_population = *total_population;
                 }
-                EventType::ResourceProduced {
-                    resource, amount, ..
-                } => match resource {
-                    ResourceType::Food => metrics.total_food_produced += amount,
-                    ResourceType::Wood => metrics.total_wood_produced += amount,
-                },
-                EventType::ResourceConsumed {
-                    resource, amount, ..
-                } => match resource {
-                    ResourceType::Food => metrics.total_food_consumed += amount,
-                    ResourceType::Wood => metrics.total_wood_consumed += amount,
-                },
-                EventType::HouseCompleted { total_houses, .. } => {
-                    metrics.houses_built += 1;
-                    metrics.final_houses = *total_houses;
+                self.population_history.push(*total_population);
+            }
+            EventType::WorkerDied {
+                cause,
+                total_population,
+                ..
+            } => {
+                match cause {
+                    DeathCause::Starvation => metrics.starvation_deaths += 1,
+                    DeathCause::NoShelter => metrics.shelter_deaths += 1,
+                    DeathCause::OldAge => metrics.old_age_deaths += 1,
+                    DeathCause::Departed => {}
                 }
-                EventType::HouseDecayed {
-                    maintenance_level, ..
-                } => {
-                    house_maintenance_sum += maintenance_level;
-                    house_maintenance_count += 1;
+                if *cause != DeathCause::Departed {
+                    metrics.total_deaths += 1;
                 }
-                EventType::TradeExecuted {
-                    quantity,
-                    price,
-                    side,
-                    ..
-                } => {
-                    metrics.trades_executed += 1;
-                    metrics.trade_volume += quantity;
-                    let trade_value = quantity * price;
-                    match side {
-                        crate::events::TradeSide::Sell => metrics.trade_profit += trade_value,
-                        crate::events::TradeSide::Buy => metrics.trade_profit -= trade_value,
-                    }
+                self.population_history.push(*total_population);
+            }
+            EventType::ResourceProduced {
+                resource, amount, ..
+            } => match resource {
+                ResourceType::Food => metrics.total_food_produced += amount,
+                ResourceType::Wood => metrics.total_wood_produced += amount,
+                ResourceType::Labor => {}
+            },
+            EventType::ResourceConsumed {
+                resource, amount, ..
+            } => match resource {
+                ResourceType::Food => metrics.total_food_consumed += amount,
+                ResourceType::Wood => metrics.total_wood_consumed += amount,
+                ResourceType::Labor => {}
+            },
+            EventType::HouseCompleted { total_houses, .. } => {
+                metrics.houses_built += 1;
+                metrics.final_houses = *total_houses;
+            }
+            EventType::HouseDecayed {
+                maintenance_level, ..
+            } => {
+                self.house_maintenance_sum += maintenance_level;
+                self.house_maintenance_count += 1;
+            }
+            EventType::HouseCollapsed { total_houses, .. } => {
+                metrics.houses_collapsed += 1;
+                metrics.final_houses = *total_houses;
+            }
+            EventType::TradeExecuted {
+                quantity,
+                price,
+                side,
+                ..
+            } => {
+                metrics.trades_executed += 1;
+                metrics.trade_volume += quantity;
+                let trade_value = quantity * price;
+                match side {
+                    crate::events::TradeSide::Sell => metrics.trade_profit += trade_value,
+                    crate::events::TradeSide::Buy => metrics.trade_profit -= trade_value,
                 }
-                EventType::VillageStateSnapshot {
-                    population,
-                    houses,
-                    money,
-                    ..
-                } => {
-                    metrics.final_population = *population;
-                    metrics.final_houses = *houses;
-                    money_history.push(*money);
-                    if *population == 0 {
-                        metrics.days_survived = event.tick;
-                    }
+            }
+            EventType::VillageStateSnapshot {
+                population,
+                houses,
+                food,
+                wood,
+                money,
+            } => {
+                self.holdings = Holdings {
+                    money: *money,
+                    food: *food,
+                    wood: *wood,
+                };
+                metrics.final_population = *population;
+                metrics.final_houses = *houses;
+                if *population == 0 {
+                    metrics.days_survived = event.tick;
                 }
-                _ => {}
             }
+            EventType::VillageBankrupt { .. } => {
+                metrics.final_population = 0;
+                metrics.final_houses = 0;
+                metrics.days_survived = event.tick;
+            }
+            EventType::AgeCensus { ages } => self.final_ages = Some(ages.clone()),
+            _ => {}
         }
+    }
+
+    /// Derives the scores from the totals observed so far, valuing the final
+    /// holdings at `prices`.
+    fn finalize(self, prices: &LastPrices) -> VillageMetrics {
+        let mut metrics = self.metrics;
+        metrics.final_wealth = prices.wealth(&self.holdings);
+        let initial_population = metrics.initial_population;
+        let population_history = self.population_history;
+
+        metrics.age_distribution = match self.final_ages {
+            Some(ages) => MetricsCalculator::age_distribution(&ages, AGE_BUCKET_WIDTH),
+            None => vec![(0..u32::MAX, metrics.final_population)],
+        };
+
+        metrics.food_self_sufficiency = MetricsCalculator::self_sufficiency(
+            metrics.total_food_produced,
+            metrics.total_food_consumed,
+        );
+        metrics.wood_self_sufficiency = MetricsCalculator::self_sufficiency(
+            metrics.total_wood_produced,
+            metrics.total_wood_consumed,
+        );
 
-        if house_maintenance_count > 0 {
+        if self.house_maintenance_count > 0 {
             metrics.average_house_maintenance =
-                house_maintenance_sum / Decimal::from(house_maintenance_count);
+                self.house_maintenance_sum / Decimal::from(self.house_maintenance_count);
         }
 
         metrics.survival_score = if initial_population > 0 {
@@ -197,8 +310,8 @@ impl MetricsCalculator {
             population_history.iter().sum::<usize>() as f64 / population_history.len() as f64;
         if avg_population > 0.0 {
             let total_production = metrics.total_food_produced + metrics.total_wood_produced;
-            metrics.economic_efficiency =
-                total_production.to_f64().unwrap_or(0.0) / (avg_population * days_simulated as f64);
+            metrics.economic_efficiency = total_production.to_f64().unwrap_or(0.0)
+                / (avg_population * self.days_simulated as f64);
         }
 
         if metrics.trades_executed > 0 {
@@ -228,22 +341,139 @@ impl MetricsCalculator {
 
         metrics
     }
+}
+
+/// Streaming counterpart to [`MetricsCalculator::calculate_scenario_metrics`].
+///
+/// Feed events with [`observe`](Self::observe) as they happen and call
+/// [`finalize`](Self::finalize) at the end; the event log never has to be
+/// kept in memory. Events for villages not passed to `new` are ignored.
+pub struct MetricsAccumulator {
+    villages: Vec<VillageAccumulator>,
+    index: HashMap<String, usize>,
+    days_simulated: usize,
+    surplus: SurplusTotals,
+    prices: LastPrices,
+}
+
+/// Running totals of `MarketSurplus` events.
+#[derive(Default)]
+struct SurplusTotals {
+    realized: Decimal,
+    potential: Decimal,
+}
+
+impl SurplusTotals {
+    fn observe(&mut self, event: &Event) {
+        if let EventType::MarketSurplus {
+            realized,
+            potential,
+        } = &event.event_type
+        {
+            self.realized += realized;
+            self.potential += potential;
+        }
+    }
+
+    fn efficiency(&self) -> Option<f64> {
+        if self.potential.is_zero() {
+            return None;
+        }
+        (self.realized / self.potential).to_f64()
+    }
+}
+
+impl MetricsAccumulator {
+    pub fn new(village_configs: &[(String, usize)], days_simulated: usize) -> Self {
+        Self {
+            villages: village_configs
+                .iter()
+                .map(|(id, initial_pop)| VillageAccumulator::new(id, *initial_pop, days_simulated))
+                .collect(),
+            index: village_configs
+                .iter()
+                .enumerate()
+                .map(|(i, (id, _))| (id.clone(), i))
+                .collect(),
+            days_simulated,
+            surplus: SurplusTotals::default(),
+            prices: LastPrices::default(),
+        }
+    }
+
+    pub fn observe(&mut self, event: &Event) {
+        self.surplus.observe(event);
+        self.prices.observe(event);
+        if let Some(&i) = self.index.get(&event.village_id) {
+            self.villages[i].observe(event);
+        }
+    }
+
+    pub fn finalize(self) -> ScenarioMetrics {
+        let villages = self
+            .villages
+            .into_iter()
+            .map(|village| village.finalize(&self.prices))
+            .collect();
+        MetricsCalculator::scenario_from_villages(
+            villages,
+            self.days_simulated,
+            self.surplus.efficiency(),
+        )
+    }
+}
+
+impl MetricsCalculator {
+    pub fn calculate_village_metrics(
+        village_id: &str,
+        events: &[Event],
+        initial_population: usize,
+        days_simulated: usize,
+    ) -> VillageMetrics {
+        let mut accumulator =
+            VillageAccumulator::new(village_id, initial_population, days_simulated);
+        let mut prices = LastPrices::default();
+        for event in events {
+            prices.observe(event);
+            if event.village_id == village_id {
+                accumulator.observe(event);
+            }
+        }
+        accumulator.finalize(&prices)
+    }
 
     pub fn calculate_scenario_metrics(
         events: &[Event],
         village_configs: &[(String, usize)], // (village_id, initial_population)
         days_simulated: usize,
+    ) -> ScenarioMetrics {
+        let villages = village_configs
+            .iter()
+            .map(|(village_id, initial_pop)| {
+                Self::calculate_village_metrics(village_id, events, *initial_pop, days_simulated)
+            })
+            .collect();
+        let mut surplus = SurplusTotals::default();
+        for event in events {
+            surplus.observe(event);
+        }
+        Self::scenario_from_villages(villages, days_simulated, surplus.efficiency())
+    }
+
+    /// Aggregates per-village metrics into scenario-wide metrics.
+    fn scenario_from_villages(
+        village_metrics: Vec<VillageMetrics>,
+        days_simulated: usize,
+        market_efficiency: Option<f64>,
     ) -> ScenarioMetrics {
         let mut villages = HashMap::new();
         let mut total_initial_pop = 0;
         let mut total_final_pop = 0;
 
-        for (village_id, initial_pop) in village_configs {
-            let metrics =
-                Self::calculate_village_metrics(village_id, events, *initial_pop, days_simulated);
-            total_initial_pop += initial_pop;
+        for metrics in village_metrics {
+            total_initial_pop += metrics.initial_population;
             total_final_pop += metrics.final_population;
-            villages.insert(village_id.clone(), metrics);
+            villages.insert(metrics.village_id.clone(), metrics);
         }
 
         let aggregate_survival_rate = if total_initial_pop > 0 {
@@ -278,7 +508,32 @@ impl MetricsCalculator {
             aggregate_growth_rate,
             total_trade_volume,
             economic_inequality,
+            market_efficiency,
+        }
+    }
+
+    /// Ratio of own production to consumption, or `None` if nothing was consumed.
+    pub fn self_sufficiency(produced: Decimal, consumed: Decimal) -> Option<f64> {
+        if consumed.is_zero() {
+            return None;
         }
+        (produced / consumed).to_f64()
+    }
+
+    /// Count ages into contiguous buckets of `bucket_width` days, from 0 up to
+    /// the bucket holding the oldest age. Empty buckets are kept so gaps show.
+    pub fn age_distribution(ages: &[u32], bucket_width: u32) -> Vec<(Range<u32>, usize)> {
+        let Some(&oldest) = ages.iter().max() else {
+            return Vec::new();
+        };
+
+        let mut buckets: Vec<(Range<u32>, usize)> = (0..=oldest / bucket_width)
+            .map(|i| (i * bucket_width..(i + 1) * bucket_width, 0))
+            .collect();
+        for &age in ages {
+            buckets[(age / bucket_width) as usize].1 += 1;
+        }
+        buckets
     }
 
     pub fn calculate_gini_coefficient(values: &[f64]) -> f64 {
@@ -326,11 +581,27 @@ impl std::fmt::Display for VillageMetrics {
             self.economic_efficiency
         )?;
         writeln!(f, "  - Trade: {:.2} profit/trade", self.trade_effectiveness)?;
+        writeln!(f, "  - Final wealth: {:.2}", self.final_wealth)?;
         writeln!(
             f,
             "  - Stability: {:.2} (σ={:.1})",
             self.stability_score, self.population_variance
         )?;
+        let ratio = |r: Option<f64>| r.map_or("n/a".to_string(), |r| format!("{:.2}", r));
+        writeln!(
+            f,
+            "  - Self-sufficiency: food {}, wood {}",
+            ratio(self.food_self_sufficiency),
+            ratio(self.wood_self_sufficiency)
+        )?;
+        if self.age_distribution.len() > 1 {
+            let buckets: Vec<String> = self
+                .age_distribution
+                .iter()
+                .map(|(range, count)| format!("{}-{}:{}", range.start, range.end - 1, count))
+                .collect();
+            writeln!(f, "  - Ages: {}", buckets.join(" "))?;
+        }
         Ok(())
     }
 }
@@ -354,6 +625,9 @@ impl std::fmt::Display for ScenarioMetrics {
             "  Economic Inequality (Gini): {:.3}",
             self.economic_inequality
         )?;
+        if let Some(efficiency) = self.market_efficiency {
+            writeln!(f, "  Market Efficiency: {:.1}%", efficiency * 100.0)?;
+        }
         writeln!(f, "\nVillage Scores (Growth Multiplier):")?;
         let mut sorted_villages: Vec<_> = self.villages.iter().collect();
         sorted_villages.sort_by(|a, b| b.1.overall_score.partial_cmp(&a.1.overall_score).unwrap());
@@ -141,11 +141,15 @@ mod tests {
             total_deaths: 5,
             starvation_deaths: 2,
             shelter_deaths: 3,
+            old_age_deaths: 0,
             total_food_produced: dec!(100.0),
             total_wood_produced: dec!(80.0),
             total_food_consumed: dec!(90.0),
             total_wood_consumed: dec!(70.0),
+            food_self_sufficiency: Some(100.0 / 90.0),
+            wood_self_sufficiency: None,
             houses_built: 2,
+            houses_collapsed: 0,
             final_houses: 3,
             average_house_maintenance: dec!(0.8),
             trades_executed: 10,
@@ -153,10 +157,203 @@ mod tests {
             trade_profit: dec!(15.0),
             days_survived: 100,
             population_variance: 2.5,
+            age_distribution: vec![(0..u32::MAX, 8)],
+            final_wealth: dec!(250.0),
         };
 
         let display = format!("{}", metrics);
         assert!(display.contains("Village test Metrics:"));
         assert!(display.contains("Overall Score: 0.70"));
+        assert!(display.contains("Self-sufficiency: food 1.11, wood n/a"));
+        assert!(display.contains("Final wealth: 250.00"));
+    }
+
+    #[test]
+    fn test_age_distribution_from_census() {
+        let mut events = create_test_events();
+        events.push(Event {
+            timestamp: Utc::now(),
+            tick: 20,
+            village_id: "test_village".to_string(),
+            event_type: EventType::AgeCensus {
+                ages: vec![0, 10, 49, 50, 120, 149],
+            },
+        });
+
+        let metrics = MetricsCalculator::calculate_village_metrics("test_village", &events, 10, 20);
+
+        assert_eq!(
+            metrics.age_distribution,
+            vec![(0..50, 3), (50..100, 1), (100..150, 2)]
+        );
+    }
+
+    #[test]
+    fn test_age_distribution_without_census_is_single_bucket() {
+        let events = create_test_events();
+        let metrics = MetricsCalculator::calculate_village_metrics("test_village", &events, 10, 20);
+
+        assert_eq!(
+            metrics.age_distribution,
+            vec![(0..u32::MAX, metrics.final_population)]
+        );
+    }
+
+    #[test]
+    fn test_streaming_accumulator_matches_batch_metrics() {
+        let mut events = create_test_events();
+        for event in &mut events[..3] {
+            event.village_id = "village_a".to_string();
+        }
+        events.push(Event {
+            timestamp: Utc::now(),
+            tick: 4,
+            village_id: "village_b".to_string(),
+            event_type: EventType::TradeExecuted {
+                resource: ResourceType::Wood,
+                quantity: dec!(3),
+                price: dec!(1.5),
+                counterparty: "village_a".to_string(),
+                side: TradeSide::Sell,
+            },
+        });
+        let village_configs = vec![("village_a".to_string(), 10), ("village_b".to_string(), 5)];
+
+        let batch = MetricsCalculator::calculate_scenario_metrics(&events, &village_configs, 10);
+        let mut accumulator = MetricsAccumulator::new(&village_configs, 10);
+        for event in &events {
+            accumulator.observe(event);
+        }
+        let streamed = accumulator.finalize();
+
+        assert_eq!(
+            serde_json::to_value(&streamed).unwrap(),
+            serde_json::to_value(&batch).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_market_efficiency_aggregates_surplus_over_ticks() {
+        let surplus = |tick, realized, potential| Event {
+            timestamp: Utc::now(),
+            tick,
+            village_id: "market".to_string(),
+            event_type: EventType::MarketSurplus {
+                realized,
+                potential,
+            },
+        };
+        let village_configs = vec![("test_village".to_string(), 10)];
+
+        let efficient = vec![surplus(0, dec!(40), dec!(40)), surplus(1, dec!(0), dec!(0))];
+        let metrics =
+            MetricsCalculator::calculate_scenario_metrics(&efficient, &village_configs, 2);
+        assert_eq!(metrics.market_efficiency, Some(1.0));
+
+        // A pruned tick drags the ratio below 1: (40 + 30) / (40 + 80)
+        let pruned = vec![
+            surplus(0, dec!(40), dec!(40)),
+            surplus(1, dec!(30), dec!(80)),
+        ];
+        let mut accumulator = MetricsAccumulator::new(&village_configs, 2);
+        for event in &pruned {
+            accumulator.observe(event);
+        }
+        let efficiency = accumulator.finalize().market_efficiency.unwrap();
+        assert!((efficiency - 70.0 / 120.0).abs() < 1e-9);
+
+        let no_trade = MetricsCalculator::calculate_scenario_metrics(&[], &village_configs, 2);
+        assert_eq!(no_trade.market_efficiency, None);
+    }
+
+    #[test]
+    fn test_final_wealth_values_last_snapshot_at_last_prices() {
+        let mut events = create_test_events();
+        let cleared = |tick, wood_price, food_price| Event {
+            timestamp: Utc::now(),
+            tick,
+            village_id: "market".to_string(),
+            event_type: EventType::AuctionCleared {
+                wood_price,
+                food_price,
+                wood_volume: 1,
+                food_volume: 1,
+                total_participants: 2,
+            },
+        };
+        events.push(cleared(5, Some(dec!(2)), Some(dec!(1))));
+        // A tick where food doesn't trade keeps its earlier price
+        events.push(cleared(6, Some(dec!(3)), None));
+
+        let metrics = MetricsCalculator::calculate_village_metrics("test_village", &events, 10, 10);
+        // 95 money + 48 wood * 3 + 45 food * 1
+        assert_eq!(metrics.final_wealth, dec!(284));
+    }
+
+    #[test]
+    fn test_self_sufficiency_from_production_and_consumption() {
+        let base_time = Utc::now();
+        let event = |event_type| Event {
+            timestamp: base_time,
+            tick: 1,
+            village_id: "importer".to_string(),
+            event_type,
+        };
+        let events = vec![
+            event(EventType::ResourceProduced {
+                resource: ResourceType::Food,
+                amount: dec!(100),
+                workers_assigned: 5,
+            }),
+            event(EventType::TradeExecuted {
+                resource: ResourceType::Food,
+                quantity: dec!(50),
+                price: dec!(1),
+                counterparty: "market".to_string(),
+                side: TradeSide::Buy,
+            }),
+            event(EventType::ResourceConsumed {
+                resource: ResourceType::Food,
+                amount: dec!(50),
+                purpose: ConsumptionPurpose::WorkerFeeding,
+            }),
+        ];
+
+        let metrics = MetricsCalculator::calculate_village_metrics("importer", &events, 5, 10);
+
+        assert_eq!(metrics.food_self_sufficiency, Some(2.0));
+        assert_eq!(metrics.wood_self_sufficiency, None);
+    }
+
+    #[test]
+    fn test_metrics_json_has_fields_and_round_trips() {
+        let village_configs = vec![("test_village".to_string(), 10)];
+        let metrics = MetricsCalculator::calculate_scenario_metrics(
+            &create_test_events(),
+            &village_configs,
+            10,
+        );
+
+        let json = metrics.to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["total_days"], 10);
+        let village = &value["villages"]["test_village"];
+        assert_eq!(village["total_births"], 1);
+        assert_eq!(village["starvation_deaths"], 1);
+        assert_eq!(village["final_population"], 10);
+
+        let parsed: ScenarioMetrics = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.villages["test_village"].total_deaths, 1);
+        let reparsed: serde_json::Value = serde_json::from_str(&parsed.to_json().unwrap()).unwrap();
+        assert_eq!(reparsed, value);
+
+        // Metrics saved before houses could collapse still load
+        let mut old = value.clone();
+        old["villages"]["test_village"]
+            .as_object_mut()
+            .unwrap()
+            .remove("houses_collapsed");
+        let parsed: ScenarioMetrics = serde_json::from_value(old).unwrap();
+        assert_eq!(parsed.villages["test_village"].houses_collapsed, 0);
     }
 }
@@ -1,8 +1,11 @@
 //! Query and filter simulation events.
 
 use crate::cli::QueryFilters;
+use crate::core::VillageTotals;
 use crate::events::{Event, EventType, ResourceType, TradeSide};
+use crate::types::ResourceTypeExt;
 use rust_decimal::Decimal;
+use serde::Serialize;
 use serde_json;
 use std::fs;
 use std::path::Path;
@@ -20,31 +23,31 @@ pub fn query_events(file: &Path, filters: &QueryFilters) -> Result<Vec<Event>, S
         .into_iter()
         .filter(|event| {
             // Village filter
-            if let Some(ref village) = filters.village {
-                if event.village_id != *village {
-                    return false;
-                }
+            if let Some(ref village) = filters.village
+                && event.village_id != *village
+            {
+                return false;
             }
 
             // Event type filter
-            if let Some(ref event_type) = filters.event_type {
-                if !event_matches_type(&event.event_type, event_type) {
-                    return false;
-                }
+            if let Some(ref event_type) = filters.event_type
+                && !event_matches_type(&event.event_type, event_type)
+            {
+                return false;
             }
 
             // Resource filter
-            if let Some(ref resource) = filters.resource {
-                if !event_has_resource(&event.event_type, resource) {
-                    return false;
-                }
+            if let Some(ref resource) = filters.resource
+                && !event_has_resource(&event.event_type, resource)
+            {
+                return false;
             }
 
             // Tick range filter
-            if let Some((start, end)) = filters.tick_range {
-                if event.tick < start || event.tick > end {
-                    return false;
-                }
+            if let Some((start, end)) = filters.tick_range
+                && (event.tick < start || event.tick > end)
+            {
+                return false;
             }
 
             true
@@ -55,13 +58,14 @@ pub fn query_events(file: &Path, filters: &QueryFilters) -> Result<Vec<Event>, S
 }
 
 /// Check if an event matches the given type string
-fn event_matches_type(event_type: &EventType, type_str: &str) -> bool {
+pub(crate) fn event_matches_type(event_type: &EventType, type_str: &str) -> bool {
     let type_lower = type_str.to_lowercase();
 
     match event_type {
         EventType::WorkerAllocation { .. } => {
             type_lower.contains("allocation") || type_lower.contains("worker")
         }
+        EventType::AllocationIntent { .. } => type_lower.contains("intent"),
         EventType::ResourceProduced { .. } => {
             type_lower.contains("produced") || type_lower.contains("production")
         }
@@ -86,7 +90,58 @@ fn event_matches_type(event_type: &EventType, type_str: &str) -> bool {
             type_lower.contains("decay") || type_lower.contains("house")
         }
         EventType::AuctionCleared { .. } => {
-            type_lower.contains("auction") || type_lower.contains("clear") || type_lower.contains("market")
+            type_lower.contains("auction")
+                || type_lower.contains("clear")
+                || type_lower.contains("market")
+        }
+        EventType::AgeCensus { .. } => type_lower.contains("age") || type_lower.contains("census"),
+        EventType::SlotAdded { .. } => type_lower.contains("slot"),
+        EventType::LaborWasted { .. } => {
+            type_lower.contains("waste") || type_lower.contains("labor")
+        }
+        EventType::PriceControlBinding { .. } => {
+            type_lower.contains("control")
+                || type_lower.contains("ceiling")
+                || type_lower.contains("floor")
+        }
+        EventType::EstateInherited { .. } => {
+            type_lower.contains("estate") || type_lower.contains("inherit")
+        }
+        EventType::FoodImported { .. } => {
+            type_lower.contains("import") || type_lower.contains("backstop")
+        }
+        EventType::GlobalModifierActive { .. } => {
+            type_lower.contains("modifier") || type_lower.contains("global")
+        }
+        EventType::SeasonalFactor { .. } => {
+            type_lower.contains("season") || type_lower.contains("factor")
+        }
+        EventType::ExternalPrice { .. } => {
+            type_lower.contains("external") || type_lower.contains("price")
+        }
+        EventType::MarketSurplus { .. } => {
+            type_lower.contains("surplus") || type_lower.contains("efficiency")
+        }
+        EventType::HouseCollapsed { .. } => {
+            type_lower.contains("collapse") || type_lower.contains("house")
+        }
+        EventType::PriceLimitHit { .. } => {
+            type_lower.contains("limit") || type_lower.contains("price")
+        }
+        EventType::RoundingRemainder { .. } => {
+            type_lower.contains("round") || type_lower.contains("remainder")
+        }
+        EventType::CashFlow { .. } => type_lower.contains("cash") || type_lower.contains("flow"),
+        EventType::OrderBlocked { .. } => {
+            type_lower.contains("block") || type_lower.contains("embargo")
+        }
+        EventType::OrderKilled { .. } => type_lower.contains("kill") || type_lower.contains("fok"),
+        EventType::VillageBankrupt { .. } => type_lower.contains("bankrupt"),
+        EventType::ConstructionStalled { .. } => {
+            type_lower.contains("construction") || type_lower.contains("stall")
+        }
+        EventType::MoneyAdjusted { .. } => {
+            type_lower.contains("money") || type_lower.contains("fee")
         }
     }
 }
@@ -96,20 +151,26 @@ fn event_has_resource(event_type: &EventType, resource_str: &str) -> bool {
     let resource_lower = resource_str.to_lowercase();
     let is_food = resource_lower.contains("food");
     let is_wood = resource_lower.contains("wood");
+    let is_labor = resource_lower.contains("labor");
 
     match event_type {
         EventType::ResourceProduced { resource, .. }
         | EventType::ResourceConsumed { resource, .. } => match resource {
             ResourceType::Food => is_food,
             ResourceType::Wood => is_wood,
+            ResourceType::Labor => is_labor,
         },
         EventType::TradeExecuted { resource, .. } => match resource {
             ResourceType::Food => is_food,
             ResourceType::Wood => is_wood,
+            ResourceType::Labor => is_labor,
         },
-        EventType::OrderPlaced { resource, .. } => match resource {
+        EventType::OrderPlaced { resource, .. }
+        | EventType::OrderBlocked { resource, .. }
+        | EventType::OrderKilled { resource, .. } => match resource {
             ResourceType::Food => is_food,
             ResourceType::Wood => is_wood,
+            ResourceType::Labor => is_labor,
         },
         _ => false,
     }
@@ -133,6 +194,7 @@ pub fn format_query_results(events: &[Event], verbose: bool) -> String {
     for event in events {
         let type_name = match &event.event_type {
             EventType::WorkerAllocation { .. } => "WorkerAllocation",
+            EventType::AllocationIntent { .. } => "AllocationIntent",
             EventType::ResourceProduced { .. } => "ResourceProduced",
             EventType::ResourceConsumed { .. } => "ResourceConsumed",
             EventType::TradeExecuted { .. } => "TradeExecuted",
@@ -143,6 +205,25 @@ pub fn format_query_results(events: &[Event], verbose: bool) -> String {
             EventType::VillageStateSnapshot { .. } => "VillageStateSnapshot",
             EventType::HouseDecayed { .. } => "HouseDecayed",
             EventType::AuctionCleared { .. } => "AuctionCleared",
+            EventType::AgeCensus { .. } => "AgeCensus",
+            EventType::SlotAdded { .. } => "SlotAdded",
+            EventType::LaborWasted { .. } => "LaborWasted",
+            EventType::PriceControlBinding { .. } => "PriceControlBinding",
+            EventType::EstateInherited { .. } => "EstateInherited",
+            EventType::FoodImported { .. } => "FoodImported",
+            EventType::GlobalModifierActive { .. } => "GlobalModifierActive",
+            EventType::SeasonalFactor { .. } => "SeasonalFactor",
+            EventType::ExternalPrice { .. } => "ExternalPrice",
+            EventType::MarketSurplus { .. } => "MarketSurplus",
+            EventType::HouseCollapsed { .. } => "HouseCollapsed",
+            EventType::PriceLimitHit { .. } => "PriceLimitHit",
+            EventType::RoundingRemainder { .. } => "RoundingRemainder",
+            EventType::CashFlow { .. } => "CashFlow",
+            EventType::OrderBlocked { .. } => "OrderBlocked",
+            EventType::OrderKilled { .. } => "OrderKilled",
+            EventType::VillageBankrupt { .. } => "VillageBankrupt",
+            EventType::ConstructionStalled { .. } => "ConstructionStalled",
+            EventType::MoneyAdjusted { .. } => "MoneyAdjusted",
         };
         *type_counts.entry(type_name).or_insert(0) += 1;
     }
@@ -183,6 +264,21 @@ fn format_event_details(event_type: &EventType) -> String {
                 food_workers, wood_workers, construction_workers
             )
         }
+        EventType::AllocationIntent {
+            wood,
+            food,
+            construction,
+            processing,
+            slot_expansion,
+            repair,
+            worker_days,
+        } => {
+            let total = *wood + *food + *construction + *processing + *slot_expansion + *repair;
+            format!(
+                "Asked for {} of {} worker-days ({} food, {} wood, {} construction)",
+                total, worker_days, food, wood, construction
+            )
+        }
         EventType::ResourceProduced {
             resource,
             amount,
@@ -253,10 +349,131 @@ fn format_event_details(event_type: &EventType) -> String {
         EventType::HouseDecayed { house_id, .. } => {
             format!("House {} decayed", house_id)
         }
-        EventType::AuctionCleared { wood_price, food_price, wood_volume, food_volume, .. } => {
-            format!("Auction cleared - Wood: {} @ {:?}, Food: {} @ {:?}", 
-                wood_volume, wood_price, food_volume, food_price)
+        EventType::AuctionCleared {
+            wood_price,
+            food_price,
+            wood_volume,
+            food_volume,
+            ..
+        } => {
+            format!(
+                "Auction cleared - Wood: {} @ {:?}, Food: {} @ {:?}",
+                wood_volume, wood_price, food_volume, food_price
+            )
         }
+        EventType::AgeCensus { ages } => {
+            format!("Age census: {} workers", ages.len())
+        }
+        EventType::SlotAdded {
+            resource,
+            total_slots,
+        } => {
+            format!("{:?} slot added ({} full slots)", resource, total_slots)
+        }
+        EventType::LaborWasted {
+            resource,
+            wasted_worker_days,
+        } => {
+            format!("{} {:?} worker-days wasted", wasted_worker_days, resource)
+        }
+        EventType::PriceControlBinding {
+            resource,
+            control,
+            price,
+            rationed_quantity,
+            ..
+        } => {
+            format!(
+                "{:?} {:?} binding at {}, {} rationed",
+                resource, control, price, rationed_quantity
+            )
+        }
+        EventType::EstateInherited { worker_id, food } => {
+            format!("{} food inherited from worker {}", food, worker_id)
+        }
+        EventType::FoodImported { amount, cost } => {
+            format!("{} food imported for {}", amount, cost)
+        }
+        EventType::GlobalModifierActive {
+            resource,
+            multiplier,
+            active,
+        } => {
+            let state = if *active { "on" } else { "off" };
+            format!("{:?} x{} modifier {}", resource, multiplier, state)
+        }
+        EventType::SeasonalFactor { resource, factor } => {
+            format!("{:?} x{} seasonal", resource, factor)
+        }
+        EventType::ExternalPrice { resource, price } => {
+            format!("{:?} at {} externally", resource, price)
+        }
+        EventType::MarketSurplus {
+            realized,
+            potential,
+        } => format!("surplus {} of {}", realized, potential),
+        EventType::HouseCollapsed {
+            house_id,
+            total_houses,
+            ..
+        } => {
+            format!("House {} collapsed, {} left", house_id, total_houses)
+        }
+        EventType::RoundingRemainder { resource, amount } => {
+            format!("{:?} settlement adjusted by {}", resource, amount)
+        }
+        EventType::PriceLimitHit {
+            resource,
+            limit_price,
+            clamped_price,
+        } => {
+            format!(
+                "{:?} bid {} clamped to {}",
+                resource, limit_price, clamped_price
+            )
+        }
+        EventType::CashFlow {
+            production_value,
+            consumption_value,
+            trade_cash,
+            ..
+        } => {
+            format!(
+                "Produced {:.2}, consumed {:.2}, traded {:.2}",
+                production_value, consumption_value, trade_cash
+            )
+        }
+        EventType::OrderBlocked {
+            resource,
+            quantity,
+            price,
+            side,
+        } => {
+            format!("{:?} {} {:?} @ {} blocked", side, quantity, resource, price)
+        }
+        EventType::OrderKilled {
+            resource,
+            quantity,
+            price,
+            side,
+            ..
+        } => {
+            format!("{:?} {} {:?} @ {} killed", side, quantity, resource, price)
+        }
+        EventType::VillageBankrupt {
+            heir,
+            food,
+            wood,
+            money,
+            workers,
+        } => format!(
+            "{} workers lost, food={} wood={} money={} to {}",
+            workers, food, wood, money, heir
+        ),
+        EventType::ConstructionStalled { reason, progress } => {
+            format!("{:?} at progress {}", reason, progress)
+        }
+        EventType::MoneyAdjusted { amount, reason } => format!("{:?} {}", reason, amount),
     }
 }
 
@@ -275,6 +492,7 @@ pub fn export_to_csv(events: &[Event], output: &Path) -> Result<(), String> {
     for event in events {
         let type_name = match &event.event_type {
             EventType::WorkerAllocation { .. } => "WorkerAllocation",
+            EventType::AllocationIntent { .. } => "AllocationIntent",
             EventType::ResourceProduced { .. } => "ResourceProduced",
             EventType::ResourceConsumed { .. } => "ResourceConsumed",
             EventType::TradeExecuted { .. } => "TradeExecuted",
@@ -285,6 +503,25 @@ pub fn export_to_csv(events: &[Event], output: &Path) -> Result<(), String> {
             EventType::VillageStateSnapshot { .. } => "VillageStateSnapshot",
             EventType::HouseDecayed { .. } => "HouseDecayed",
             EventType::AuctionCleared { .. } => "AuctionCleared",
+            EventType::AgeCensus { .. } => "AgeCensus",
+            EventType::SlotAdded { .. } => "SlotAdded",
+            EventType::LaborWasted { .. } => "LaborWasted",
+            EventType::PriceControlBinding { .. } => "PriceControlBinding",
+            EventType::EstateInherited { .. } => "EstateInherited",
+            EventType::FoodImported { .. } => "FoodImported",
+            EventType::GlobalModifierActive { .. } => "GlobalModifierActive",
+            EventType::SeasonalFactor { .. } => "SeasonalFactor",
+            EventType::ExternalPrice { .. } => "ExternalPrice",
+            EventType::MarketSurplus { .. } => "MarketSurplus",
+            EventType::HouseCollapsed { .. } => "HouseCollapsed",
+            EventType::PriceLimitHit { .. } => "PriceLimitHit",
+            EventType::RoundingRemainder { .. } => "RoundingRemainder",
+            EventType::CashFlow { .. } => "CashFlow",
+            EventType::OrderBlocked { .. } => "OrderBlocked",
+            EventType::OrderKilled { .. } => "OrderKilled",
+            EventType::VillageBankrupt { .. } => "VillageBankrupt",
+            EventType::ConstructionStalled { .. } => "ConstructionStalled",
+            EventType::MoneyAdjusted { .. } => "MoneyAdjusted",
         };
 
         let details = format_event_details(&event.event_type);
@@ -299,6 +536,340 @@ pub fn export_to_csv(events: &[Event], output: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// A village's state as of its last snapshot in a run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FinalVillageState {
+    pub village_id: String,
+    pub population: usize,
+    pub food: Decimal,
+    pub wood: Decimal,
+    pub money: Decimal,
+    pub houses: usize,
+}
+
+impl From<&FinalVillageState> for VillageTotals {
+    fn from(state: &FinalVillageState) -> Self {
+        Self {
+            population: state.population,
+            houses: state.houses,
+            food: state.food,
+            wood: state.wood,
+            money: state.money,
+        }
+    }
+}
+
+/// Reconstruct each village's final state from its last `VillageStateSnapshot`.
+///
+/// Villages are returned sorted by id.
+pub fn final_village_states(events: &[Event]) -> Vec<FinalVillageState> {
+    let mut latest: std::collections::BTreeMap<&str, FinalVillageState> =
+        std::collections::BTreeMap::new();
+
+    for event in events {
+        if let EventType::VillageStateSnapshot {
+            population,
+            houses,
+            food,
+            wood,
+            money,
+        } = &event.event_type
+        {
+            latest.insert(
+                &event.village_id,
+                FinalVillageState {
+                    village_id: event.village_id.clone(),
+                    population: *population,
+                    food: *food,
+                    wood: *wood,
+                    money: *money,
+                    houses: *houses,
+                },
+            );
+        }
+    }
+
+    latest.into_values().collect()
+}
+
+/// Write final village states as JSON if `output` ends in `.json`, CSV otherwise.
+pub fn export_final_states(states: &[FinalVillageState], output: &Path) -> Result<(), String> {
+    let is_json = output
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+    let contents = if is_json {
+        serde_json::to_string_pretty(states)
+            .map_err(|e| format!("Failed to serialize summary: {}", e))?
+    } else {
+        format_final_states_csv(states)
+    };
+
+    fs::write(output, contents).map_err(|e| format!("Failed to write summary: {}", e))
+}
+
+/// Format final village states as CSV with a header row.
+pub fn format_final_states_csv(states: &[FinalVillageState]) -> String {
+    let mut csv = String::from("village_id,population,food,wood,money,houses\n");
+    for state in states {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            state.village_id, state.population, state.food, state.wood, state.money, state.houses
+        ));
+    }
+    csv
+}
+
+/// An inferred transfer of a resource from one village to another.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TradeFlow {
+    pub tick: usize,
+    pub resource: ResourceType,
+    pub buyer: String,
+    pub seller: String,
+    pub quantity: Decimal,
+    pub price: Decimal,
+}
+
+/// Infer who traded with whom from each village's `TradeExecuted` events.
+///
+/// Fills only record "market" as the counterparty, and with a uniform clearing
+/// price there is no true pairing, so one is allocated: within each tick and
+/// resource, every buyer's quantity is split across the sellers in proportion
+/// to each seller's share of the total sold. Rows are sorted by tick, resource,
+/// buyer and seller.
+pub fn reconstruct_trade_flows(events: &[Event]) -> Vec<TradeFlow> {
+    use std::collections::BTreeMap;
+
+    type Side = BTreeMap<String, Decimal>;
+    // (tick, resource name) -> (resource, buyers, sellers, price)
+    let mut markets: BTreeMap<(usize, &str), (ResourceType, Side, Side, Decimal)> = BTreeMap::new();
+
+    for event in events {
+        if let EventType::TradeExecuted {
+            resource,
+            quantity,
+            price,
+            side,
+            ..
+        } = &event.event_type
+        {
+            let (_, buyers, sellers, market_price) = markets
+                .entry((event.tick, resource.as_str()))
+                .or_insert_with(|| (*resource, Side::new(), Side::new(), *price));
+            *market_price = *price;
+            let traders = match side {
+                TradeSide::Buy => buyers,
+                TradeSide::Sell => sellers,
+            };
+            *traders.entry(event.village_id.clone()).or_default() += quantity;
+        }
+    }
+
+    let mut flows = Vec::new();
+    for ((tick, _), (resource, buyers, sellers, price)) in markets {
+        let total_sold: Decimal = sellers.values().sum();
+        if total_sold.is_zero() {
+            continue;
+        }
+        for (buyer, bought) in &buyers {
+            for (seller, sold) in &sellers {
+                let quantity = *bought * *sold / total_sold;
+                if quantity > Decimal::ZERO {
+                    flows.push(TradeFlow {
+                        tick,
+                        resource,
+                        buyer: buyer.clone(),
+                        seller: seller.clone(),
+                        quantity,
+                        price,
+                    });
+                }
+            }
+        }
+    }
+    flows
+}
+
+/// What `compact_events` keeps besides changed snapshots.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompactOptions {
+    /// Keep every snapshot on ticks that are a multiple of this, changed or
+    /// not, so state can be read at those ticks without scanning back
+    pub keyframe_interval: Option<usize>,
+}
+
+/// Drop `VillageStateSnapshot` and `WorkerAllocation` events identical to the
+/// same village's previous one.
+///
+/// Every dropped event repeats the last one kept, so forward-filling each
+/// village's latest snapshot and allocation reconstructs the full log's state
+/// at every tick. All other events are kept as they are.
+pub fn compact_events(events: &[Event], options: &CompactOptions) -> Vec<Event> {
+    use std::collections::HashMap;
+
+    // (village, is snapshot) -> last snapshot or allocation seen
+    let mut last: HashMap<(&str, bool), &EventType> = HashMap::new();
+    events
+        .iter()
+        .filter(|event| {
+            let is_snapshot = match event.event_type {
+                EventType::VillageStateSnapshot { .. } => true,
+                EventType::WorkerAllocation { .. } => false,
+                _ => return true,
+            };
+            let keyframe = options
+                .keyframe_interval
+                .is_some_and(|n| n > 0 && event.tick.is_multiple_of(n));
+            let previous = last.insert((&event.village_id, is_snapshot), &event.event_type);
+            keyframe || previous != Some(&event.event_type)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Format trade flows as CSV with a header row.
+pub fn format_trade_flows_csv(flows: &[TradeFlow]) -> String {
+    let mut csv = String::from("tick,resource,buyer,seller,quantity,price\n");
+    for flow in flows {
+        csv.push_str(&format!(
+            "{},{:?},{},{},{},{}\n",
+            flow.tick, flow.resource, flow.buyer, flow.seller, flow.quantity, flow.price
+        ));
+    }
+    csv
+}
+
+/// One village's activity over a fixed range of ticks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RollupRow {
+    /// First tick in the bucket
+    pub bucket_start: usize,
+    pub village_id: String,
+    /// Population at the bucket's last snapshot, carried forward from earlier
+    /// buckets when it has none
+    pub population: usize,
+    pub food_produced: Decimal,
+    pub wood_produced: Decimal,
+    /// Buy and sell fills the village took part in
+    pub trades: usize,
+    /// Volume-weighted prices of the village's trades; `None` without any
+    pub avg_food_price: Option<Decimal>,
+    pub avg_wood_price: Option<Decimal>,
+}
+
+/// Aggregate events into buckets of `bucket_size` ticks, one row per bucket
+/// and village, to downsample long runs.
+///
+/// Every village with a snapshot, production or trade gets a row in every
+/// bucket up to the last tick. Rows are sorted by bucket, then village id.
+pub fn rollup_events(events: &[Event], bucket_size: usize) -> Vec<RollupRow> {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    #[derive(Default)]
+    struct Bucket {
+        population: Option<usize>,
+        food_produced: Decimal,
+        wood_produced: Decimal,
+        trades: usize,
+        // resource -> (quantity, value)
+        traded: BTreeMap<&'static str, (Decimal, Decimal)>,
+    }
+
+    let bucket_size = bucket_size.max(1);
+    let mut buckets: BTreeMap<(usize, &str), Bucket> = BTreeMap::new();
+    let mut villages = BTreeSet::new();
+    let mut last_tick = 0;
+    for event in events {
+        last_tick = last_tick.max(event.tick);
+        let key = (event.tick / bucket_size, event.village_id.as_str());
+        match &event.event_type {
+            EventType::VillageStateSnapshot { population, .. } => {
+                buckets.entry(key).or_default().population = Some(*population);
+            }
+            EventType::ResourceProduced {
+                resource, amount, ..
+            } => {
+                let bucket = buckets.entry(key).or_default();
+                match resource {
+                    ResourceType::Food => bucket.food_produced += amount,
+                    ResourceType::Wood => bucket.wood_produced += amount,
+                    ResourceType::Labor => {}
+                }
+            }
+            EventType::TradeExecuted {
+                resource,
+                quantity,
+                price,
+                ..
+            } => {
+                let bucket = buckets.entry(key).or_default();
+                bucket.trades += 1;
+                let (volume, value) = bucket.traded.entry(resource.as_str()).or_default();
+                *volume += quantity;
+                *value += quantity * price;
+            }
+            _ => continue,
+        }
+        villages.insert(event.village_id.as_str());
+    }
+
+    let mut rows = Vec::new();
+    let mut population: BTreeMap<&str, usize> = BTreeMap::new();
+    for bucket_index in 0..=last_tick / bucket_size {
+        for &village_id in &villages {
+            let bucket = buckets
+                .remove(&(bucket_index, village_id))
+                .unwrap_or_default();
+            let village_population = population.entry(village_id).or_default();
+            if let Some(latest) = bucket.population {
+                *village_population = latest;
+            }
+            let avg_price = |resource: ResourceType| {
+                bucket
+                    .traded
+                    .get(resource.as_str())
+                    .filter(|(volume, _)| !volume.is_zero())
+                    .map(|(volume, value)| value / volume)
+            };
+            rows.push(RollupRow {
+                bucket_start: bucket_index * bucket_size,
+                village_id: village_id.to_string(),
+                population: *village_population,
+                food_produced: bucket.food_produced,
+                wood_produced: bucket.wood_produced,
+                trades: bucket.trades,
+                avg_food_price: avg_price(ResourceType::Food),
+                avg_wood_price: avg_price(ResourceType::Wood),
+            });
+        }
+    }
+    rows
+}
+
+/// Format rollup rows as CSV with a header row; missing prices are left blank.
+pub fn format_rollup_csv(rows: &[RollupRow]) -> String {
+    let price =
+        |price: Option<Decimal>| price.map(|p| p.round_dp(4).to_string()).unwrap_or_default();
+    let mut csv = String::from(
+        "bucket_start,village_id,population,food_produced,wood_produced,trades,avg_food_price,avg_wood_price\n",
+    );
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            row.bucket_start,
+            row.village_id,
+            row.population,
+            row.food_produced,
+            row.wood_produced,
+            row.trades,
+            price(row.avg_food_price),
+            price(row.avg_wood_price)
+        ));
+    }
+    csv
+}
+
 /// Generate a resource balance timeline.
 pub fn resource_timeline(
     events: &[crate::events::Event],
@@ -330,12 +901,14 @@ pub fn resource_timeline(
             } => match resource {
                 crate::events::ResourceType::Food => food_balance += amount,
                 crate::events::ResourceType::Wood => wood_balance += amount,
+                crate::events::ResourceType::Labor => {}
             },
             EventType::ResourceConsumed {
                 resource, amount, ..
             } => match resource {
                 crate::events::ResourceType::Food => food_balance -= amount,
                 crate::events::ResourceType::Wood => wood_balance -= amount,
+                crate::events::ResourceType::Labor => {}
             },
             EventType::TradeExecuted {
                 resource,
@@ -349,6 +922,7 @@ pub fn resource_timeline(
                     (crate::events::ResourceType::Food, TradeSide::Sell) => food_balance -= qty,
                     (crate::events::ResourceType::Wood, TradeSide::Buy) => wood_balance += qty,
                     (crate::events::ResourceType::Wood, TradeSide::Sell) => wood_balance -= qty,
+                    (crate::events::ResourceType::Labor, _) => {}
                 }
             }
             _ => {}
@@ -454,3 +1028,151 @@ fn interpolate_value(history: &[(usize, Decimal)], tick: usize) -> Decimal {
         (None, None) => Decimal::ZERO,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventLogger;
+    use rust_decimal_macros::dec;
+
+    fn snapshot(population: usize, houses: usize, food: Decimal, wood: Decimal) -> EventType {
+        EventType::VillageStateSnapshot {
+            population,
+            houses,
+            food,
+            wood,
+            money: dec!(100),
+        }
+    }
+
+    #[test]
+    fn test_final_village_states_uses_last_snapshot() {
+        let mut logger = EventLogger::new();
+        logger.log(0, "b".to_string(), snapshot(10, 2, dec!(50), dec!(20)));
+        logger.log(0, "a".to_string(), snapshot(8, 1, dec!(30), dec!(10)));
+        logger.log(
+            1,
+            "market".to_string(),
+            EventType::AuctionCleared {
+                wood_price: None,
+                food_price: None,
+                wood_volume: 0,
+                food_volume: 0,
+                total_participants: 2,
+            },
+        );
+        logger.log(1, "b".to_string(), snapshot(11, 3, dec!(45.5), dec!(12)));
+        logger.log(1, "a".to_string(), snapshot(7, 1, dec!(28), dec!(9.5)));
+
+        let states = final_village_states(logger.get_events());
+
+        assert_eq!(
+            states,
+            vec![
+                FinalVillageState {
+                    village_id: "a".to_string(),
+                    population: 7,
+                    food: dec!(28),
+                    wood: dec!(9.5),
+                    money: dec!(100),
+                    houses: 1,
+                },
+                FinalVillageState {
+                    village_id: "b".to_string(),
+                    population: 11,
+                    food: dec!(45.5),
+                    wood: dec!(12),
+                    money: dec!(100),
+                    houses: 3,
+                },
+            ]
+        );
+        assert_eq!(
+            format_final_states_csv(&states),
+            "village_id,population,food,wood,money,houses\na,7,28,9.5,100,1\nb,11,45.5,12,100,3\n"
+        );
+    }
+
+    fn trade(side: TradeSide, quantity: Decimal) -> EventType {
+        EventType::TradeExecuted {
+            resource: ResourceType::Wood,
+            quantity,
+            price: dec!(2),
+            counterparty: "market".to_string(),
+            side,
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_trade_flows_splits_buyer_across_sellers() {
+        let mut logger = EventLogger::new();
+        logger.log(3, "buyer".to_string(), trade(TradeSide::Buy, dec!(10)));
+        logger.log(3, "seller_a".to_string(), trade(TradeSide::Sell, dec!(6)));
+        logger.log(3, "seller_b".to_string(), trade(TradeSide::Sell, dec!(4)));
+
+        let flows = reconstruct_trade_flows(logger.get_events());
+
+        assert_eq!(flows.len(), 2);
+        assert_eq!(
+            format_trade_flows_csv(&flows),
+            "tick,resource,buyer,seller,quantity,price\n\
+             3,Wood,buyer,seller_a,6,2\n\
+             3,Wood,buyer,seller_b,4,2\n"
+        );
+    }
+
+    #[test]
+    fn test_rollup_buckets_a_100_tick_log_into_two_rows_per_village() {
+        let mut logger = EventLogger::new();
+        for tick in 0..100 {
+            logger.log(
+                tick,
+                "a".to_string(),
+                snapshot(10 + tick / 10, 2, dec!(50), dec!(20)),
+            );
+            logger.log(
+                tick,
+                "a".to_string(),
+                EventType::ResourceProduced {
+                    resource: ResourceType::Food,
+                    amount: dec!(2),
+                    workers_assigned: 2,
+                },
+            );
+        }
+        let trade = |quantity, price| EventType::TradeExecuted {
+            resource: ResourceType::Wood,
+            quantity,
+            price,
+            counterparty: "market".to_string(),
+            side: TradeSide::Buy,
+        };
+        logger.log(10, "a".to_string(), trade(dec!(1), dec!(2)));
+        logger.log(20, "a".to_string(), trade(dec!(3), dec!(4)));
+        logger.log(
+            60,
+            "market".to_string(),
+            EventType::AuctionCleared {
+                wood_price: Some(dec!(4)),
+                food_price: None,
+                wood_volume: 3,
+                food_volume: 0,
+                total_participants: 1,
+            },
+        );
+
+        let rows = rollup_events(logger.get_events(), 50);
+        assert_eq!(rows.len(), 2, "The market's own events get no row");
+        let (first, second) = (&rows[0], &rows[1]);
+        assert_eq!((first.bucket_start, second.bucket_start), (0, 50));
+        // Population at each bucket's last tick: 49 and 99
+        assert_eq!((first.population, second.population), (14, 19));
+        assert_eq!(first.food_produced, dec!(100));
+        assert_eq!(second.food_produced, dec!(100));
+        assert_eq!((first.trades, second.trades), (2, 0));
+        // (1 × 2 + 3 × 4) / 4
+        assert_eq!(first.avg_wood_price, Some(dec!(3.5)));
+        assert_eq!(second.avg_wood_price, None);
+        assert_eq!(first.avg_food_price, None);
+    }
+}
@@ -1,7 +1,12 @@
+use crate::events::ResourceType;
+use crate::strategies::{DefaultPrices, OrderSizing};
 use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Scenario {
@@ -26,6 +31,580 @@ pub struct SimulationParameters {
     pub base_food_production: Decimal,
     pub base_wood_production: Decimal,
     pub second_slot_productivity: f64,
+    /// Wood price strategies assume before wood has ever cleared
+    #[serde(default = "default_wood_price")]
+    pub default_wood_price: Decimal,
+    /// Food price strategies assume before food has ever cleared
+    #[serde(default = "default_food_price")]
+    pub default_food_price: Decimal,
+    /// Highest maintenance level a house can be upgraded to; each whole level
+    /// adds 1 capacity (0 disables upgrades)
+    #[serde(default)]
+    pub max_house_upgrade_level: u32,
+    /// Wood needed to raise a house's maintenance level by 1 above zero
+    #[serde(default = "default_house_upgrade_wood")]
+    pub house_upgrade_wood: Decimal,
+    /// Conversions villages can run with worker-days allocated to processing
+    #[serde(default)]
+    pub recipes: Vec<Recipe>,
+    /// How tools (built by recipes) raise worker productivity and wear out;
+    /// tools do nothing when unset
+    #[serde(default)]
+    pub tools: Option<ToolProductivity>,
+    /// Worker-days of slot expansion needed to add one production slot
+    #[serde(default = "default_slot_expansion_days")]
+    pub slot_expansion_days: Decimal,
+    /// Wood consumed when a slot expansion completes
+    #[serde(default = "default_slot_expansion_wood")]
+    pub slot_expansion_wood: Decimal,
+    /// Shelter units each worker needs (below 1 models crowding, above 1 spacious housing)
+    #[serde(default = "default_shelter_per_worker")]
+    pub shelter_per_worker: Decimal,
+    /// Ticks making up one day. Food, maintenance, decay and labor are
+    /// scaled per tick, while day counters (age, starvation, exposure,
+    /// growth) only advance once a full day has passed
+    #[serde(default = "default_ticks_per_day")]
+    pub ticks_per_day: u32,
+    /// Auctions run each tick; strategies re-quote through `on_trade_result`
+    /// before every round after the first, while market agents only carry
+    /// over their unfilled orders
+    #[serde(default = "default_rounds_per_tick")]
+    pub rounds_per_tick: u32,
+    /// Lowest balance a village's money may reach when trades are applied
+    #[serde(default)]
+    pub money_floor: Decimal,
+    /// Turn the market off: strategies still run but their orders are
+    /// discarded, so villages live on their own production
+    #[serde(default)]
+    pub autarky: bool,
+    /// Let villages trade workers as `Labor`; otherwise labor orders are dropped
+    #[serde(default)]
+    pub labor_market: bool,
+    /// Age-driven mortality; workers never die of old age when unset
+    #[serde(default)]
+    pub old_age: Option<OldAgeMortality>,
+    /// Removes villages that stay insolvent; villages never go bankrupt when unset
+    #[serde(default)]
+    pub bankruptcy: Option<BankruptcyRule>,
+    /// Scales the birth chance up when a village holds large food and wood buffers
+    #[serde(default)]
+    pub prosperity_bonus: ProsperityBonus,
+    /// Only roll for births while the houses have room for another worker
+    #[serde(default)]
+    pub require_housing_for_birth: bool,
+    /// Roll for births once per pair of eligible workers instead of once per
+    /// worker; a birth resets both partners' counters
+    #[serde(default)]
+    pub require_pairing: bool,
+    /// Days knocked off `days_without_food` / `days_without_shelter` by each
+    /// fed or sheltered day; both reset to 0 at once when unset
+    #[serde(default)]
+    pub recovery_rate: Option<u32>,
+    /// Days a day without food or shelter takes off a worker's
+    /// `days_with_both`; unset, such a day resets it to 0
+    #[serde(default)]
+    pub reproduction_setback: Option<u32>,
+    /// Share the food evenly when it can't give everyone a full ration; a
+    /// worker on half a ration gains half a day of `days_without_food`
+    #[serde(default)]
+    pub partial_rations: bool,
+    /// Fraction (0-1) of food kept in the granary, released only when the
+    /// remaining food can't feed every worker
+    #[serde(default)]
+    pub granary_fraction: Decimal,
+    /// Imports whatever food workers lack before they eat; off when unset
+    #[serde(default)]
+    pub food_backstop: Option<FoodBackstop>,
+    /// Wood spent burying each worker who dies (capped at the wood on hand)
+    #[serde(default)]
+    pub death_cost_wood: Decimal,
+    /// Food each dead worker leaves to the village
+    #[serde(default)]
+    pub estate_food: Decimal,
+    /// Whether villages eat, build and age before or after the tick's auction
+    #[serde(default)]
+    pub phase_order: PhaseOrder,
+    /// Ticks before produced wood and food can be eaten, used or traded
+    #[serde(default)]
+    pub production_lag: usize,
+    /// Maintenance level below which a decaying house collapses and is removed
+    #[serde(default)]
+    pub collapse_threshold: Option<Decimal>,
+    /// Decimal places trade values are settled to (unrounded if unset)
+    #[serde(default)]
+    pub settlement_rounding: Option<u32>,
+    /// How food output falls off as more worker-days are applied
+    #[serde(default)]
+    pub food_production_curve: ProductionCurve,
+    /// How wood output falls off as more worker-days are applied
+    #[serde(default)]
+    pub wood_production_curve: ProductionCurve,
+    /// How a village's worker-days fall off as its workforce grows
+    #[serde(default)]
+    pub labor_efficiency: LaborEfficiency,
+    /// Clearing prices the run starts from, as if each resource had already
+    /// cleared at that price before the first tick
+    #[serde(default)]
+    pub initial_prices: HashMap<ResourceType, Decimal>,
+    /// Highest price each resource may clear at; demand is rationed when it binds
+    #[serde(default)]
+    pub price_ceilings: HashMap<ResourceType, Decimal>,
+    /// Lowest price each resource may clear at; supply is rationed when it binds
+    #[serde(default)]
+    pub price_floors: HashMap<ResourceType, Decimal>,
+    /// Most units of each resource that may trade per tick across all villages
+    #[serde(default)]
+    pub max_market_volume: HashMap<ResourceType, u64>,
+    /// Highest multiple of the last clearing price a bid may be placed at
+    #[serde(default)]
+    pub max_price_move: Option<Decimal>,
+    /// Outside prices a resource with only buyers or only sellers clears
+    /// against, with the outside market taking the other side
+    #[serde(default)]
+    pub oracle_prices: HashMap<ResourceType, Decimal>,
+    /// How far over budget a village's purchases may run before the auction
+    /// prunes them. The overrun stays on the books as debt, taking money up to
+    /// this far below `money_floor`, and later auctions budget against it
+    #[serde(default)]
+    pub budget_tolerance: Decimal,
+    /// Money charged per order submitted to the auction, filled or not.
+    /// Only the first round of a tick charges it, so orders resting into
+    /// later rounds aren't charged again
+    #[serde(default)]
+    pub listing_fee: Decimal,
+    /// Settle for a best-effort auction when it can't converge in its
+    /// iteration budget, rather than cancelling the tick's trades
+    #[serde(default)]
+    pub degrade_on_max_iterations: bool,
+    /// Drop bids from villages with no money before the auction starts,
+    /// except those their own asks pay for
+    #[serde(default)]
+    pub skip_insolvent_bids: bool,
+    /// Priority of each village's orders among orders at the same price;
+    /// villages left out have priority 0
+    #[serde(default)]
+    pub participant_priority: HashMap<String, i64>,
+    /// Share the volume at the marginal price and priority in proportion to
+    /// order size, instead of filling earlier orders first
+    #[serde(default)]
+    pub pro_rata_allocation: bool,
+    /// Panic on a strategy allocation that doesn't sum to the village's
+    /// worker-days instead of rescaling it
+    #[serde(default)]
+    pub strict_allocations: bool,
+    /// Log each strategy's allocation as it returned it, every tick
+    #[serde(default)]
+    pub log_allocation_intent: bool,
+    /// Check that settling each auction conserves money, food and wood, even
+    /// in release builds (debug builds always check)
+    #[serde(default)]
+    pub verify_conservation: bool,
+    /// Production multipliers applied to every village over a range of ticks,
+    /// e.g. a drought
+    #[serde(default)]
+    pub global_modifiers: Vec<Modifier>,
+    /// Repeating good-year/bad-year production cycles
+    #[serde(default)]
+    pub seasonality: Vec<Seasonality>,
+    /// Resources villages are barred from trading; their orders never reach the auction
+    #[serde(default)]
+    pub embargoes: Vec<Embargo>,
+    /// An infinitely deep "rest of world" market per resource, quoting
+    /// around a randomly walking price
+    #[serde(default)]
+    pub external_market: HashMap<ResourceType, ExternalMarket>,
+}
+
+impl SimulationParameters {
+    /// Combined multiplier of the global modifiers active for `resource` at `tick`.
+    pub fn production_multiplier(&self, resource: ResourceType, tick: usize) -> Decimal {
+        self.global_modifiers
+            .iter()
+            .filter(|m| m.resource == resource && m.is_active(tick))
+            .map(|m| m.multiplier)
+            .product()
+    }
+
+    /// Combined factor of the seasonal cycles applying to `village`'s
+    /// `resource` at `tick`.
+    pub fn seasonal_factor(&self, village: &str, resource: ResourceType, tick: usize) -> Decimal {
+        self.seasonality
+            .iter()
+            .filter(|s| s.resource == resource && s.applies_to(village))
+            .map(|s| s.factor(tick))
+            .product()
+    }
+}
+
+/// Shape of a seasonal production cycle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum SeasonalShape {
+    /// `1 + amplitude * sin(2π·tick/period)`
+    #[default]
+    Sine,
+    /// `1 + amplitude` for the first half of each period, `1 - amplitude` for the second
+    Square,
+}
+
+/// A production cycle for one resource, repeating every `period` ticks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Seasonality {
+    pub period: usize,
+    pub amplitude: Decimal,
+    pub resource: ResourceType,
+    #[serde(default)]
+    pub shape: SeasonalShape,
+    /// Village the cycle applies to; every village when unset
+    #[serde(default)]
+    pub village: Option<String>,
+}
+
+impl Seasonality {
+    pub fn applies_to(&self, village: &str) -> bool {
+        self.village.as_deref().is_none_or(|v| v == village)
+    }
+
+    /// Production multiplier at `tick`, rounded to 6 decimal places. A cycle
+    /// with no period, which validation rejects, has no effect.
+    pub fn factor(&self, tick: usize) -> Decimal {
+        if self.period == 0 {
+            return Decimal::ONE;
+        }
+        let phase = (tick % self.period) as f64 / self.period as f64;
+        let wave = match self.shape {
+            SeasonalShape::Sine => (2.0 * std::f64::consts::PI * phase).sin(),
+            SeasonalShape::Square if phase < 0.5 => 1.0,
+            SeasonalShape::Square => -1.0,
+        };
+        let wave = Decimal::from_f64(wave).unwrap_or(Decimal::ZERO).round_dp(6);
+        Decimal::ONE + self.amplitude * wave
+    }
+}
+
+/// A production multiplier for one resource, active from `start_tick` up to
+/// but not including `end_tick`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Modifier {
+    pub start_tick: usize,
+    pub end_tick: usize,
+    pub resource: ResourceType,
+    pub multiplier: Decimal,
+}
+
+impl Modifier {
+    pub fn is_active(&self, tick: usize) -> bool {
+        (self.start_tick..self.end_tick).contains(&tick)
+    }
+}
+
+/// Bars one village from trading one resource.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Embargo {
+    pub village: String,
+    pub resource: ResourceType,
+}
+
+/// A "rest of world" market for one resource.
+///
+/// Each tick it bids `spread / 2` below and asks `spread / 2` above its
+/// price for effectively unlimited quantities. After the first tick the
+/// price takes a seeded random-walk step of up to `volatility` of itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalMarket {
+    pub initial_price: Decimal,
+    pub volatility: Decimal,
+    /// Gap between the external bid and ask as a fraction of the price
+    #[serde(default = "default_external_spread")]
+    pub spread: Decimal,
+}
+
+fn default_external_spread() -> Decimal {
+    dec!(0.1)
+}
+
+impl ExternalMarket {
+    /// Lowest price the walk can reach.
+    pub const MIN_PRICE: Decimal = dec!(0.01);
+
+    /// The price after one step from `price`, for a `shock` drawn from [-1, 1].
+    pub fn next_price(&self, price: Decimal, shock: f64) -> Decimal {
+        let shock = Decimal::from_f64(shock).unwrap_or(Decimal::ZERO);
+        (price * (Decimal::ONE + self.volatility * shock))
+            .round_dp(4)
+            .max(Self::MIN_PRICE)
+    }
+
+    /// The external bid and ask around `price`.
+    pub fn quotes(&self, price: Decimal) -> (Decimal, Decimal) {
+        let half_spread = self.spread / Decimal::TWO;
+        (
+            (price * (Decimal::ONE - half_spread)).round_dp(4),
+            (price * (Decimal::ONE + half_spread)).round_dp(4),
+        )
+    }
+}
+
+/// Order of the village update and the auction within a tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PhaseOrder {
+    /// Villages produce and consume, then trade what they have left
+    #[default]
+    ConsumeFirst,
+    /// Villages trade first, so food bought this tick can be eaten this tick
+    TradeFirst,
+}
+
+/// Birth-chance multiplier for villages with resource buffers beyond what they need.
+///
+/// The factor is `1 + bonus_per_day * excess`, where `excess` is the smaller of
+/// the food and wood buffers (in days) beyond their thresholds, capped at
+/// `max_factor`. The default leaves the factor at 1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProsperityBonus {
+    /// Days of food per worker a village must hold before the bonus starts
+    pub food_days_threshold: Decimal,
+    /// Days of house upkeep in wood a village must hold before the bonus starts
+    pub wood_days_threshold: Decimal,
+    /// Added to the factor for each day of buffer beyond both thresholds
+    pub bonus_per_day: f64,
+    pub max_factor: f64,
+}
+
+impl Default for ProsperityBonus {
+    fn default() -> Self {
+        Self {
+            food_days_threshold: Decimal::ZERO,
+            wood_days_threshold: Decimal::ZERO,
+            bonus_per_day: 0.0,
+            max_factor: 1.0,
+        }
+    }
+}
+
+impl ProsperityBonus {
+    /// Birth-chance multiplier for the given food and wood buffers (in days).
+    pub fn factor(&self, food_days: Decimal, wood_days: Decimal) -> f64 {
+        let excess = (food_days - self.food_days_threshold)
+            .min(wood_days - self.wood_days_threshold)
+            .max(Decimal::ZERO)
+            .to_f64()
+            .unwrap_or(0.0);
+        (1.0 + self.bonus_per_day * excess).clamp(1.0, self.max_factor.max(1.0))
+    }
+}
+
+/// Old-age mortality, rolled once per day for each worker.
+///
+/// A worker's daily chance of dying rises linearly from 0 at `onset_age` to
+/// certainty at `max_age`, so nobody lives past `max_age` days.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OldAgeMortality {
+    /// Age in days from which the death chance starts rising
+    pub onset_age: u32,
+    /// Age in days at which a worker is certain to die
+    pub max_age: u32,
+}
+
+impl OldAgeMortality {
+    /// Chance a worker of `age` days dies of old age today.
+    pub fn death_chance(&self, age: u32) -> f64 {
+        if age >= self.max_age {
+            1.0
+        } else if age <= self.onset_age {
+            0.0
+        } else {
+            f64::from(age - self.onset_age) / f64::from(self.max_age - self.onset_age)
+        }
+    }
+}
+
+/// Emergency food imports that keep workers from going hungry.
+///
+/// Before workers eat, any shortfall in the food (and granary) needed to feed
+/// everyone this tick is bought from outside the economy at `price` per unit,
+/// as far as the village's money allows. A price of 0 makes the food free.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FoodBackstop {
+    #[serde(default)]
+    pub price: Decimal,
+}
+
+/// When an insolvent village is wound up.
+///
+/// A village with no money whose food covers fewer than
+/// `food_days_threshold` days is insolvent; after `grace_ticks` insolvent
+/// ticks in a row it is removed and its stock goes to `heir`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BankruptcyRule {
+    pub food_days_threshold: Decimal,
+    pub grace_ticks: usize,
+    /// Village inheriting the food, wood and money left; without one (or once
+    /// it is gone itself) they go to the market and leave the economy
+    #[serde(default)]
+    pub heir: Option<String>,
+}
+
+/// Diminishing-returns curve for turning worker-days into output.
+///
+/// Every curve stops at the village's slot capacity (full plus partial
+/// slots); labor beyond it produces nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ProductionCurve {
+    /// Full output from the full slots, half from the partial slots
+    #[default]
+    Step,
+    /// The i-th worker-day yields `base * ratio^i`
+    ExponentialDecay { ratio: f64 },
+    /// Cobb-Douglas-like `base * worker_days^alpha`
+    PowerLaw { alpha: f64 },
+}
+
+impl ProductionCurve {
+    /// Output when every full and partial slot is worked, at `units_per_slot`
+    /// per full-productivity worker-day.
+    pub fn max_output(self, slots: (u32, u32), units_per_slot: Decimal) -> Decimal {
+        let worker_days = f64::from(slots.0 + slots.1);
+        let output = match self {
+            ProductionCurve::Step => {
+                return (Decimal::from(slots.0) + Decimal::from(slots.1) * dec!(0.5))
+                    * units_per_slot;
+            }
+            ProductionCurve::ExponentialDecay { ratio } if ratio != 1.0 => {
+                (1.0 - ratio.powf(worker_days)) / (1.0 - ratio)
+            }
+            ProductionCurve::ExponentialDecay { .. } => worker_days,
+            ProductionCurve::PowerLaw { alpha } => worker_days.powf(alpha),
+        };
+        Decimal::from_f64(output).unwrap_or(Decimal::ZERO) * units_per_slot
+    }
+}
+
+/// Aggregate returns to labor: fatigue and coordination costs that make a
+/// large workforce worth less than the sum of its workers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum LaborEfficiency {
+    /// Every worker-day counts in full
+    #[default]
+    Linear,
+    /// `raw^alpha` effective worker-days, with `alpha` in (0, 1]. Up to one
+    /// worker-day counts in full, since a root would inflate fractions of a day
+    PowerLaw { alpha: f64 },
+    /// `raw * (1 - congestion)` effective worker-days
+    Congestion { congestion: Decimal },
+}
+
+impl LaborEfficiency {
+    /// Effective worker-days from `raw` worker-days, to 6 decimal places.
+    pub fn effective(self, raw: Decimal) -> Decimal {
+        match self {
+            LaborEfficiency::Linear => raw,
+            LaborEfficiency::PowerLaw { .. } if raw <= Decimal::ONE => raw,
+            LaborEfficiency::PowerLaw { alpha } => {
+                let raw = raw.to_f64().unwrap_or(0.0);
+                Decimal::from_f64(raw.powf(alpha))
+                    .unwrap_or(Decimal::ZERO)
+                    .round_dp(6)
+            }
+            LaborEfficiency::Congestion { congestion } => raw * (Decimal::ONE - congestion),
+        }
+    }
+}
+
+/// A good that recipes can consume or produce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Good {
+    Food,
+    Wood,
+    Money,
+    /// Capital goods that raise productivity under `tools`; never traded
+    Tools,
+}
+
+impl Good {
+    /// The tradable resource this good corresponds to, if any
+    pub fn resource(self) -> Option<ResourceType> {
+        match self {
+            Good::Food => Some(ResourceType::Food),
+            Good::Wood => Some(ResourceType::Wood),
+            Good::Money | Good::Tools => None,
+        }
+    }
+}
+
+/// Productivity from a village's stock of tools.
+///
+/// Each tool per worker adds `boost_per_tool` to food and wood output, up
+/// to `max_multiplier` in total. Tools lose `depreciation_rate` of their
+/// stock every day.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ToolProductivity {
+    pub boost_per_tool: Decimal,
+    pub max_multiplier: Decimal,
+    pub depreciation_rate: Decimal,
+}
+
+impl ToolProductivity {
+    /// Output multiplier for `tools` shared among `workers`.
+    pub fn multiplier(&self, tools: Decimal, workers: usize) -> Decimal {
+        if workers == 0 {
+            return Decimal::ONE;
+        }
+        let per_worker = tools / Decimal::from(workers);
+        (Decimal::ONE + self.boost_per_tool * per_worker).min(self.max_multiplier)
+    }
+}
+
+/// An amount of a good per processing worker-day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecipeAmount {
+    pub good: Good,
+    pub amount: Decimal,
+}
+
+/// Converts input goods into output goods, scaled by the worker-days allocated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recipe {
+    pub name: String,
+    pub inputs: Vec<RecipeAmount>,
+    pub outputs: Vec<RecipeAmount>,
+}
+
+fn default_wood_price() -> Decimal {
+    DefaultPrices::default().wood
+}
+
+fn default_food_price() -> Decimal {
+    DefaultPrices::default().food
+}
+
+fn default_quality() -> Decimal {
+    Decimal::ONE
+}
+
+fn default_house_upgrade_wood() -> Decimal {
+    Decimal::from(5)
+}
+
+fn default_slot_expansion_days() -> Decimal {
+    Decimal::from(100)
+}
+
+fn default_slot_expansion_wood() -> Decimal {
+    Decimal::from(20)
+}
+
+fn default_shelter_per_worker() -> Decimal {
+    Decimal::ONE
+}
+
+fn default_ticks_per_day() -> u32 {
+    1
+}
+
+fn default_rounds_per_tick() -> u32 {
+    1
 }
 
 impl Default for SimulationParameters {
@@ -34,7 +613,7 @@ impl Default for SimulationParameters {
             days_to_simulate: 100,
             days_without_food_before_starvation: 10,
             days_without_shelter_before_death: 30,
-            days_before_growth_chance: 50,  // Changed from 100 to allow births before simulation ends
+            days_before_growth_chance: 50, // Changed from 100 to allow births before simulation ends
             growth_chance_per_day: 0.05,
             house_construction_days: 60,
             house_construction_wood: Decimal::from(10),
@@ -43,6 +622,58 @@ impl Default for SimulationParameters {
             base_food_production: Decimal::from(1),
             base_wood_production: Decimal::from(1),
             second_slot_productivity: 0.75,
+            default_wood_price: default_wood_price(),
+            default_food_price: default_food_price(),
+            max_house_upgrade_level: 0,
+            house_upgrade_wood: default_house_upgrade_wood(),
+            recipes: Vec::new(),
+            tools: None,
+            slot_expansion_days: default_slot_expansion_days(),
+            slot_expansion_wood: default_slot_expansion_wood(),
+            shelter_per_worker: default_shelter_per_worker(),
+            ticks_per_day: default_ticks_per_day(),
+            rounds_per_tick: default_rounds_per_tick(),
+            money_floor: Decimal::ZERO,
+            autarky: false,
+            labor_market: false,
+            old_age: None,
+            bankruptcy: None,
+            prosperity_bonus: ProsperityBonus::default(),
+            require_housing_for_birth: false,
+            require_pairing: false,
+            recovery_rate: None,
+            reproduction_setback: None,
+            partial_rations: false,
+            granary_fraction: Decimal::ZERO,
+            food_backstop: None,
+            death_cost_wood: Decimal::ZERO,
+            estate_food: Decimal::ZERO,
+            phase_order: PhaseOrder::default(),
+            production_lag: 0,
+            collapse_threshold: None,
+            settlement_rounding: None,
+            food_production_curve: ProductionCurve::default(),
+            wood_production_curve: ProductionCurve::default(),
+            labor_efficiency: LaborEfficiency::default(),
+            initial_prices: HashMap::new(),
+            price_ceilings: HashMap::new(),
+            price_floors: HashMap::new(),
+            max_market_volume: HashMap::new(),
+            max_price_move: None,
+            oracle_prices: HashMap::new(),
+            budget_tolerance: Decimal::ZERO,
+            listing_fee: Decimal::ZERO,
+            degrade_on_max_iterations: false,
+            skip_insolvent_bids: false,
+            participant_priority: HashMap::new(),
+            pro_rata_allocation: false,
+            strict_allocations: false,
+            log_allocation_intent: false,
+            verify_conservation: false,
+            global_modifiers: Vec::new(),
+            seasonality: Vec::new(),
+            embargoes: Vec::new(),
+            external_market: HashMap::new(),
         }
     }
 }
@@ -58,6 +689,48 @@ pub struct VillageConfig {
     pub food_slots: (usize, usize),
     pub wood_slots: (usize, usize),
     pub strategy: StrategyConfig,
+    /// Non-living trader: no workers, production or lifecycle, it only posts
+    /// `agent_orders` each tick from its fixed initial inventory
+    #[serde(default)]
+    pub is_market_agent: bool,
+    #[serde(default)]
+    pub agent_orders: Vec<AgentOrder>,
+    /// Counters every initial worker starts with, e.g. to begin mid-famine
+    #[serde(default)]
+    pub initial_worker_state: InitialWorkerState,
+    /// Worker-days already spent on the next house, out of the 60 it needs
+    #[serde(default)]
+    pub initial_construction_progress: Decimal,
+    /// Land quality: multiplies everything the village's food slots produce
+    #[serde(default = "default_quality")]
+    pub food_quality: Decimal,
+    /// Land quality: multiplies everything the village's wood slots produce
+    #[serde(default = "default_quality")]
+    pub wood_quality: Decimal,
+}
+
+/// Starting lifecycle counters for a village's initial workers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InitialWorkerState {
+    pub days_without_food: u32,
+    pub days_without_shelter: u32,
+    pub days_with_both: u32,
+}
+
+/// An order a market agent posts every tick.
+///
+/// Asks are capped at the agent's remaining stock, so a fixed inventory
+/// runs dry once it has all been sold.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentOrder {
+    pub resource: ResourceType,
+    pub is_buy: bool,
+    pub quantity: u32,
+    pub price: Decimal,
+    /// Show only this much of the order to each auction round, as an iceberg
+    #[serde(default)]
+    pub visible_quantity: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,18 +741,39 @@ pub enum StrategyConfig {
         wood_weight: f64,
         construction_weight: f64,
         repair_weight: f64,
+        /// Order sizes; `None` keeps the strategy's own defaults
+        #[serde(default)]
+        sizing: Option<OrderSizing>,
     },
     Survival {
         min_food_days: usize,
         min_shelter_buffer: usize,
+        #[serde(default)]
+        sizing: Option<OrderSizing>,
     },
     Growth {
         target_population: usize,
         house_buffer: usize,
+        #[serde(default)]
+        sizing: Option<OrderSizing>,
     },
     Trading {
         price_multiplier: f64,
         max_trade_fraction: f64,
+        /// Replaces `max_trade_fraction` entirely when given
+        #[serde(default)]
+        sizing: Option<OrderSizing>,
+    },
+    Greedy {
+        #[serde(default)]
+        sizing: Option<OrderSizing>,
+    },
+    Undercutting {
+        undercut: Decimal,
+        buffer_days: u32,
+        /// Sizes both the bids and the undercutting asks
+        #[serde(default)]
+        sizing: Option<OrderSizing>,
     },
 }
 
@@ -90,10 +784,206 @@ impl Default for StrategyConfig {
             wood_weight: 0.25,
             construction_weight: 0.25,
             repair_weight: 0.25,
+            sizing: None,
         }
     }
 }
 
+/// Errors from loading or validating a scenario.
+#[derive(Debug)]
+pub enum SimError {
+    ScenarioRead {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    ScenarioParse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    UnknownScenario {
+        name: String,
+        available: Vec<String>,
+    },
+    Validation(String),
+    WarmStartRead {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl fmt::Display for SimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimError::ScenarioRead { path, source } => {
+                write!(
+                    f,
+                    "Error reading scenario file {}: {}",
+                    path.display(),
+                    source
+                )
+            }
+            SimError::ScenarioParse { path, source } => {
+                write!(
+                    f,
+                    "Error parsing scenario file {}: {}",
+                    path.display(),
+                    source
+                )
+            }
+            SimError::UnknownScenario { name, available } => {
+                write!(
+                    f,
+                    "Unknown scenario: {}. Available scenarios: {}",
+                    name,
+                    available.join(", ")
+                )
+            }
+            SimError::Validation(msg) => write!(f, "Invalid scenario: {}", msg),
+            SimError::WarmStartRead { path, source } => {
+                write!(
+                    f,
+                    "Error reading warm-start events {}: {}",
+                    path.display(),
+                    source
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SimError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SimError::ScenarioRead { source, .. } => Some(source),
+            SimError::ScenarioParse { source, .. } => Some(source),
+            SimError::WarmStartRead { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Load a scenario from `file` if given, otherwise the built-in scenario `name`.
+pub fn load_scenario(file: Option<&Path>, name: &str) -> Result<Scenario, SimError> {
+    match file {
+        Some(path) => {
+            let contents =
+                std::fs::read_to_string(path).map_err(|source| SimError::ScenarioRead {
+                    path: path.to_path_buf(),
+                    source,
+                })?;
+            serde_json::from_str(&contents).map_err(|source| SimError::ScenarioParse {
+                path: path.to_path_buf(),
+                source,
+            })
+        }
+        None => {
+            let mut scenarios = create_standard_scenarios();
+            scenarios.remove(name).ok_or_else(|| {
+                let mut available: Vec<String> = scenarios.into_keys().collect();
+                available.sort();
+                SimError::UnknownScenario {
+                    name: name.to_string(),
+                    available,
+                }
+            })
+        }
+    }
+}
+
+/// Load every scenario in `file`, which may hold one scenario object or an
+/// array of them, otherwise the built-in scenario `name`.
+pub fn load_scenarios(file: Option<&Path>, name: &str) -> Result<Vec<Scenario>, SimError> {
+    let Some(path) = file else {
+        return load_scenario(None, name).map(|scenario| vec![scenario]);
+    };
+    let contents = std::fs::read_to_string(path).map_err(|source| SimError::ScenarioRead {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let parse_error = |source| SimError::ScenarioParse {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let value: serde_json::Value = serde_json::from_str(&contents).map_err(parse_error)?;
+    if value.is_array() {
+        serde_json::from_value(value).map_err(parse_error)
+    } else {
+        serde_json::from_value(value)
+            .map(|scenario| vec![scenario])
+            .map_err(parse_error)
+    }
+}
+
+/// Outcome of loading and validating one scenario file
+#[derive(Debug)]
+pub struct ScenarioCheck {
+    pub path: PathBuf,
+    /// Number of scenarios the file holds, or why it failed
+    pub result: Result<usize, SimError>,
+}
+
+impl ScenarioCheck {
+    pub fn passed(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Load and validate every `.json` file in `dir`, in path order. Other files
+/// are skipped; files that don't parse as scenarios are reported as failures.
+pub fn check_scenario_dir(dir: &Path) -> Result<Vec<ScenarioCheck>, SimError> {
+    let read_error = |source| SimError::ScenarioRead {
+        path: dir.to_path_buf(),
+        source,
+    };
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let result = load_scenarios(Some(&path), "").and_then(|scenarios| {
+                for scenario in &scenarios {
+                    scenario
+                        .validate()
+                        .map_err(|e| SimError::Validation(format!("{}: {}", scenario.name, e)))?;
+                }
+                Ok(scenarios.len())
+            });
+            ScenarioCheck { path, result }
+        })
+        .collect())
+}
+
+/// Pass/fail table for `check_scenario_dir` results
+pub fn format_scenario_checks(checks: &[ScenarioCheck]) -> String {
+    let mut output = String::new();
+    for check in checks {
+        match &check.result {
+            Ok(count) => output.push_str(&format!(
+                "PASS  {} ({} scenario{})\n",
+                check.path.display(),
+                count,
+                if *count == 1 { "" } else { "s" }
+            )),
+            Err(e) => output.push_str(&format!("FAIL  {}\n      {}\n", check.path.display(), e)),
+        }
+    }
+    let failed = checks.iter().filter(|c| !c.passed()).count();
+    output.push_str(&format!(
+        "\n{} passed, {} failed\n",
+        checks.len() - failed,
+        failed
+    ));
+    output
+}
+
 impl Scenario {
     pub fn new(name: String) -> Self {
         Self {
@@ -125,14 +1015,216 @@ impl Scenario {
         if self.villages.is_empty() {
             return Err("Scenario must have at least one village".to_string());
         }
+        if self.parameters.ticks_per_day == 0 {
+            return Err("ticks_per_day must be at least 1".to_string());
+        }
+        if self
+            .parameters
+            .old_age
+            .is_some_and(|old_age| old_age.onset_age > old_age.max_age)
+        {
+            return Err("old_age onset_age must not exceed max_age".to_string());
+        }
+        if self.parameters.recovery_rate == Some(0) {
+            return Err("recovery_rate must be at least 1".to_string());
+        }
+        if self.parameters.rounds_per_tick == 0 {
+            return Err("rounds_per_tick must be at least 1".to_string());
+        }
+        if self.parameters.granary_fraction < Decimal::ZERO
+            || self.parameters.granary_fraction > Decimal::ONE
+        {
+            return Err("granary_fraction must be between 0 and 1".to_string());
+        }
+        if let Some(tools) = self.parameters.tools
+            && (tools.boost_per_tool < Decimal::ZERO
+                || tools.max_multiplier < Decimal::ONE
+                || tools.depreciation_rate < Decimal::ZERO
+                || tools.depreciation_rate > Decimal::ONE)
+        {
+            return Err(
+                "tools needs a non-negative boost, a max_multiplier of at least 1 and a depreciation_rate between 0 and 1"
+                    .to_string(),
+            );
+        }
+        for (resource, curve) in [
+            ("food", self.parameters.food_production_curve),
+            ("wood", self.parameters.wood_production_curve),
+        ] {
+            match curve {
+                ProductionCurve::ExponentialDecay { ratio } if !(ratio > 0.0 && ratio <= 1.0) => {
+                    return Err(format!(
+                        "{}_production_curve ratio must be in (0, 1]",
+                        resource
+                    ));
+                }
+                ProductionCurve::PowerLaw { alpha } if !(alpha > 0.0 && alpha <= 1.0) => {
+                    return Err(format!(
+                        "{}_production_curve alpha must be in (0, 1]",
+                        resource
+                    ));
+                }
+                _ => {}
+            }
+        }
+        match self.parameters.labor_efficiency {
+            LaborEfficiency::PowerLaw { alpha } if !(alpha > 0.0 && alpha <= 1.0) => {
+                return Err("labor_efficiency alpha must be in (0, 1]".to_string());
+            }
+            LaborEfficiency::Congestion { congestion }
+                if congestion < Decimal::ZERO || congestion >= Decimal::ONE =>
+            {
+                return Err("labor_efficiency congestion must be in [0, 1)".to_string());
+            }
+            _ => {}
+        }
+        if self
+            .parameters
+            .food_backstop
+            .is_some_and(|backstop| backstop.price < Decimal::ZERO)
+        {
+            return Err("food_backstop price must not be negative".to_string());
+        }
+        if self
+            .parameters
+            .max_price_move
+            .is_some_and(|max_move| max_move <= Decimal::ZERO)
+        {
+            return Err("max_price_move must be positive".to_string());
+        }
+        if let Some((resource, _)) = self
+            .parameters
+            .initial_prices
+            .iter()
+            .find(|(_, price)| **price <= Decimal::ZERO)
+        {
+            return Err(format!("Initial price for {:?} must be positive", resource));
+        }
+        if self.parameters.money_floor < Decimal::ZERO {
+            return Err("money_floor must not be negative".to_string());
+        }
+        if self.parameters.budget_tolerance < Decimal::ZERO {
+            return Err("budget_tolerance must not be negative".to_string());
+        }
+        if self.parameters.listing_fee < Decimal::ZERO {
+            return Err("listing_fee must not be negative".to_string());
+        }
+        for season in &self.parameters.seasonality {
+            if season.period == 0 {
+                return Err(format!(
+                    "Seasonality for {:?} must have a period of at least 1",
+                    season.resource
+                ));
+            }
+            if season.amplitude < Decimal::ZERO || season.amplitude > Decimal::ONE {
+                return Err(format!(
+                    "Seasonality amplitude for {:?} must be between 0 and 1",
+                    season.resource
+                ));
+            }
+            if let Some(village) = &season.village
+                && !self.villages.iter().any(|v| &v.id == village)
+            {
+                return Err(format!(
+                    "Seasonality for {:?} names unknown village {}",
+                    season.resource, village
+                ));
+            }
+        }
+        for modifier in &self.parameters.global_modifiers {
+            if modifier.start_tick >= modifier.end_tick {
+                return Err(format!(
+                    "Global modifier for {:?} must start before it ends",
+                    modifier.resource
+                ));
+            }
+            if modifier.multiplier < Decimal::ZERO {
+                return Err(format!(
+                    "Global modifier for {:?} must not have a negative multiplier",
+                    modifier.resource
+                ));
+            }
+        }
+
+        if let Some(rule) = &self.parameters.bankruptcy {
+            if rule.grace_ticks == 0 {
+                return Err("bankruptcy grace_ticks must be at least 1".to_string());
+            }
+            if let Some(heir) = &rule.heir
+                && !self.villages.iter().any(|v| &v.id == heir)
+            {
+                return Err(format!("Bankruptcy heir {} is not a village", heir));
+            }
+        }
+
+        for village in self.parameters.participant_priority.keys() {
+            if !self.villages.iter().any(|v| &v.id == village) {
+                return Err(format!(
+                    "participant_priority names unknown village {}",
+                    village
+                ));
+            }
+        }
+
+        for embargo in &self.parameters.embargoes {
+            if !self.villages.iter().any(|v| v.id == embargo.village) {
+                return Err(format!(
+                    "Embargo on {:?} names unknown village {}",
+                    embargo.resource, embargo.village
+                ));
+            }
+        }
+
+        for (resource, market) in &self.parameters.external_market {
+            if *resource == ResourceType::Labor {
+                return Err("external_market only trades Food and Wood".to_string());
+            }
+            if market.initial_price < ExternalMarket::MIN_PRICE {
+                return Err(format!(
+                    "external_market {:?} initial_price must be at least {}",
+                    resource,
+                    ExternalMarket::MIN_PRICE
+                ));
+            }
+            if market.volatility < Decimal::ZERO || market.volatility >= Decimal::ONE {
+                return Err(format!(
+                    "external_market {:?} volatility must be in [0, 1)",
+                    resource
+                ));
+            }
+            if market.spread <= Decimal::ZERO || market.spread >= Decimal::TWO {
+                return Err(format!(
+                    "external_market {:?} spread must be in (0, 2)",
+                    resource
+                ));
+            }
+        }
 
         for village in &self.villages {
+            if village.is_market_agent {
+                if village.initial_workers > 0 {
+                    return Err(format!("Market agent {} must not have workers", village.id));
+                }
+                continue;
+            }
             if village.initial_workers == 0 {
                 return Err(format!(
                     "Village {} must have at least one worker",
                     village.id
                 ));
             }
+            if village.food_quality < Decimal::ZERO || village.wood_quality < Decimal::ZERO {
+                return Err(format!(
+                    "Village {} must not have negative land quality",
+                    village.id
+                ));
+            }
+            if village.initial_construction_progress < Decimal::ZERO {
+                return Err(format!(
+                    "Village {} must not start with negative construction progress",
+                    village.id
+                ));
+            }
             if village.food_slots.0 == 0 || village.wood_slots.0 == 0 {
                 return Err(format!(
                     "Village {} must have at least one slot for food and wood",
@@ -207,28 +1299,41 @@ pub fn create_standard_scenarios() -> HashMap<String, Scenario> {
     let mut scenarios = HashMap::new();
 
     let mut basic = Scenario::new("basic_two_villages".to_string());
-    basic.description = "Two villages with balanced strategies and complementary resources".to_string();
+    basic.description =
+        "Two villages with balanced strategies and complementary resources".to_string();
     basic.add_village(VillageConfig {
         id: "village_a".to_string(),
         initial_workers: 10,
         initial_houses: 2,
-        initial_food: Decimal::from(70),  // More food
-        initial_wood: Decimal::from(30),  // Less wood
+        initial_food: Decimal::from(70), // More food
+        initial_wood: Decimal::from(30), // Less wood
         initial_money: Decimal::from(100),
-        food_slots: (12, 8),  // Better at food
-        wood_slots: (8, 12),  // Decent at wood
+        food_slots: (12, 8), // Better at food
+        wood_slots: (8, 12), // Decent at wood
         strategy: StrategyConfig::default(),
+        is_market_agent: false,
+        agent_orders: Vec::new(),
+        initial_worker_state: InitialWorkerState::default(),
+        initial_construction_progress: Decimal::ZERO,
+        food_quality: Decimal::ONE,
+        wood_quality: Decimal::ONE,
     });
     basic.add_village(VillageConfig {
         id: "village_b".to_string(),
         initial_workers: 10,
         initial_houses: 2,
-        initial_food: Decimal::from(30),  // Less food
-        initial_wood: Decimal::from(70),  // More wood
+        initial_food: Decimal::from(30), // Less food
+        initial_wood: Decimal::from(70), // More wood
         initial_money: Decimal::from(100),
-        food_slots: (8, 12),  // Decent at food
-        wood_slots: (12, 8),  // Better at wood
+        food_slots: (8, 12), // Decent at food
+        wood_slots: (12, 8), // Better at wood
         strategy: StrategyConfig::default(),
+        is_market_agent: false,
+        agent_orders: Vec::new(),
+        initial_worker_state: InitialWorkerState::default(),
+        initial_construction_progress: Decimal::ZERO,
+        food_quality: Decimal::ONE,
+        wood_quality: Decimal::ONE,
     });
     scenarios.insert("basic".to_string(), basic);
 
@@ -246,6 +1351,12 @@ pub fn create_standard_scenarios() -> HashMap<String, Scenario> {
         food_slots: (11, 9),
         wood_slots: (9, 11),
         strategy: StrategyConfig::default(),
+        is_market_agent: false,
+        agent_orders: Vec::new(),
+        initial_worker_state: InitialWorkerState::default(),
+        initial_construction_progress: Decimal::ZERO,
+        food_quality: Decimal::ONE,
+        wood_quality: Decimal::ONE,
     });
     custom.add_village(VillageConfig {
         id: "village_2".to_string(),
@@ -257,6 +1368,12 @@ pub fn create_standard_scenarios() -> HashMap<String, Scenario> {
         food_slots: (9, 11),
         wood_slots: (11, 9),
         strategy: StrategyConfig::default(),
+        is_market_agent: false,
+        agent_orders: Vec::new(),
+        initial_worker_state: InitialWorkerState::default(),
+        initial_construction_progress: Decimal::ZERO,
+        food_quality: Decimal::ONE,
+        wood_quality: Decimal::ONE,
     });
     scenarios.insert("custom".to_string(), custom);
 
@@ -274,7 +1391,14 @@ pub fn create_standard_scenarios() -> HashMap<String, Scenario> {
         strategy: StrategyConfig::Survival {
             min_food_days: 15,
             min_shelter_buffer: 2,
+            sizing: None,
         },
+        is_market_agent: false,
+        agent_orders: Vec::new(),
+        initial_worker_state: InitialWorkerState::default(),
+        initial_construction_progress: Decimal::ZERO,
+        food_quality: Decimal::ONE,
+        wood_quality: Decimal::ONE,
     });
     scenarios.insert("scarcity".to_string(), scarcity);
 
@@ -293,7 +1417,14 @@ pub fn create_standard_scenarios() -> HashMap<String, Scenario> {
         strategy: StrategyConfig::Growth {
             target_population: 50,
             house_buffer: 3,
+            sizing: None,
         },
+        is_market_agent: false,
+        agent_orders: Vec::new(),
+        initial_worker_state: InitialWorkerState::default(),
+        initial_construction_progress: Decimal::ZERO,
+        food_quality: Decimal::ONE,
+        wood_quality: Decimal::ONE,
     });
     scenarios.insert("growth".to_string(), growth);
 
@@ -313,7 +1444,14 @@ pub fn create_standard_scenarios() -> HashMap<String, Scenario> {
         strategy: StrategyConfig::Trading {
             price_multiplier: 1.0,
             max_trade_fraction: 0.5,
+            sizing: None,
         },
+        is_market_agent: false,
+        agent_orders: Vec::new(),
+        initial_worker_state: InitialWorkerState::default(),
+        initial_construction_progress: Decimal::ZERO,
+        food_quality: Decimal::ONE,
+        wood_quality: Decimal::ONE,
     });
     trading.add_village(VillageConfig {
         id: "food_specialist".to_string(),
@@ -327,7 +1465,14 @@ pub fn create_standard_scenarios() -> HashMap<String, Scenario> {
         strategy: StrategyConfig::Trading {
             price_multiplier: 1.0,
             max_trade_fraction: 0.5,
+            sizing: None,
         },
+        is_market_agent: false,
+        agent_orders: Vec::new(),
+        initial_worker_state: InitialWorkerState::default(),
+        initial_construction_progress: Decimal::ZERO,
+        food_quality: Decimal::ONE,
+        wood_quality: Decimal::ONE,
     });
     scenarios.insert("trading".to_string(), trading);
 
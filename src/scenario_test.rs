@@ -18,6 +18,12 @@ mod tests {
             food_slots: (10, 10),
             wood_slots: (10, 10),
             strategy: StrategyConfig::default(),
+            is_market_agent: false,
+            agent_orders: Vec::new(),
+            initial_worker_state: InitialWorkerState::default(),
+            initial_construction_progress: dec!(0),
+            food_quality: dec!(1),
+            wood_quality: dec!(1),
         };
 
         scenario.add_village(village);
@@ -26,6 +32,26 @@ mod tests {
         assert_eq!(scenario.name, "test_scenario");
     }
 
+    #[test]
+    fn test_labor_efficiency_power_law_shrinks_large_workforces() {
+        let efficiency = LaborEfficiency::PowerLaw { alpha: 0.8 };
+        let effective = efficiency.effective(dec!(100));
+        assert!(effective < dec!(100));
+        assert_eq!(effective, dec!(39.810717));
+        assert_eq!(efficiency.effective(dec!(1)), dec!(1));
+        // Below one worker-day the exponent would add labor, so it isn't applied
+        assert_eq!(efficiency.effective(dec!(0.5)), dec!(0.5));
+        assert_eq!(efficiency.effective(dec!(0)), dec!(0));
+        assert_eq!(LaborEfficiency::Linear.effective(dec!(100)), dec!(100));
+        assert_eq!(
+            LaborEfficiency::Congestion {
+                congestion: dec!(0.1)
+            }
+            .effective(dec!(100)),
+            dec!(90)
+        );
+    }
+
     #[test]
     fn test_scenario_validation() {
         let mut scenario = Scenario::new("invalid".to_string());
@@ -42,12 +68,31 @@ mod tests {
             food_slots: (1, 1),
             wood_slots: (1, 1),
             strategy: StrategyConfig::default(),
+            is_market_agent: false,
+            agent_orders: Vec::new(),
+            initial_worker_state: InitialWorkerState::default(),
+            initial_construction_progress: dec!(0),
+            food_quality: dec!(1),
+            wood_quality: dec!(1),
         });
 
         assert!(scenario.validate().is_err());
 
         scenario.villages[0].initial_workers = 5;
         assert!(scenario.validate().is_ok());
+
+        // Production curves must have diminishing returns
+        scenario.parameters.food_production_curve =
+            ProductionCurve::ExponentialDecay { ratio: 1.5 };
+        assert!(scenario.validate().is_err());
+        scenario.parameters.food_production_curve =
+            ProductionCurve::ExponentialDecay { ratio: 0.9 };
+        scenario.parameters.wood_production_curve = ProductionCurve::PowerLaw { alpha: 0.0 };
+        assert!(scenario.validate().is_err());
+        scenario.parameters.wood_production_curve = ProductionCurve::PowerLaw { alpha: f64::NAN };
+        assert!(scenario.validate().is_err());
+        scenario.parameters.wood_production_curve = ProductionCurve::PowerLaw { alpha: 0.5 };
+        assert!(scenario.validate().is_ok());
     }
 
     #[test]
@@ -80,18 +125,28 @@ mod tests {
                 wood_weight: 0.3,
                 construction_weight: 0.2,
                 repair_weight: 0.2,
+                sizing: None,
             },
             StrategyConfig::Survival {
                 min_food_days: 20,
                 min_shelter_buffer: 3,
+                sizing: None,
             },
             StrategyConfig::Growth {
                 target_population: 100,
                 house_buffer: 5,
+                sizing: None,
             },
             StrategyConfig::Trading {
                 price_multiplier: 1.5,
                 max_trade_fraction: 0.3,
+                sizing: None,
+            },
+            StrategyConfig::Greedy { sizing: None },
+            StrategyConfig::Undercutting {
+                undercut: dec!(0.05),
+                buffer_days: 10,
+                sizing: None,
             },
         ];
 
@@ -100,4 +155,71 @@ mod tests {
             let _deserialized: StrategyConfig = serde_json::from_str(&json).unwrap();
         }
     }
+
+    #[test]
+    fn test_load_scenario_missing_file() {
+        let path = std::env::temp_dir().join("village_model_missing_scenario.json");
+        let _ = std::fs::remove_file(&path);
+
+        let result = load_scenario(Some(&path), "basic");
+
+        assert!(matches!(result, Err(SimError::ScenarioRead { .. })));
+    }
+
+    #[test]
+    fn test_load_scenario_bad_json() {
+        let path = std::env::temp_dir().join("village_model_bad_scenario.json");
+        std::fs::write(&path, "{ not valid json").unwrap();
+
+        let result = load_scenario(Some(&path), "basic");
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(SimError::ScenarioParse { .. })));
+    }
+
+    #[test]
+    fn test_load_scenario_unknown_name() {
+        let result = load_scenario(None, "no_such_scenario");
+
+        match result {
+            Err(SimError::UnknownScenario { available, .. }) => {
+                assert!(available.contains(&"basic".to_string()))
+            }
+            other => panic!("expected UnknownScenario, got {:?}", other.map(|s| s.name)),
+        }
+    }
+
+    #[test]
+    fn test_check_scenario_dir_reports_each_file() {
+        let dir = std::env::temp_dir().join("village_model_validate_dir");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let valid = load_scenario(None, "basic").unwrap();
+        let mut invalid = valid.clone();
+        invalid.name = "empty".to_string();
+        invalid.villages.clear();
+        std::fs::write(
+            dir.join("a_valid.json"),
+            serde_json::to_string(&valid).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("b_invalid.json"),
+            serde_json::to_string(&invalid).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a scenario").unwrap();
+
+        let checks = check_scenario_dir(&dir).unwrap();
+        let report = format_scenario_checks(&checks);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(checks.len(), 2, "non-JSON files are skipped");
+        assert!(checks[0].passed());
+        assert!(matches!(checks[1].result, Err(SimError::Validation(_))));
+        assert!(report.contains("FAIL"));
+        assert!(report.contains("at least one village"));
+        assert!(report.ends_with("1 passed, 1 failed\n"));
+    }
 }
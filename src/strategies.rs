@@ -12,12 +12,18 @@
 //! - **Trading**: Specializes in one resource and trades aggressively
 //! - **Balanced**: Adapts dynamically to current needs
 //! - **Greedy**: Maximizes immediate production value
+//! - **Undercutting**: Balanced allocation, sells surplus just below the best resting ask
+//! - **Exploring**: Wraps another strategy and randomly perturbs some of its decisions
 
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
 
 use crate::scenario::StrategyConfig;
+use crate::types::{OrderRequest, ResourceType};
 
 // === HELPER FUNCTIONS ===
 
@@ -36,29 +42,39 @@ fn calculate_resource_urgency(days_of_supply: u32, half_life_days: f64) -> f64 {
     1.0 / (1.0 + days_of_supply as f64 / half_life_days)
 }
 
+/// Highest bid premium any strategy pays, reached when supply runs out
+pub const MAX_DESPERATION_MULTIPLIER: Decimal = dec!(2.0);
+
+/// Bid multiplier for a resource with `days_of_supply` left.
+///
+/// 1.0 at `floor_days` or more of supply, rising linearly to
+/// [`MAX_DESPERATION_MULTIPLIER`] as supply reaches zero.
+pub fn desperation_multiplier(days_of_supply: u32, floor_days: u32) -> Decimal {
+    if days_of_supply >= floor_days {
+        return dec!(1.0);
+    }
+    let shortfall = Decimal::from(floor_days - days_of_supply) / Decimal::from(floor_days);
+    dec!(1.0) + (MAX_DESPERATION_MULTIPLIER - dec!(1.0)) * shortfall
+}
+
 /// Calculate bid price for food based on market price and urgency
-fn calculate_food_bid_price(market_price: Option<Decimal>, multiplier: Decimal) -> Decimal {
-    market_price.unwrap_or(get_default_price(false)) * multiplier
+fn calculate_food_bid_price(market: &MarketState, multiplier: Decimal) -> Decimal {
+    market.food_price() * multiplier
 }
 
 /// Calculate bid price for wood based on market price and urgency
-fn calculate_wood_bid_price(market_price: Option<Decimal>, multiplier: Decimal) -> Decimal {
-    market_price.unwrap_or(get_default_price(true)) * multiplier
+fn calculate_wood_bid_price(market: &MarketState, multiplier: Decimal) -> Decimal {
+    market.wood_price() * multiplier
 }
 
 /// Calculate ask price for food based on market price and discount
-fn calculate_food_ask_price(market_price: Option<Decimal>, multiplier: Decimal) -> Decimal {
-    market_price.unwrap_or(get_default_price(false)) * multiplier
+fn calculate_food_ask_price(market: &MarketState, multiplier: Decimal) -> Decimal {
+    market.food_price() * multiplier
 }
 
 /// Calculate ask price for wood based on market price and discount
-fn calculate_wood_ask_price(market_price: Option<Decimal>, multiplier: Decimal) -> Decimal {
-    market_price.unwrap_or(get_default_price(true)) * multiplier
-}
-
-/// Get default price for a resource type
-fn get_default_price(is_wood: bool) -> Decimal {
-    if is_wood { dec!(5.0) } else { dec!(1.0) }
+fn calculate_wood_ask_price(market: &MarketState, multiplier: Decimal) -> Decimal {
+    market.wood_price() * multiplier
 }
 
 /// Calculate marginal productivity for a resource given current workers
@@ -79,9 +95,9 @@ fn calculate_marginal_productivity(current_workers: u32, slots: (u32, u32)) -> D
 /// Calculate marginal cost of producing one unit of a resource
 /// Cost = 1 / (productivity * production_rate)
 fn calculate_marginal_cost(
-    current_workers: u32, 
+    current_workers: u32,
     slots: (u32, u32),
-    base_production_rate: Decimal
+    base_production_rate: Decimal,
 ) -> Decimal {
     let productivity = calculate_marginal_productivity(current_workers, slots);
     if productivity > dec!(0) {
@@ -91,16 +107,95 @@ fn calculate_marginal_cost(
     }
 }
 
-/// Check if village can afford a quantity at a given price
-fn can_afford_quantity(
-    money: Decimal,
-    price: Decimal,
-    quantity: u32,
-    reserve_fraction: Decimal,
-) -> bool {
-    let total_cost = price * Decimal::from(quantity);
-    let available_money = money * (dec!(1) - reserve_fraction);
-    total_cost <= available_money
+// === ORDER SIZING ===
+/// Limits on how many units a strategy bids for or offers in one order.
+///
+/// Bids cover a shortfall but never commit more than `max_money_fraction` of
+/// the village's money; asks offer `sell_fraction` of the stock held above a
+/// buffer. Every order is also capped per resource.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OrderSizing {
+    /// Largest share of its money a village commits to one bid
+    pub max_money_fraction: Decimal,
+    /// Share of the stock above the buffer offered in one ask
+    pub sell_fraction: Decimal,
+    /// Most food units in a single order
+    pub max_food_quantity: u32,
+    /// Most wood units in a single order
+    pub max_wood_quantity: u32,
+    /// Wood stock restocked to by strategies that keep a fixed amount on hand
+    /// rather than days of use (Growth and Greedy)
+    #[serde(default = "default_wood_stock_target")]
+    pub wood_stock_target: u32,
+}
+
+fn default_wood_stock_target() -> u32 {
+    OrderSizing::default().wood_stock_target
+}
+
+impl Default for OrderSizing {
+    fn default() -> Self {
+        Self {
+            max_money_fraction: dec!(0.8),
+            sell_fraction: dec!(0.5),
+            max_food_quantity: 50,
+            max_wood_quantity: 20,
+            wood_stock_target: 30,
+        }
+    }
+}
+
+impl OrderSizing {
+    /// Largest single order for `resource`; strategies don't size labor orders.
+    pub fn max_quantity(&self, resource: ResourceType) -> u32 {
+        match resource {
+            ResourceType::Food => self.max_food_quantity,
+            ResourceType::Wood => self.max_wood_quantity,
+            ResourceType::Labor => 0,
+        }
+    }
+
+    /// Units to bid for to bring `held` up to `target_days` of `daily_use`.
+    pub fn bid_quantity(
+        &self,
+        resource: ResourceType,
+        held: Decimal,
+        daily_use: Decimal,
+        target_days: u32,
+        money: Decimal,
+        price: Decimal,
+    ) -> u32 {
+        let shortfall = daily_use * Decimal::from(target_days) - held;
+        self.bid_for_shortfall(resource, shortfall, money, price)
+    }
+
+    /// Units to bid for to cover `shortfall`, limited by what the money
+    /// fraction buys at `price` and by the resource's cap.
+    pub fn bid_for_shortfall(
+        &self,
+        resource: ResourceType,
+        shortfall: Decimal,
+        money: Decimal,
+        price: Decimal,
+    ) -> u32 {
+        let mut quantity = shortfall.max(Decimal::ZERO).ceil();
+        if price > Decimal::ZERO {
+            quantity = quantity.min((money * self.max_money_fraction / price).floor());
+        }
+        quantity
+            .to_u32()
+            .unwrap_or(0)
+            .min(self.max_quantity(resource))
+    }
+
+    /// Units to offer out of `held`, keeping `buffer` back.
+    pub fn ask_quantity(&self, resource: ResourceType, held: Decimal, buffer: Decimal) -> u32 {
+        ((held - buffer).max(Decimal::ZERO) * self.sell_fraction)
+            .floor()
+            .to_u32()
+            .unwrap_or(0)
+            .min(self.max_quantity(resource))
+    }
 }
 
 /// Trait for village decision-making strategies.
@@ -111,13 +206,37 @@ fn can_afford_quantity(
 pub trait Strategy: Send + Sync {
     /// Decide worker allocation and market orders based on village state
     fn decide_allocation_and_orders(
-        &self,
+        &mut self,
         village_state: &VillageState,
         market_state: &MarketState,
     ) -> StrategyDecision;
 
     /// Get a descriptive name for the strategy
     fn name(&self) -> &str;
+
+    /// Seed the strategy's own RNG.
+    ///
+    /// Strategies that make random choices must draw only from an RNG seeded
+    /// here, never from the village RNG, so their decisions cannot shift
+    /// births and deaths between otherwise identical runs.
+    fn seed_rng(&mut self, _seed: u64) {}
+
+    /// Re-quote between auction rounds when `rounds_per_tick` runs several
+    /// in one tick.
+    ///
+    /// `fills` reports how much of each of the village's orders filled in the
+    /// round just run, and both states already reflect its trades and prices.
+    /// Only the orders of the returned decision are used, since the tick's
+    /// allocation was fixed before the first round. By default the strategy
+    /// simply decides again.
+    fn on_trade_result(
+        &mut self,
+        village_state: &VillageState,
+        market_state: &MarketState,
+        _fills: &[OrderFill],
+    ) -> StrategyDecision {
+        self.decide_allocation_and_orders(village_state, market_state)
+    }
 }
 
 /// Current state of a village for strategy decisions.
@@ -132,15 +251,57 @@ pub struct VillageState {
     pub food: Decimal,
     pub money: Decimal,
     pub houses: usize,
+    /// Workers the current houses can shelter, given `shelter_per_worker`
     pub house_capacity: usize,
+    /// Shelter units each worker needs
+    pub shelter_per_worker: Decimal,
     pub wood_slots: (u32, u32),
     pub food_slots: (u32, u32),
+    /// Land quality multipliers on the village's food and wood output
+    pub food_quality: Decimal,
+    pub wood_quality: Decimal,
     pub worker_days: Decimal,
-    pub days_without_food: Vec<u32>,
+    pub days_without_food: Vec<Decimal>,
     pub days_without_shelter: Vec<u32>,
     pub construction_progress: Decimal,
+    /// Whole upgrade levels still available across all houses (0 if upgrades are disabled)
+    pub upgradable_house_levels: u32,
+    /// Wood needed per house upgrade level
+    pub house_upgrade_cost: Decimal,
+    /// Tools on hand; recipes that output `Tools` turn processing into more
+    pub tools: Decimal,
+    /// Whether processing can make tools now (a recipe outputs them and the
+    /// village holds a worker-day's inputs) and more tools would raise output
+    pub can_make_tools: bool,
 }
 
+impl VillageState {
+    /// Worker-days that fill every full and partial slot for `resource`;
+    /// labor beyond this produces nothing.
+    pub fn optimal_worker_days(&self, resource: ResourceType) -> Decimal {
+        let (full, partial) = match resource {
+            ResourceType::Food => self.food_slots,
+            ResourceType::Wood => self.wood_slots,
+            ResourceType::Labor => (0, 0),
+        };
+        Decimal::from(full + partial)
+    }
+
+    /// Worker-days to put into making tools: a share of the village's labor
+    /// when labor rather than land limits its output and tools would help.
+    pub fn toolmaking_days(&self) -> Decimal {
+        let slot_days = self.optimal_worker_days(ResourceType::Food)
+            + self.optimal_worker_days(ResourceType::Wood);
+        if !self.can_make_tools || self.worker_days >= slot_days {
+            return dec!(0);
+        }
+        self.worker_days * TOOLMAKING_SHARE
+    }
+}
+
+/// Share of a labor-bound village's worker-days spent making tools.
+const TOOLMAKING_SHARE: Decimal = dec!(0.1);
+
 /// Market information for trading decisions.
 ///
 /// Provides price history and current order book state
@@ -149,30 +310,186 @@ pub struct VillageState {
 pub struct MarketState {
     pub last_wood_price: Option<Decimal>,
     pub last_food_price: Option<Decimal>,
+    pub default_prices: DefaultPrices,
+    /// Orders submitted for wood on the previous tick
+    pub wood_book: OrderBook,
+    /// Orders submitted for food on the previous tick
+    pub food_book: OrderBook,
+}
+
+impl MarketState {
+    /// Last wood clearing price, or the default if wood hasn't traded yet.
+    pub fn wood_price(&self) -> Decimal {
+        self.last_wood_price.unwrap_or(self.default_prices.wood)
+    }
+
+    /// Last food clearing price, or the default if food hasn't traded yet.
+    pub fn food_price(&self) -> Decimal {
+        self.last_food_price.unwrap_or(self.default_prices.food)
+    }
+}
+
+/// Aggregated bid and ask levels for one resource.
+///
+/// Bids are sorted from the highest price down and asks from the lowest
+/// price up, so the first level on each side is the best one.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderBook {
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+}
+
+/// Total quantity resting at one price.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookLevel {
+    pub price: Decimal,
+    pub quantity: u32,
+    /// How much of `quantity` each village posted, when the book knows
+    #[serde(default)]
+    pub by_village: Vec<(String, u32)>,
+}
+
+impl OrderBook {
+    /// Builds the book for `resource` from submitted orders, merging orders
+    /// at the same price into one level.
+    pub fn from_orders(orders: &[OrderRequest], resource: ResourceType) -> Self {
+        Self::from_village_orders(orders.iter().map(|order| (None, order)), resource)
+    }
+
+    /// Like `from_orders`, but remembers how much each named village posted
+    /// at every level, so `without_village` can take it back out.
+    pub fn from_village_orders<'a>(
+        orders: impl IntoIterator<Item = (Option<&'a str>, &'a OrderRequest)>,
+        resource: ResourceType,
+    ) -> Self {
+        let mut book = Self::default();
+        for (village, order) in orders.into_iter().filter(|(_, o)| o.resource == resource) {
+            let side = if order.is_buy {
+                &mut book.bids
+            } else {
+                &mut book.asks
+            };
+            let level = match side.iter().position(|level| level.price == order.price) {
+                Some(i) => &mut side[i],
+                None => {
+                    side.push(BookLevel {
+                        price: order.price,
+                        quantity: 0,
+                        by_village: Vec::new(),
+                    });
+                    side.last_mut().unwrap()
+                }
+            };
+            level.quantity += order.quantity;
+            let Some(village) = village else {
+                continue;
+            };
+            match level.by_village.iter_mut().find(|(id, _)| id == village) {
+                Some((_, quantity)) => *quantity += order.quantity,
+                None => level.by_village.push((village.to_string(), order.quantity)),
+            }
+        }
+        book.bids
+            .sort_by_key(|level| std::cmp::Reverse(level.price));
+        book.asks.sort_by_key(|level| level.price);
+        book
+    }
+
+    /// Highest resting bid price, if anyone is buying.
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.first().map(|level| level.price)
+    }
+
+    /// Lowest resting ask price, if anyone is selling.
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.first().map(|level| level.price)
+    }
+
+    /// The book as `village_id` sees the rest of the market: its own orders
+    /// are taken out and levels only it posted are dropped.
+    pub fn without_village(&self, village_id: &str) -> Self {
+        let strip = |levels: &[BookLevel]| {
+            levels
+                .iter()
+                .filter_map(|level| {
+                    let own: u32 = level
+                        .by_village
+                        .iter()
+                        .filter(|(id, _)| id == village_id)
+                        .map(|(_, quantity)| quantity)
+                        .sum();
+                    (level.quantity > own).then(|| BookLevel {
+                        price: level.price,
+                        quantity: level.quantity - own,
+                        by_village: level
+                            .by_village
+                            .iter()
+                            .filter(|(id, _)| id != village_id)
+                            .cloned()
+                            .collect(),
+                    })
+                })
+                .collect()
+        };
+        Self {
+            bids: strip(&self.bids),
+            asks: strip(&self.asks),
+        }
+    }
+}
+
+/// Prices assumed for resources that have no clearing price yet.
+///
+/// Configured per scenario so every strategy starts from the same prior.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DefaultPrices {
+    pub wood: Decimal,
+    pub food: Decimal,
+}
+
+impl Default for DefaultPrices {
+    fn default() -> Self {
+        Self {
+            wood: dec!(5.0),
+            food: dec!(1.0),
+        }
+    }
 }
 
 /// Strategy output containing allocation and trading decisions.
 ///
 /// All trading orders are optional - strategies only generate
 /// orders when they want to participate in the market.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct StrategyDecision {
     pub allocation: WorkerAllocation,
     pub wood_bid: Option<(Decimal, u32)>, // (price, quantity)
     pub wood_ask: Option<(Decimal, u32)>,
     pub food_bid: Option<(Decimal, u32)>,
     pub food_ask: Option<(Decimal, u32)>,
+    pub house_upgrade_wood: Decimal, // Wood to spend on house upgrades
+}
+
+/// How much of one order filled in an auction round.
+#[derive(Debug, Clone)]
+pub struct OrderFill {
+    pub order: OrderRequest,
+    pub filled: u32,
 }
 
 /// Worker allocation decision.
 ///
 /// Values represent worker-days to allocate to each task.
 /// Should sum to approximately village.worker_days.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WorkerAllocation {
     pub wood: Decimal,
     pub food: Decimal,
     pub construction: Decimal,
+    pub processing: Decimal,     // Worker-days spent running scenario recipes
+    pub slot_expansion: Decimal, // Worker-days spent adding a production slot
+    pub slot_expansion_target: ResourceType, // Which resource gains the slot
+    pub repair: Decimal,         // Worker-days spent repairing decayed houses
 }
 
 // === SURVIVAL STRATEGY ===
@@ -189,9 +506,11 @@ pub struct WorkerAllocation {
 /// # Parameters
 /// - `min_food_days`: Target food buffer (default: 20 days)
 /// - `min_wood_days`: Target wood buffer (default: 10 days)
+/// - `sizing`: Order sizes (default: [`OrderSizing::default`])
 pub struct SurvivalStrategy {
     min_food_days: u32,
     min_wood_days: u32,
+    sizing: OrderSizing,
 }
 
 impl SurvivalStrategy {
@@ -199,16 +518,19 @@ impl SurvivalStrategy {
         Self {
             min_food_days,
             min_wood_days: min_shelter_buffer,
+            sizing: OrderSizing::default(),
         }
     }
+
+    pub fn with_sizing(mut self, sizing: OrderSizing) -> Self {
+        self.sizing = sizing;
+        self
+    }
 }
 
 impl Default for SurvivalStrategy {
     fn default() -> Self {
-        Self {
-            min_food_days: 20,
-            min_wood_days: 10,
-        }
+        Self::new(20, 10)
     }
 }
 
@@ -218,7 +540,7 @@ impl Strategy for SurvivalStrategy {
     }
 
     fn decide_allocation_and_orders(
-        &self,
+        &mut self,
         village: &VillageState,
         market: &MarketState,
     ) -> StrategyDecision {
@@ -237,6 +559,10 @@ impl Strategy for SurvivalStrategy {
             wood: dec!(0),
             food: dec!(0),
             construction: dec!(0),
+            processing: dec!(0),
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
         };
 
         // Critical food shortage
@@ -282,41 +608,58 @@ impl Strategy for SurvivalStrategy {
         let mut food_ask = None;
 
         // Buy food if critically low
-        if food_days < 10 && village.money > dec!(20) {
-            let quantity = ((self.min_food_days - food_days) * village.workers as u32).min(50);
-            let price = calculate_food_bid_price(market.last_food_price, dec!(1.1)); // 10% above market
-            if can_afford_quantity(village.money, price, quantity, dec!(0.2)) {
+        if food_days < 10.min(self.min_food_days) && village.money > dec!(20) {
+            let multiplier = desperation_multiplier(food_days, self.min_food_days);
+            let price = calculate_food_bid_price(market, multiplier);
+            let quantity = self.sizing.bid_quantity(
+                ResourceType::Food,
+                village.food,
+                food_per_day,
+                self.min_food_days,
+                village.money,
+                price,
+            );
+            if quantity > 0 {
                 food_bid = Some((price, quantity));
-            } else {
-                // Adjust price to what we can afford
-                let max_price = village.money / Decimal::from(quantity) * dec!(0.8);
-                food_bid = Some((price.min(max_price), quantity));
             }
         }
 
         // Buy wood if critically low
-        if wood_days < 10 && village.money > dec!(20) {
-            let quantity = (self.min_wood_days - wood_days).min(20);
-            let price = calculate_wood_bid_price(market.last_wood_price, dec!(1.1));
-            let max_price = village.money / Decimal::from(quantity) * dec!(0.5);
-            wood_bid = Some((price.min(max_price), quantity));
+        if wood_days < 10.min(self.min_wood_days) && village.money > dec!(20) {
+            let multiplier = desperation_multiplier(wood_days, self.min_wood_days);
+            let price = calculate_wood_bid_price(market, multiplier);
+            let quantity = self.sizing.bid_quantity(
+                ResourceType::Wood,
+                village.wood,
+                wood_per_day,
+                self.min_wood_days,
+                village.money,
+                price,
+            );
+            if quantity > 0 {
+                wood_bid = Some((price, quantity));
+            }
         }
 
         // Sell excess if we have good buffers
         if food_days > self.min_food_days * 2 {
-            let excess = village.food - Decimal::from(self.min_food_days) * food_per_day;
-            let quantity = (excess / dec!(2)).to_u32().unwrap_or(0).min(50);
+            let buffer = Decimal::from(self.min_food_days) * food_per_day;
+            let quantity = self
+                .sizing
+                .ask_quantity(ResourceType::Food, village.food, buffer);
             if quantity > 0 {
-                let price = calculate_food_ask_price(market.last_food_price, dec!(0.9));
+                let price = calculate_food_ask_price(market, dec!(0.9));
                 food_ask = Some((price, quantity));
             }
         }
 
         if wood_days > self.min_wood_days * 2 {
-            let excess = village.wood - Decimal::from(self.min_wood_days) * wood_per_day;
-            let quantity = (excess / dec!(2)).to_u32().unwrap_or(0).min(20);
+            let buffer = Decimal::from(self.min_wood_days) * wood_per_day;
+            let quantity = self
+                .sizing
+                .ask_quantity(ResourceType::Wood, village.wood, buffer);
             if quantity > 0 {
-                let price = calculate_wood_ask_price(market.last_wood_price, dec!(0.9));
+                let price = calculate_wood_ask_price(market, dec!(0.9));
                 wood_ask = Some((price, quantity));
             }
         }
@@ -327,6 +670,7 @@ impl Strategy for SurvivalStrategy {
             wood_ask,
             food_bid,
             food_ask,
+            house_upgrade_wood: dec!(0),
         }
     }
 }
@@ -337,7 +681,8 @@ impl Strategy for SurvivalStrategy {
 /// # Philosophy
 /// Maintains optimal worker-to-house ratio for population growth. Trades
 /// to acquire resources needed for expansion. Prioritizes long-term growth
-/// over short-term efficiency.
+/// over short-term efficiency. When housing is short and upgrades are enabled,
+/// spends spare wood upgrading existing houses.
 ///
 /// # Performance
 /// - **Excels**: Stable markets, mid-to-late game, resource-rich environments
@@ -348,8 +693,18 @@ impl Strategy for SurvivalStrategy {
 pub struct GrowthStrategy {
     target_worker_to_house_ratio: f64,
     house_buffer: usize,
+    sizing: OrderSizing,
 }
 
+/// Growth stocks up on 30 days of food at a time, so it allows larger food orders.
+const GROWTH_SIZING: OrderSizing = OrderSizing {
+    max_money_fraction: dec!(0.8),
+    sell_fraction: dec!(0.5),
+    max_food_quantity: 100,
+    max_wood_quantity: 20,
+    wood_stock_target: 30,
+};
+
 impl GrowthStrategy {
     pub fn new(target_population: usize, house_buffer: usize) -> Self {
         // Convert target population to worker-to-house ratio
@@ -363,8 +718,14 @@ impl GrowthStrategy {
         Self {
             target_worker_to_house_ratio: target_ratio.clamp(2.0, 4.5), // Keep ratio reasonable
             house_buffer,
+            sizing: GROWTH_SIZING,
         }
     }
+
+    pub fn with_sizing(mut self, sizing: OrderSizing) -> Self {
+        self.sizing = sizing;
+        self
+    }
 }
 
 impl Default for GrowthStrategy {
@@ -372,6 +733,7 @@ impl Default for GrowthStrategy {
         Self {
             target_worker_to_house_ratio: 3.5, // Leave room for growth
             house_buffer: 2,
+            sizing: GROWTH_SIZING,
         }
     }
 }
@@ -382,11 +744,13 @@ impl Strategy for GrowthStrategy {
     }
 
     fn decide_allocation_and_orders(
-        &self,
+        &mut self,
         village: &VillageState,
         market: &MarketState,
     ) -> StrategyDecision {
-        let worker_days = village.worker_days;
+        // Tools come off the top; the rest is split for growth
+        let toolmaking = village.toolmaking_days();
+        let worker_days = village.worker_days - toolmaking;
 
         // Calculate if we need more houses, accounting for buffer
         let current_ratio = village.workers as f64 / village.house_capacity.max(1) as f64;
@@ -403,6 +767,10 @@ impl Strategy for GrowthStrategy {
             } else {
                 dec!(0)
             },
+            processing: toolmaking,
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
         };
 
         // Adjust remaining allocation
@@ -418,31 +786,66 @@ impl Strategy for GrowthStrategy {
         let food_ask = None;
 
         // Need wood for construction - buy more aggressively if we're below buffer
-        if need_houses && village.wood < dec!(30) && village.money > dec!(50) {
-            let urgency_multiplier = if available_slots < self.house_buffer {
-                dec!(1.3) // More urgent when below buffer
-            } else {
-                dec!(1.2)
-            };
-            let quantity = 20u32;
-            let price = calculate_wood_bid_price(market.last_wood_price, urgency_multiplier);
-            wood_bid = Some((price, quantity));
+        let wood_target = Decimal::from(self.sizing.wood_stock_target);
+        if need_houses && village.wood < wood_target && village.money > dec!(50) {
+            // Free housing slots stand in for days of supply
+            let urgency_multiplier =
+                desperation_multiplier(available_slots as u32, self.house_buffer as u32 + 1);
+            let price = calculate_wood_bid_price(market, urgency_multiplier);
+            let quantity = self.sizing.bid_for_shortfall(
+                ResourceType::Wood,
+                wood_target - village.wood,
+                village.money,
+                price,
+            );
+            if quantity > 0 {
+                wood_bid = Some((price, quantity));
+            }
         }
 
         // Need food for population
         let food_per_day = Decimal::from(village.workers);
         let food_days = calculate_resource_days(village.food, food_per_day);
         if food_days < 30 && village.money > dec!(30) {
-            let quantity = (30 * village.workers as u32).min(100);
-            let price = calculate_food_bid_price(market.last_food_price, dec!(1.15));
-            food_bid = Some((price, quantity));
+            let price = calculate_food_bid_price(market, desperation_multiplier(food_days, 30));
+            let quantity = self.sizing.bid_quantity(
+                ResourceType::Food,
+                village.food,
+                food_per_day,
+                30,
+                village.money,
+                price,
+            );
+            if quantity > 0 {
+                food_bid = Some((price, quantity));
+            }
         }
 
         // Sell excess only if we have plenty
         if village.wood > dec!(100) && !need_houses {
-            let quantity = 20u32;
-            let price = calculate_wood_ask_price(market.last_wood_price, dec!(0.85));
-            wood_ask = Some((price, quantity));
+            let quantity = self
+                .sizing
+                .ask_quantity(ResourceType::Wood, village.wood, dec!(100));
+            if quantity > 0 {
+                let price = calculate_wood_ask_price(market, dec!(0.85));
+                wood_ask = Some((price, quantity));
+            }
+        }
+
+        // Upgrades add capacity without construction labor, so when housing is
+        // limiting spend wood beyond the next house and upkeep on upgrades
+        let mut house_upgrade_wood = dec!(0);
+        if need_houses
+            && village.upgradable_house_levels > 0
+            && village.house_upgrade_cost > dec!(0)
+        {
+            let reserve = dec!(10) + Decimal::from(village.houses) * dec!(0.1) * dec!(10);
+            let levels = ((village.wood - reserve) / village.house_upgrade_cost)
+                .floor()
+                .to_u32()
+                .unwrap_or(0)
+                .min(village.upgradable_house_levels);
+            house_upgrade_wood = Decimal::from(levels) * village.house_upgrade_cost;
         }
 
         StrategyDecision {
@@ -451,6 +854,7 @@ impl Strategy for GrowthStrategy {
             wood_ask,
             food_bid,
             food_ask,
+            house_upgrade_wood,
         }
     }
 }
@@ -474,24 +878,32 @@ impl Strategy for GrowthStrategy {
 /// - Asks at 102% of break-even (slight profit when selling)
 pub struct TradingStrategy {
     price_multiplier: Decimal,
-    max_trade_fraction: Decimal,
+    sizing: OrderSizing,
 }
 
 impl TradingStrategy {
+    /// `max_trade_fraction` is the share of its whole stock the strategy
+    /// offers at once; it becomes the sizing's `sell_fraction`.
     pub fn new(price_multiplier: f64, max_trade_fraction: f64) -> Self {
         Self {
             price_multiplier: Decimal::from_f64(price_multiplier).unwrap_or(dec!(1.0)),
-            max_trade_fraction: Decimal::from_f64(max_trade_fraction).unwrap_or(dec!(0.3)),
+            sizing: OrderSizing {
+                sell_fraction: Decimal::from_f64(max_trade_fraction).unwrap_or(dec!(0.3)),
+                max_wood_quantity: 30,
+                ..OrderSizing::default()
+            },
         }
     }
+
+    pub fn with_sizing(mut self, sizing: OrderSizing) -> Self {
+        self.sizing = sizing;
+        self
+    }
 }
 
 impl Default for TradingStrategy {
     fn default() -> Self {
-        Self {
-            price_multiplier: dec!(1.0),
-            max_trade_fraction: dec!(0.3),
-        }
+        Self::new(1.0, 0.3)
     }
 }
 
@@ -501,38 +913,38 @@ impl Strategy for TradingStrategy {
     }
 
     fn decide_allocation_and_orders(
-        &self,
+        &mut self,
         village: &VillageState,
         market: &MarketState,
     ) -> StrategyDecision {
         let worker_days = village.worker_days;
-        
+
         // Base production rates (from actual simulation)
-        let base_food_rate = dec!(2.0);  // Food per worker-day
-        let base_wood_rate = dec!(0.1);  // Wood per worker-day
+        let base_food_rate = dec!(2.0) * village.food_quality; // Food per worker-day
+        let base_wood_rate = dec!(0.1) * village.wood_quality; // Wood per worker-day
 
-        // Start with balanced allocation  
+        // Start with balanced allocation
         let construction_allocation = worker_days * dec!(0.1);
         let remaining = worker_days - construction_allocation;
-        
+
         // Calculate current marginal costs for initial balanced allocation
         let food_workers_est = (remaining * dec!(0.5)).to_u32().unwrap_or(0);
         let wood_workers_est = (remaining * dec!(0.5)).to_u32().unwrap_or(0);
-        
+
         let food_marginal_cost = calculate_marginal_cost(
-            food_workers_est, 
+            food_workers_est,
             (village.food_slots.0, village.food_slots.1),
-            base_food_rate
+            base_food_rate,
         );
         let wood_marginal_cost = calculate_marginal_cost(
             wood_workers_est,
-            (village.wood_slots.0, village.wood_slots.1), 
-            base_wood_rate
+            (village.wood_slots.0, village.wood_slots.1),
+            base_wood_rate,
         );
-        
+
         // Break-even exchange rate: How much wood is 1 food worth?
         let wood_per_food_breakeven = food_marginal_cost / wood_marginal_cost;
-        
+
         // Adjust allocation based on which resource is more valuable to produce
         let (food_allocation, wood_allocation) = if food_marginal_cost < wood_marginal_cost {
             // Food is cheaper to produce - allocate more to food
@@ -545,11 +957,15 @@ impl Strategy for TradingStrategy {
             let wood_weight = dec!(0.7);
             (remaining * food_weight, remaining * wood_weight)
         };
-        
+
         let allocation = WorkerAllocation {
             food: food_allocation,
             wood: wood_allocation,
             construction: construction_allocation,
+            processing: dec!(0),
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
         };
 
         // Trading based on marginal cost analysis
@@ -557,10 +973,11 @@ impl Strategy for TradingStrategy {
         let mut wood_ask = None;
         let mut food_bid = None;
         let mut food_ask = None;
-        
+
         // Use market prices if available, otherwise use break-even ratio
-        let _market_wood_per_food = if let (Some(wood_price), Some(food_price)) = 
-            (market.last_wood_price, market.last_food_price) {
+        let _market_wood_per_food = if let (Some(wood_price), Some(food_price)) =
+            (market.last_wood_price, market.last_food_price)
+        {
             if food_price > dec!(0) {
                 wood_price / food_price
             } else {
@@ -572,32 +989,26 @@ impl Strategy for TradingStrategy {
 
         // Determine what to trade based on inventory and production efficiency
         let food_days = calculate_resource_days(village.food, Decimal::from(village.workers));
-        let wood_days = calculate_resource_days(village.wood, Decimal::from(village.houses) * dec!(0.1));
-        
+        let wood_days =
+            calculate_resource_days(village.wood, Decimal::from(village.houses) * dec!(0.1));
+
         // If we have excess food and need wood
         if food_days > 20 && wood_days < 15 && village.food > dec!(30) {
-            let quantity = (village.food * self.max_trade_fraction)
-                .to_u32()
-                .unwrap_or(0)
-                .min(50);
+            let quantity = self
+                .sizing
+                .ask_quantity(ResourceType::Food, village.food, dec!(0));
             if quantity > 0 {
                 // Ask slightly above our break-even
-                let food_price = if let Some(market_price) = market.last_food_price {
-                    market_price * dec!(1.02) * self.price_multiplier
-                } else {
-                    // Convert break-even ratio to food price
-                    dec!(1.0) * dec!(1.02) * self.price_multiplier
-                };
+                let food_price = market.food_price() * dec!(1.02) * self.price_multiplier;
                 food_ask = Some((food_price, quantity));
             }
         }
-        
+
         // If we have excess wood and need food
         if wood_days > 20 && food_days < 15 && village.wood > dec!(20) {
-            let quantity = (village.wood * self.max_trade_fraction)
-                .to_u32()
-                .unwrap_or(0)
-                .min(30);
+            let quantity = self
+                .sizing
+                .ask_quantity(ResourceType::Wood, village.wood, dec!(0));
             if quantity > 0 {
                 // Ask slightly above our break-even
                 let wood_price = if let Some(market_price) = market.last_wood_price {
@@ -609,36 +1020,42 @@ impl Strategy for TradingStrategy {
                 wood_ask = Some((wood_price, quantity));
             }
         }
-        
+
         // If we urgently need food
         if food_days < 10 && village.money > dec!(20) {
-            let quantity = ((15 - food_days) * village.workers as u32).min(50);
+            // Bid slightly below market/break-even for profit
+            let food_price = market.food_price() * dec!(0.98) * self.price_multiplier;
+            let quantity = self.sizing.bid_quantity(
+                ResourceType::Food,
+                village.food,
+                Decimal::from(village.workers),
+                15,
+                village.money,
+                food_price,
+            );
             if quantity > 0 {
-                // Bid slightly below market/break-even for profit
-                let food_price = if let Some(market_price) = market.last_food_price {
-                    market_price * dec!(0.98) * self.price_multiplier
-                } else {
-                    dec!(1.0) * dec!(0.98) * self.price_multiplier
-                };
-                if can_afford_quantity(village.money, food_price, quantity, dec!(0.2)) {
-                    food_bid = Some((food_price, quantity));
-                }
+                food_bid = Some((food_price, quantity));
             }
         }
-        
+
         // If we urgently need wood
         if wood_days < 10 && village.money > dec!(20) {
-            let quantity = (15 - wood_days).min(20);
+            // Bid slightly below market/break-even for profit
+            let wood_price = if let Some(market_price) = market.last_wood_price {
+                market_price * dec!(0.98) * self.price_multiplier
+            } else {
+                wood_per_food_breakeven * dec!(0.98) * self.price_multiplier
+            };
+            let quantity = self.sizing.bid_quantity(
+                ResourceType::Wood,
+                village.wood,
+                Decimal::from(village.houses) * dec!(0.1),
+                15,
+                village.money,
+                wood_price,
+            );
             if quantity > 0 {
-                // Bid slightly below market/break-even for profit
-                let wood_price = if let Some(market_price) = market.last_wood_price {
-                    market_price * dec!(0.98) * self.price_multiplier  
-                } else {
-                    wood_per_food_breakeven * dec!(0.98) * self.price_multiplier
-                };
-                if can_afford_quantity(village.money, wood_price, quantity, dec!(0.2)) {
-                    wood_bid = Some((wood_price, quantity));
-                }
+                wood_bid = Some((wood_price, quantity));
             }
         }
 
@@ -648,6 +1065,7 @@ impl Strategy for TradingStrategy {
             wood_ask,
             food_bid,
             food_ask,
+            house_upgrade_wood: dec!(0),
         }
     }
 }
@@ -667,11 +1085,13 @@ impl Strategy for TradingStrategy {
 /// # Adaptation
 /// - Food/wood urgency: Inverse of days of supply
 /// - Construction: 30% when over capacity, 10% otherwise
+/// - Repair: `repair_weight` share once houses have decayed (builds otherwise)
 pub struct BalancedStrategy {
     food_weight: f64,
     wood_weight: f64,
     construction_weight: f64,
     repair_weight: f64,
+    sizing: OrderSizing,
 }
 
 impl BalancedStrategy {
@@ -686,18 +1106,19 @@ impl BalancedStrategy {
             wood_weight,
             construction_weight,
             repair_weight,
+            sizing: OrderSizing::default(),
         }
     }
+
+    pub fn with_sizing(mut self, sizing: OrderSizing) -> Self {
+        self.sizing = sizing;
+        self
+    }
 }
 
 impl Default for BalancedStrategy {
     fn default() -> Self {
-        Self {
-            food_weight: 0.25,
-            wood_weight: 0.25,
-            construction_weight: 0.25,
-            repair_weight: 0.25,
-        }
+        Self::new(0.25, 0.25, 0.25, 0.25)
     }
 }
 
@@ -707,7 +1128,7 @@ impl Strategy for BalancedStrategy {
     }
 
     fn decide_allocation_and_orders(
-        &self,
+        &mut self,
         village: &VillageState,
         market: &MarketState,
     ) -> StrategyDecision {
@@ -735,15 +1156,27 @@ impl Strategy for BalancedStrategy {
         // More houses = more repair needed
         let repair_need = (village.houses as f64 * 0.02 * self.repair_weight).min(0.2);
 
-        // Combined construction effort for both new houses and repairs
-        let construction_need = new_house_need + repair_need;
+        // Repair labor only helps decayed houses; otherwise that effort builds
+        let intact_capacity = (Decimal::from(village.houses * 5))
+            .checked_div(village.shelter_per_worker)
+            .and_then(|c| c.floor().to_usize())
+            .unwrap_or(0);
+        let (construction_need, repair_need) = if village.house_capacity < intact_capacity {
+            (new_house_need, repair_need)
+        } else {
+            (new_house_need + repair_need, 0.0)
+        };
 
-        let total = food_urgency + wood_urgency + construction_need;
+        let total = food_urgency + wood_urgency + construction_need + repair_need;
 
         let allocation = WorkerAllocation {
             food: worker_days * Decimal::from_f64(food_urgency / total).unwrap(),
             wood: worker_days * Decimal::from_f64(wood_urgency / total).unwrap(),
             construction: worker_days * Decimal::from_f64(construction_need / total).unwrap(),
+            processing: dec!(0),
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: worker_days * Decimal::from_f64(repair_need / total).unwrap(),
         };
 
         // Moderate trading
@@ -754,32 +1187,52 @@ impl Strategy for BalancedStrategy {
 
         // Buy if below target buffer
         if food_days < 15 && village.money > dec!(30) {
-            let quantity = ((15 - food_days) * village.workers as u32).min(50);
-            let price = calculate_food_bid_price(market.last_food_price, dec!(1.05));
-            food_bid = Some((price, quantity));
+            let price = calculate_food_bid_price(market, desperation_multiplier(food_days, 15));
+            let quantity = self.sizing.bid_quantity(
+                ResourceType::Food,
+                village.food,
+                food_per_day,
+                15,
+                village.money,
+                price,
+            );
+            if quantity > 0 {
+                food_bid = Some((price, quantity));
+            }
         }
 
         if wood_days < 15 && village.money > dec!(30) {
-            let quantity = (15 - wood_days).min(20);
-            let price = calculate_wood_bid_price(market.last_wood_price, dec!(1.05));
-            wood_bid = Some((price, quantity));
+            let price = calculate_wood_bid_price(market, desperation_multiplier(wood_days, 15));
+            let quantity = self.sizing.bid_quantity(
+                ResourceType::Wood,
+                village.wood,
+                wood_per_day,
+                15,
+                village.money,
+                price,
+            );
+            if quantity > 0 {
+                wood_bid = Some((price, quantity));
+            }
         }
 
         // Sell if above target buffer
         if food_days > 30 {
-            let excess = village.food - dec!(20) * food_per_day;
-            let quantity = (excess * dec!(0.5)).to_u32().unwrap_or(0).min(50);
+            let quantity =
+                self.sizing
+                    .ask_quantity(ResourceType::Food, village.food, dec!(20) * food_per_day);
             if quantity > 0 {
-                let price = calculate_food_ask_price(market.last_food_price, dec!(0.95));
+                let price = calculate_food_ask_price(market, dec!(0.95));
                 food_ask = Some((price, quantity));
             }
         }
 
         if wood_days > 30 {
-            let excess = village.wood - dec!(20) * wood_per_day;
-            let quantity = (excess * dec!(0.5)).to_u32().unwrap_or(0).min(20);
+            let quantity =
+                self.sizing
+                    .ask_quantity(ResourceType::Wood, village.wood, dec!(20) * wood_per_day);
             if quantity > 0 {
-                let price = calculate_wood_ask_price(market.last_wood_price, dec!(0.95));
+                let price = calculate_wood_ask_price(market, dec!(0.95));
                 wood_ask = Some((price, quantity));
             }
         }
@@ -790,6 +1243,7 @@ impl Strategy for BalancedStrategy {
             wood_ask,
             food_bid,
             food_ask,
+            house_upgrade_wood: dec!(0),
         }
     }
 }
@@ -798,19 +1252,49 @@ impl Strategy for BalancedStrategy {
 /// Maximizes immediate production value with no long-term planning.
 ///
 /// # Philosophy
-/// Allocates all workers to highest-value resource based on market prices.
-/// No construction. Emergency trades only at premium prices. Sells all
-/// surplus aggressively.
+/// Allocates workers to the highest-value resource based on market prices,
+/// up to its slot capacity; the rest go to the other resource, and only labor
+/// neither can use goes to construction. Emergency trades only at premium
+/// prices. Sells all surplus aggressively.
 ///
 /// # Performance
 /// - **Excels**: Short games, price volatility exploitation, pure production
 /// - **Struggles**: Long-term sustainability, population growth, market downturns
 ///
 /// # Trade Behavior
-/// - Buys only in emergencies at 150% market price
+/// - Buys only in emergencies, at up to `MAX_DESPERATION_MULTIPLIER` times market price
 /// - Sells all surplus at 80% market price
-#[derive(Default)]
-pub struct GreedyStrategy;
+///
+/// # Parameters
+/// - `sizing`: Order sizes (default: spend all money, sell all surplus, and
+///   restock wood to 10)
+pub struct GreedyStrategy {
+    sizing: OrderSizing,
+}
+
+/// Greedy spends freely and sells everything above a one-day reserve.
+const GREEDY_SIZING: OrderSizing = OrderSizing {
+    max_money_fraction: dec!(1),
+    sell_fraction: dec!(1),
+    max_food_quantity: 100,
+    max_wood_quantity: 50,
+    wood_stock_target: 10,
+};
+
+impl GreedyStrategy {
+    pub fn with_sizing(mut self, sizing: OrderSizing) -> Self {
+        self.sizing = sizing;
+        self
+    }
+}
+
+impl Default for GreedyStrategy {
+    fn default() -> Self {
+        Self {
+            sizing: GREEDY_SIZING,
+        }
+    }
+}
 
 impl Strategy for GreedyStrategy {
     fn name(&self) -> &str {
@@ -818,29 +1302,39 @@ impl Strategy for GreedyStrategy {
     }
 
     fn decide_allocation_and_orders(
-        &self,
+        &mut self,
         village: &VillageState,
         market: &MarketState,
     ) -> StrategyDecision {
-        let worker_days = village.worker_days;
+        let toolmaking = village.toolmaking_days();
+        let worker_days = village.worker_days - toolmaking;
 
         // Calculate which resource gives more immediate value
-        let food_value = dec!(2.0) * market.last_food_price.unwrap_or(dec!(1.0));
-        let wood_value = dec!(0.1) * market.last_wood_price.unwrap_or(dec!(5.0));
-
-        // Allocate everything to highest value production
-        let allocation = if food_value > wood_value {
-            WorkerAllocation {
-                wood: dec!(0),
-                food: worker_days,
-                construction: dec!(0),
-            }
+        let food_value = dec!(2.0) * village.food_quality * market.food_price();
+        let wood_value = dec!(0.1) * village.wood_quality * market.wood_price();
+
+        // Fill the highest value resource's slots first, then the other's
+        let (primary, secondary) = if food_value > wood_value {
+            (ResourceType::Food, ResourceType::Wood)
         } else {
-            WorkerAllocation {
-                wood: worker_days,
-                food: dec!(0),
-                construction: dec!(0),
-            }
+            (ResourceType::Wood, ResourceType::Food)
+        };
+        let primary_days = worker_days.min(village.optimal_worker_days(primary));
+        let secondary_days =
+            (worker_days - primary_days).min(village.optimal_worker_days(secondary));
+        let (food, wood) = if primary == ResourceType::Food {
+            (primary_days, secondary_days)
+        } else {
+            (secondary_days, primary_days)
+        };
+        let allocation = WorkerAllocation {
+            wood,
+            food,
+            construction: worker_days - primary_days - secondary_days,
+            processing: toolmaking,
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
         };
 
         // Only trade when desperate
@@ -851,33 +1345,56 @@ impl Strategy for GreedyStrategy {
 
         // Emergency buying only
         if village.food < Decimal::from(village.workers) && village.money > dec!(10) {
-            let quantity = (village.workers as u32 * 5).min(50);
-            let price = calculate_food_bid_price(market.last_food_price, dec!(1.5)); // Will pay premium
-            food_bid = Some((price, quantity));
+            let food_per_day = Decimal::from(village.workers);
+            let food_days = calculate_resource_days(village.food, food_per_day);
+            let price = calculate_food_bid_price(market, desperation_multiplier(food_days, 1));
+            let quantity = self.sizing.bid_quantity(
+                ResourceType::Food,
+                village.food,
+                food_per_day,
+                5,
+                village.money,
+                price,
+            );
+            if quantity > 0 {
+                food_bid = Some((price, quantity));
+            }
         }
 
         if village.wood < dec!(1) && village.houses > 0 && village.money > dec!(10) {
-            let quantity = 10u32;
-            let price = calculate_wood_bid_price(market.last_wood_price, dec!(1.5));
-            wood_bid = Some((price, quantity));
+            let wood_days =
+                calculate_resource_days(village.wood, Decimal::from(village.houses) * dec!(0.1));
+            let price = calculate_wood_bid_price(market, desperation_multiplier(wood_days, 10));
+            let quantity = self.sizing.bid_for_shortfall(
+                ResourceType::Wood,
+                Decimal::from(self.sizing.wood_stock_target) - village.wood,
+                village.money,
+                price,
+            );
+            if quantity > 0 {
+                wood_bid = Some((price, quantity));
+            }
         }
 
         // Sell everything we can
         if village.food > Decimal::from(village.workers * 2) {
-            let quantity = (village.food - Decimal::from(village.workers))
-                .to_u32()
-                .unwrap_or(0)
-                .min(100);
+            let quantity = self.sizing.ask_quantity(
+                ResourceType::Food,
+                village.food,
+                Decimal::from(village.workers),
+            );
             if quantity > 0 {
-                let price = calculate_food_ask_price(market.last_food_price, dec!(0.8)); // Will sell cheap
+                let price = calculate_food_ask_price(market, dec!(0.8)); // Will sell cheap
                 food_ask = Some((price, quantity));
             }
         }
 
         if village.wood > dec!(2) {
-            let quantity = (village.wood - dec!(1)).to_u32().unwrap_or(0).min(50);
+            let quantity = self
+                .sizing
+                .ask_quantity(ResourceType::Wood, village.wood, dec!(1));
             if quantity > 0 {
-                let price = calculate_wood_ask_price(market.last_wood_price, dec!(0.8));
+                let price = calculate_wood_ask_price(market, dec!(0.8));
                 wood_ask = Some((price, quantity));
             }
         }
@@ -888,10 +1405,188 @@ impl Strategy for GreedyStrategy {
             wood_ask,
             food_bid,
             food_ask,
+            house_upgrade_wood: dec!(0),
+        }
+    }
+}
+
+// === UNDERCUTTING STRATEGY ===
+/// Allocates like [`BalancedStrategy`] but sells surplus just below the
+/// cheapest resting ask, so its asks fill before the competition's.
+///
+/// # Trade Behavior
+/// - Bids as the balanced strategy does
+/// - Sells food and wood held beyond `buffer_days` of need at the previous
+///   tick's best ask minus `undercut`, or at the market price if nobody was selling
+///
+/// # Parameters
+/// - `sizing`: Order sizes for both its bids and asks (default: the balanced
+///   strategy's bids, and asks that offer the whole surplus)
+pub struct UndercuttingStrategy {
+    inner: BalancedStrategy,
+    undercut: Decimal,
+    buffer_days: u32,
+    sizing: OrderSizing,
+}
+
+impl UndercuttingStrategy {
+    pub fn new(undercut: Decimal, buffer_days: u32) -> Self {
+        Self {
+            inner: BalancedStrategy::default(),
+            undercut,
+            buffer_days,
+            sizing: GREEDY_SIZING,
+        }
+    }
+
+    pub fn with_sizing(mut self, sizing: OrderSizing) -> Self {
+        self.inner.sizing = sizing;
+        self.sizing = sizing;
+        self
+    }
+
+    /// Price just below the best ask other villages posted in `book`, never
+    /// below `undercut` itself. The village's own ask is left out, so it
+    /// doesn't chase its own price down tick after tick.
+    fn ask_price(&self, book: &OrderBook, village_id: &str, market_price: Decimal) -> Decimal {
+        book.without_village(village_id)
+            .best_ask()
+            .map(|best| (best - self.undercut).max(self.undercut))
+            .unwrap_or(market_price)
+    }
+}
+
+impl Default for UndercuttingStrategy {
+    fn default() -> Self {
+        Self::new(dec!(0.01), 15)
+    }
+}
+
+impl Strategy for UndercuttingStrategy {
+    fn name(&self) -> &str {
+        "Undercutting"
+    }
+
+    fn decide_allocation_and_orders(
+        &mut self,
+        village: &VillageState,
+        market: &MarketState,
+    ) -> StrategyDecision {
+        let mut decision = self.inner.decide_allocation_and_orders(village, market);
+        let buffer_days = Decimal::from(self.buffer_days);
+
+        let food_need = Decimal::from(village.workers) * buffer_days;
+        let food_surplus = self
+            .sizing
+            .ask_quantity(ResourceType::Food, village.food, food_need);
+        decision.food_ask = (food_surplus > 0).then(|| {
+            let price = self.ask_price(&market.food_book, &village.id, market.food_price());
+            (price, food_surplus)
+        });
+
+        let wood_need = Decimal::from(village.houses) * dec!(0.1) * buffer_days;
+        let wood_surplus = self
+            .sizing
+            .ask_quantity(ResourceType::Wood, village.wood, wood_need);
+        decision.wood_ask = (wood_surplus > 0).then(|| {
+            let price = self.ask_price(&market.wood_book, &village.id, market.wood_price());
+            (price, wood_surplus)
+        });
+
+        decision
+    }
+}
+
+// === EXPLORING STRATEGY ===
+/// Largest fraction by which [`ExploringStrategy`] scales a decision up or down.
+pub const MAX_EXPLORATION_PERTURBATION: f64 = 0.2;
+
+/// Epsilon-greedy wrapper: follows `inner`, but with probability `epsilon`
+/// perturbs its decision so villages try allocations and prices near it.
+///
+/// # Perturbation
+/// - Food, wood and construction worker-days are each scaled by a random
+///   factor within [`MAX_EXPLORATION_PERTURBATION`], then rescaled to the
+///   original total
+/// - Each order price is scaled by its own random factor; quantities are kept
+///
+/// All draws come from the strategy RNG, so a seeded run explores identically.
+pub struct ExploringStrategy {
+    inner: Box<dyn Strategy>,
+    epsilon: f64,
+    rng: StdRng,
+}
+
+impl ExploringStrategy {
+    pub fn new(inner: Box<dyn Strategy>, epsilon: f64) -> Self {
+        Self {
+            inner,
+            epsilon: epsilon.clamp(0.0, 1.0),
+            rng: StdRng::seed_from_u64(0),
+        }
+    }
+
+    /// Random factor in `1 ± MAX_EXPLORATION_PERTURBATION`.
+    fn factor(&mut self) -> Decimal {
+        let offset = self
+            .rng
+            .random_range(-MAX_EXPLORATION_PERTURBATION..=MAX_EXPLORATION_PERTURBATION);
+        Decimal::from_f64(1.0 + offset)
+            .unwrap_or(Decimal::ONE)
+            .round_dp(3)
+    }
+
+    fn perturb(&mut self, decision: &mut StrategyDecision) {
+        let allocation = &mut decision.allocation;
+        let total = allocation.food + allocation.wood + allocation.construction;
+        let food = allocation.food * self.factor();
+        let wood = allocation.wood * self.factor();
+        let construction = allocation.construction * self.factor();
+        let perturbed_total = food + wood + construction;
+        if perturbed_total > dec!(0) {
+            let scale = total / perturbed_total;
+            allocation.food = food * scale;
+            allocation.wood = wood * scale;
+            allocation.construction = construction * scale;
+        }
+
+        for order in [
+            &mut decision.wood_bid,
+            &mut decision.wood_ask,
+            &mut decision.food_bid,
+            &mut decision.food_ask,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            order.0 *= self.factor();
         }
     }
 }
 
+impl Strategy for ExploringStrategy {
+    fn name(&self) -> &str {
+        "Exploring"
+    }
+
+    fn decide_allocation_and_orders(
+        &mut self,
+        village: &VillageState,
+        market: &MarketState,
+    ) -> StrategyDecision {
+        let mut decision = self.inner.decide_allocation_and_orders(village, market);
+        if self.rng.random_bool(self.epsilon) {
+            self.perturb(&mut decision);
+        }
+        decision
+    }
+
+    fn seed_rng(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.inner.seed_rng(seed);
+    }
+}
+
 // === DEFAULT STRATEGY (legacy) ===
 /// Legacy fixed allocation strategy with no trading.
 ///
@@ -905,7 +1600,7 @@ impl Strategy for DefaultStrategy {
     }
 
     fn decide_allocation_and_orders(
-        &self,
+        &mut self,
         village: &VillageState,
         _market: &MarketState,
     ) -> StrategyDecision {
@@ -913,6 +1608,10 @@ impl Strategy for DefaultStrategy {
             wood: village.worker_days * dec!(0.7),
             food: village.worker_days * dec!(0.2),
             construction: village.worker_days * dec!(0.1),
+            processing: dec!(0),
+            slot_expansion: dec!(0),
+            slot_expansion_target: ResourceType::Food,
+            repair: dec!(0),
         };
 
         StrategyDecision {
@@ -921,6 +1620,7 @@ impl Strategy for DefaultStrategy {
             wood_ask: None,
             food_bid: None,
             food_ask: None,
+            house_upgrade_wood: dec!(0),
         }
     }
 }
@@ -936,30 +1636,94 @@ pub fn create_strategy(config: &StrategyConfig) -> Box<dyn Strategy> {
             wood_weight,
             construction_weight,
             repair_weight,
-        } => Box::new(BalancedStrategy::new(
-            *food_weight,
-            *wood_weight,
-            *construction_weight,
-            *repair_weight,
-        )),
+            sizing,
+        } => {
+            let mut strategy = BalancedStrategy::new(
+                *food_weight,
+                *wood_weight,
+                *construction_weight,
+                *repair_weight,
+            );
+            if let Some(sizing) = sizing {
+                strategy.sizing = *sizing;
+            }
+            Box::new(strategy)
+        }
         StrategyConfig::Survival {
             min_food_days,
             min_shelter_buffer,
-        } => Box::new(SurvivalStrategy::new(
-            *min_food_days as u32,
-            *min_shelter_buffer as u32,
-        )),
+            sizing,
+        } => {
+            let mut strategy =
+                SurvivalStrategy::new(*min_food_days as u32, *min_shelter_buffer as u32);
+            if let Some(sizing) = sizing {
+                strategy.sizing = *sizing;
+            }
+            Box::new(strategy)
+        }
         StrategyConfig::Growth {
             target_population,
             house_buffer,
-        } => Box::new(GrowthStrategy::new(*target_population, *house_buffer)),
+            sizing,
+        } => {
+            let mut strategy = GrowthStrategy::new(*target_population, *house_buffer);
+            if let Some(sizing) = sizing {
+                strategy.sizing = *sizing;
+            }
+            Box::new(strategy)
+        }
         StrategyConfig::Trading {
             price_multiplier,
             max_trade_fraction,
-        } => Box::new(TradingStrategy::new(*price_multiplier, *max_trade_fraction)),
+            sizing,
+        } => {
+            let mut strategy = TradingStrategy::new(*price_multiplier, *max_trade_fraction);
+            if let Some(sizing) = sizing {
+                strategy.sizing = *sizing;
+            }
+            Box::new(strategy)
+        }
+        StrategyConfig::Greedy { sizing } => {
+            let mut strategy = GreedyStrategy::default();
+            if let Some(sizing) = sizing {
+                strategy = strategy.with_sizing(*sizing);
+            }
+            Box::new(strategy)
+        }
+        StrategyConfig::Undercutting {
+            undercut,
+            buffer_days,
+            sizing,
+        } => {
+            let mut strategy = UndercuttingStrategy::new(*undercut, *buffer_days);
+            if let Some(sizing) = sizing {
+                strategy = strategy.with_sizing(*sizing);
+            }
+            Box::new(strategy)
+        }
     }
 }
 
+/// Strategy names recognized by [`create_strategy_by_name`].
+pub const STRATEGY_NAMES: &[&str] = &[
+    "default",
+    "survival",
+    "growth",
+    "trading",
+    "balanced",
+    "greedy",
+    "undercutting",
+    "exploring",
+];
+
+/// Returns true if `name` (case-insensitive) refers to a known strategy.
+///
+/// `create_strategy_by_name` silently falls back to the default strategy,
+/// so callers should check names with this first when input comes from users.
+pub fn is_known_strategy(name: &str) -> bool {
+    STRATEGY_NAMES.contains(&name.to_lowercase().as_str())
+}
+
 /// Create a strategy by name.
 ///
 /// Used by CLI and testing to create strategies dynamically.
@@ -970,7 +1734,12 @@ pub fn create_strategy_by_name(name: &str) -> Box<dyn Strategy> {
         "growth" => Box::new(GrowthStrategy::default()),
         "trading" => Box::new(TradingStrategy::default()),
         "balanced" => Box::new(BalancedStrategy::default()),
-        "greedy" => Box::new(GreedyStrategy),
+        "greedy" => Box::new(GreedyStrategy::default()),
+        "undercutting" => Box::new(UndercuttingStrategy::default()),
+        "exploring" => Box::new(ExploringStrategy::new(
+            Box::new(BalancedStrategy::default()),
+            0.1,
+        )),
         _ => Box::new(DefaultStrategy),
     }
 }
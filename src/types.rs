@@ -17,6 +17,7 @@ impl ResourceTypeExt for ResourceType {
         match self {
             ResourceType::Wood => "wood",
             ResourceType::Food => "food",
+            ResourceType::Labor => "labor",
         }
     }
 
@@ -24,11 +25,24 @@ impl ResourceTypeExt for ResourceType {
         match s {
             "wood" => Some(ResourceType::Wood),
             "food" => Some(ResourceType::Food),
+            "labor" => Some(ResourceType::Labor),
             _ => None,
         }
     }
 }
 
+/// How long an order stays in the market
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeInForce {
+    /// Fills what it can; an unfilled remainder may rest into later auction
+    /// rounds of the tick
+    #[default]
+    GoodTillCancel,
+    /// Fills completely in the auction it is submitted to or is cancelled,
+    /// never resting
+    FillOrKill,
+}
+
 /// A request to place an order in the market
 #[derive(Debug, Clone)]
 pub struct OrderRequest {
@@ -36,6 +50,15 @@ pub struct OrderRequest {
     pub is_buy: bool,
     pub quantity: u32,
     pub price: Decimal,
+    /// Fill the whole quantity or not at all
+    pub all_or_nothing: bool,
+    pub time_in_force: TimeInForce,
+    /// Queue position among same-price orders; lower fills first.
+    /// `None` queues at the current tick, after explicit earlier priorities.
+    pub priority: Option<u64>,
+    /// Shown quantity of an iceberg order; the rest stays hidden until the
+    /// shown part fills. `None` shows the whole quantity.
+    pub visible_quantity: Option<u32>,
 }
 
 /// Unique identifier for a village
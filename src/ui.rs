@@ -19,7 +19,26 @@ use ratatui::{
 use rust_decimal::Decimal;
 use rust_decimal::prelude::ToPrimitive;
 
-use crate::events::{DeathCause, Event as SimEvent, EventLogger, EventType};
+use crate::events::{DeathCause, Event as SimEvent, EventLogger, EventType, ResourceType};
+use crate::query::event_matches_type;
+
+/// Breakpoints the `b` key cycles through before turning breakpoints off
+const BREAKPOINT_PRESETS: [&str; 3] = ["starvation", "died", "completed"];
+
+/// Whether an event satisfies a breakpoint. Uses the same type names as
+/// `query --event-type`, plus `starvation` for starvation deaths only.
+fn breakpoint_matches(event_type: &EventType, breakpoint: &str) -> bool {
+    if breakpoint.to_lowercase().contains("starv") {
+        return matches!(
+            event_type,
+            EventType::WorkerDied {
+                cause: DeathCause::Starvation,
+                ..
+            }
+        );
+    }
+    event_matches_type(event_type, breakpoint)
+}
 
 /// UI mode for viewing simulation data
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -99,11 +118,63 @@ struct VillageState {
     last_wood_trade: Option<(Decimal, Decimal)>, // (amount, price)
 }
 
+impl VillageState {
+    /// Production history for `resource`; labor has none
+    fn history(&self, resource: ResourceType) -> Option<&ResourceHistory> {
+        match resource {
+            ResourceType::Food => Some(&self.food_history),
+            ResourceType::Wood => Some(&self.wood_history),
+            ResourceType::Labor => None,
+        }
+    }
+
+    fn history_mut(&mut self, resource: ResourceType) -> Option<&mut ResourceHistory> {
+        match resource {
+            ResourceType::Food => Some(&mut self.food_history),
+            ResourceType::Wood => Some(&mut self.wood_history),
+            ResourceType::Labor => None,
+        }
+    }
+}
+
+fn resource_icon(resource: ResourceType) -> &'static str {
+    match resource {
+        ResourceType::Food => "🌾",
+        ResourceType::Wood => "🪵",
+        ResourceType::Labor => "👷",
+    }
+}
+
+/// Whole-economy totals reconstructed from events
+#[derive(Debug, Default)]
+struct EconomyAggregate {
+    population: usize,
+    food: Decimal,
+    wood: Decimal,
+    money: Decimal,
+    trades: usize,     // Buy-side trades executed on `trade_tick`
+    trade_tick: usize, // Tick the trade count refers to
+    wood_price: Option<Decimal>,
+    food_price: Option<Decimal>,
+}
+
+impl EconomyAggregate {
+    /// Number of trades executed on the given tick
+    fn trades_at(&self, tick: usize) -> usize {
+        if self.trade_tick == tick {
+            self.trades
+        } else {
+            0
+        }
+    }
+}
+
 /// Main UI state
 pub struct UIState {
     events: Vec<SimEvent>,
     current_tick: usize,
     villages: HashMap<String, VillageState>,
+    economy: EconomyAggregate,
     #[allow(dead_code)]
     mode: UIMode,
     seconds_per_tick: f32, // Changed from playback_speed
@@ -111,6 +182,7 @@ pub struct UIState {
     recent_events: Vec<String>, // Formatted event strings
     paused: bool,
     last_tick_time: Instant,
+    breakpoint: Option<String>, // Event type that pauses playback
 }
 
 impl UIState {
@@ -121,12 +193,14 @@ impl UIState {
             events,
             current_tick: 0,
             villages: HashMap::new(),
+            economy: EconomyAggregate::default(),
             mode: UIMode::Replay,
             seconds_per_tick: 0.5, // 2 ticks per second default
             max_tick,
             recent_events: Vec::new(),
             paused: false,
             last_tick_time: Instant::now(),
+            breakpoint: None,
         };
 
         // Process all events up to tick 0 to get initial state
@@ -170,6 +244,13 @@ impl UIState {
                 wood,
                 money,
             } => {
+                // Replace this village's previous contribution to the totals
+                self.economy.population =
+                    self.economy.population + *population - village.population;
+                self.economy.food += *food - village.food;
+                self.economy.wood += *wood - village.wood;
+                self.economy.money += *money - village.money;
+
                 village.population = *population;
                 village.houses = *houses;
                 village.food = *food;
@@ -190,24 +271,18 @@ impl UIState {
             }
             EventType::ResourceProduced {
                 resource, amount, ..
-            } => match resource {
-                crate::events::ResourceType::Food => {
-                    village.food_history.record_production(*amount);
-                }
-                crate::events::ResourceType::Wood => {
-                    village.wood_history.record_production(*amount);
+            } => {
+                if let Some(history) = village.history_mut(*resource) {
+                    history.record_production(*amount);
                 }
-            },
+            }
             EventType::ResourceConsumed {
                 resource, amount, ..
-            } => match resource {
-                crate::events::ResourceType::Food => {
-                    village.food_history.record_consumption(*amount);
-                }
-                crate::events::ResourceType::Wood => {
-                    village.wood_history.record_consumption(*amount);
+            } => {
+                if let Some(history) = village.history_mut(*resource) {
+                    history.record_consumption(*amount);
                 }
-            },
+            }
             EventType::WorkerBorn { .. } => {
                 village.last_birth = Some(event.tick);
             }
@@ -225,25 +300,43 @@ impl UIState {
                 side,
                 ..
             } => {
+                if matches!(side, crate::events::TradeSide::Buy) {
+                    if self.economy.trade_tick != event.tick {
+                        self.economy.trade_tick = event.tick;
+                        self.economy.trades = 0;
+                    }
+                    self.economy.trades += 1;
+                }
+
                 let signed_quantity = match side {
                     crate::events::TradeSide::Buy => *quantity,
                     crate::events::TradeSide::Sell => -*quantity,
                 };
                 match resource {
-                    crate::events::ResourceType::Food => {
+                    ResourceType::Food => {
                         village.last_food_trade = Some((signed_quantity, *price));
                     }
-                    crate::events::ResourceType::Wood => {
+                    ResourceType::Wood => {
                         village.last_wood_trade = Some((signed_quantity, *price));
                     }
+                    ResourceType::Labor => {}
                 }
             }
+            EventType::AuctionCleared {
+                wood_price,
+                food_price,
+                ..
+            } => {
+                self.economy.wood_price = *wood_price;
+                self.economy.food_price = *food_price;
+            }
             _ => {}
         }
     }
 
-    /// Advance simulation by one tick
-    fn step_forward(&mut self) {
+    /// Advance simulation by one tick, returning whether an event on it hit the breakpoint
+    fn step_forward(&mut self) -> bool {
+        let mut hit = false;
         if self.current_tick < self.max_tick {
             let next_tick = self.current_tick + 1;
 
@@ -251,11 +344,37 @@ impl UIState {
             for event in &self.events.clone() {
                 if event.tick == next_tick {
                     self.process_event(event);
+                    hit |= self
+                        .breakpoint
+                        .as_deref()
+                        .is_some_and(|bp| breakpoint_matches(&event.event_type, bp));
                 }
             }
 
             self.current_tick = next_tick;
         }
+        hit
+    }
+
+    /// Auto-advance one tick of playback, pausing if the breakpoint is hit
+    fn advance_playback(&mut self) {
+        if self.step_forward() {
+            self.paused = true;
+        }
+        self.last_tick_time = Instant::now();
+    }
+
+    /// Cycle the breakpoint through the presets and off
+    fn cycle_breakpoint(&mut self) {
+        let next = match self.breakpoint.as_deref() {
+            None => Some(BREAKPOINT_PRESETS[0]),
+            Some(current) => BREAKPOINT_PRESETS
+                .iter()
+                .position(|p| *p == current)
+                .and_then(|i| BREAKPOINT_PRESETS.get(i + 1))
+                .copied(),
+        };
+        self.breakpoint = next.map(str::to_string);
     }
 
     /// Go back one tick
@@ -263,6 +382,7 @@ impl UIState {
         if self.current_tick > 0 {
             // Clear state and replay from beginning
             self.villages.clear();
+            self.economy = EconomyAggregate::default();
             self.recent_events.clear();
             let target = self.current_tick - 1;
             self.current_tick = 0;
@@ -274,6 +394,7 @@ impl UIState {
     fn jump_to_tick(&mut self, tick: usize) {
         if tick <= self.max_tick {
             self.villages.clear();
+            self.economy = EconomyAggregate::default();
             self.recent_events.clear();
             self.current_tick = 0;
             self.process_events_to_tick(tick);
@@ -281,8 +402,8 @@ impl UIState {
     }
 }
 
-/// Run the UI event viewer
-pub fn run_ui(event_file: &str) -> io::Result<()> {
+/// Run the UI event viewer, optionally pausing whenever an event matches `break_on`
+pub fn run_ui(event_file: &str, break_on: Option<&str>) -> io::Result<()> {
     // Load events from file
     let events = if Path::new(event_file).exists() {
         EventLogger::load_from_file(event_file)?
@@ -311,6 +432,7 @@ pub fn run_ui(event_file: &str) -> io::Result<()> {
 
     // Create UI state
     let mut ui_state = UIState::new(events);
+    ui_state.breakpoint = break_on.map(str::to_string);
 
     // Main loop
     let res = run_app(&mut terminal, &mut ui_state);
@@ -338,38 +460,37 @@ fn run_app<B: ratatui::backend::Backend>(
     loop {
         terminal.draw(|f| draw_ui(f, ui_state))?;
 
-        if event::poll(Duration::from_millis(50))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Char(' ') => ui_state.paused = !ui_state.paused,
-                        KeyCode::Right => {
-                            ui_state.step_forward();
-                            ui_state.last_tick_time = Instant::now();
-                        }
-                        KeyCode::Left => {
-                            ui_state.step_backward();
-                            ui_state.last_tick_time = Instant::now();
-                        }
-                        KeyCode::Home => {
-                            ui_state.jump_to_tick(0);
-                            ui_state.last_tick_time = Instant::now();
-                        }
-                        KeyCode::End => {
-                            ui_state.jump_to_tick(ui_state.max_tick);
-                            ui_state.last_tick_time = Instant::now();
-                        }
-                        KeyCode::Char('+') | KeyCode::Char('=') => {
-                            ui_state.seconds_per_tick =
-                                (ui_state.seconds_per_tick / 2.0).max(0.0625); // Max 16 ticks/sec
-                        }
-                        KeyCode::Char('-') => {
-                            ui_state.seconds_per_tick = (ui_state.seconds_per_tick * 2.0).min(4.0); // Min 0.25 ticks/sec
-                        }
-                        _ => {}
-                    }
+        if event::poll(Duration::from_millis(50))?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            match key.code {
+                KeyCode::Char('q') => return Ok(()),
+                KeyCode::Char(' ') => ui_state.paused = !ui_state.paused,
+                KeyCode::Char('b') => ui_state.cycle_breakpoint(),
+                KeyCode::Right => {
+                    ui_state.step_forward();
+                    ui_state.last_tick_time = Instant::now();
+                }
+                KeyCode::Left => {
+                    ui_state.step_backward();
+                    ui_state.last_tick_time = Instant::now();
+                }
+                KeyCode::Home => {
+                    ui_state.jump_to_tick(0);
+                    ui_state.last_tick_time = Instant::now();
                 }
+                KeyCode::End => {
+                    ui_state.jump_to_tick(ui_state.max_tick);
+                    ui_state.last_tick_time = Instant::now();
+                }
+                KeyCode::Char('+') | KeyCode::Char('=') => {
+                    ui_state.seconds_per_tick = (ui_state.seconds_per_tick / 2.0).max(0.0625); // Max 16 ticks/sec
+                }
+                KeyCode::Char('-') => {
+                    ui_state.seconds_per_tick = (ui_state.seconds_per_tick * 2.0).min(4.0); // Min 0.25 ticks/sec
+                }
+                _ => {}
             }
         }
 
@@ -377,8 +498,7 @@ fn run_app<B: ratatui::backend::Backend>(
         if !ui_state.paused && ui_state.current_tick < ui_state.max_tick {
             let elapsed = ui_state.last_tick_time.elapsed().as_secs_f32();
             if elapsed >= ui_state.seconds_per_tick {
-                ui_state.step_forward();
-                ui_state.last_tick_time = Instant::now();
+                ui_state.advance_playback();
             }
         }
     }
@@ -389,6 +509,7 @@ fn draw_ui(f: &mut Frame, ui_state: &UIState) {
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),  // Header
+            Constraint::Length(3),  // Economy summary
             Constraint::Min(10),    // Main content
             Constraint::Length(10), // Event log
             Constraint::Length(1),  // Footer
@@ -402,11 +523,18 @@ fn draw_ui(f: &mut Frame, ui_state: &UIState) {
         format!("{:.1} ticks/s", 1.0 / ui_state.seconds_per_tick)
     };
 
+    let breakpoint_display = ui_state
+        .breakpoint
+        .as_ref()
+        .map(|bp| format!(" - Break on: {bp}"))
+        .unwrap_or_default();
+
     let header = Paragraph::new(format!(
-        "Village Simulation Viewer - Day {}/{} - Speed: {} {}",
+        "Village Simulation Viewer - Day {}/{} - Speed: {}{} {}",
         ui_state.current_tick,
         ui_state.max_tick,
         speed_display,
+        breakpoint_display,
         if ui_state.paused { "[PAUSED]" } else { "" }
     ))
     .style(
@@ -418,6 +546,9 @@ fn draw_ui(f: &mut Frame, ui_state: &UIState) {
     .block(Block::default().borders(Borders::BOTTOM));
     f.render_widget(header, chunks[0]);
 
+    // Economy summary across all villages
+    draw_economy(f, chunks[1], ui_state);
+
     // Main content - villages
     let village_chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -428,7 +559,7 @@ fn draw_ui(f: &mut Frame, ui_state: &UIState) {
                 .map(|_| Constraint::Ratio(1, ui_state.villages.len() as u32))
                 .collect::<Vec<_>>(),
         )
-        .split(chunks[1]);
+        .split(chunks[2]);
 
     for (i, (_, village)) in ui_state.villages.iter().enumerate() {
         draw_village(f, village_chunks[i], village);
@@ -448,13 +579,54 @@ fn draw_ui(f: &mut Frame, ui_state: &UIState) {
                 .title("Recent Events"),
         )
         .style(Style::default().fg(Color::White));
-    f.render_widget(events_list, chunks[2]);
+    f.render_widget(events_list, chunks[3]);
 
     // Footer
-    let footer = Paragraph::new("[Q] Quit  [Space] Pause  [←→] Step  [Home/End] Jump  [+/-] Speed")
-        .style(Style::default().fg(Color::DarkGray))
-        .alignment(Alignment::Center);
-    f.render_widget(footer, chunks[3]);
+    let footer = Paragraph::new(
+        "[Q] Quit  [Space] Pause  [←→] Step  [Home/End] Jump  [+/-] Speed  [B] Breakpoint",
+    )
+    .style(Style::default().fg(Color::DarkGray))
+    .alignment(Alignment::Center);
+    f.render_widget(footer, chunks[4]);
+}
+
+fn draw_economy(f: &mut Frame, area: Rect, ui_state: &UIState) {
+    let economy = &ui_state.economy;
+    let format_price = |price: Option<Decimal>| match price {
+        Some(p) => format!("{:.2}", p),
+        None => "-".to_string(),
+    };
+
+    let summary = Line::from(vec![
+        Span::raw("👥 "),
+        Span::styled(
+            format!("{}", economy.population),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!("  🌾 {:.1}", economy.food)),
+        Span::raw(format!("  🪵 {:.1}", economy.wood)),
+        Span::styled(
+            format!("  💰 {:.0}", economy.money),
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::raw(format!(
+            "  Trades: {}",
+            economy.trades_at(ui_state.current_tick)
+        )),
+        Span::styled(
+            format!(
+                "  Prices 🪵 {} 🌾 {}",
+                format_price(economy.wood_price),
+                format_price(economy.food_price)
+            ),
+            Style::default().fg(Color::Cyan),
+        ),
+    ]);
+
+    let economy_para = Paragraph::new(summary)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title(" Economy "));
+    f.render_widget(economy_para, area);
 }
 
 fn draw_village(f: &mut Frame, area: Rect, village: &VillageState) {
@@ -601,85 +773,50 @@ fn draw_village(f: &mut Frame, area: Rect, village: &VillageState) {
     let workers_para = Paragraph::new(workers);
     f.render_widget(workers_para, inner_chunks[2]);
 
-    // Production info
-    let food_avg_10 = village.food_history.avg_production(10);
-    let food_avg_50 = village.food_history.avg_production(50);
-    let wood_avg_10 = village.wood_history.avg_production(10);
-    let wood_avg_50 = village.wood_history.avg_production(50);
-
-    let food_cons_avg = village.food_history.avg_consumption(10);
-    let wood_cons_avg = village.wood_history.avg_consumption(10);
-
-    // Calculate net production
-    let food_net = food_avg_10 - food_cons_avg;
-    let wood_net = wood_avg_10 - wood_cons_avg;
-
-    let production_info = vec![
-        Line::from(Span::styled(
-            "Production (last→10→50):",
-            Style::default().add_modifier(Modifier::UNDERLINED),
-        )),
-        Line::from(vec![
-            Span::raw("  🌾 "),
-            Span::styled(
-                format!("{:4.1}", village.food_history.last_production),
-                Style::default().fg(Color::White),
-            ),
-            Span::styled(" → ", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                format!("{:4.1}", food_avg_10),
-                Style::default().fg(Color::Gray),
-            ),
-            Span::styled(" → ", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                format!("{:4.1}", food_avg_50),
-                Style::default().fg(Color::DarkGray),
-            ),
-        ]),
-        Line::from(vec![
-            Span::raw("  🪵 "),
+    // Production info, one line per resource with a history
+    let mut production_info = vec![Line::from(Span::styled(
+        "Production (last→10→50):",
+        Style::default().add_modifier(Modifier::UNDERLINED),
+    ))];
+    let mut net_spans = vec![Span::styled(
+        "Net/tick (10 avg):",
+        Style::default().add_modifier(Modifier::UNDERLINED),
+    )];
+    for &resource in ResourceType::all() {
+        let Some(history) = village.history(resource) else {
+            continue;
+        };
+        let icon = resource_icon(resource);
+        let net = history.avg_production(10) - history.avg_consumption(10);
+        production_info.push(Line::from(vec![
+            Span::raw(format!("  {} ", icon)),
             Span::styled(
-                format!("{:4.1}", village.wood_history.last_production),
+                format!("{:4.1}", history.last_production),
                 Style::default().fg(Color::White),
             ),
             Span::styled(" → ", Style::default().fg(Color::DarkGray)),
             Span::styled(
-                format!("{:4.1}", wood_avg_10),
+                format!("{:4.1}", history.avg_production(10)),
                 Style::default().fg(Color::Gray),
             ),
             Span::styled(" → ", Style::default().fg(Color::DarkGray)),
             Span::styled(
-                format!("{:4.1}", wood_avg_50),
+                format!("{:4.1}", history.avg_production(50)),
                 Style::default().fg(Color::DarkGray),
             ),
-        ]),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled(
-                "Net/tick (10 avg):",
-                Style::default().add_modifier(Modifier::UNDERLINED),
-            ),
-            Span::raw("  "),
-            Span::raw("🌾 "),
-            Span::styled(
-                format!("{:+4.1}", food_net),
-                Style::default().fg(if food_net >= Decimal::ZERO {
-                    Color::Green
-                } else {
-                    Color::Red
-                }),
-            ),
-            Span::raw("  🪵 "),
-            Span::styled(
-                format!("{:+4.1}", wood_net),
-                Style::default().fg(if wood_net >= Decimal::ZERO {
-                    Color::Green
-                } else {
-                    Color::Red
-                }),
-            ),
-        ]),
-    ];
+        ]));
+        net_spans.push(Span::raw(format!("  {} ", icon)));
+        net_spans.push(Span::styled(
+            format!("{:+4.1}", net),
+            Style::default().fg(if net >= Decimal::ZERO {
+                Color::Green
+            } else {
+                Color::Red
+            }),
+        ));
+    }
+    production_info.push(Line::from(""));
+    production_info.push(Line::from(net_spans));
     let production_para = Paragraph::new(production_info);
     f.render_widget(production_para, inner_chunks[3]);
 
@@ -718,6 +855,8 @@ fn draw_village(f: &mut Frame, area: Rect, village: &VillageState) {
             let death_text = match cause {
                 DeathCause::Starvation => "💀 Starved",
                 DeathCause::NoShelter => "🥶 No shelter",
+                DeathCause::OldAge => "🕯️ Old age",
+                DeathCause::Departed => "🚶 Departed",
             };
             recent_info.push(Line::from(Span::styled(
                 death_text,
@@ -744,3 +883,183 @@ fn draw_village(f: &mut Frame, area: Rect, village: &VillageState) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::TradeSide;
+    use rust_decimal_macros::dec;
+
+    fn snapshot(population: usize, food: Decimal, wood: Decimal, money: Decimal) -> EventType {
+        EventType::VillageStateSnapshot {
+            population,
+            houses: 1,
+            food,
+            wood,
+            money,
+        }
+    }
+
+    #[test]
+    fn test_economy_totals_across_villages() {
+        let mut logger = EventLogger::new();
+        logger.log(
+            0,
+            "a".to_string(),
+            snapshot(5, dec!(50), dec!(10), dec!(100)),
+        );
+        logger.log(0, "b".to_string(), snapshot(3, dec!(20), dec!(4), dec!(60)));
+        logger.log(
+            0,
+            "a".to_string(),
+            EventType::TradeExecuted {
+                resource: ResourceType::Wood,
+                quantity: dec!(2),
+                price: dec!(5),
+                counterparty: "market".to_string(),
+                side: TradeSide::Buy,
+            },
+        );
+        logger.log(
+            0,
+            "market".to_string(),
+            EventType::AuctionCleared {
+                wood_price: Some(dec!(5)),
+                food_price: None,
+                wood_volume: 2,
+                food_volume: 0,
+                total_participants: 2,
+            },
+        );
+        // A later snapshot replaces, rather than adds to, village a's contribution
+        logger.log(
+            1,
+            "a".to_string(),
+            snapshot(6, dec!(45), dec!(12), dec!(90)),
+        );
+
+        let mut ui = UIState::new(logger.get_events().to_vec());
+        assert_eq!(ui.economy.population, 8);
+        assert_eq!(ui.economy.food, dec!(70));
+        assert_eq!(ui.economy.wood, dec!(14));
+        assert_eq!(ui.economy.money, dec!(160));
+        assert_eq!(ui.economy.trades_at(0), 1);
+        assert_eq!(ui.economy.wood_price, Some(dec!(5)));
+
+        ui.step_forward();
+        assert_eq!(ui.economy.population, 9);
+        assert_eq!(ui.economy.food, dec!(65));
+        assert_eq!(ui.economy.money, dec!(150));
+        assert_eq!(ui.economy.trades_at(1), 0);
+
+        ui.step_backward();
+        assert_eq!(ui.economy.population, 8);
+    }
+
+    #[test]
+    fn test_production_is_recorded_for_every_resource_with_a_history() {
+        let mut logger = EventLogger::new();
+        for (i, &resource) in ResourceType::all().iter().enumerate() {
+            logger.log(
+                0,
+                "a".to_string(),
+                EventType::ResourceProduced {
+                    resource,
+                    amount: Decimal::from(i + 1),
+                    workers_assigned: 1,
+                },
+            );
+        }
+
+        let ui = UIState::new(logger.get_events().to_vec());
+        let village = &ui.villages["a"];
+        for (i, &resource) in ResourceType::all().iter().enumerate() {
+            match village.history(resource) {
+                Some(history) => assert_eq!(history.last_production, Decimal::from(i + 1)),
+                None => assert_eq!(resource, ResourceType::Labor),
+            }
+        }
+        assert_eq!(village.food_history.production_history.len(), 1);
+        assert_eq!(village.wood_history.production_history.len(), 1);
+    }
+
+    #[test]
+    fn test_compacted_log_replays_to_the_same_state() {
+        use crate::query::{CompactOptions, compact_events};
+
+        let mut logger = EventLogger::new();
+        for tick in 0..3 {
+            logger.log(
+                tick,
+                "a".to_string(),
+                snapshot(3, dec!(5), dec!(5), dec!(10)),
+            );
+        }
+        logger.log(3, "a".to_string(), snapshot(4, dec!(2), dec!(5), dec!(10)));
+        let events = logger.get_events().to_vec();
+
+        let compacted = compact_events(&events, &CompactOptions::default());
+        let snapshot_ticks: Vec<usize> = compacted.iter().map(|e| e.tick).collect();
+        assert_eq!(snapshot_ticks, vec![0, 3]);
+
+        let mut full = UIState::new(events);
+        let mut compact = UIState::new(compacted);
+        for tick in 0..=3 {
+            full.jump_to_tick(tick);
+            compact.jump_to_tick(tick);
+            let (a, b) = (&full.villages["a"], &compact.villages["a"]);
+            assert_eq!(
+                (a.population, a.food, a.wood, a.money, a.houses),
+                (b.population, b.food, b.wood, b.money, b.houses)
+            );
+        }
+    }
+
+    #[test]
+    fn test_breakpoint_halts_playback_at_first_match() {
+        let mut logger = EventLogger::new();
+        for tick in 0..=6 {
+            logger.log(
+                tick,
+                "a".to_string(),
+                snapshot(3, dec!(5), dec!(5), dec!(10)),
+            );
+        }
+        let died = |cause| EventType::WorkerDied {
+            worker_id: 1,
+            cause,
+            total_population: 2,
+        };
+        logger.log(2, "a".to_string(), died(DeathCause::NoShelter));
+        logger.log(4, "a".to_string(), died(DeathCause::Starvation));
+        logger.log(5, "a".to_string(), died(DeathCause::Starvation));
+
+        let mut ui = UIState::new(logger.get_events().to_vec());
+        ui.breakpoint = Some("starvation".to_string());
+        while !ui.paused && ui.current_tick < ui.max_tick {
+            ui.advance_playback();
+        }
+        // The shelter death at tick 2 doesn't match; the first starvation does
+        assert!(ui.paused);
+        assert_eq!(ui.current_tick, 4);
+
+        // Any death matches a "died" breakpoint
+        ui.jump_to_tick(0);
+        ui.paused = false;
+        ui.breakpoint = Some("died".to_string());
+        while !ui.paused && ui.current_tick < ui.max_tick {
+            ui.advance_playback();
+        }
+        assert_eq!(ui.current_tick, 2);
+
+        // Without a breakpoint playback runs to the end
+        ui.jump_to_tick(0);
+        ui.paused = false;
+        ui.breakpoint = None;
+        while !ui.paused && ui.current_tick < ui.max_tick {
+            ui.advance_playback();
+        }
+        assert!(!ui.paused);
+        assert_eq!(ui.current_tick, 6);
+    }
+}
@@ -1,9 +1,11 @@
 //! ASCII-based visualization tools for simulation data.
 
 use crate::analysis::{PriceHistory, SimulationAnalysis};
-use crate::events::TradeSide;
+use crate::events::{Event, EventType, TradeSide};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::ToPrimitive;
+use std::collections::BTreeMap;
+use std::str::FromStr;
 
 /// Generate an ASCII price chart.
 pub fn price_chart(price_history: &PriceHistory, width: usize, height: usize) -> String {
@@ -56,8 +58,8 @@ pub fn price_chart(price_history: &PriceHistory, width: usize, height: usize) ->
     for row in grid.iter_mut().take(height) {
         row[0] = '│';
     }
-    for x in 0..width {
-        grid[height - 1][x] = '─';
+    for cell in grid[height - 1].iter_mut() {
+        *cell = '─';
     }
     grid[height - 1][0] = '└';
 
@@ -77,8 +79,8 @@ pub fn price_chart(price_history: &PriceHistory, width: usize, height: usize) ->
 
     // Add labels
     chart.push_str(&format!("{:>6.2} ┤", max_price));
-    for x in 1..width {
-        chart.push(grid[0][x]);
+    for &ch in grid[0].iter().skip(1) {
+        chart.push(ch);
     }
     chart.push('\n');
 
@@ -228,8 +230,6 @@ pub fn resource_timeline(
     village_id: &str,
     width: usize,
 ) -> String {
-    use crate::events::EventType;
-
     let mut timeline = String::new();
     timeline.push_str(&format!("Resource Timeline: {}\n", village_id));
     timeline.push_str(&"─".repeat(width));
@@ -252,12 +252,14 @@ pub fn resource_timeline(
             } => match resource {
                 crate::events::ResourceType::Food => food_balance += amount,
                 crate::events::ResourceType::Wood => wood_balance += amount,
+                crate::events::ResourceType::Labor => {}
             },
             EventType::ResourceConsumed {
                 resource, amount, ..
             } => match resource {
                 crate::events::ResourceType::Food => food_balance -= amount,
                 crate::events::ResourceType::Wood => wood_balance -= amount,
+                crate::events::ResourceType::Labor => {}
             },
             EventType::TradeExecuted {
                 resource,
@@ -271,6 +273,7 @@ pub fn resource_timeline(
                     (crate::events::ResourceType::Food, TradeSide::Sell) => food_balance -= qty,
                     (crate::events::ResourceType::Wood, TradeSide::Buy) => wood_balance += qty,
                     (crate::events::ResourceType::Wood, TradeSide::Sell) => wood_balance -= qty,
+                    (crate::events::ResourceType::Labor, _) => {}
                 }
             }
             _ => {}
@@ -406,6 +409,166 @@ pub fn strategy_matrix(analyses: &[SimulationAnalysis]) -> String {
     matrix
 }
 
+/// A quantity [`render_chart`] can plot over time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartMetric {
+    Population,
+    Houses,
+    Food,
+    Wood,
+    Money,
+    /// Clearing prices, one series per resource rather than per village
+    Prices,
+}
+
+impl FromStr for ChartMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "population" => Ok(Self::Population),
+            "houses" => Ok(Self::Houses),
+            "food" => Ok(Self::Food),
+            "wood" => Ok(Self::Wood),
+            "money" => Ok(Self::Money),
+            "prices" | "price" => Ok(Self::Prices),
+            other => Err(format!(
+                "Unknown metric '{}' (expected population, houses, food, wood, money or prices)",
+                other
+            )),
+        }
+    }
+}
+
+/// One named line of a chart: (tick, value) points in tick order.
+pub type ChartSeries = (String, Vec<(usize, f64)>);
+
+/// Collect the series for `metric`, one per village from their state snapshots
+/// or one per resource from cleared auctions for prices.
+pub fn chart_series(events: &[Event], metric: ChartMetric) -> Vec<ChartSeries> {
+    let mut series: BTreeMap<String, Vec<(usize, f64)>> = BTreeMap::new();
+    for event in events {
+        match &event.event_type {
+            EventType::VillageStateSnapshot {
+                population,
+                houses,
+                food,
+                wood,
+                money,
+            } => {
+                let value = match metric {
+                    ChartMetric::Population => *population as f64,
+                    ChartMetric::Houses => *houses as f64,
+                    ChartMetric::Food => food.to_f64().unwrap_or(0.0),
+                    ChartMetric::Wood => wood.to_f64().unwrap_or(0.0),
+                    ChartMetric::Money => money.to_f64().unwrap_or(0.0),
+                    ChartMetric::Prices => continue,
+                };
+                series
+                    .entry(event.village_id.clone())
+                    .or_default()
+                    .push((event.tick, value));
+            }
+            EventType::AuctionCleared {
+                wood_price,
+                food_price,
+                ..
+            } if metric == ChartMetric::Prices => {
+                for (name, price) in [("Wood", wood_price), ("Food", food_price)] {
+                    if let Some(price) = price {
+                        series
+                            .entry(name.to_string())
+                            .or_default()
+                            .push((event.tick, price.to_f64().unwrap_or(0.0)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    series.into_iter().collect()
+}
+
+/// Size in pixels of charts written by [`render_chart`].
+#[cfg(feature = "plot")]
+pub const CHART_SIZE: (u32, u32) = (1024, 640);
+
+/// Draw `metric` over time as a line chart with a legend, writing SVG when
+/// `out_path` ends in `.svg` and PNG otherwise.
+#[cfg(feature = "plot")]
+pub fn render_chart(
+    events: &[Event],
+    metric: ChartMetric,
+    out_path: &std::path::Path,
+) -> Result<(), String> {
+    use plotters::prelude::*;
+
+    let is_svg = out_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+    if is_svg {
+        let root = SVGBackend::new(out_path, CHART_SIZE).into_drawing_area();
+        draw_chart(&root, events, metric)
+    } else {
+        let root = BitMapBackend::new(out_path, CHART_SIZE).into_drawing_area();
+        draw_chart(&root, events, metric)
+    }
+}
+
+#[cfg(feature = "plot")]
+fn draw_chart<DB: plotters::prelude::DrawingBackend>(
+    root: &plotters::prelude::DrawingArea<DB, plotters::coord::Shift>,
+    events: &[Event],
+    metric: ChartMetric,
+) -> Result<(), String> {
+    use plotters::prelude::*;
+
+    let series = chart_series(events, metric);
+    let points = || series.iter().flat_map(|(_, points)| points.iter());
+    let max_tick = points().map(|(tick, _)| *tick).max().unwrap_or(0).max(1);
+    let max_value = points().map(|(_, value)| *value).fold(0.0, f64::max);
+    let max_value = if max_value > 0.0 {
+        max_value * 1.05
+    } else {
+        1.0
+    };
+
+    let err = |e: DrawingAreaErrorKind<DB::ErrorType>| e.to_string();
+    root.fill(&WHITE).map_err(err)?;
+    let mut chart = ChartBuilder::on(root)
+        .caption(format!("{:?} over time", metric), ("sans-serif", 28))
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0..max_tick, 0.0..max_value)
+        .map_err(err)?;
+    chart
+        .configure_mesh()
+        .x_desc("Tick")
+        .y_desc(format!("{:?}", metric))
+        .draw()
+        .map_err(err)?;
+
+    for (index, (name, points)) in series.iter().enumerate() {
+        let color = Palette99::pick(index).to_rgba();
+        chart
+            .draw_series(LineSeries::new(
+                points.iter().copied(),
+                color.stroke_width(2),
+            ))
+            .map_err(err)?
+            .label(name.as_str())
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(err)?;
+    root.present().map_err(err)
+}
+
 // Helper function to interpolate values
 fn interpolate_value(history: &[(usize, Decimal)], tick: usize) -> Decimal {
     if history.is_empty() {
@@ -440,3 +603,45 @@ fn interpolate_value(history: &[(usize, Decimal)], tick: usize) -> Decimal {
         (None, None) => Decimal::ZERO,
     }
 }
+
+#[cfg(all(test, feature = "plot"))]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_render_chart_writes_png_with_a_series_per_village() {
+        let mut events = Vec::new();
+        for tick in 0..5 {
+            for (village, population) in [("village_a", 5 + tick), ("village_b", 8 - tick)] {
+                events.push(Event {
+                    tick,
+                    timestamp: chrono::Utc::now(),
+                    village_id: village.to_string(),
+                    event_type: EventType::VillageStateSnapshot {
+                        population,
+                        houses: 2,
+                        food: dec!(10),
+                        wood: dec!(10),
+                        money: dec!(100),
+                    },
+                });
+            }
+        }
+        let series = chart_series(&events, ChartMetric::Population);
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].0, "village_a");
+        assert_eq!(series[0].1.len(), 5);
+
+        let path = std::env::temp_dir().join("village_model_population_chart.png");
+        render_chart(&events, ChartMetric::Population, &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // PNG signature, then the IHDR chunk holds width and height big-endian
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+        let width = u32::from_be_bytes(bytes[16..20].try_into().unwrap());
+        let height = u32::from_be_bytes(bytes[20..24].try_into().unwrap());
+        assert_eq!((width, height), CHART_SIZE);
+    }
+}
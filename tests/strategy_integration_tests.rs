@@ -2,7 +2,9 @@
 
 use rust_decimal::{Decimal, prelude::FromPrimitive};
 use rust_decimal_macros::dec;
+use village_model::scenario::StrategyConfig;
 use village_model::strategies::*;
+use village_model::types::{OrderRequest, ResourceType, TimeInForce};
 
 /// Helper to create a test village state.
 fn create_test_village(id: &str, workers: usize, food: f64, wood: f64, money: f64) -> VillageState {
@@ -13,13 +15,20 @@ fn create_test_village(id: &str, workers: usize, food: f64, wood: f64, money: f6
         food: Decimal::from_f64(food).unwrap(),
         money: Decimal::from_f64(money).unwrap(),
         house_capacity: workers * 2,
+        shelter_per_worker: dec!(1),
         houses: workers / 5 + 1,
         wood_slots: (10, 10),
         food_slots: (10, 10),
+        food_quality: dec!(1),
+        wood_quality: dec!(1),
         worker_days: Decimal::from(workers),
-        days_without_food: vec![0; workers],
+        days_without_food: vec![Decimal::ZERO; workers],
         days_without_shelter: vec![0; workers],
         construction_progress: dec!(0),
+        upgradable_house_levels: 0,
+        house_upgrade_cost: dec!(5),
+        tools: dec!(0),
+        can_make_tools: false,
     }
 }
 
@@ -28,12 +37,15 @@ fn create_test_market(wood_price: Option<f64>, food_price: Option<f64>) -> Marke
     MarketState {
         last_wood_price: wood_price.map(|p| Decimal::from_f64(p).unwrap()),
         last_food_price: food_price.map(|p| Decimal::from_f64(p).unwrap()),
+        default_prices: DefaultPrices::default(),
+        wood_book: OrderBook::default(),
+        food_book: OrderBook::default(),
     }
 }
 
 #[test]
 fn test_survival_strategy_prioritizes_resources() {
-    let strategy = SurvivalStrategy::new(20, 10);
+    let mut strategy = SurvivalStrategy::new(20, 10);
 
     // Test with low resources
     let village = create_test_village("test", 10, 5.0, 5.0, 100.0);
@@ -70,7 +82,7 @@ fn test_survival_strategy_prioritizes_resources() {
 
 #[test]
 fn test_growth_strategy_builds_houses() {
-    let strategy = GrowthStrategy::new(50, 3);
+    let mut strategy = GrowthStrategy::new(50, 3);
 
     // Test with good resources but need houses
     let village = create_test_village("test", 10, 100.0, 100.0, 200.0);
@@ -100,7 +112,7 @@ fn test_trading_strategy_specializes() {
     village_food.food_slots = (20, 10);
     village_food.wood_slots = (5, 5);
 
-    let strategy_food = TradingStrategy::new(1.0, 0.3);
+    let mut strategy_food = TradingStrategy::new(1.0, 0.3);
     let market = create_test_market(Some(5.0), Some(1.0));
 
     let decision_food = strategy_food.decide_allocation_and_orders(&village_food, &market);
@@ -119,7 +131,7 @@ fn test_trading_strategy_specializes() {
     village_wood.food_slots = (5, 5);
     village_wood.wood_slots = (20, 10);
 
-    let strategy_wood = TradingStrategy::new(1.0, 0.3);
+    let mut strategy_wood = TradingStrategy::new(1.0, 0.3);
     let decision_wood = strategy_wood.decide_allocation_and_orders(&village_wood, &market);
 
     // Should allocate mostly to wood
@@ -134,7 +146,7 @@ fn test_trading_strategy_specializes() {
 
 #[test]
 fn test_balanced_strategy_adapts() {
-    let strategy = BalancedStrategy::new(0.25, 0.25, 0.25, 0.25);
+    let mut strategy = BalancedStrategy::new(0.25, 0.25, 0.25, 0.25);
 
     // Test with low food
     let mut village = create_test_village("test", 10, 5.0, 100.0, 100.0);
@@ -157,7 +169,7 @@ fn test_balanced_strategy_adapts() {
 
 #[test]
 fn test_greedy_strategy_maximizes_value() {
-    let strategy = GreedyStrategy;
+    let mut strategy = GreedyStrategy::default();
 
     // Test with different price scenarios
     let village = create_test_village("test", 10, 50.0, 50.0, 100.0);
@@ -195,19 +207,19 @@ fn test_greedy_strategy_maximizes_value() {
 
 #[test]
 fn test_strategies_handle_edge_cases() {
-    let strategies: Vec<Box<dyn Strategy>> = vec![
+    let mut strategies: Vec<Box<dyn Strategy>> = vec![
         Box::new(SurvivalStrategy::default()),
         Box::new(GrowthStrategy::default()),
         Box::new(TradingStrategy::default()),
         Box::new(BalancedStrategy::default()),
-        Box::new(GreedyStrategy),
+        Box::new(GreedyStrategy::default()),
     ];
 
     // Test with zero workers
     let empty_village = create_test_village("empty", 0, 0.0, 0.0, 0.0);
     let market = create_test_market(None, None);
 
-    for strategy in &strategies {
+    for strategy in &mut strategies {
         let decision = strategy.decide_allocation_and_orders(&empty_village, &market);
 
         // Should handle gracefully
@@ -219,7 +231,7 @@ fn test_strategies_handle_edge_cases() {
     // Test with no money
     let broke_village = create_test_village("broke", 10, 100.0, 100.0, 0.0);
 
-    for strategy in &strategies {
+    for strategy in &mut strategies {
         let decision = strategy.decide_allocation_and_orders(&broke_village, &market);
 
         // Should not try to buy anything
@@ -227,3 +239,336 @@ fn test_strategies_handle_edge_cases() {
         assert!(decision.wood_bid.is_none() || decision.wood_bid.unwrap().1 == 0);
     }
 }
+
+#[test]
+fn test_default_food_price_sets_cold_market_bid() {
+    let mut strategy = SurvivalStrategy::new(20, 10);
+    let village = create_test_village("test", 10, 5.0, 5.0, 1000.0);
+
+    // No trades yet, so bids are anchored on the configured defaults
+    let mut market = create_test_market(None, None);
+    let cheap_bid = strategy
+        .decide_allocation_and_orders(&village, &market)
+        .food_bid
+        .expect("Should bid for food when low");
+
+    market.default_prices.food = dec!(3.0);
+    let expensive_bid = strategy
+        .decide_allocation_and_orders(&village, &market)
+        .food_bid
+        .expect("Should bid for food when low");
+
+    // Half a day of food left is near zero supply, so both pay the full premium
+    assert_eq!(cheap_bid.0, dec!(2.0));
+    assert_eq!(expensive_bid.0, dec!(6.0));
+}
+
+#[test]
+fn test_growth_strategy_upgrades_when_housing_short() {
+    let mut strategy = GrowthStrategy::new(50, 3);
+
+    // Houses are full and there's wood to spare
+    let mut village = create_test_village("test", 10, 100.0, 100.0, 200.0);
+    village.houses = 2;
+    village.house_capacity = 10;
+
+    let decision = strategy.decide_allocation_and_orders(&village, &create_test_market(None, None));
+    assert_eq!(decision.house_upgrade_wood, dec!(0), "Upgrades disabled");
+
+    village.upgradable_house_levels = 4;
+    let decision = strategy.decide_allocation_and_orders(&village, &create_test_market(None, None));
+    // Keeps 12 wood in reserve, spends the rest in whole levels up to the cap
+    assert_eq!(decision.house_upgrade_wood, dec!(20));
+}
+
+#[test]
+fn test_undercutting_strategy_prices_below_lowest_ask() {
+    let mut strategy = UndercuttingStrategy::new(dec!(0.01), 15);
+    let village = create_test_village("test", 10, 500.0, 5.0, 100.0);
+
+    let ask = |price, quantity| OrderRequest {
+        resource: ResourceType::Food,
+        is_buy: false,
+        quantity,
+        price,
+        all_or_nothing: false,
+        time_in_force: TimeInForce::GoodTillCancel,
+        priority: None,
+        visible_quantity: None,
+    };
+    let mut market = create_test_market(Some(5.0), Some(1.0));
+    market.food_book = OrderBook::from_orders(
+        &[ask(dec!(1.40), 20), ask(dec!(1.25), 10), ask(dec!(1.25), 5)],
+        ResourceType::Food,
+    );
+    assert_eq!(market.food_book.asks[0].quantity, 15);
+
+    let decision = strategy.decide_allocation_and_orders(&village, &market);
+    let (price, quantity) = decision.food_ask.expect("Should sell surplus food");
+    assert_eq!(price, dec!(1.24));
+    assert_eq!(quantity, 100);
+}
+
+#[test]
+fn test_undercutting_strategy_ignores_its_own_ask() {
+    let mut strategy = UndercuttingStrategy::new(dec!(0.01), 15);
+    let village = create_test_village("test", 10, 500.0, 5.0, 100.0);
+
+    let ask = |price, quantity| OrderRequest {
+        resource: ResourceType::Food,
+        is_buy: false,
+        quantity,
+        price,
+        all_or_nothing: false,
+        time_in_force: TimeInForce::GoodTillCancel,
+        priority: None,
+        visible_quantity: None,
+    };
+    let (own, other, shared) = (
+        ask(dec!(1.10), 100),
+        ask(dec!(1.40), 20),
+        ask(dec!(1.25), 5),
+    );
+    let mut market = create_test_market(Some(5.0), Some(1.0));
+    market.food_book = OrderBook::from_village_orders(
+        [
+            (Some("test"), &own),
+            (Some("other"), &other),
+            (Some("test"), &shared),
+            (Some("other"), &shared),
+        ],
+        ResourceType::Food,
+    );
+    assert_eq!(market.food_book.best_ask(), Some(dec!(1.10)));
+
+    // Its own 1.10 ask is skipped; the 1.25 level still has another seller
+    let decision = strategy.decide_allocation_and_orders(&village, &market);
+    let (price, _) = decision.food_ask.expect("Should sell surplus food");
+    assert_eq!(price, dec!(1.24));
+}
+
+#[test]
+fn test_desperation_multiplier_falls_with_supply_and_respects_cap() {
+    let multipliers: Vec<Decimal> = (0..=25)
+        .map(|days| desperation_multiplier(days, 20))
+        .collect();
+
+    assert!(multipliers.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert!(multipliers[..20].windows(2).all(|pair| pair[0] > pair[1]));
+    assert_eq!(multipliers[0], MAX_DESPERATION_MULTIPLIER);
+    assert_eq!(multipliers[10], dec!(1.5));
+    assert!(multipliers[20..].iter().all(|&m| m == dec!(1.0)));
+    assert!(multipliers.iter().all(|&m| m <= MAX_DESPERATION_MULTIPLIER));
+
+    // No floor means no premium
+    assert_eq!(desperation_multiplier(0, 0), dec!(1.0));
+}
+
+#[test]
+fn test_exploring_strategy_perturbs_with_probability_epsilon() {
+    let village = create_test_village("test", 10, 5.0, 5.0, 100.0);
+    let market = create_test_market(Some(5.0), Some(1.0));
+    let decide = |epsilon: f64| {
+        let mut strategy = ExploringStrategy::new(Box::new(BalancedStrategy::default()), epsilon);
+        strategy.seed_rng(7);
+        (0..5)
+            .map(|_| strategy.decide_allocation_and_orders(&village, &market))
+            .collect::<Vec<_>>()
+    };
+    let baseline = BalancedStrategy::default().decide_allocation_and_orders(&village, &market);
+
+    assert!(decide(0.0).iter().all(|decision| *decision == baseline));
+
+    let explored = decide(1.0);
+    assert!(explored.iter().all(|decision| *decision != baseline));
+    assert_eq!(explored, decide(1.0), "Same seed explores the same way");
+    for decision in &explored {
+        let allocation = &decision.allocation;
+        let total = allocation.food + allocation.wood + allocation.construction;
+        let baseline_total =
+            baseline.allocation.food + baseline.allocation.wood + baseline.allocation.construction;
+        assert!((total - baseline_total).abs() < dec!(0.0001));
+    }
+}
+
+#[test]
+fn test_greedy_strategy_caps_labor_at_slot_capacity() {
+    let mut village = create_test_village("test", 30, 5.0, 5.0, 100.0);
+    village.food_slots = (8, 4);
+    village.wood_slots = (5, 5);
+    assert_eq!(village.optimal_worker_days(ResourceType::Food), dec!(12));
+    assert_eq!(village.optimal_worker_days(ResourceType::Wood), dec!(10));
+
+    // Food is worth more, so its slots fill first and wood takes the overflow
+    let market = create_test_market(Some(5.0), Some(1.0));
+    let allocation = GreedyStrategy::default()
+        .decide_allocation_and_orders(&village, &market)
+        .allocation;
+    assert_eq!(allocation.food, dec!(12));
+    assert_eq!(allocation.wood, dec!(10));
+    assert_eq!(allocation.construction, dec!(8));
+}
+
+#[test]
+fn test_labor_bound_villages_invest_in_tools() {
+    let market = create_test_market(Some(5.0), Some(1.0));
+    let mut strategies: Vec<Box<dyn Strategy>> = vec![
+        Box::new(GrowthStrategy::default()),
+        Box::new(GreedyStrategy::default()),
+    ];
+
+    // 10 workers against 40 slot-days: labor is what limits output
+    let mut village = create_test_village("test", 10, 500.0, 50.0, 100.0);
+    village.can_make_tools = true;
+    for strategy in &mut strategies {
+        let allocation = strategy
+            .decide_allocation_and_orders(&village, &market)
+            .allocation;
+        assert_eq!(allocation.processing, dec!(1), "{}", strategy.name());
+        let total = allocation.food
+            + allocation.wood
+            + allocation.construction
+            + allocation.processing
+            + allocation.repair
+            + allocation.slot_expansion;
+        assert_eq!(total, village.worker_days, "{}", strategy.name());
+    }
+
+    // With every slot worked, tools aren't worth the labor
+    village.food_slots = (4, 0);
+    village.wood_slots = (4, 0);
+    for strategy in &mut strategies {
+        let allocation = strategy
+            .decide_allocation_and_orders(&village, &market)
+            .allocation;
+        assert_eq!(allocation.processing, dec!(0), "{}", strategy.name());
+    }
+
+    // Nor when no recipe can make them
+    let mut village = create_test_village("test", 10, 500.0, 50.0, 100.0);
+    village.can_make_tools = false;
+    for strategy in &mut strategies {
+        let allocation = strategy
+            .decide_allocation_and_orders(&village, &market)
+            .allocation;
+        assert_eq!(allocation.processing, dec!(0), "{}", strategy.name());
+    }
+}
+
+#[test]
+fn test_order_sizing_bids_respect_money_and_inventory_together() {
+    let sizing = OrderSizing::default();
+
+    // Money binds: 0.8 × 100 buys 20 food at 4.0, well short of the 195 shortfall
+    let quantity = sizing.bid_quantity(
+        ResourceType::Food,
+        dec!(5),
+        dec!(10),
+        20,
+        dec!(100),
+        dec!(4),
+    );
+    assert_eq!(quantity, 20);
+
+    // Inventory binds: only 10 short of the target despite ample money
+    let quantity = sizing.bid_quantity(
+        ResourceType::Food,
+        dec!(190),
+        dec!(10),
+        20,
+        dec!(1000),
+        dec!(1),
+    );
+    assert_eq!(quantity, 10);
+
+    // Neither binds, so the per-resource cap does
+    let quantity = sizing.bid_quantity(
+        ResourceType::Wood,
+        dec!(0),
+        dec!(10),
+        20,
+        dec!(1000),
+        dec!(1),
+    );
+    assert_eq!(quantity, sizing.max_wood_quantity);
+
+    // Nothing to buy when stocked above target
+    let quantity = sizing.bid_quantity(
+        ResourceType::Food,
+        dec!(300),
+        dec!(10),
+        20,
+        dec!(1000),
+        dec!(1),
+    );
+    assert_eq!(quantity, 0);
+
+    // Asks keep the buffer back and sell a fraction of the rest, up to the cap
+    assert_eq!(
+        sizing.ask_quantity(ResourceType::Food, dec!(100), dec!(40)),
+        30
+    );
+    assert_eq!(
+        sizing.ask_quantity(ResourceType::Wood, dec!(100), dec!(40)),
+        20
+    );
+    assert_eq!(
+        sizing.ask_quantity(ResourceType::Food, dec!(30), dec!(40)),
+        0
+    );
+}
+
+#[test]
+fn test_strategy_orders_follow_configured_sizing() {
+    let sizing = OrderSizing {
+        max_money_fraction: dec!(0.25),
+        ..OrderSizing::default()
+    };
+    let mut strategy = SurvivalStrategy::new(20, 10).with_sizing(sizing);
+    let village = create_test_village("test", 10, 5.0, 50.0, 100.0);
+    let market = create_test_market(Some(5.0), Some(1.0));
+
+    let decision = strategy.decide_allocation_and_orders(&village, &market);
+    let (price, quantity) = decision
+        .food_bid
+        .expect("Starving village should bid for food");
+    assert!(quantity > 0);
+    assert!(
+        price * Decimal::from(quantity) <= village.money * sizing.max_money_fraction,
+        "Bid of {quantity} at {price} spends more than a quarter of the money"
+    );
+    assert!(Decimal::from(quantity) <= dec!(20) * Decimal::from(village.workers) - village.food);
+}
+
+#[test]
+fn test_greedy_and_growth_restock_wood_to_configured_target() {
+    let sizing = OrderSizing {
+        wood_stock_target: 40,
+        ..OrderSizing::default()
+    };
+    let market = create_test_market(Some(1.0), Some(1.0));
+
+    let mut village = create_test_village("test", 10, 100.0, 0.0, 1000.0);
+    let mut greedy = create_strategy(&StrategyConfig::Greedy {
+        sizing: Some(sizing),
+    });
+    let (_, quantity) = greedy
+        .decide_allocation_and_orders(&village, &market)
+        .wood_bid
+        .expect("Greedy without wood should buy some");
+    assert_eq!(quantity, sizing.max_wood_quantity);
+
+    // Growth needs housing here, and restocks up to the target
+    village.house_capacity = village.workers;
+    village.wood = dec!(25);
+    let mut growth = create_strategy(&StrategyConfig::Growth {
+        target_population: 20,
+        house_buffer: 2,
+        sizing: Some(sizing),
+    });
+    let (_, quantity) = growth
+        .decide_allocation_and_orders(&village, &market)
+        .wood_bid
+        .expect("Growth below its wood target should buy some");
+    assert_eq!(quantity, 15);
+}